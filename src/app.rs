@@ -11,12 +11,16 @@ use nak_rust::config::AppConfig;
 use nak_rust::logging::{log_info, log_error, log_warning};
 use nak_rust::nxm::NxmHandler;
 use nak_rust::deps::{check_command_available, ensure_cabextract, ensure_winetricks};
+use nak_rust::installers::InstallPhase;
 use nak_rust::steam::detect_steam_path_checked;
 
 // ============================================================================
 // Types
 // ============================================================================
 
+/// Disk usage summary text per AppID
+pub type PrefixSizes = Vec<(u32, String)>;
+
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum Page {
     FirstRunSetup,
@@ -59,6 +63,9 @@ pub struct InstallWizard {
     pub available_disk_gb: f64,         // Available disk space in GB (cached)
     // Proton selection
     pub selected_proton: Option<String>, // Selected Proton config_name
+    // Dependencies an earlier install left in the prefix this install will use
+    pub existing_deps: Option<String>,   // Description shown in Proton selection
+    pub reuse_deps: bool,                // Skip installing them again if they match
     // Plugin installation (set when installing from marketplace)
     pub plugin_manifest: Option<nak_rust::marketplace::PluginManifest>,
 }
@@ -81,11 +88,35 @@ impl Default for InstallWizard {
             disk_space_override: false,
             available_disk_gb: 0.0,
             selected_proton: None,
+            existing_deps: None,
+            reuse_deps: true,
             plugin_manifest: None,
         }
     }
 }
 
+impl InstallWizard {
+    /// Get ready to run a failed install again, keeping every choice
+    ///
+    /// A failed install removes its shortcut and prefix but leaves the mod
+    /// manager files, so a new MO2 install that failed after extracting MO2
+    /// is retried as an existing install, resuming at prefix creation: only
+    /// the prefix and dependencies are set up again. `failed_phase` is the
+    /// phase the install failed in. Returns whether that happened.
+    pub fn prepare_retry(&mut self, failed_phase: Option<InstallPhase>) -> bool {
+        self.last_install_error = None;
+        self.step = WizardStep::ProtonSelect;
+        let extracted = std::path::Path::new(&self.path).join("ModOrganizer.exe").is_file();
+        // An extract that failed half way may have left ModOrganizer.exe behind
+        let past_extract = failed_phase.is_none_or(|phase| phase > InstallPhase::Extract);
+        if self.manager_type == "MO2" && self.install_type == "New" && extracted && past_extract {
+            self.install_type = "Existing".to_string();
+            return true;
+        }
+        false
+    }
+}
+
 // ============================================================================
 // Application State
 // ============================================================================
@@ -103,6 +134,8 @@ pub struct MyApp {
 
     pub logs: Arc<Mutex<Vec<String>>>,
     pub install_progress: Arc<Mutex<f32>>,
+    /// Phase the running (or last failed) install is in
+    pub install_phase: Arc<Mutex<Option<InstallPhase>>>,
 
     pub cancel_install: Arc<AtomicBool>,
 
@@ -119,6 +152,10 @@ pub struct MyApp {
 
     // Steam migration popup (for users with legacy prefixes)
     pub show_steam_migration_popup: bool,
+    /// One-time note for atomic distro (Bazzite/Silverblue/SteamOS) users
+    pub show_atomic_distro_note: bool,
+    /// Rendered release notes of this version, shown once after an update
+    pub whats_new: Option<String>,
 
     // Updater state
     pub update_info: Arc<Mutex<Option<nak_rust::updater::UpdateInfo>>>,
@@ -138,6 +175,26 @@ pub struct MyApp {
     pub marketplace_state: Option<crate::ui::MarketplaceState>,
     pub marketplace_async: crate::ui::MarketplaceAsync,
 
+    // Prefix manager background actions (DXVK install, etc.)
+    pub prefix_action_status: Arc<Mutex<String>>,
+    pub prefix_action_result: Arc<Mutex<Option<Result<String, String>>>>,
+
+    // Prefix list scan (runs off the UI thread; poll timer applies the result)
+    pub prefix_scan_result: Arc<Mutex<Option<Vec<crate::ui::PrefixRow>>>>,
+    pub is_scanning_prefixes: Arc<Mutex<bool>>,
+    pub prefix_rescan_requested: Arc<Mutex<bool>>,
+    /// Disk usage summaries from the background size pass
+    pub prefix_sizes_result: Arc<Mutex<Option<PrefixSizes>>>,
+
+    // "Pre-download dependencies" task in the task queue (status/result go through prefix_action_*)
+    pub precache_task: Option<u64>,
+
+    // Transient in-app toast for finished background downloads (message, is_error)
+    pub toast: Arc<Mutex<Option<(String, bool)>>>,
+
+    // Report dialog content from a background check (title, text)
+    pub report_result: Arc<Mutex<Option<(String, String)>>>,
+
     // Background task handles (update checker, dep setup, etc.)
     pub background_tasks: Vec<JoinHandle<()>>,
 }
@@ -191,6 +248,7 @@ impl Default for MyApp {
 
             logs: Arc::new(Mutex::new(Vec::new())),
             install_progress: Arc::new(Mutex::new(0.0)),
+            install_phase: Arc::new(Mutex::new(None)),
 
             cancel_install: Arc::new(AtomicBool::new(false)),
 
@@ -203,6 +261,8 @@ impl Default for MyApp {
             steam_protons: nak_rust::steam::find_steam_protons(),
 
             show_steam_migration_popup: false, // Will be set below if legacy data found
+            show_atomic_distro_note: false,
+            whats_new: None,
 
             // Updater
             update_info: Arc::new(Mutex::new(None)),
@@ -226,6 +286,17 @@ impl Default for MyApp {
                 install_result: Arc::new(Mutex::new(None)),
             },
 
+            // Prefix manager actions
+            prefix_action_status: Arc::new(Mutex::new(String::new())),
+            prefix_action_result: Arc::new(Mutex::new(None)),
+            prefix_scan_result: Arc::new(Mutex::new(None)),
+            is_scanning_prefixes: Arc::new(Mutex::new(false)),
+            prefix_rescan_requested: Arc::new(Mutex::new(false)),
+            prefix_sizes_result: Arc::new(Mutex::new(None)),
+            precache_task: None,
+            toast: Arc::new(Mutex::new(None)),
+            report_result: Arc::new(Mutex::new(None)),
+
             // Background tasks
             background_tasks: Vec::new(),
         };
@@ -290,6 +361,30 @@ impl Default for MyApp {
             }
         }
 
+        // Heavy mod lists hit these limits with confusing USVFS errors
+        for warning in nak_rust::logging::ResourceLimits::detect().warnings() {
+            log_warning(&warning);
+        }
+
+        if !app.config.atomic_distro_note_shown && nak_rust::config::is_atomic_distro() {
+            log_info("Atomic distro detected - keeping all NaK tools under $HOME");
+            app.show_atomic_distro_note = true;
+        }
+
+        // First launch of a new version: show its notes if the updater saved them
+        let current_version = env!("CARGO_PKG_VERSION");
+        if app.config.last_seen_version.is_empty() {
+            app.config.last_seen_version = current_version.to_string();
+            app.config.save();
+        } else if app.config.last_seen_version != current_version {
+            app.whats_new = nak_rust::updater::load_release_notes(current_version)
+                .map(|notes| nak_rust::updater::render_release_notes(&notes));
+            if app.whats_new.is_none() {
+                app.config.last_seen_version = current_version.to_string();
+                app.config.save();
+            }
+        }
+
         app
     }
 }
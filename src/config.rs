@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
 
 fn get_home() -> String {
     // Use dirs::home_dir() which handles edge cases (HOME unset, empty, etc.)
@@ -29,6 +32,97 @@ pub fn normalize_path_for_steam(path: &str) -> String {
     }
 }
 
+/// Replace a file's contents without ever leaving it half-written
+///
+/// Writes a temporary file next to `path`, syncs it to disk and renames it
+/// over `path`, so a crash or power loss leaves either the old or the new
+/// contents. An existing file keeps its permissions; a symlink keeps
+/// pointing at the (replaced) file it pointed at.
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    write_atomic_with_mode(path, data, None)
+}
+
+/// `write_atomic` for files only the user may read (API keys, account info)
+///
+/// The temporary file is created with mode 0600, so the contents are never
+/// readable by others, not even briefly.
+pub fn write_atomic_private(path: &Path, data: &[u8]) -> io::Result<()> {
+    write_atomic_with_mode(path, data, Some(0o600))
+}
+
+fn write_atomic_with_mode(path: &Path, data: &[u8], mode: Option<u32>) -> io::Result<()> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a file path", path.display()))
+    })?;
+    // Unique per process and call, so concurrent saves of one file don't share a temp file
+    static TMP_COUNTER: AtomicU32 = AtomicU32::new(0);
+    let tmp = path.with_file_name(format!(
+        ".{}.{}-{}.nak-tmp",
+        name.to_string_lossy(),
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mode = mode.or_else(|| fs::metadata(&path).ok().map(|m| m.permissions().mode() & 0o7777));
+
+    let result = (|| {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(mode.unwrap_or(0o666))
+            .open(&tmp)?;
+        if let Some(mode) = mode {
+            // The umask may have stripped bits on create
+            file.set_permissions(fs::Permissions::from_mode(mode))?;
+        }
+        file.write_all(data)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp, &path)?;
+        // Make the rename itself survive a power loss
+        if let Some(dir) = path.parent().and_then(|dir| fs::File::open(dir).ok()) {
+            let _ = dir.sync_all();
+        }
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// os-release IDs of image-based distros with a read-only `/usr`
+const ATOMIC_DISTRO_IDS: &[&str] = &[
+    "bazzite", "bluefin", "aurora", "silverblue", "kinoite", "sericea", "onyx",
+    "steamos", "endless", "vanilla",
+];
+
+/// Detect an atomic/immutable distro (Bazzite, Fedora Silverblue/Kinoite, SteamOS, ...)
+///
+/// On these systems `/usr` is read-only and `$HOME` may live under `/var/home`,
+/// so NaK must keep every binary it installs under `$HOME` and never write
+/// to system locations.
+pub fn is_atomic_distro() -> bool {
+    static ATOMIC: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *ATOMIC.get_or_init(|| {
+        if std::path::Path::new("/run/ostree-booted").exists() || get_home().starts_with("/var/home/") {
+            return true;
+        }
+
+        let os_release = fs::read_to_string("/etc/os-release").unwrap_or_default();
+        os_release.lines().any(|line| {
+            let Some((key, value)) = line.split_once('=') else {
+                return false;
+            };
+            matches!(key, "ID" | "ID_LIKE" | "VARIANT_ID")
+                && value
+                    .trim_matches('"')
+                    .split_whitespace()
+                    .any(|id| ATOMIC_DISTRO_IDS.contains(&id))
+        })
+    })
+}
+
 fn default_data_path() -> String {
     format!("{}/NaK", get_home())
 }
@@ -50,7 +144,7 @@ pub struct AppConfig {
     /// (shown once when user has legacy NaK prefixes)
     #[serde(default)]
     pub steam_migration_shown: bool,
-    /// Custom cache location (for downloads, tmp files during install)
+    /// Custom location for the dependency cache (winetricks downloads, .NET installers)
     /// If empty/not set, uses ~/.cache/nak/
     #[serde(default)]
     pub cache_location: String,
@@ -63,6 +157,107 @@ pub struct AppConfig {
     /// that auto-detection cannot find. If set and valid, takes priority over auto-detection.
     #[serde(default)]
     pub custom_steam_path: String,
+    /// What to do when Steam is running while NaK edits shortcuts.vdf/config.vdf
+    #[serde(default)]
+    pub steam_running_policy: SteamRunningPolicy,
+    /// Restart Steam after NaK changes its config files so they take effect
+    #[serde(default = "default_true")]
+    pub restart_steam_after_changes: bool,
+    /// Whether the one-time atomic distro (Bazzite/Silverblue/SteamOS) note has been shown
+    #[serde(default)]
+    pub atomic_distro_note_shown: bool,
+    /// Number of log files to keep next to the NaK binary
+    #[serde(default = "default_log_retention_count")]
+    pub log_retention_count: usize,
+    /// Delete log files older than this many days (0 = no age limit)
+    #[serde(default = "default_log_retention_days")]
+    pub log_retention_days: u32,
+    /// Proton (config name, e.g. "GE-Proton10-18") used to run the custom .NET installers.
+    /// If unset or not installed, the newest installed GE-Proton 10+ is used, then the instance's Proton.
+    #[serde(default)]
+    pub dotnet_proton: Option<String>,
+    /// .NET components new prefixes get (names from `installers::DOTNET_COMPONENTS`,
+    /// usually a `DOTNET_PRESETS` entry). Empty = all of them.
+    #[serde(default)]
+    pub dotnet_components: Vec<String>,
+    /// Keep the downloaded MO2 archive (and an extracted copy) in the cache so
+    /// new instances of the same version skip the download and extraction
+    #[serde(default = "default_true")]
+    pub cache_mo2: bool,
+    /// Seconds to wait for a first launch to initialize a prefix before it is
+    /// force-killed (0 = default, 180)
+    #[serde(default)]
+    pub prefix_init_timeout_secs: u64,
+    /// Extra plugin registries (base URLs holding a registry.toml), listed
+    /// after the official marketplace; earlier registries win on duplicate ids
+    #[serde(default)]
+    pub marketplace_registries: Vec<String>,
+    /// Heavy background tasks (installs, downloads, prefix resets) allowed to
+    /// run at once; the rest wait in a queue
+    #[serde(default = "default_max_parallel_tasks")]
+    pub max_parallel_tasks: usize,
+    /// Games whose install path the user confirmed for the prefix registry
+    /// (game name -> install path). New prefixes only get registry entries
+    /// for these, and only while the game is still detected at that path.
+    #[serde(default)]
+    pub registry_game_paths: BTreeMap<String, String>,
+    /// Extra folders searched for a mod manager whose prefix has no working
+    /// `manager_link`. Relative paths are taken from the prefix's drive_c.
+    #[serde(default)]
+    pub manager_search_dirs: Vec<String>,
+    /// How many folders deep the mod manager search goes (0 = default, 6)
+    #[serde(default)]
+    pub manager_search_depth: usize,
+    /// NaK version whose "What's New" notes were last seen (empty = fresh install)
+    #[serde(default)]
+    pub last_seen_version: String,
+    /// Proxy for all downloads, e.g. "http://proxy.example:3128" (empty = use
+    /// HTTP_PROXY/HTTPS_PROXY from the environment). NO_PROXY still applies.
+    #[serde(default)]
+    pub http_proxy: String,
+    /// Terminal for "Open Terminal" on a prefix, with the flags that make it
+    /// run a command (e.g. "wezterm start --"). Empty = first one found.
+    #[serde(default)]
+    pub terminal_command: String,
+    /// Send an anonymous report when an install fails (opt-in, see `telemetry`)
+    #[serde(default)]
+    pub telemetry_enabled: bool,
+    /// Where failure reports are POSTed (empty = nowhere, even when enabled)
+    #[serde(default)]
+    pub telemetry_endpoint: String,
+    /// Go straight to Finished after an install, keeping the default 96 DPI
+    #[serde(default)]
+    pub skip_dpi_setup: bool,
+}
+
+/// Behavior when Steam is running during a VDF write
+///
+/// Steam keeps its VDF files in memory and writes them back on exit,
+/// which silently discards changes made while it was running.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SteamRunningPolicy {
+    /// Close Steam before writing (ends running games and downloads)
+    CloseSteam,
+    /// Only log a warning and write anyway (default)
+    #[default]
+    Warn,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_log_retention_count() -> usize {
+    10
+}
+
+fn default_max_parallel_tasks() -> usize {
+    1
+}
+
+fn default_log_retention_days() -> u32 {
+    30
 }
 
 impl Default for AppConfig {
@@ -75,6 +270,26 @@ impl Default for AppConfig {
             cache_location: String::new(),
             selected_steam_account: String::new(),
             custom_steam_path: String::new(),
+            steam_running_policy: SteamRunningPolicy::default(),
+            restart_steam_after_changes: true,
+            atomic_distro_note_shown: false,
+            log_retention_count: default_log_retention_count(),
+            log_retention_days: default_log_retention_days(),
+            dotnet_proton: None,
+            dotnet_components: Vec::new(),
+            cache_mo2: true,
+            prefix_init_timeout_secs: 0,
+            marketplace_registries: Vec::new(),
+            max_parallel_tasks: default_max_parallel_tasks(),
+            registry_game_paths: BTreeMap::new(),
+            manager_search_dirs: Vec::new(),
+            manager_search_depth: 0,
+            last_seen_version: String::new(),
+            http_proxy: String::new(),
+            terminal_command: String::new(),
+            telemetry_enabled: false,
+            telemetry_endpoint: String::new(),
+            skip_dpi_setup: false,
         }
     }
 }
@@ -129,9 +344,10 @@ impl AppConfig {
             let _ = fs::create_dir_all(parent);
         }
         if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = fs::write(&path, json);
-            // Restrict config file to owner-only (may contain account info)
-            let _ = fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+            // Owner-only (may contain account info)
+            if let Err(e) = write_atomic_private(&path, json.as_bytes()) {
+                eprintln!("Warning: failed to save {}: {}", path.display(), e);
+            }
         }
     }
 
@@ -150,7 +366,7 @@ impl AppConfig {
         PathBuf::from(format!("{}/.cache/nak", get_home()))
     }
 
-    /// Get the cache directory (custom location or default ~/.cache/nak/)
+    /// Get the dependency cache directory (custom location or default ~/.cache/nak/)
     pub fn get_cache_dir(&self) -> PathBuf {
         if self.cache_location.is_empty() {
             Self::get_default_cache_dir()
@@ -176,27 +392,51 @@ impl AppConfig {
 // ============================================================================
 
 /// Type of mod manager
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum ManagerType {
     MO2,
     Plugin,
+    /// Any other Windows mod manager, identified by its executable
+    Generic {
+        /// Executable name relative to the install folder (e.g. "Kortex.exe")
+        exe_name: String,
+        /// Whether the manager is an Electron app (needs the Electron launch workaround)
+        #[serde(default)]
+        is_electron: bool,
+    },
 }
 
 impl std::fmt::Display for ManagerType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ManagerType::MO2 => write!(f, "MO2"),
-            ManagerType::Plugin => write!(f, "Plugin"),
-        }
+        write!(f, "{}", self.display_name())
     }
 }
 
 impl ManagerType {
     /// Get the display name for this manager type
-    pub fn display_name(&self) -> &'static str {
+    ///
+    /// Generic managers are named after their executable without ".exe".
+    pub fn display_name(&self) -> &str {
         match self {
             ManagerType::MO2 => "MO2",
             ManagerType::Plugin => "Plugin",
+            ManagerType::Generic { exe_name, .. } => {
+                let file = exe_name.rsplit(['/', '\\']).next().unwrap_or(exe_name);
+                file.strip_suffix(".exe")
+                    .or_else(|| file.strip_suffix(".EXE"))
+                    .unwrap_or(file)
+            }
+        }
+    }
+
+    /// Executable that nxm:// links are passed to
+    ///
+    /// MO2 (and plugins, which follow its layout) ship a separate
+    /// `nxmhandler.exe`; generic managers get the link on their main exe.
+    pub fn nxm_exe_name(&self) -> &str {
+        match self {
+            ManagerType::Generic { exe_name, .. } => exe_name,
+            _ => "nxmhandler.exe",
         }
     }
 }
@@ -222,6 +462,263 @@ pub struct ManagedPrefix {
     /// Optional for backward compatibility with existing installs
     #[serde(default)]
     pub proton_config_name: Option<String>,
+    /// DXVK release tag installed over Proton's bundled DXVK (None = Proton default)
+    #[serde(default)]
+    pub dxvk_version: Option<String>,
+    /// Prefix-wide Windows version chosen in the prefix manager (None = installer default, win11)
+    #[serde(default)]
+    pub windows_version: Option<String>,
+    /// Extra arguments passed to the mod manager, as the user typed them
+    /// (appended after `%command%` in the Steam shortcut's launch options)
+    #[serde(default)]
+    pub launch_args: Option<String>,
+    /// Run the mod manager with Proton's Wayland driver instead of XWayland (experimental)
+    #[serde(default)]
+    pub native_wayland: bool,
+    /// Sync primitive code from `steam::SYNC_MODES` (None = Proton's default)
+    #[serde(default)]
+    pub sync_mode: Option<String>,
+    /// Locale for `LANG`/`LC_ALL` (and so Wine's codepage), e.g. "ja_JP.UTF-8"
+    /// (None = the system locale)
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Toggles from the launch options builder
+    #[serde(default)]
+    pub launch_toggles: LaunchToggles,
+}
+
+/// Launch log wrapper in an instance's NaK Tools folder (see `LaunchToggles::launch_log`)
+pub const LAUNCH_LOG_SCRIPT: &str = "Launch Log.sh";
+
+/// Latest launch's log in NaK Tools; older ones are `launch.log.1`, `.2`, ...
+pub const LAUNCH_LOG_FILE: &str = "launch.log";
+
+/// Launch options builder toggles for a prefix (see `steam::apply_launch_toggles`)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LaunchToggles {
+    /// Run through Feral GameMode (`gamemoderun`)
+    #[serde(default)]
+    pub gamemode: bool,
+    /// Show the MangoHud overlay (`mangohud`)
+    #[serde(default)]
+    pub mangohud: bool,
+    /// MangoHud config file (`MANGOHUD_CONFIGFILE`), used with `mangohud`
+    /// (empty = MangoHud's own config)
+    #[serde(default)]
+    pub mangohud_config: String,
+    /// Use WineD3D instead of DXVK (`PROTON_USE_WINED3D=1`)
+    #[serde(default)]
+    pub wined3d: bool,
+    /// `WINEDLLOVERRIDES` value, e.g. "dinput8=n,b" (empty = not set)
+    #[serde(default)]
+    pub dll_overrides: String,
+    /// Run inside a gamescope window of this size (width, height), experimental
+    #[serde(default)]
+    pub gamescope: Option<(u32, u32)>,
+    /// Steam AppID protonfixes applies its game fixes for (`SteamAppId`), e.g. 489830
+    /// for Skyrim SE. None = the instance's game if known (`ManagedPrefix::associated_game`),
+    /// Some(0) = none, so protonfixes treats the manager as a plain non-Steam game
+    #[serde(default)]
+    pub game_id: Option<u32>,
+    /// Run through NaK Tools' `Launch Log.sh`, which keeps the output of the
+    /// last launches in rotating `launch.log` files
+    #[serde(default)]
+    pub launch_log: bool,
+    /// Path of the wrapper used with `launch_log`, filled in by
+    /// `ManagedPrefix::effective_launch_toggles`
+    #[serde(skip)]
+    pub launch_log_script: Option<PathBuf>,
+}
+
+/// Deepest folder level `dir_size` walks into; real prefixes stay far above it
+const MAX_WALK_DEPTH: usize = 64;
+
+/// Total size of the files under `root`, without following symlinks
+///
+/// Each folder is walked once, going by its device and inode, so a folder
+/// that shows up twice (bind mounts) can't make the walk loop, and nothing
+/// below `MAX_WALK_DEPTH` is counted. Folders that can't be read (permission
+/// denied, removed mid-walk) are skipped and logged.
+pub fn dir_size(root: &Path) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    let mut size = 0;
+    let mut visited = std::collections::HashSet::new();
+    let mut unreadable: Vec<PathBuf> = Vec::new();
+    let mut too_deep = false;
+    let mut pending = vec![(root.to_path_buf(), 0)];
+    if let Ok(metadata) = fs::metadata(root) {
+        visited.insert((metadata.dev(), metadata.ino()));
+    }
+
+    while let Some((dir, depth)) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => {
+                unreadable.push(dir);
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            // DirEntry metadata doesn't follow symlinks
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_file() {
+                size += metadata.len();
+            } else if metadata.is_dir() {
+                if depth + 1 >= MAX_WALK_DEPTH {
+                    too_deep = true;
+                } else if visited.insert((metadata.dev(), metadata.ino())) {
+                    pending.push((entry.path(), depth + 1));
+                }
+            }
+        }
+    }
+
+    if !unreadable.is_empty() {
+        crate::logging::log_warning(&format!(
+            "Skipped {} unreadable folder(s) while measuring {} (first: {})",
+            unreadable.len(),
+            root.display(),
+            unreadable[0].display()
+        ));
+    }
+    if too_deep {
+        crate::logging::log_warning(&format!(
+            "Stopped measuring {} at {} folder levels; the size is incomplete",
+            root.display(),
+            MAX_WALK_DEPTH
+        ));
+    }
+    size
+}
+
+/// How long a computed disk usage is reused before walking the folders again
+const DISK_USAGE_CACHE_SECS: u64 = 300;
+
+/// Disk usage computed this session, keyed by AppID
+static DISK_USAGE_CACHE: std::sync::OnceLock<
+    parking_lot::Mutex<std::collections::HashMap<u32, (std::time::Instant, PrefixDiskUsage)>>,
+> = std::sync::OnceLock::new();
+
+/// Disk space used by one managed instance, in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrefixDiskUsage {
+    /// The Wine prefix (`compatdata/<appid>`)
+    pub prefix: u64,
+    /// The install's `mods` folder
+    pub mods: u64,
+    /// The install's `downloads` folder
+    pub downloads: u64,
+    /// Everything else in the install folder
+    pub other: u64,
+}
+
+impl PrefixDiskUsage {
+    pub fn total(&self) -> u64 {
+        self.prefix + self.mods + self.downloads + self.other
+    }
+
+    /// "12.3 GB (prefix 4.1 GB, mods 7.0 GB, downloads 1.2 GB, other 20.0 MB)"
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = [
+            ("prefix", self.prefix),
+            ("mods", self.mods),
+            ("downloads", self.downloads),
+            ("other", self.other),
+        ]
+        .iter()
+        .filter(|(_, bytes)| *bytes > 0)
+        .map(|(label, bytes)| format!("{} {}", label, ManagedPrefixes::format_size(*bytes)))
+        .collect();
+
+        if parts.is_empty() {
+            return ManagedPrefixes::format_size(0);
+        }
+        format!("{} ({})", ManagedPrefixes::format_size(self.total()), parts.join(", "))
+    }
+}
+
+/// Known game an instance is for: MO2's `gameName`, else the instance name
+pub fn instance_game(install_path: &Path, name: &str) -> Option<&'static crate::game_finder::KnownGame> {
+    let ini = fs::read_to_string(install_path.join("ModOrganizer.ini")).unwrap_or_default();
+    ini.lines()
+        .find_map(|line| line.trim().strip_prefix("gameName="))
+        .and_then(|game| crate::game_finder::find_by_title(game.trim()))
+        .or_else(|| crate::game_finder::find_by_title(name))
+}
+
+impl ManagedPrefix {
+    /// Known game this instance is for: MO2's `gameName`, else the instance name
+    pub fn associated_game(&self) -> Option<&'static crate::game_finder::KnownGame> {
+        instance_game(Path::new(&self.install_path), &self.name)
+    }
+
+    /// Launch toggles as written to the Steam shortcut, with an automatic
+    /// `game_id` resolved to the associated game's AppID and the launch log
+    /// wrapper pointing at this instance's NaK Tools
+    pub fn effective_launch_toggles(&self) -> LaunchToggles {
+        let mut toggles = self.launch_toggles.clone();
+        if toggles.game_id.is_none() {
+            toggles.game_id = self.associated_game().and_then(|game| game.steam_app_id.parse().ok());
+        }
+        if toggles.launch_log {
+            toggles.launch_log_script = Some(self.launch_log_dir().join(LAUNCH_LOG_SCRIPT));
+        }
+        toggles
+    }
+
+    /// NaK Tools folder, where the launch log wrapper writes `launch.log`
+    pub fn launch_log_dir(&self) -> PathBuf {
+        PathBuf::from(&self.install_path).join("NaK Tools")
+    }
+
+    /// Disk usage of the prefix and install folder
+    ///
+    /// Walks both trees, so call it off the UI thread. Results are cached for
+    /// a few minutes; use `cached_disk_usage` to read the cache without walking.
+    pub fn disk_usage(&self) -> PrefixDiskUsage {
+        if let Some(usage) = self.cached_disk_usage() {
+            return usage;
+        }
+
+        // The prefix folder is compatdata/<appid>/pfx; count the whole appid folder
+        let pfx = PathBuf::from(&self.prefix_path);
+        let prefix_dir = pfx.parent().filter(|p| p.ends_with(self.app_id.to_string())).unwrap_or(pfx.as_path());
+        let install = PathBuf::from(&self.install_path);
+        let mods = ManagedPrefixes::get_prefix_size(&install.join("mods").to_string_lossy());
+        let downloads = ManagedPrefixes::get_prefix_size(&install.join("downloads").to_string_lossy());
+        let install_total = ManagedPrefixes::get_prefix_size(&self.install_path);
+
+        let usage = PrefixDiskUsage {
+            prefix: ManagedPrefixes::get_prefix_size(&prefix_dir.to_string_lossy()),
+            mods,
+            downloads,
+            other: install_total.saturating_sub(mods + downloads),
+        };
+        DISK_USAGE_CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .insert(self.app_id, (std::time::Instant::now(), usage));
+        usage
+    }
+
+    /// Disk usage from the cache, if it was computed recently
+    pub fn cached_disk_usage(&self) -> Option<PrefixDiskUsage> {
+        let cache = DISK_USAGE_CACHE.get()?.lock();
+        cache
+            .get(&self.app_id)
+            .filter(|(at, _)| at.elapsed().as_secs() < DISK_USAGE_CACHE_SECS)
+            .map(|(_, usage)| *usage)
+    }
+
+    /// Drop the cached disk usage, e.g. after the prefix was reset or deleted
+    pub fn forget_disk_usage(app_id: u32) {
+        if let Some(cache) = DISK_USAGE_CACHE.get() {
+            cache.lock().remove(&app_id);
+        }
+    }
 }
 
 /// Container for all managed prefixes
@@ -256,7 +753,9 @@ impl ManagedPrefixes {
             let _ = fs::create_dir_all(parent);
         }
         if let Ok(json) = serde_json::to_string_pretty(self) {
-            let _ = fs::write(path, json);
+            if let Err(e) = write_atomic(&path, json.as_bytes()) {
+                eprintln!("Warning: failed to save {}: {}", path.display(), e);
+            }
         }
     }
 
@@ -284,6 +783,13 @@ impl ManagedPrefixes {
             library_path: library_path.to_string(),
             created: Utc::now(),
             proton_config_name: proton_config_name.map(|s| s.to_string()),
+            dxvk_version: None,
+            windows_version: None,
+            launch_args: None,
+            native_wayland: false,
+            sync_mode: None,
+            locale: None,
+            launch_toggles: LaunchToggles::default(),
         });
 
         prefixes.save();
@@ -298,6 +804,112 @@ impl ManagedPrefixes {
         prefixes.save();
     }
 
+    /// Record the DXVK version installed into a prefix (None = reverted to Proton's)
+    pub fn update_dxvk_version(app_id: u32, version: Option<&str>) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.dxvk_version = version.map(|s| s.to_string());
+        }
+        prefixes.save();
+    }
+
+    /// Record the Windows version chosen for a prefix
+    pub fn update_windows_version(app_id: u32, version: Option<&str>) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.windows_version = version.map(|s| s.to_string());
+        }
+        prefixes.save();
+    }
+
+    /// Record the launch arguments for a prefix (None or empty = no arguments)
+    pub fn update_launch_args(app_id: u32, args: Option<&str>) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.launch_args = args.map(str::trim).filter(|s| !s.is_empty()).map(|s| s.to_string());
+        }
+        prefixes.save();
+    }
+
+    /// Record the sync primitive for a prefix (None or "default" = Proton's default)
+    pub fn update_sync_mode(app_id: u32, mode: Option<&str>) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.sync_mode = mode.filter(|m| *m != "default").map(|m| m.to_string());
+        }
+        prefixes.save();
+    }
+
+    /// Record the locale for a prefix (None or empty = the system locale)
+    pub fn update_locale(app_id: u32, locale: Option<&str>) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.locale = locale.filter(|l| !l.is_empty()).map(|l| l.to_string());
+        }
+        prefixes.save();
+    }
+
+    /// Record the launch options builder toggles for a prefix
+    pub fn update_launch_toggles(app_id: u32, toggles: &LaunchToggles) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.launch_toggles = toggles.clone();
+        }
+        prefixes.save();
+    }
+
+    /// Record a new mod manager folder for a prefix (after the user moved it)
+    pub fn update_install_path(app_id: u32, install_path: &str) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.install_path = install_path.to_string();
+        }
+        prefixes.save();
+    }
+
+    /// Record where Steam actually keeps a prefix (when it isn't where NaK expected)
+    pub fn update_prefix_path(app_id: u32, prefix_path: &str) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.prefix_path = prefix_path.to_string();
+        }
+        prefixes.save();
+    }
+
+    /// Record whether a prefix runs with Proton's native Wayland driver
+    pub fn update_native_wayland(app_id: u32, enabled: bool) {
+        let mut prefixes = Self::load();
+        if let Some(prefix) = prefixes.prefixes.iter_mut().find(|p| p.app_id == app_id) {
+            prefix.native_wayland = enabled;
+        }
+        prefixes.save();
+    }
+
+    /// Rename a managed prefix
+    ///
+    /// Fails if the name is empty or another managed prefix already uses it.
+    /// The prefix folder itself is keyed by AppID, so nothing moves on disk.
+    pub fn rename(app_id: u32, new_name: &str) -> Result<(), String> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+
+        let mut prefixes = Self::load();
+        if prefixes.prefixes.iter().any(|p| p.app_id != app_id && p.name == new_name) {
+            return Err(format!("A prefix named '{}' already exists", new_name));
+        }
+
+        let prefix = prefixes
+            .prefixes
+            .iter_mut()
+            .find(|p| p.app_id == app_id)
+            .ok_or_else(|| "Prefix not found".to_string())?;
+        prefix.name = new_name.to_string();
+        prefixes.save();
+        Ok(())
+    }
+
     /// Remove a managed prefix entry (does NOT delete files)
     pub fn unregister(app_id: u32) {
         let mut prefixes = Self::load();
@@ -311,33 +923,18 @@ impl ManagedPrefixes {
     }
 
     /// Get the size of a prefix directory in bytes
-    #[allow(dead_code)]
+    ///
+    /// Symlinks aren't followed: prefixes link into the install folder (Global
+    /// Instance, game saves) and counting those would double them or loop.
     pub fn get_prefix_size(prefix_path: &str) -> u64 {
         let path = PathBuf::from(prefix_path);
         if !path.exists() {
             return 0;
         }
-
-        fn dir_size(path: &PathBuf) -> u64 {
-            let mut size = 0;
-            if let Ok(entries) = fs::read_dir(path) {
-                for entry in entries.flatten() {
-                    let entry_path = entry.path();
-                    if entry_path.is_dir() {
-                        size += dir_size(&entry_path);
-                    } else if let Ok(metadata) = entry.metadata() {
-                        size += metadata.len();
-                    }
-                }
-            }
-            size
-        }
-
         dir_size(&path)
     }
 
     /// Format bytes as human-readable size
-    #[allow(dead_code)]
     pub fn format_size(bytes: u64) -> String {
         const KB: u64 = 1024;
         const MB: u64 = KB * 1024;
@@ -397,3 +994,92 @@ impl ManagedPrefixes {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dir_size_survives_loops() {
+        let dir = std::env::temp_dir().join(format!("nak_dir_size_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("drive_c/users/steamuser")).unwrap();
+        fs::write(dir.join("drive_c/file.dll"), [0u8; 10]).unwrap();
+        fs::write(dir.join("drive_c/users/steamuser/save.ess"), [0u8; 5]).unwrap();
+        // A link back to the top, and one to itself
+        std::os::unix::fs::symlink(&dir, dir.join("drive_c/users/steamuser/loop")).unwrap();
+        std::os::unix::fs::symlink("self", dir.join("drive_c/self")).unwrap();
+        assert_eq!(ManagedPrefixes::get_prefix_size(&dir.to_string_lossy()), 15);
+
+        // Nothing below the depth limit is counted
+        let mut deep = dir.join("deep");
+        for _ in 0..MAX_WALK_DEPTH {
+            deep = deep.join("d");
+        }
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(deep.join("lost.bin"), [0u8; 7]).unwrap();
+        assert_eq!(dir_size(&dir), 15);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = std::env::temp_dir().join(format!("nak_write_atomic_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("config.vdf");
+        write_atomic(&file, b"old").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o640)).unwrap();
+        write_atomic(&file, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+        assert_eq!(fs::metadata(&file).unwrap().permissions().mode() & 0o7777, 0o640);
+
+        // Writing through a symlink replaces its target, not the link
+        let link = dir.join("link.vdf");
+        std::os::unix::fs::symlink(&file, &link).unwrap();
+        write_atomic(&link, b"via link").unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "via link");
+
+        let key = dir.join("key");
+        write_atomic_private(&key, b"secret").unwrap();
+        assert_eq!(fs::metadata(&key).unwrap().permissions().mode() & 0o7777, 0o600);
+
+        // No temp files left behind
+        let names: Vec<String> = fs::read_dir(&dir).unwrap().flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        assert!(names.iter().all(|n| !n.ends_with(".nak-tmp")), "{:?}", names);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_write_atomic_concurrent() {
+        let dir = std::env::temp_dir().join(format!("nak_write_atomic_concurrent_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("config.json");
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let file = file.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..20 {
+                        write_atomic(&file, format!("writer {}", i).as_bytes()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert!(fs::read_to_string(&file).unwrap().starts_with("writer "));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
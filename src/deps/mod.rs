@@ -8,12 +8,17 @@ pub mod precache;
 pub mod tools;
 
 use std::error::Error;
-use std::path::Path;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
 
 use crate::config::AppConfig;
-use crate::logging::{log_error, log_install};
+use crate::logging::{log_error, log_install, log_warning};
 use crate::runtime_wrap;
 use crate::steam::SteamProton;
 
@@ -35,6 +40,124 @@ pub const STANDARD_VERBS: &[&str] = &[
     "xact_x64",       // XACT Audio (64-bit)
 ];
 
+/// Architecture a verb is installed for, chosen with a `:32`/`:64` suffix
+/// (e.g. `xact:32`). No suffix means both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerbArch {
+    X86,
+    X64,
+    Both,
+}
+
+/// Components winetricks ships as separate verbs per architecture (name, 32-bit verb, 64-bit verb)
+///
+/// Every other verb installs both architectures into a win64 prefix, so an
+/// architecture can't be picked for it.
+const SPLIT_ARCH_VERBS: &[(&str, &str, &str)] = &[("xact", "xact", "xact_x64")];
+
+/// DLLs a verb must leave in the prefix: (verb, DLL, architecture).
+/// 32-bit DLLs live in `syswow64`, 64-bit ones in `system32`.
+const VERB_DLLS: &[(&str, &str, VerbArch)] = &[
+    ("d3dx9", "d3dx9_43.dll", VerbArch::Both),
+    ("d3dx11_43", "d3dx11_43.dll", VerbArch::Both),
+    ("d3dcompiler_43", "d3dcompiler_43.dll", VerbArch::Both),
+    ("d3dcompiler_47", "d3dcompiler_47.dll", VerbArch::Both),
+    ("xact", "xactengine3_7.dll", VerbArch::X86),
+    ("xact", "x3daudio1_7.dll", VerbArch::X86),
+    ("xact_x64", "xactengine3_7.dll", VerbArch::X64),
+    ("xact_x64", "x3daudio1_7.dll", VerbArch::X64),
+];
+
+/// Winetricks verbs for one verb spec (`verb`, `verb:32` or `verb:64`)
+///
+/// `xact` stands for both halves of XACT. An architecture suffix on a verb
+/// that installs both at once is dropped with a warning.
+pub fn expand_verb_spec(spec: &str) -> Vec<String> {
+    let (name, arch) = match spec.rsplit_once(':') {
+        Some((name, "32")) => (name, VerbArch::X86),
+        Some((name, "64")) => (name, VerbArch::X64),
+        _ => (spec, VerbArch::Both),
+    };
+    let Some((_, x86, x64)) = SPLIT_ARCH_VERBS.iter().find(|(n, ..)| *n == name) else {
+        if arch != VerbArch::Both {
+            log_warning(&format!("{} always installs both architectures, ignoring the suffix in '{}'", name, spec));
+        }
+        return vec![name.to_string()];
+    };
+    match arch {
+        VerbArch::X86 => vec![x86.to_string()],
+        VerbArch::X64 => vec![x64.to_string()],
+        VerbArch::Both => vec![x86.to_string(), x64.to_string()],
+    }
+}
+
+/// Expand a list of verb specs, keeping the first occurrence of each verb
+pub fn expand_verb_specs(specs: &[&str]) -> Vec<String> {
+    let mut verbs: Vec<String> = Vec::new();
+    for verb in specs.iter().flat_map(|spec| expand_verb_spec(spec)) {
+        if !verbs.contains(&verb) {
+            verbs.push(verb);
+        }
+    }
+    verbs
+}
+
+/// Architecture of an existing prefix from its `system.reg` header ("win32"/"win64")
+pub fn prefix_arch(prefix_path: &Path) -> Option<String> {
+    std::fs::read_to_string(prefix_path.join("system.reg"))
+        .ok()?
+        .lines()
+        .take(10)
+        .find_map(|l| l.trim().strip_prefix("#arch=").map(str::to_string))
+}
+
+/// DLLs the given verbs should have installed that aren't in the prefix
+///
+/// Catches half-installed dual-arch components, e.g. a 64-bit XACT without
+/// the 32-bit one that older games load for voices. Returns
+/// "verb: 32-bit/64-bit DLL" descriptions.
+pub fn missing_verb_dlls(prefix_path: &Path, verbs: &[&str]) -> Vec<String> {
+    let windows = prefix_path.join("drive_c/windows");
+    let mut missing = Vec::new();
+    for (verb, dll, arch) in VERB_DLLS.iter().filter(|(v, ..)| verbs.contains(v)) {
+        let dirs: &[(&str, &str)] = match arch {
+            VerbArch::X86 => &[("syswow64", "32-bit")],
+            VerbArch::X64 => &[("system32", "64-bit")],
+            VerbArch::Both => &[("syswow64", "32-bit"), ("system32", "64-bit")],
+        };
+        for (dir, label) in dirs {
+            if !windows.join(dir).join(dll).is_file() {
+                missing.push(format!("{}: {} {}", verb, label, dll));
+            }
+        }
+    }
+    missing
+}
+
+/// Wine binaries used to drive winetricks
+#[derive(Debug, Clone)]
+pub struct WineBinaries {
+    pub wine: PathBuf,
+    pub wineserver: PathBuf,
+}
+
+impl WineBinaries {
+    /// Use the wine/wineserver shipped with a Proton build
+    pub fn from_proton(proton: &SteamProton) -> Result<Self, Box<dyn Error>> {
+        proton.validate_binaries()?;
+        let (Some(wine), Some(wineserver)) = (proton.wine_binary(), proton.wineserver_binary()) else {
+            return Err(format!("Wine binaries not found in Proton '{}'", proton.name).into());
+        };
+        Ok(Self { wine, wineserver })
+    }
+
+    /// Use the runner configured for a Bottles bottle
+    pub fn from_bottle(bottle_path: &Path) -> Result<Self, Box<dyn Error>> {
+        let (wine, wineserver) = crate::game_finder::find_bottle_wine(bottle_path)
+            .ok_or_else(|| format!("Could not find the Wine runner for bottle {:?}", bottle_path))?;
+        Ok(Self { wine, wineserver })
+    }
+}
 
 /// Run winetricks to install dependencies
 ///
@@ -54,48 +177,29 @@ pub fn run_winetricks(
     if verbs.is_empty() {
         return Ok(());
     }
+    let wine = WineBinaries::from_proton(proton)?;
+    run_winetricks_with(prefix_path, &wine, verbs, log_callback)
+}
 
-    // Ensure winetricks is available (downloads to ~/.config/nak/bin/)
-    let winetricks_path = ensure_winetricks()?;
-
-    // Ensure cabextract is available (required by winetricks for cab extraction)
-    ensure_cabextract()?;
-
-    let Some(wine_bin) = proton.wine_binary() else {
-        return Err("Wine binary not found in Proton".into());
-    };
-
-    let Some(wineserver_bin) = proton.wineserver_binary() else {
-        return Err("Wineserver binary not found in Proton".into());
-    };
-
-    // Set up cache directory
-    let cache_dir = AppConfig::get_default_cache_dir();
-    std::fs::create_dir_all(&cache_dir)?;
+/// Run winetricks against any Wine build (Bottles runners, system Wine, etc.)
+///
+/// Same as `run_winetricks` but takes the wine/wineserver paths directly,
+/// so prefixes that aren't managed by Proton can be used.
+pub fn run_winetricks_with(
+    prefix_path: &Path,
+    wine: &WineBinaries,
+    verbs: &[&str],
+    log_callback: impl Fn(String),
+) -> Result<(), Box<dyn Error>> {
+    if verbs.is_empty() {
+        return Ok(());
+    }
 
     let verbs_str = verbs.join(" ");
     log_callback(format!("Installing dependencies via winetricks: {}", verbs_str));
     log_install(&format!("Running winetricks with verbs: {}", verbs_str));
 
-    // Build PATH with NaK bin directory so winetricks can find cabextract
-    let nak_bin = tools::get_nak_bin_path();
-    let current_path = std::env::var("PATH").unwrap_or_default();
-    let new_path = format!("{}:{}", nak_bin.display(), current_path);
-
-    // Run winetricks directly - ~/.config/nak/bin/ is accessible from
-    // both native and Flatpak environments.
-    // Use build_command so env vars are forwarded via --env= flags in Flatpak.
-    let envs: Vec<(&str, String)> = vec![
-        ("PATH", new_path),
-        ("WINE", wine_bin.display().to_string()),
-        ("WINESERVER", wineserver_bin.display().to_string()),
-        ("WINEPREFIX", prefix_path.display().to_string()),
-        ("WINETRICKS_CACHE", cache_dir.display().to_string()),
-    ];
-    let status = runtime_wrap::build_command(&winetricks_path, &envs)
-        .arg("-q") // Quiet mode
-        .args(verbs)
-        .status()?;
+    let status = winetricks_command(prefix_path, wine)?.args(verbs).status()?;
 
     if !status.success() {
         let err_msg = format!("Winetricks failed with exit code: {:?}", status.code());
@@ -119,6 +223,19 @@ pub fn install_standard_deps(
     run_winetricks(prefix_path, proton, STANDARD_VERBS, log_callback)
 }
 
+/// Install all standard dependencies into a Bottles bottle
+///
+/// Uses the bottle's own runner (from bottle.yml) so the prefix stays
+/// consistent with what Bottles launches it with.
+pub fn install_standard_deps_to_bottle(
+    bottle_path: &Path,
+    log_callback: impl Fn(String),
+) -> Result<(), Box<dyn Error>> {
+    let wine = WineBinaries::from_bottle(bottle_path)?;
+    log_install(&format!("Installing dependencies into bottle {:?} using {:?}", bottle_path, wine.wine));
+    run_winetricks_with(bottle_path, &wine, STANDARD_VERBS, log_callback)
+}
+
 /// Run winetricks with cancellation support.
 ///
 /// Like `run_winetricks` but uses spawn + poll so the child process
@@ -129,75 +246,375 @@ pub fn run_winetricks_cancellable(
     verbs: &[&str],
     log_callback: impl Fn(String),
     cancel_flag: &Arc<AtomicBool>,
+    pause_flag: &AtomicBool,
 ) -> Result<(), Box<dyn Error>> {
     if verbs.is_empty() {
         return Ok(());
     }
+    let wine = WineBinaries::from_proton(proton)?;
+    run_winetricks_with_cancellable(prefix_path, &wine, verbs, log_callback, cancel_flag, pause_flag)
+}
 
-    let winetricks_path = ensure_winetricks()?;
-    ensure_cabextract()?;
-
-    let Some(wine_bin) = proton.wine_binary() else {
-        return Err("Wine binary not found in Proton".into());
-    };
-
-    let Some(wineserver_bin) = proton.wineserver_binary() else {
-        return Err("Wineserver binary not found in Proton".into());
-    };
-
-    let cache_dir = AppConfig::get_default_cache_dir();
-    std::fs::create_dir_all(&cache_dir)?;
+/// Run winetricks against any Wine build with cancellation (and pause) support
+pub fn run_winetricks_with_cancellable(
+    prefix_path: &Path,
+    wine: &WineBinaries,
+    verbs: &[&str],
+    log_callback: impl Fn(String),
+    cancel_flag: &Arc<AtomicBool>,
+    pause_flag: &AtomicBool,
+) -> Result<(), Box<dyn Error>> {
+    if verbs.is_empty() {
+        return Ok(());
+    }
 
     let verbs_str = verbs.join(" ");
     log_callback(format!("Installing dependencies via winetricks: {}", verbs_str));
     log_install(&format!("Running winetricks with verbs: {}", verbs_str));
 
-    let nak_bin = tools::get_nak_bin_path();
-    let current_path = std::env::var("PATH").unwrap_or_default();
-    let new_path = format!("{}:{}", nak_bin.display(), current_path);
+    let mut command = winetricks_command(prefix_path, wine)?;
+    command.args(verbs);
+    let status = wait_or_cancel(command, cancel_flag, pause_flag, &log_callback)?;
+    if !status.success() {
+        let err_msg = format!("Winetricks failed with exit code: {:?}", status.code());
+        log_error(&err_msg);
+        return Err(err_msg.into());
+    }
+    log_install("Winetricks completed successfully");
+    Ok(())
+}
+
+/// Winetricks command for a prefix, with `-q` and the env it needs (verbs not added)
+///
+/// Downloads winetricks/cabextract into ~/.config/nak/bin/ if missing, which is
+/// reachable from both native and Flatpak environments. Uses build_command so
+/// env vars are forwarded via --env= flags in Flatpak.
+fn winetricks_command(prefix_path: &Path, wine: &WineBinaries) -> Result<Command, Box<dyn Error>> {
+    let winetricks_path = ensure_winetricks()?;
+    // Required by winetricks for cab extraction
+    ensure_cabextract()?;
+
+    let cache_dir = AppConfig::load().get_cache_dir();
+    std::fs::create_dir_all(&cache_dir)?;
 
-    // Use build_command so env vars are forwarded via --env= flags in Flatpak.
-    let envs: Vec<(&str, String)> = vec![
+    // NaK bin directory first so winetricks can find cabextract
+    let new_path = winetricks_search_path(&tools::get_nak_bin_path(), &host_path());
+
+    let mut envs: Vec<(&str, String)> = vec![
         ("PATH", new_path),
-        ("WINE", wine_bin.display().to_string()),
-        ("WINESERVER", wineserver_bin.display().to_string()),
+        ("WINE", wine.wine.display().to_string()),
+        ("WINESERVER", wine.wineserver.display().to_string()),
         ("WINEPREFIX", prefix_path.display().to_string()),
         ("WINETRICKS_CACHE", cache_dir.display().to_string()),
     ];
-    let mut child = runtime_wrap::build_command(&winetricks_path, &envs)
-        .arg("-q")
-        .args(verbs)
-        .spawn()?;
+    // A prefix winetricks creates must be win64 so both halves of dual-arch verbs
+    // install; an existing prefix keeps its architecture
+    if prefix_arch(prefix_path).is_none() {
+        envs.push(("WINEARCH", "win64".to_string()));
+    }
+    // Nothing may open a window nobody can answer: no crash dialog from
+    // winedbg, no menu entries or file associations from winemenubuilder
+    envs.push(("WINEDLLOVERRIDES", "winedbg.exe=d;winemenubuilder.exe=d".to_string()));
+    // winetricks downloads with wget/curl, which read the proxy from the environment
+    envs.extend(crate::net::proxy_env());
+    let mut command = runtime_wrap::build_command(&winetricks_path, &envs);
+    command.arg("-q");
+    Ok(command)
+}
 
-    loop {
-        match child.try_wait()? {
-            Some(status) => {
-                if !status.success() {
-                    let err_msg = format!("Winetricks failed with exit code: {:?}", status.code());
-                    log_error(&err_msg);
-                    return Err(err_msg.into());
-                }
-                log_install("Winetricks completed successfully");
-                return Ok(());
-            }
-            None => {
-                if cancel_flag.load(Ordering::Relaxed) {
-                    let _ = child.kill();
-                    let _ = child.wait();
-                    return Err("Cancelled".into());
+/// Used when the host's PATH can't be read (and kept when it lacks them)
+const DEFAULT_HOST_PATH: &str = "/usr/local/bin:/usr/bin:/bin";
+
+/// PATH of the system winetricks runs on
+///
+/// In Flatpak, winetricks runs on the host through `flatpak-spawn --host`,
+/// which doesn't inherit the sandbox's PATH (`/app/bin` doesn't exist on the
+/// host), so the host is asked for its own.
+fn host_path() -> String {
+    if runtime_wrap::is_flatpak() {
+        return match runtime_wrap::command_for("printenv").arg("PATH").output() {
+            Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            _ => DEFAULT_HOST_PATH.to_string(),
+        };
+    }
+    std::env::var("PATH").unwrap_or_default()
+}
+
+/// PATH for winetricks: NaK's bin directory (cabextract on SteamOS), then `host_path`
+///
+/// Sandbox-only `/app` entries are dropped, and the standard system folders
+/// are added when missing, so an empty or Flatpak PATH still finds wget and 7z.
+fn winetricks_search_path(nak_bin: &Path, host_path: &str) -> String {
+    let nak_bin = nak_bin.display().to_string();
+    let mut entries = vec![nak_bin.as_str()];
+    for entry in host_path.split(':').chain(DEFAULT_HOST_PATH.split(':')) {
+        if !entry.is_empty() && !entry.starts_with("/app/") && entry != "/app" && !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+    entries.join(":")
+}
+
+/// Output silence after which a winetricks run is reported as stuck
+///
+/// .NET installers can work quietly for a few minutes; a dialog waiting
+/// for a click never prints anything again.
+const STALL_WARNING_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// When a child last printed, and whether its current silence was reported
+struct StallWatch {
+    last_output: Arc<Mutex<Instant>>,
+    warned: bool,
+}
+
+impl StallWatch {
+    fn new() -> Self {
+        Self { last_output: Arc::new(Mutex::new(Instant::now())), warned: false }
+    }
+
+    /// Count from `now` again (output arrived, or the task was paused)
+    fn reset(&mut self, now: Instant) {
+        *self.last_output.lock() = now;
+        self.warned = false;
+    }
+
+    /// True once per silence, when nothing was printed for `limit`
+    fn check(&mut self, now: Instant, limit: Duration) -> bool {
+        let silent_for = now.saturating_duration_since(*self.last_output.lock());
+        if silent_for < limit {
+            self.warned = false;
+            return false;
+        }
+        !std::mem::replace(&mut self.warned, true)
+    }
+
+    /// Pass a child's output through to stderr, noting when it last printed
+    fn watch(&self, stream: impl Read + Send + 'static) {
+        let last_output = Arc::clone(&self.last_output);
+        std::thread::spawn(move || {
+            let mut stream = stream;
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = stream.read(&mut buf) {
+                if n == 0 {
+                    break;
                 }
-                std::thread::sleep(std::time::Duration::from_millis(250));
+                *last_output.lock() = Instant::now();
+                let _ = std::io::stderr().write_all(&buf[..n]);
             }
+        });
+    }
+}
+
+/// Run a command, killing it if `cancel_flag` is set and stopping it while `pause_flag` is
+///
+/// Warns through `log_callback` when the command prints nothing for
+/// `STALL_WARNING_AFTER`, which usually means a Wine dialog is waiting
+/// for input. Cancelling the task kills it.
+fn wait_or_cancel(
+    mut command: Command,
+    cancel_flag: &Arc<AtomicBool>,
+    pause_flag: &AtomicBool,
+    log_callback: &dyn Fn(String),
+) -> Result<ExitStatus, Box<dyn Error>> {
+    let mut child = command.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let mut stall = StallWatch::new();
+    if let Some(stdout) = child.stdout.take() {
+        stall.watch(stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        stall.watch(stderr);
+    }
+    let mut pause = crate::tasks::ChildPause::new(&child);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
         }
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        let paused = pause_flag.load(Ordering::Relaxed) && !cancelled;
+        pause.sync(paused);
+        if paused {
+            stall.reset(Instant::now());
+        } else if stall.check(Instant::now(), STALL_WARNING_AFTER) {
+            let msg = format!(
+                "winetricks appears stuck, likely waiting on a dialog (no output for {} minutes). \
+                 Answer the Wine window if one is open, or cancel to kill it.",
+                STALL_WARNING_AFTER.as_secs() / 60
+            );
+            log_warning(&msg);
+            log_callback(msg);
+        }
+        if cancelled {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("Cancelled".into());
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
     }
 }
 
+/// Whether a failed verb should fail the whole install
+///
+/// The mod managers don't start without the .NET and Visual C++ runtimes.
+/// Everything else (d3dx, xact, d3dcompiler) only matters to some tools.
+pub fn is_critical_verb(verb: &str) -> bool {
+    verb.starts_with("dotnet") || verb.starts_with("vcrun")
+}
+
+/// Per-verb results of `run_winetricks_per_verb`, in the order run
+#[derive(Debug, Clone, Default)]
+pub struct VerbResults {
+    pub results: Vec<(String, Result<(), String>)>,
+}
+
+impl VerbResults {
+    /// Verbs that failed
+    pub fn failed(&self) -> Vec<&str> {
+        self.results.iter().filter(|(_, r)| r.is_err()).map(|(v, _)| v.as_str()).collect()
+    }
+
+    /// Failed verbs that should fail the install (see `is_critical_verb`)
+    pub fn critical_failures(&self) -> Vec<&str> {
+        self.failed().into_iter().filter(|v| is_critical_verb(v)).collect()
+    }
+}
+
+/// Run winetricks once per verb, carrying on past failures
+///
+/// One `winetricks -q a b c` run stops at the first failing verb and the
+/// rest are never attempted. Here each verb gets its own run and its own
+/// result, so a broken d3dx download doesn't cost the .NET runtimes and the
+/// log names the verb that failed. Only setup problems and cancellation
+/// return `Err`; callers decide what to do with failed verbs. `verbs` may
+/// carry architecture suffixes (see `expand_verb_spec`).
+pub fn run_winetricks_per_verb(
+    prefix_path: &Path,
+    wine: &WineBinaries,
+    verbs: &[&str],
+    log_callback: impl Fn(String),
+    cancel_flag: &Arc<AtomicBool>,
+    pause_flag: &AtomicBool,
+) -> Result<VerbResults, Box<dyn Error>> {
+    let verbs = expand_verb_specs(verbs);
+    let mut report = VerbResults::default();
+    for (i, verb) in verbs.iter().enumerate() {
+        log_callback(format!("Installing {} ({} of {})...", verb, i + 1, verbs.len()));
+        log_install(&format!("Running winetricks verb: {}", verb));
+
+        let mut command = winetricks_command(prefix_path, wine)?;
+        command.arg(verb);
+        let result = match wait_or_cancel(command, cancel_flag, pause_flag, &log_callback)? {
+            status if status.success() => Ok(()),
+            status => {
+                let msg = format!("exit code {:?}", status.code());
+                log_error(&format!("Winetricks verb {} failed with {}", verb, msg));
+                log_callback(format!("{} failed ({}), continuing", verb, msg));
+                Err(msg)
+            }
+        };
+        report.results.push((verb.to_string(), result));
+    }
+    log_install(&format!(
+        "Winetricks finished: {} of {} verbs installed",
+        verbs.len() - report.failed().len(),
+        verbs.len()
+    ));
+    Ok(report)
+}
+
 /// Install standard deps with cancellation support
 pub fn install_standard_deps_cancellable(
     prefix_path: &Path,
     proton: &SteamProton,
     log_callback: impl Fn(String),
     cancel_flag: &Arc<AtomicBool>,
+    pause_flag: &AtomicBool,
 ) -> Result<(), Box<dyn Error>> {
-    run_winetricks_cancellable(prefix_path, proton, STANDARD_VERBS, log_callback, cancel_flag)
+    run_winetricks_cancellable(prefix_path, proton, STANDARD_VERBS, log_callback, cancel_flag, pause_flag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verb_results() {
+        let report = VerbResults {
+            results: vec![
+                ("vcrun2022".to_string(), Ok(())),
+                ("dotnet8".to_string(), Err("exit code Some(1)".to_string())),
+                ("d3dx9".to_string(), Err("exit code Some(1)".to_string())),
+                ("xact".to_string(), Ok(())),
+            ],
+        };
+        assert_eq!(report.failed(), vec!["dotnet8", "d3dx9"]);
+        assert_eq!(report.critical_failures(), vec!["dotnet8"]);
+        assert!(is_critical_verb("dotnetdesktop6"));
+        assert!(!is_critical_verb("d3dcompiler_47"));
+    }
+
+    #[test]
+    fn test_winetricks_search_path() {
+        // SteamOS: cabextract only exists in NaK's bin directory
+        let nak_bin = Path::new("/home/deck/.config/nak/bin");
+        assert_eq!(
+            winetricks_search_path(nak_bin, "/home/deck/.local/bin:/usr/bin:/bin"),
+            "/home/deck/.config/nak/bin:/home/deck/.local/bin:/usr/bin:/bin:/usr/local/bin"
+        );
+        // The sandbox's PATH leaks through when the host can't be asked
+        assert_eq!(
+            winetricks_search_path(nak_bin, "/app/bin:/usr/bin:/home/deck/.config/nak/bin"),
+            "/home/deck/.config/nak/bin:/usr/bin:/usr/local/bin:/bin"
+        );
+        assert_eq!(winetricks_search_path(nak_bin, ""), "/home/deck/.config/nak/bin:/usr/local/bin:/usr/bin:/bin");
+    }
+
+    #[test]
+    fn test_stall_watch() {
+        let start = Instant::now();
+        let limit = Duration::from_secs(600);
+        let mut stall = StallWatch::new();
+        stall.reset(start);
+        assert!(!stall.check(start + Duration::from_secs(599), limit));
+        // Reported once per silence, not on every poll
+        assert!(stall.check(start + limit, limit));
+        assert!(!stall.check(start + Duration::from_secs(900), limit));
+
+        // Output (or a pause) starts the count again
+        stall.reset(start + Duration::from_secs(900));
+        assert!(!stall.check(start + Duration::from_secs(1000), limit));
+        assert!(stall.check(start + Duration::from_secs(1500), limit));
+    }
+
+    #[test]
+    fn test_expand_verb_specs() {
+        assert_eq!(expand_verb_spec("xact"), vec!["xact", "xact_x64"]);
+        assert_eq!(expand_verb_spec("xact:32"), vec!["xact"]);
+        assert_eq!(expand_verb_spec("xact:64"), vec!["xact_x64"]);
+        assert_eq!(expand_verb_spec("d3dx9:32"), vec!["d3dx9"]);
+        assert_eq!(expand_verb_specs(STANDARD_VERBS), STANDARD_VERBS);
+        assert_eq!(expand_verb_specs(&["xact:64", "xact", "vcrun2022"]), vec!["xact_x64", "xact", "vcrun2022"]);
+    }
+
+    #[test]
+    fn test_missing_verb_dlls() {
+        let prefix = std::env::temp_dir().join(format!("nak_verb_dlls_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&prefix);
+        let windows = prefix.join("drive_c/windows");
+        std::fs::create_dir_all(windows.join("system32")).unwrap();
+        std::fs::create_dir_all(windows.join("syswow64")).unwrap();
+        let reg = "WINE REGISTRY Version 2\n;; All keys relative to \\Machine\n\n#arch=win64\n";
+        std::fs::write(prefix.join("system.reg"), reg).unwrap();
+        assert_eq!(prefix_arch(&prefix).as_deref(), Some("win64"));
+
+        // 64-bit XACT and d3dx9 only landed in system32
+        for dll in ["xactengine3_7.dll", "x3daudio1_7.dll", "d3dx9_43.dll"] {
+            std::fs::write(windows.join("system32").join(dll), "").unwrap();
+        }
+        assert_eq!(
+            missing_verb_dlls(&prefix, &["xact", "xact_x64", "d3dx9", "vcrun2022"]),
+            vec!["d3dx9: 32-bit d3dx9_43.dll", "xact: 32-bit xactengine3_7.dll", "xact: 32-bit x3daudio1_7.dll"]
+        );
+
+        let _ = std::fs::remove_dir_all(&prefix);
+    }
 }
@@ -3,15 +3,20 @@
 //! Downloads all dependency files upfront using winetricks' cache mechanism.
 //! This allows offline installations and faster setup.
 
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
 
 use crate::config::AppConfig;
-use crate::installers::fetch_latest_mo2_release;
+use crate::installers::{
+    cached_mo2_version, dotnet_installers_cached, estimate_full_deps_size, fetch_latest_mo2_release,
+    get_available_disk_space, precache_dotnet_installers, precache_mo2, TaskContext, CUSTOM_DOTNET_DEPS,
+};
+use crate::logging::log_install;
+use crate::utils::move_dir;
+use crate::steam::find_steam_protons;
 
-use super::{ensure_winetricks, STANDARD_VERBS};
+use super::{ensure_winetricks, run_winetricks_with_cancellable, WineBinaries, STANDARD_VERBS};
 
 /// Information about a file to pre-cache
 #[derive(Debug, Clone)]
@@ -22,9 +27,72 @@ pub struct CacheFile {
     pub size_estimate_mb: u64,
 }
 
-/// Get the cache directory for winetricks
+/// Get the cache directory for winetricks (`cache_location` in config.json, or ~/.cache/nak/)
 pub fn get_winetricks_cache_dir() -> PathBuf {
-    AppConfig::get_default_cache_dir()
+    AppConfig::load().get_cache_dir()
+}
+
+/// Entries of ~/.cache/nak/ that stay there whatever the cache location is
+const NON_DEP_CACHE_ENTRIES: &[&str] = &["mo2", "tmp"];
+
+/// Check a folder can hold the dependency cache (creating it if needed)
+///
+/// It must be an absolute path, writable, and have room for a full
+/// dependency download.
+pub fn validate_cache_dir(dir: &Path) -> Result<(), String> {
+    if !dir.is_absolute() {
+        return Err(format!("{} is not an absolute path", dir.display()));
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+
+    let probe = dir.join(".nak_write_test");
+    fs::write(&probe, b"").map_err(|e| format!("{} is not writable: {}", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+
+    let required_gb = estimate_full_deps_size() as f64 / 1024.0;
+    if let Some(available_gb) = get_available_disk_space(dir) {
+        if available_gb < required_gb {
+            return Err(format!(
+                "{} has {:.1} GB free, but the dependency cache needs about {:.1} GB",
+                dir.display(),
+                available_gb,
+                required_gb
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Move the dependency cache from `from` into `to`
+///
+/// Entries already present in `to` are left where they are, as are the MO2
+/// cache and tmp folder. Reports `(bytes_copied, total_bytes)` per entry
+/// while copying across filesystems. Returns how many entries were moved.
+pub fn migrate_cache(from: &Path, to: &Path, progress: impl Fn(u64, u64)) -> Result<usize, Box<dyn Error>> {
+    if from == to || !from.is_dir() {
+        return Ok(0);
+    }
+    fs::create_dir_all(to)?;
+
+    let mut moved = 0;
+    for entry in fs::read_dir(from)?.flatten() {
+        let src = entry.path();
+        let name = entry.file_name();
+        let dst = to.join(&name);
+        if NON_DEP_CACHE_ENTRIES.iter().any(|n| name == *n) || dst.exists() || to.starts_with(&src) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            move_dir(&src, &dst, &progress)?;
+        } else if fs::rename(&src, &dst).is_err() {
+            fs::copy(&src, &dst)?;
+            fs::remove_file(&src)?;
+        }
+        moved += 1;
+    }
+
+    log_install(&format!("Moved {} cache entries from {} to {}", moved, from.display(), to.display()));
+    Ok(moved)
 }
 
 /// Get mod manager installer files to pre-cache
@@ -56,174 +124,185 @@ pub fn get_mod_manager_files() -> Vec<CacheFile> {
     files
 }
 
+/// Marker in the cache listing the winetricks verbs whose downloads are cached
+const PRECACHED_VERBS_FILE: &str = "nak_precached_verbs.txt";
+
 /// Status of the dependency cache
 #[derive(Debug, Clone)]
 pub struct CacheStatus {
     pub winetricks_available: bool,
     pub verbs_to_cache: Vec<String>,
-    pub mod_manager_files: Vec<CacheFile>,
+    /// Standard verbs whose downloads are already in the winetricks cache
+    pub verbs_cached: Vec<String>,
+    /// MO2 release in the cache (None = nothing cached)
+    pub mo2_version: Option<String>,
+    pub dotnet_installers_cached: bool,
     pub total_estimated_mb: u64,
 }
 
-/// Get cache status
+/// Get cache status (local checks only, no network)
 pub fn get_cache_status() -> CacheStatus {
-    let winetricks_available = super::get_winetricks_path().exists();
-    let mod_manager_files = get_mod_manager_files();
-
-    // Estimate total size for winetricks deps
-    // These are rough estimates based on typical download sizes
-    let verb_sizes: u64 = STANDARD_VERBS.iter().map(|v| estimate_verb_size(v)).sum();
-    let mm_sizes: u64 = mod_manager_files.iter().map(|f| f.size_estimate_mb).sum();
+    let verbs_cached = cached_verbs();
 
     CacheStatus {
-        winetricks_available,
+        winetricks_available: super::get_winetricks_path().exists(),
         verbs_to_cache: STANDARD_VERBS.iter().map(|s| s.to_string()).collect(),
-        mod_manager_files,
-        total_estimated_mb: verb_sizes + mm_sizes,
-    }
-}
-
-/// Estimate download size for a winetricks verb
-fn estimate_verb_size(verb: &str) -> u64 {
-    match verb {
-        "vcrun2022" => 40,        // ~25MB x86 + ~15MB x64
-        "dotnet6" | "dotnet7" | "dotnet8" => 60, // ~30MB each arch
-        "dotnetdesktop6" => 120,  // Larger desktop runtime
-        "d3dx9" => 95,            // DirectX June 2010 redist
-        "d3dcompiler_47" => 6,    // Small DLL downloads
-        "d3dcompiler_43" => 2,
-        "d3dx11_43" => 2,
-        "xact" | "xact_x64" => 10, // Part of DirectX redist (shared)
-        _ => 10, // Default estimate
+        verbs_cached,
+        mo2_version: cached_mo2_version().map(|(version, _)| version),
+        dotnet_installers_cached: dotnet_installers_cached(),
+        // Rough figure for a cold cache, plus ~40MB for the MO2 archive
+        total_estimated_mb: estimate_full_deps_size() + 40,
     }
 }
 
 impl CacheStatus {
+    /// Everything an MO2 install downloads is cached, so it can run offline
     pub fn is_complete(&self) -> bool {
         self.winetricks_available
+            && self.mo2_version.is_some()
+            && self.dotnet_installers_cached
+            && self.verbs_to_cache.iter().all(|v| self.verbs_cached.contains(v))
     }
 
     pub fn total_files(&self) -> usize {
-        self.verbs_to_cache.len() + self.mod_manager_files.len()
+        // winetricks + verbs + MO2 + .NET installers
+        1 + self.verbs_to_cache.len() + 1 + CUSTOM_DOTNET_DEPS.len()
     }
 
     pub fn cached_count(&self) -> usize {
-        if self.winetricks_available { 1 } else { 0 }
-    }
-}
-
-/// Pre-cache all dependencies using winetricks
-///
-/// This downloads:
-/// 1. Winetricks script itself
-/// 2. All dependency files via winetricks --cache-only (if supported)
-/// 3. MO2 installer
-pub fn precache_all<F, S>(
-    progress_callback: F,
-    status_callback: S,
-    cancel_flag: Arc<AtomicBool>,
-) -> Result<usize, Box<dyn std::error::Error + Send + Sync>>
-where
-    F: Fn(u64, u64) + Send + 'static,
-    S: Fn(&str) + Send + 'static,
-{
-    let mut downloaded = 0;
-
-    // Step 1: Ensure winetricks is downloaded
-    status_callback("Downloading winetricks...");
-    progress_callback(0, 100);
-
-    if cancel_flag.load(Ordering::Relaxed) {
-        return Err("Cancelled by user".into());
+        usize::from(self.winetricks_available)
+            + self.verbs_to_cache.iter().filter(|v| self.verbs_cached.contains(v)).count()
+            + usize::from(self.mo2_version.is_some())
+            + if self.dotnet_installers_cached { CUSTOM_DOTNET_DEPS.len() } else { 0 }
     }
 
-    match ensure_winetricks() {
-        Ok(_) => {
-            downloaded += 1;
-            status_callback("Winetricks downloaded");
-        }
-        Err(e) => {
-            return Err(format!("Failed to download winetricks: {}", e).into());
+    /// One-line summary for the Settings page
+    pub fn summary(&self) -> String {
+        if self.is_complete() {
+            return format!(
+                "Ready for offline installs (MO2 {})",
+                self.mo2_version.as_deref().unwrap_or("?")
+            );
         }
+        format!(
+            "{} of {} downloads cached (about {} MB for a full pre-download)",
+            self.cached_count(),
+            self.total_files(),
+            self.total_estimated_mb
+        )
     }
+}
 
-    progress_callback(10, 100);
-
-    // Step 2: Ensure cabextract is available (winetricks needs it)
-    status_callback("Checking cabextract...");
-    if let Err(e) = super::tools::ensure_cabextract() {
-        status_callback(&format!("Warning: cabextract not available: {}", e));
-    }
-
-    progress_callback(15, 100);
+/// Standard verbs recorded as cached by an earlier pre-download
+pub fn cached_verbs() -> Vec<String> {
+    fs::read_to_string(get_winetricks_cache_dir().join(PRECACHED_VERBS_FILE))
+        .map(|content| content.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
 
-    // Step 3: Download MO2 installer
-    if cancel_flag.load(Ordering::Relaxed) {
-        return Err("Cancelled by user".into());
+/// Fill the winetricks cache for the standard verbs
+///
+/// winetricks has no download-only mode, so the verbs run against a scratch
+/// prefix in NaK's tmp folder, which is deleted afterwards. Returns false if
+/// every verb was cached already.
+fn precache_winetricks_verbs(ctx: &TaskContext) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut cached = cached_verbs();
+    let missing: Vec<&str> = STANDARD_VERBS.iter().copied().filter(|v| !cached.iter().any(|c| c == v)).collect();
+    if missing.is_empty() {
+        return Ok(false);
     }
 
-    let mod_manager_files = get_mod_manager_files();
-    let cache_dir = AppConfig::get_default_cache_dir();
-    fs::create_dir_all(&cache_dir)?;
+    let proton = find_steam_protons()
+        .into_iter()
+        .find(|p| p.wine_binary().is_some())
+        .ok_or("No Proton with a Wine binary is installed; install one in Steam first")?;
+    let wine = WineBinaries::from_proton(&proton)?;
+
+    let scratch = AppConfig::get_tmp_path().join("precache_prefix");
+    let _ = fs::remove_dir_all(&scratch);
+    fs::create_dir_all(&scratch)?;
+
+    ctx.set_status(format!("Downloading {} Windows components (this takes a while)...", missing.len()));
+    let log_ctx = ctx.clone();
+    let result = run_winetricks_with_cancellable(
+        &scratch,
+        &wine,
+        &missing,
+        move |msg| log_ctx.log(msg),
+        &ctx.cancel_flag,
+        &ctx.pause_flag,
+    );
+    let _ = fs::remove_dir_all(&scratch);
+    result?;
+
+    cached.extend(missing.iter().map(|v| v.to_string()));
+    crate::config::write_atomic(
+        &get_winetricks_cache_dir().join(PRECACHED_VERBS_FILE),
+        (cached.join("\n") + "\n").as_bytes(),
+    )?;
+    Ok(true)
+}
 
-    for (i, file) in mod_manager_files.iter().enumerate() {
-        if cancel_flag.load(Ordering::Relaxed) {
+/// Pre-download everything an MO2 install needs, without touching any instance
+///
+/// This downloads:
+/// 1. Winetricks (and cabextract)
+/// 2. The latest MO2 release, extracted into the MO2 cache
+/// 3. The custom .NET installers
+/// 4. Every standard winetricks verb's files into the winetricks cache
+///
+/// Installs started afterwards use the cached files and work offline.
+/// Proton isn't included: NaK uses the Protons Steam already has installed.
+/// Returns how many items were downloaded (0 = the cache was already warm).
+pub fn precache_all(ctx: &TaskContext) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut downloaded = 0;
+    let cancelled = || -> Result<(), Box<dyn std::error::Error>> {
+        if ctx.is_cancelled() {
             return Err("Cancelled by user".into());
         }
+        Ok(())
+    };
 
-        let dest_path = cache_dir.join(&file.filename);
-        if dest_path.exists() {
-            status_callback(&format!("{} already cached", file.description));
-            continue;
-        }
-
-        status_callback(&format!("Downloading {}...", file.description));
-
-        match download_file(&file.url, &dest_path) {
-            Ok(_) => {
-                downloaded += 1;
-                status_callback(&format!("{} downloaded", file.description));
-            }
-            Err(e) => {
-                status_callback(&format!("Warning: Failed to download {}: {}", file.description, e));
-            }
-        }
-
-        let progress = 15 + ((i + 1) * 35 / mod_manager_files.len().max(1));
-        progress_callback(progress as u64, 100);
+    // Step 1: winetricks itself, and cabextract (winetricks needs it)
+    ctx.set_status("Downloading winetricks...".to_string());
+    ctx.set_progress(0.0);
+    ensure_winetricks().map_err(|e| format!("Failed to download winetricks: {}", e))?;
+    if let Err(e) = super::tools::ensure_cabextract() {
+        ctx.log(format!("Warning: cabextract not available: {}", e));
     }
-
-    // Step 4: Pre-cache winetricks dependencies
-    // Note: winetricks doesn't have a --cache-only mode, but we can
-    // point WINETRICKS_CACHE to our cache dir for future installs
-    status_callback("Winetricks cache directory configured");
-    progress_callback(100, 100);
+    ctx.set_progress(0.05);
+    cancelled()?;
+
+    // Step 2: MO2
+    let had_mo2 = cached_mo2_version();
+    let version = precache_mo2(ctx)?;
+    if had_mo2.map(|(cached, _)| cached).as_ref() != Some(&version) {
+        downloaded += 1;
+    }
+    ctx.log(format!("MO2 {} cached", version));
+    ctx.set_progress(0.20);
+    cancelled()?;
+
+    // Step 3: .NET installers
+    downloaded += precache_dotnet_installers(ctx)?;
+    ctx.set_progress(0.40);
+    cancelled()?;
+
+    // Step 4: winetricks verbs
+    if precache_winetricks_verbs(ctx)? {
+        downloaded += 1;
+    }
+    ctx.set_progress(1.0);
 
     if downloaded == 0 {
-        status_callback("All files already cached!");
+        ctx.set_status("All files already cached!".to_string());
     } else {
-        status_callback(&format!("Pre-cached {} files successfully!", downloaded));
+        ctx.set_status("Dependencies pre-downloaded; installs can now run offline".to_string());
     }
 
     Ok(downloaded)
 }
 
-/// Download a file
-fn download_file(url: &str, dest: &PathBuf) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let response = ureq::get(url)
-        .set("User-Agent", "NaK-Rust-Agent")
-        .call()
-        .map_err(|e| format!("HTTP request failed: {}", e))?;
-
-    let mut file = fs::File::create(dest)
-        .map_err(|e| format!("Failed to create file: {}", e))?;
-
-    std::io::copy(&mut response.into_reader(), &mut file)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-
-    Ok(())
-}
-
 /// Clear the cache
 pub fn clear_cache() -> Result<(), std::io::Error> {
     let cache_dir = get_winetricks_cache_dir();
@@ -238,6 +317,33 @@ pub fn clear_cache() -> Result<(), std::io::Error> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_migrate_cache() {
+        let dir = std::env::temp_dir().join(format!("nak_cache_migrate_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let from = dir.join("old");
+        let to = dir.join("new");
+        fs::create_dir_all(from.join("dotnet48")).unwrap();
+        fs::create_dir_all(from.join("mo2")).unwrap();
+        fs::create_dir_all(to.join("vcrun2022")).unwrap();
+        fs::create_dir_all(from.join("vcrun2022")).unwrap();
+        fs::write(from.join("dotnet48/ndp48.exe"), "exe").unwrap();
+        fs::write(from.join("vcrun2022/stale.exe"), "old").unwrap();
+        fs::write(from.join(PRECACHED_VERBS_FILE), "dotnet48\n").unwrap();
+
+        assert_eq!(migrate_cache(&from, &to, |_, _| {}).unwrap(), 2);
+        assert_eq!(fs::read_to_string(to.join("dotnet48/ndp48.exe")).unwrap(), "exe");
+        assert!(to.join(PRECACHED_VERBS_FILE).is_file());
+        // Non-dependency and already-present entries stay behind
+        assert!(from.join("mo2").is_dir());
+        assert!(from.join("vcrun2022/stale.exe").is_file());
+        assert!(!to.join("vcrun2022/stale.exe").exists());
+
+        assert!(validate_cache_dir(Path::new("relative/cache")).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_cache_status() {
         let status = get_cache_status();
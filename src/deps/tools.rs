@@ -15,6 +15,7 @@ const MAX_DOWNLOAD_BYTES: u64 = 10 * 1024 * 1024;
 
 use crate::config::AppConfig;
 use crate::logging::{log_error, log_info, log_warning};
+use crate::runtime_wrap;
 
 // ============================================================================
 // NaK Bin Directory (~/.config/nak/bin/)
@@ -68,7 +69,7 @@ pub fn ensure_winetricks() -> Result<PathBuf, Box<dyn Error>> {
     // Check for updates by downloading fresh copy
     log_info("Checking for winetricks updates...");
 
-    match ureq::get(WINETRICKS_URL).call() {
+    match crate::net::get(WINETRICKS_URL).call() {
         Ok(response) => {
             // Reject suspiciously large responses before reading body
             if let Some(len) = response.header("Content-Length")
@@ -131,18 +132,46 @@ pub fn ensure_winetricks() -> Result<PathBuf, Box<dyn Error>> {
 const CABEXTRACT_URL: &str =
     "https://github.com/SulfurNitride/NaK/releases/download/Cabextract/cabextract-linux-x86_64.zip";
 
+/// Check whether a command is on the PATH of the system winetricks runs on
+///
+/// Winetricks runs through `runtime_wrap`, so in Flatpak this asks the host:
+/// a cabextract inside the sandbox is no use to the host's winetricks.
+fn host_has_command(cmd: &str) -> bool {
+    runtime_wrap::command_for("which")
+        .arg(cmd)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Make sure a downloaded tool can run: executable bit set, not on a `noexec` mount
+fn check_tool_runnable(path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+    if crate::game_finder::is_noexec_mount(path) {
+        return Err(format!(
+            "{} is on a drive mounted noexec, so it can't be run. Install cabextract with your package manager, \
+             or make sure {} is on a drive that allows running programs.",
+            path.display(),
+            get_nak_bin_path().display()
+        )
+        .into());
+    }
+    let mut perms = fs::metadata(path)?.permissions();
+    if perms.mode() & 0o111 != 0o111 {
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)?;
+    }
+    Ok(())
+}
+
 /// Ensures cabextract is available (either system or downloaded).
 ///
 /// Winetricks requires cabextract for DirectX cab extraction.
-/// On SteamOS and other immutable systems, we download a static binary.
+/// On SteamOS and other immutable systems, `/usr` is read-only and no
+/// cabextract is installed, so we download a static binary into
+/// ~/.config/nak/bin/, which winetricks gets at the front of its PATH.
 pub fn ensure_cabextract() -> Result<PathBuf, Box<dyn Error>> {
     // First check if system has cabextract
-    if Command::new("which")
-        .arg("cabextract")
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-    {
+    if host_has_command("cabextract") {
         return Ok(PathBuf::from("cabextract"));
     }
 
@@ -151,6 +180,8 @@ pub fn ensure_cabextract() -> Result<PathBuf, Box<dyn Error>> {
     let cabextract_path = bin_dir.join("cabextract");
 
     if cabextract_path.exists() {
+        // A copy restored from a backup or another drive may have lost its executable bit
+        check_tool_runnable(&cabextract_path)?;
         return Ok(cabextract_path);
     }
 
@@ -158,7 +189,7 @@ pub fn ensure_cabextract() -> Result<PathBuf, Box<dyn Error>> {
     log_warning("System cabextract not found, downloading...");
     fs::create_dir_all(&bin_dir)?;
 
-    let response = ureq::get(CABEXTRACT_URL).call().map_err(|e| {
+    let response = crate::net::get(CABEXTRACT_URL).call().map_err(|e| {
         format!(
             "Failed to download cabextract: {}. Please install cabextract manually.",
             e
@@ -180,11 +211,14 @@ pub fn ensure_cabextract() -> Result<PathBuf, Box<dyn Error>> {
 
     if !status.success() {
         // Fallback: extract using the zip crate (safe, no shell interpolation)
+        #[cfg(feature = "full")]
         if let Ok(file) = fs::File::open(&zip_path) {
             if let Ok(mut archive) = zip::ZipArchive::new(file) {
                 let _ = archive.extract(&bin_dir);
             }
         }
+        #[cfg(not(feature = "full"))]
+        log_warning("unzip failed to extract cabextract (installer builds have no zip fallback)");
     }
 
     // Clean up zip file
@@ -192,9 +226,7 @@ pub fn ensure_cabextract() -> Result<PathBuf, Box<dyn Error>> {
 
     // Make executable
     if cabextract_path.exists() {
-        let mut perms = fs::metadata(&cabextract_path)?.permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(&cabextract_path, perms)?;
+        check_tool_runnable(&cabextract_path)?;
         log_info(&format!("cabextract downloaded to {:?}", cabextract_path));
         Ok(cabextract_path)
     } else {
@@ -165,3 +165,41 @@ pub fn find_bottles_prefixes() -> Vec<PathBuf> {
 
     prefixes
 }
+
+/// Find the wine and wineserver binaries of the runner a bottle uses
+///
+/// Reads the `Runner:` entry from the bottle's bottle.yml and resolves it in
+/// Bottles' runners folder. `sys-*` runners map to the system Wine in PATH.
+pub fn find_bottle_wine(bottle_path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let config = fs::read_to_string(bottle_path.join("bottle.yml")).ok()?;
+    let runner = config
+        .lines()
+        .find_map(|line| line.strip_prefix("Runner:"))
+        .map(|r| r.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+        .filter(|r| !r.is_empty())?;
+
+    // Runner names come from the config file - reject anything that isn't a plain folder name
+    if runner.contains('/') || runner.contains("..") {
+        return None;
+    }
+
+    if runner.starts_with("sys-") {
+        let path = std::env::var("PATH").ok()?;
+        let find = |bin: &str| {
+            path.split(':')
+                .map(|dir| Path::new(dir).join(bin))
+                .find(|p| p.is_file())
+        };
+        return Some((find("wine")?, find("wineserver")?));
+    }
+
+    // bottles/bottles/<name> -> bottles/runners/<runner>
+    let runner_dir = bottle_path.parent()?.parent()?.join("runners").join(&runner);
+    let wine = runner_dir.join("bin/wine");
+    let wineserver = runner_dir.join("bin/wineserver");
+    if wine.exists() && wineserver.exists() {
+        Some((wine, wineserver))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,127 @@
+//! Filesystem type detection
+//!
+//! Wine prefixes on NTFS/exFAT (common for Steam libraries shared with Windows)
+//! can corrupt because those filesystems lack proper Unix permissions,
+//! case-sensitivity and symlink semantics. These helpers look up the
+//! filesystem type backing a path from /proc/self/mounts.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Filesystem types that are known to be unsafe for Wine prefixes
+const WINDOWS_FILESYSTEMS: &[&str] = &["ntfs", "ntfs3", "ntfs-3g", "fuseblk", "exfat", "vfat", "msdos"];
+
+/// Decode the octal escapes (`\040` for space etc.) used in /proc/self/mounts
+fn unescape_mount_path(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() {
+            let code = std::str::from_utf8(&bytes[i + 1..i + 4])
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+            if let Some(code) = code {
+                out.push(code);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Filesystem type and mount options of the mount holding `path`, from /proc/self/mounts text
+///
+/// Uses the longest matching mount point, so nested mounts resolve correctly.
+fn mount_entry<'a>(mounts: &'a str, path: &Path) -> Option<(&'a str, &'a str)> {
+    let mut best: Option<(PathBuf, &str, &str)> = None;
+    for line in mounts.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(_device), Some(mount_point), Some(fs_type), Some(options)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+
+        let mount_point = PathBuf::from(unescape_mount_path(mount_point));
+        if !path.starts_with(&mount_point) {
+            continue;
+        }
+
+        let is_longer = best
+            .as_ref()
+            .map(|(current, _, _)| mount_point.as_os_str().len() >= current.as_os_str().len())
+            .unwrap_or(true);
+        if is_longer {
+            best = Some((mount_point, fs_type, options));
+        }
+    }
+
+    best.map(|(_, fs_type, options)| (fs_type, options))
+}
+
+/// Get the filesystem type (e.g. "ext4", "btrfs", "ntfs3") backing a path
+pub fn filesystem_type(path: &Path) -> Option<String> {
+    // Resolve symlinks (e.g. compatdata symlinked onto another drive)
+    let path = path.canonicalize().ok()?;
+    let mounts = fs::read_to_string("/proc/self/mounts").ok()?;
+    mount_entry(&mounts, &path).map(|(fs_type, _)| fs_type.to_string())
+}
+
+/// Check whether the mount holding `path` forbids running programs (`noexec`)
+///
+/// Some SD cards and shared drives are mounted that way; a binary NaK
+/// downloads there can't be run. Unknown paths count as executable.
+pub fn is_noexec_mount(path: &Path) -> bool {
+    let Ok(path) = path.canonicalize() else {
+        return false;
+    };
+    let mounts = fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+    mount_entry(&mounts, &path).is_some_and(|(_, options)| options.split(',').any(|o| o == "noexec"))
+}
+
+/// Check if a filesystem type is a Windows filesystem (NTFS, exFAT, FAT)
+pub fn is_windows_filesystem(fs_type: &str) -> bool {
+    WINDOWS_FILESYSTEMS.contains(&fs_type)
+}
+
+/// If a path lives on a Windows filesystem, return its filesystem type
+pub fn windows_filesystem_for(path: &Path) -> Option<String> {
+    filesystem_type(path).filter(|fs_type| is_windows_filesystem(fs_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unescape_mount_path() {
+        assert_eq!(unescape_mount_path("/mnt/My\\040Games"), "/mnt/My Games");
+        assert_eq!(unescape_mount_path("/mnt/plain"), "/mnt/plain");
+    }
+
+    #[test]
+    fn test_mount_entry() {
+        // A Steam Deck: read-only root, writable /home, SD card mounted noexec
+        let mounts = "\
+/dev/nvme0n1p4 / btrfs ro,relatime 0 0
+/dev/nvme0n1p8 /home ext4 rw,relatime 0 0
+/dev/mmcblk0p1 /run/media/deck/SD\\040Card ext4 rw,nosuid,nodev,noexec,relatime 0 0
+";
+        assert_eq!(mount_entry(mounts, Path::new("/usr/bin")), Some(("btrfs", "ro,relatime")));
+        assert_eq!(mount_entry(mounts, Path::new("/home/deck/.config/nak/bin")).unwrap().0, "ext4");
+        let (_, options) = mount_entry(mounts, Path::new("/run/media/deck/SD Card/nak")).unwrap();
+        assert!(options.split(',').any(|o| o == "noexec"));
+    }
+
+    #[test]
+    fn test_is_windows_filesystem() {
+        assert!(is_windows_filesystem("ntfs3"));
+        assert!(is_windows_filesystem("exfat"));
+        assert!(!is_windows_filesystem("ext4"));
+        assert!(!is_windows_filesystem("btrfs"));
+    }
+}
@@ -7,6 +7,7 @@
 //! - My Games folder name (Documents/My Games/*)
 //! - AppData/Local folder name
 //! - Registry path for game detection
+//! - Proton it works best with (advisory, shown in the install wizard)
 
 /// Configuration for a known game
 #[derive(Debug, Clone)]
@@ -31,6 +32,47 @@ pub struct KnownGame {
     pub registry_value: &'static str,
     /// Expected folder name in steamapps/common/
     pub steam_folder: &'static str,
+    /// Proton this game works best with (None = no preference)
+    pub proton_recommendation: Option<ProtonRecommendation>,
+}
+
+/// Proton build a game works best with
+///
+/// Advisory only: the install wizard mentions it, nothing enforces it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtonRecommendation {
+    /// GE-Proton rather than Valve's Proton
+    pub ge: bool,
+    /// Oldest major version that works well
+    pub min_major: u64,
+}
+
+impl ProtonRecommendation {
+    /// Whether the Proton named `name` (e.g. "GE-Proton10-3") satisfies it
+    ///
+    /// Proton Experimental counts as the newest Valve Proton.
+    pub fn is_met_by(&self, name: &str) -> bool {
+        if self.ge != name.starts_with("GE-Proton") {
+            return false;
+        }
+        let major = name
+            .split(|c: char| !c.is_ascii_digit())
+            .find_map(|part| part.parse::<u64>().ok());
+        match major {
+            Some(major) => major >= self.min_major,
+            None => !self.ge && name.contains("Experimental"),
+        }
+    }
+}
+
+impl std::fmt::Display for ProtonRecommendation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ge {
+            write!(f, "GE-Proton{}+", self.min_major)
+        } else {
+            write!(f, "Proton {}+", self.min_major)
+        }
+    }
 }
 
 /// All known games that NaK supports
@@ -47,6 +89,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\SureAI\Enderal",
         registry_value: "Install_Path",
         steam_folder: "Enderal",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Enderal Special Edition",
@@ -59,6 +102,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\SureAI\Enderal SE",
         registry_value: "installed path",
         steam_folder: "Enderal Special Edition",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Fallout 3",
@@ -71,6 +115,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Fallout3",
         registry_value: "Installed Path",
         steam_folder: "Fallout 3",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Fallout 4",
@@ -83,6 +128,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Fallout4",
         registry_value: "Installed Path",
         steam_folder: "Fallout 4",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Fallout 4 VR",
@@ -95,6 +141,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Fallout 4 VR",
         registry_value: "Installed Path",
         steam_folder: "Fallout 4 VR",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Fallout New Vegas",
@@ -107,6 +154,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\FalloutNV",
         registry_value: "Installed Path",
         steam_folder: "Fallout New Vegas",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Morrowind",
@@ -119,6 +167,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Morrowind",
         registry_value: "Installed Path",
         steam_folder: "Morrowind",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Oblivion",
@@ -131,6 +180,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Oblivion",
         registry_value: "Installed Path",
         steam_folder: "Oblivion",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Skyrim",
@@ -143,6 +193,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Skyrim",
         registry_value: "Installed Path",
         steam_folder: "Skyrim",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Skyrim Special Edition",
@@ -155,6 +206,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Skyrim Special Edition",
         registry_value: "Installed Path",
         steam_folder: "Skyrim Special Edition",
+        proton_recommendation: Some(ProtonRecommendation { ge: true, min_major: 10 }),
     },
     KnownGame {
         name: "Skyrim VR",
@@ -167,6 +219,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Skyrim VR",
         registry_value: "Installed Path",
         steam_folder: "Skyrim VR",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Starfield",
@@ -179,6 +232,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Bethesda Softworks\Starfield",
         registry_value: "Installed Path",
         steam_folder: "Starfield",
+        proton_recommendation: Some(ProtonRecommendation { ge: true, min_major: 9 }),
     },
     // CD Projekt RED Games
     KnownGame {
@@ -192,6 +246,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\CD Projekt Red\The Witcher 3",
         registry_value: "InstallFolder",
         steam_folder: "The Witcher 3 Wild Hunt",
+        proton_recommendation: None,
     },
     KnownGame {
         name: "Cyberpunk 2077",
@@ -204,6 +259,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\CD Projekt Red\Cyberpunk 2077",
         registry_value: "InstallFolder",
         steam_folder: "Cyberpunk 2077",
+        proton_recommendation: Some(ProtonRecommendation { ge: false, min_major: 9 }),
     },
     // Other popular moddable games
     KnownGame {
@@ -217,6 +273,7 @@ pub const KNOWN_GAMES: &[KnownGame] = &[
         registry_path: r"Software\Larian Studios\Baldur's Gate 3",
         registry_value: "InstallDir",
         steam_folder: "Baldurs Gate 3",
+        proton_recommendation: None,
     },
 ];
 
@@ -320,6 +377,13 @@ pub fn find_by_name(name: &str) -> Option<&'static KnownGame> {
         .find(|g| g.name.to_lowercase() == name_lower)
 }
 
+/// Advisory line for the install wizard, e.g. "Skyrim Special Edition works
+/// best with GE-Proton10+." (None = the game has no preference)
+pub fn recommend_proton_for_game(game: &KnownGame) -> Option<String> {
+    game.proton_recommendation
+        .map(|rec| format!("{} works best with {}.", game.name, rec))
+}
+
 /// Normalize Steam App IDs that have equivalent variants.
 fn normalize_steam_id(app_id: &str) -> &str {
     match app_id {
@@ -377,6 +441,26 @@ mod tests {
         assert_eq!(game.name, "Skyrim Special Edition");
     }
 
+    #[test]
+    fn recommend_proton_for_skyrim_se() {
+        let game = find_by_name("Skyrim Special Edition").unwrap();
+        assert_eq!(
+            recommend_proton_for_game(game).as_deref(),
+            Some("Skyrim Special Edition works best with GE-Proton10+.")
+        );
+        assert_eq!(recommend_proton_for_game(find_by_name("Oblivion").unwrap()), None);
+
+        let rec = game.proton_recommendation.unwrap();
+        assert!(rec.is_met_by("GE-Proton10-3"));
+        assert!(!rec.is_met_by("GE-Proton9-20"));
+        assert!(!rec.is_met_by("Proton 10.0"));
+
+        let valve = ProtonRecommendation { ge: false, min_major: 9 };
+        assert!(valve.is_met_by("Proton 9.0 (Beta)"));
+        assert!(valve.is_met_by("Proton Experimental"));
+        assert!(!valve.is_met_by("Proton 8.0"));
+    }
+
     #[test]
     fn find_by_title_fallout_nv() {
         let game = find_by_title("Fallout: New Vegas Ultimate Edition")
@@ -0,0 +1,224 @@
+//! Legendary detection
+//!
+//! Detects Epic games installed with the standalone Legendary CLI (the
+//! Heroic-managed copy lives under Heroic's own config and is handled in
+//! heroic.rs). Parses installed.json and config.ini for game and prefix info.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::known_games::{find_by_epic_id, find_by_title};
+use super::{Game, Launcher};
+use crate::logging::{log_info, log_warning};
+
+/// Installed game entry from installed.json (keyed by app name)
+#[derive(Debug, Deserialize)]
+struct LegendaryInstalledGame {
+    app_name: String,
+    title: Option<String>,
+    install_path: Option<String>,
+    platform: Option<String>,
+}
+
+/// Find Legendary's config folder
+///
+/// Honors `LEGENDARY_CONFIG_PATH` and `XDG_CONFIG_HOME` like Legendary does.
+fn legendary_config_dir() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("LEGENDARY_CONFIG_PATH") {
+        if !path.is_empty() {
+            return Some(PathBuf::from(path));
+        }
+    }
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var("HOME").ok().map(|h| PathBuf::from(h).join(".config")))?;
+    Some(config_home.join("legendary"))
+}
+
+/// Detect all games installed with Legendary
+pub fn detect_legendary_games() -> Vec<Game> {
+    let Some(config_dir) = legendary_config_dir() else {
+        return Vec::new();
+    };
+    let games = detect_games_in(&config_dir);
+    if !games.is_empty() {
+        log_info(&format!("Legendary: Found {} installed games", games.len()));
+    }
+    games
+}
+
+/// Detect games from a Legendary config folder
+fn detect_games_in(config_dir: &Path) -> Vec<Game> {
+    let mut games = Vec::new();
+
+    let Ok(content) = fs::read_to_string(config_dir.join("installed.json")) else {
+        return games;
+    };
+    log_info(&format!("Found Legendary installation: {}", config_dir.display()));
+
+    let installed: HashMap<String, LegendaryInstalledGame> = match serde_json::from_str(&content) {
+        Ok(installed) => installed,
+        Err(e) => {
+            log_warning(&format!("Failed to parse Legendary installed.json: {}", e));
+            return games;
+        }
+    };
+
+    let config = fs::read_to_string(config_dir.join("config.ini"))
+        .map(|c| parse_ini(&c))
+        .unwrap_or_default();
+
+    let mut installed: Vec<LegendaryInstalledGame> = installed.into_values().collect();
+    installed.sort_by(|a, b| a.app_name.cmp(&b.app_name));
+
+    for game in installed {
+        // Skip non-Windows games (we only care about Wine prefixes)
+        if !game.platform.as_deref().unwrap_or("Windows").eq_ignore_ascii_case("windows") {
+            continue;
+        }
+
+        let Some(install_path) = game.install_path.map(PathBuf::from) else {
+            continue;
+        };
+        if !install_path.exists() {
+            continue;
+        }
+
+        let title = game
+            .title
+            .or_else(|| metadata_title(config_dir, &game.app_name))
+            .unwrap_or_else(|| game.app_name.clone());
+
+        // Look up known game info: try Epic AppName first, then title match
+        let known_game = find_by_epic_id(&game.app_name).or_else(|| find_by_title(&title));
+
+        let prefix_path = game_prefix(&config, &game.app_name).filter(|p| p.exists());
+
+        games.push(Game {
+            name: title,
+            app_id: game.app_name,
+            install_path,
+            prefix_path,
+            launcher: Launcher::Legendary,
+            my_games_folder: known_game.and_then(|g| g.my_games_folder.map(String::from)),
+            appdata_local_folder: known_game.and_then(|g| g.appdata_local_folder.map(String::from)),
+            appdata_roaming_folder: known_game.and_then(|g| g.appdata_roaming_folder.map(String::from)),
+            registry_path: known_game.map(|g| g.registry_path.to_string()),
+            registry_value: known_game.map(|g| g.registry_value.to_string()),
+        });
+    }
+
+    games
+}
+
+/// Title from `metadata/<app_name>.json`, for entries installed.json doesn't name
+fn metadata_title(config_dir: &Path, app_name: &str) -> Option<String> {
+    let content = fs::read_to_string(config_dir.join("metadata").join(format!("{}.json", app_name))).ok()?;
+    let metadata: serde_json::Value = serde_json::from_str(&content).ok()?;
+    metadata
+        .get("app_title")
+        .or_else(|| metadata.get("metadata").and_then(|m| m.get("title")))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+/// Parse config.ini into section -> key -> value (section and key names lowercased)
+fn parse_ini(content: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = section.trim().to_lowercase();
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            sections
+                .entry(current.clone())
+                .or_default()
+                .insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    sections
+}
+
+/// Wine prefix Legendary launches a game with
+///
+/// Checks the game's section, then `[default]`: `wine_prefix`, and
+/// `WINEPREFIX` in the matching `.env` section.
+fn game_prefix(config: &HashMap<String, HashMap<String, String>>, app_name: &str) -> Option<PathBuf> {
+    let app = app_name.to_lowercase();
+    [app.as_str(), "default"].iter().find_map(|section| {
+        config
+            .get(*section)
+            .and_then(|s| s.get("wine_prefix"))
+            .or_else(|| config.get(&format!("{}.env", section)).and_then(|s| s.get("wineprefix")))
+            .map(String::as_str)
+            .map(expand_home)
+    })
+}
+
+/// Expand a leading `~` the way Legendary does
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_prefix_from_config() {
+        let config = parse_ini(
+            "[default]\nwine_prefix = /games/default-pfx\n\n\
+             [Fortnite]\nwine_prefix = /games/fn\n\n\
+             [Kinglet.env]\nWINEPREFIX=/games/kinglet\n",
+        );
+        assert_eq!(game_prefix(&config, "Fortnite"), Some(PathBuf::from("/games/fn")));
+        assert_eq!(game_prefix(&config, "Kinglet"), Some(PathBuf::from("/games/kinglet")));
+        assert_eq!(game_prefix(&config, "Other"), Some(PathBuf::from("/games/default-pfx")));
+        assert_eq!(game_prefix(&parse_ini(""), "Other"), None);
+    }
+
+    #[test]
+    fn test_detects_installed_windows_games() {
+        let dir = std::env::temp_dir().join(format!("nak-legendary-test-{}", std::process::id()));
+        let game_dir = dir.join("Games/Ashford");
+        fs::create_dir_all(&game_dir).unwrap();
+        let installed = serde_json::json!({
+            "Ashford": {
+                "app_name": "Ashford",
+                "title": "Ashford Saga",
+                "install_path": game_dir,
+                "platform": "Windows",
+            },
+            "MacOnly": {
+                "app_name": "MacOnly",
+                "install_path": game_dir,
+                "platform": "Mac",
+            },
+        });
+        fs::write(dir.join("installed.json"), installed.to_string()).unwrap();
+
+        let games = detect_games_in(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].name, "Ashford Saga");
+        assert_eq!(games[0].launcher, Launcher::Legendary);
+        assert_eq!(games[0].install_path, game_dir);
+    }
+}
@@ -3,6 +3,7 @@
 //! Provides unified game detection across multiple launchers:
 //! - Steam (native, Flatpak, Snap)
 //! - Heroic (GOG, Epic)
+//! - Legendary (Epic, standalone)
 //! - Bottles
 //!
 //! # Example
@@ -21,22 +22,29 @@
 #![allow(unused_imports)]
 
 mod bottles;
+pub mod filesystem;
 mod heroic;
 pub mod known_games;
+mod legendary;
 mod registry;
 mod steam;
 mod vdf;
 
 use std::path::PathBuf;
 
-pub use bottles::detect_bottles_games;
+pub use bottles::{detect_bottles_games, find_bottle_wine, find_bottles_prefixes};
+pub use filesystem::{filesystem_type, is_noexec_mount, is_windows_filesystem, windows_filesystem_for};
 pub use heroic::detect_heroic_games;
+pub use legendary::detect_legendary_games;
 pub use known_games::{
-    find_by_epic_id, find_by_gog_id, find_by_name, find_by_steam_id, find_by_title, KnownGame,
-    KNOWN_GAMES,
+    find_by_epic_id, find_by_gog_id, find_by_name, find_by_steam_id, find_by_title,
+    recommend_proton_for_game, KnownGame, ProtonRecommendation, KNOWN_GAMES,
 };
 pub use registry::{read_registry_value, wine_path_to_linux};
-pub use steam::{detect_steam_games, find_game_install_path, find_game_prefix_path, get_known_game};
+pub use steam::{
+    detect_steam_games, find_game_install_path, find_game_prefix_path, find_steam_library_folders,
+    find_windows_fs_libraries, get_known_game,
+};
 
 // ============================================================================
 // Core Types
@@ -49,6 +57,8 @@ pub enum Launcher {
     Steam { is_flatpak: bool, is_snap: bool },
     /// Heroic Games Launcher
     Heroic { store: HeroicStore },
+    /// Legendary CLI used on its own (Epic)
+    Legendary,
     /// Bottles
     Bottles,
 }
@@ -62,6 +72,7 @@ impl Launcher {
             Launcher::Steam { .. } => "Steam",
             Launcher::Heroic { store: HeroicStore::GOG } => "Heroic (GOG)",
             Launcher::Heroic { store: HeroicStore::Epic } => "Heroic (Epic)",
+            Launcher::Legendary => "Legendary (Epic)",
             Launcher::Bottles => "Bottles",
         }
     }
@@ -164,6 +175,8 @@ pub struct GameScanResult {
     pub steam_count: usize,
     /// Number of Heroic games found
     pub heroic_count: usize,
+    /// Number of standalone Legendary games found
+    pub legendary_count: usize,
     /// Number of Bottles games found
     pub bottles_count: usize,
 }
@@ -183,6 +196,7 @@ impl GameScanResult {
                     (&g.launcher, launcher_type),
                     (Launcher::Steam { .. }, "steam")
                         | (Launcher::Heroic { .. }, "heroic")
+                        | (Launcher::Legendary, "legendary")
                         | (Launcher::Bottles, "bottles")
                 )
             })
@@ -209,7 +223,7 @@ impl GameScanResult {
 
 /// Detect all installed games from all supported launchers.
 ///
-/// Games are detected in priority order: Steam → Heroic (GOG → Epic) → Bottles.
+/// Games are detected in priority order: Steam → Heroic (GOG → Epic) → Legendary → Bottles.
 /// When the same game is found from multiple launchers, the higher-priority
 /// detection is kept and duplicates are skipped. This ensures registry entries
 /// (which are shared across storefronts) prefer Steam paths, then GOG, then Epic.
@@ -219,6 +233,7 @@ pub fn detect_all_games() -> GameScanResult {
     let mut result = GameScanResult::default();
 
     // Detect in priority order: Steam first, then GOG (via Heroic), then Epic, then Bottles.
+    // Standalone Legendary installs count as Epic, after Heroic's.
     let steam_games = detect_steam_games();
     result.steam_count = steam_games.len();
     result.games.extend(steam_games);
@@ -227,6 +242,14 @@ pub fn detect_all_games() -> GameScanResult {
     result.heroic_count = heroic_games.len();
     result.games.extend(heroic_games);
 
+    // Heroic can be pointed at the standalone Legendary config; don't list those twice
+    let legendary_games: Vec<Game> = detect_legendary_games()
+        .into_iter()
+        .filter(|g| !result.games.iter().any(|h| h.install_path == g.install_path))
+        .collect();
+    result.legendary_count = legendary_games.len();
+    result.games.extend(legendary_games);
+
     let bottles_games = detect_bottles_games();
     result.bottles_count = bottles_games.len();
     result.games.extend(bottles_games);
@@ -8,8 +8,9 @@ use std::path::{Path, PathBuf};
 
 use super::known_games::{find_by_steam_id, KnownGame};
 use super::vdf::{parse_library_folders, AppManifest};
+use super::filesystem::windows_filesystem_for;
 use super::{Game, Launcher};
-use crate::logging::log_info;
+use crate::logging::{log_info, log_warning};
 
 /// All possible Steam installation paths to check
 const STEAM_PATHS: &[&str] = &[
@@ -39,6 +40,15 @@ pub fn detect_steam_games() -> Vec<Game> {
                 continue;
             }
 
+            if let Some(fs_type) = windows_filesystem_for(&library_path) {
+                log_warning(&format!(
+                    "Steam library {} is on {} - Wine prefixes there can corrupt. \
+                     Use an ext4/btrfs drive for games you mod.",
+                    library_path.display(),
+                    fs_type
+                ));
+            }
+
             // Scan for appmanifest_*.acf files
             let Ok(entries) = fs::read_dir(&steamapps) else {
                 continue;
@@ -63,6 +73,45 @@ pub fn detect_steam_games() -> Vec<Game> {
     games
 }
 
+/// Find Steam libraries that live on NTFS/exFAT/FAT filesystems
+///
+/// Returns each library path with its filesystem type.
+pub fn find_windows_fs_libraries() -> Vec<(PathBuf, String)> {
+    let Ok(home) = std::env::var("HOME") else {
+        return Vec::new();
+    };
+
+    let mut result: Vec<(PathBuf, String)> = Vec::new();
+    for steam_info in find_steam_installations(&home) {
+        for library_path in get_library_folders(&steam_info.path) {
+            if result.iter().any(|(p, _)| p == &library_path) {
+                continue;
+            }
+            if let Some(fs_type) = windows_filesystem_for(&library_path) {
+                result.push((library_path, fs_type));
+            }
+        }
+    }
+    result
+}
+
+/// Every Steam library folder of every Steam installation (native, Flatpak, Snap)
+pub fn find_steam_library_folders() -> Vec<PathBuf> {
+    let Ok(home) = std::env::var("HOME") else {
+        return Vec::new();
+    };
+
+    let mut result: Vec<PathBuf> = Vec::new();
+    for steam_info in find_steam_installations(&home) {
+        for library_path in get_library_folders(&steam_info.path) {
+            if !result.contains(&library_path) {
+                result.push(library_path);
+            }
+        }
+    }
+    result
+}
+
 /// Information about a Steam installation
 struct SteamInstallation {
     path: PathBuf,
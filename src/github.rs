@@ -1,7 +1,14 @@
 //! Shared GitHub API types
 
+use std::time::Duration;
+
 use serde::Deserialize;
 
+use crate::net::FetchError;
+
+/// Timeout for GitHub API requests
+const GITHUB_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// GitHub release metadata
 #[derive(Deserialize, Debug, Clone)]
 pub struct GithubRelease {
@@ -16,3 +23,8 @@ pub struct GithubAsset {
     pub name: String,
     pub browser_download_url: String,
 }
+
+/// Fetch release metadata from a GitHub API URL, retrying flaky responses
+pub fn fetch_release(url: &str, user_agent: &str) -> Result<GithubRelease, FetchError> {
+    crate::net::get_json(url, GITHUB_TIMEOUT, user_agent)
+}
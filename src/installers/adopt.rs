@@ -0,0 +1,232 @@
+//! Adoption of prefixes set up outside NaK (e.g. with protontricks)
+//!
+//! A non-Steam shortcut whose compatdata prefix was prepared by hand keeps
+//! its AppID and prefix: NaK finds the mod manager the shortcut runs,
+//! registers the instance, creates NaK Tools and applies its registry
+//! baseline without touching DLL overrides the prefix already sets.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::common::{apply_instance_launch_toggles, create_nak_tools_folder, find_manager_exe, setup_mo2_global_instance};
+use super::dll_overrides::{apply_reg, baseline_reg_keeping_overrides};
+use super::legacy::{find_mod_manager_install_dir, is_already_managed, is_win32_prefix, manager_type_for, ManagerSearch};
+use super::{baseline_dll_overrides, compare_dll_overrides, read_user_reg_overrides, DllOverrideChange, TaskContext};
+use crate::config::{AppConfig, ManagedPrefixes, ManagerType};
+use crate::logging::{log_install, log_warning};
+use crate::steam::{self, ShortcutsVdf, SteamProton};
+
+/// A compatdata prefix that could be handed over to NaK
+#[derive(Debug, Clone)]
+pub struct AdoptCandidate {
+    pub app_id: u32,
+    /// Shortcut name, used as the instance name
+    pub name: String,
+    /// The `compatdata/<appid>/pfx` folder
+    pub prefix_dir: PathBuf,
+    /// Mod manager folder, if one was found
+    pub install_dir: Option<PathBuf>,
+    /// How the prefix's DLL overrides differ from NaK's baseline
+    pub override_changes: Vec<DllOverrideChange>,
+    /// Why the prefix can't be adopted (None = can be adopted)
+    pub problem: Option<String>,
+}
+
+impl AdoptCandidate {
+    /// What adopting will change, for the confirmation preview
+    pub fn preview(&self) -> String {
+        let mut lines = vec![format!("{} (AppID {})", self.name, self.app_id)];
+        lines.push(format!("Prefix: {}", self.prefix_dir.display()));
+        match &self.install_dir {
+            Some(dir) => lines.push(format!("Mod manager: {}", dir.display())),
+            None => lines.push("Mod manager: not found".to_string()),
+        }
+        if let Some(problem) = &self.problem {
+            lines.push(format!("Can't adopt: {}", problem));
+            return lines.join("\n");
+        }
+
+        let (missing, kept): (Vec<&DllOverrideChange>, Vec<&DllOverrideChange>) = self
+            .override_changes
+            .iter()
+            .partition(|c| matches!(c, DllOverrideChange::Removed { .. }));
+        lines.push(String::new());
+        lines.push("NaK will register the instance, create NaK Tools and apply its registry settings.".to_string());
+        if !missing.is_empty() {
+            lines.push("DLL overrides added from NaK's baseline:".to_string());
+            lines.extend(missing.iter().map(|c| format!("  {}", c)));
+        }
+        if !kept.is_empty() {
+            lines.push("DLL overrides kept as they are:".to_string());
+            lines.extend(kept.iter().map(|c| format!("  {}", c)));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Install folder and exe of a shortcut, from its quoted StartDir and Exe
+fn shortcut_dirs(start_dir: &str, exe: &str) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let start_dir = start_dir.trim().trim_matches('"');
+    if !start_dir.is_empty() {
+        dirs.push(PathBuf::from(start_dir));
+    }
+    if let Some(parent) = Path::new(exe.trim().trim_matches('"')).parent() {
+        if !parent.as_os_str().is_empty() && !dirs.iter().any(|d| d == parent) {
+            dirs.push(parent.to_path_buf());
+        }
+    }
+    dirs
+}
+
+/// Find the mod manager of a shortcut: where the shortcut points, else inside the prefix
+fn find_install_dir(shortcut_dirs: &[PathBuf], prefix_dir: &Path) -> Option<PathBuf> {
+    if let Some(dir) = shortcut_dirs.iter().find(|d| d.is_dir() && manager_type_for(d).is_some()) {
+        return Some(dir.clone());
+    }
+    let search = ManagerSearch::for_prefix(prefix_dir, &AppConfig::load());
+    find_mod_manager_install_dir(prefix_dir, &search).ok()
+}
+
+/// Inspect a non-Steam shortcut's prefix for adoption
+pub fn inspect_adoptable_prefix(app_id: u32) -> Result<AdoptCandidate, String> {
+    if !steam::is_shortcut_app_id(app_id) {
+        return Err(format!(
+            "AppID {} is a Steam game. Add the mod manager as a non-Steam game first, then adopt that shortcut's prefix.",
+            app_id
+        ));
+    }
+    let vdf = ShortcutsVdf::load().map_err(|e| format!("Failed to read shortcuts.vdf: {}", e))?;
+    let shortcut = vdf
+        .shortcuts
+        .iter()
+        .find(|s| s.appid == app_id)
+        .ok_or_else(|| format!("No Steam shortcut with AppID {}", app_id))?;
+    let prefix_dir = steam::find_compatdata_prefixes(app_id)
+        .into_iter()
+        .find(|pfx| pfx.join("drive_c").is_dir())
+        .ok_or_else(|| format!("{} has no initialized prefix yet. Launch it once from Steam.", shortcut.app_name))?;
+
+    let install_dir = find_install_dir(&shortcut_dirs(&shortcut.start_dir, &shortcut.exe), &prefix_dir);
+    let problem = if ManagedPrefixes::load().get_by_app_id(app_id).is_some() {
+        Some("NaK already manages this prefix".to_string())
+    } else if is_win32_prefix(&prefix_dir) {
+        Some("32-bit prefix, which Proton can't run".to_string())
+    } else {
+        match &install_dir {
+            None => Some("no mod manager found where the shortcut points or inside the prefix".to_string()),
+            Some(dir) if is_already_managed(dir) => Some(format!("{} is already set up in NaK", dir.display())),
+            Some(_) => None,
+        }
+    };
+
+    Ok(AdoptCandidate {
+        app_id,
+        name: shortcut.app_name.clone(),
+        override_changes: compare_dll_overrides(&baseline_dll_overrides(), &read_user_reg_overrides(&prefix_dir)),
+        prefix_dir,
+        install_dir,
+        problem,
+    })
+}
+
+/// Shortcuts with an initialized prefix that NaK doesn't manage yet: (AppID, name)
+pub fn find_adoptable_prefixes() -> Vec<(u32, String)> {
+    let Ok(vdf) = ShortcutsVdf::load() else {
+        return Vec::new();
+    };
+    let managed = ManagedPrefixes::load();
+    vdf.shortcuts
+        .iter()
+        .filter(|s| managed.get_by_app_id(s.appid).is_none())
+        .filter(|s| steam::find_compatdata_prefixes(s.appid).iter().any(|pfx| pfx.join("drive_c").is_dir()))
+        .map(|s| (s.appid, s.app_name.clone()))
+        .collect()
+}
+
+/// Whether any prefix user already has `AppData/Local/ModOrganizer` (a Global Instance or its link)
+fn has_mo2_appdata(prefix_dir: &Path) -> bool {
+    let Ok(users) = fs::read_dir(prefix_dir.join("drive_c/users")) else {
+        return false;
+    };
+    users
+        .flatten()
+        .any(|user| fs::symlink_metadata(user.path().join("AppData/Local/ModOrganizer")).is_ok())
+}
+
+/// Hand a previewed prefix over to NaK
+///
+/// Keeps the shortcut, AppID and prefix. An existing MO2 Global Instance in
+/// the prefix is left alone; the link to the install folder's Global
+/// Instance is only created when there is none.
+pub fn adopt_prefix(candidate: &AdoptCandidate, proton: &SteamProton, ctx: &TaskContext) -> Result<(), Box<dyn Error>> {
+    if let Some(problem) = &candidate.problem {
+        return Err(problem.clone().into());
+    }
+    let install_dir = candidate.install_dir.as_deref().ok_or("Mod manager folder is unknown")?;
+    let manager_type = manager_type_for(install_dir).ok_or("No mod manager executable found")?;
+    find_manager_exe(&manager_type, install_dir).ok_or("No mod manager executable found")?;
+
+    ctx.set_status(format!("Adopting {}...", candidate.name));
+    log_install(&format!("Adopting prefix of {} (AppID {})", candidate.name, candidate.app_id));
+
+    ctx.set_status("Applying NaK registry settings...".to_string());
+    let current: BTreeMap<String, String> = read_user_reg_overrides(&candidate.prefix_dir);
+    apply_reg(&candidate.prefix_dir, proton, "adopt_settings.reg", &baseline_reg_keeping_overrides(&current))?;
+
+    if manager_type == ManagerType::MO2 && !has_mo2_appdata(&candidate.prefix_dir) {
+        setup_mo2_global_instance(&candidate.prefix_dir, install_dir);
+    }
+
+    ctx.set_status("Creating NaK Tools folder...".to_string());
+    create_nak_tools_folder(&manager_type, install_dir, &candidate.prefix_dir, candidate.app_id, &proton.path)?;
+    let proton_name = proton.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    if let Err(e) = super::write_applied_settings(&candidate.prefix_dir, install_dir, &proton_name) {
+        log_warning(&format!("Failed to write applied settings summary: {}", e));
+    }
+
+    // compatdata/<appid>/pfx -> the library holding steamapps
+    let library = candidate.prefix_dir.ancestors().nth(4).unwrap_or(&candidate.prefix_dir);
+    ManagedPrefixes::register(
+        candidate.app_id,
+        &candidate.name,
+        candidate.prefix_dir.to_str().unwrap_or(""),
+        install_dir.to_str().unwrap_or(""),
+        manager_type,
+        library.to_str().unwrap_or(""),
+        Some(&proton.config_name),
+    );
+    apply_instance_launch_toggles(candidate.app_id);
+
+    log_install(&format!("Adopted {} (AppID {})", candidate.name, candidate.app_id));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcut_dirs_and_preview() {
+        let dirs = shortcut_dirs("\"/games/MO2/\"", "\"/games/MO2/ModOrganizer.exe\"");
+        assert_eq!(dirs, [PathBuf::from("/games/MO2")]);
+        assert_eq!(shortcut_dirs("\"/games\"", "/games/MO2/ModOrganizer.exe"), [PathBuf::from("/games"), PathBuf::from("/games/MO2")]);
+
+        let candidate = AdoptCandidate {
+            app_id: 3000000001,
+            name: "MO2".to_string(),
+            prefix_dir: PathBuf::from("/steam/steamapps/compatdata/3000000001/pfx"),
+            install_dir: Some(PathBuf::from("/games/MO2")),
+            override_changes: vec![
+                DllOverrideChange::Removed { dll: "winmm".into(), baseline: "native,builtin".into() },
+                DllOverrideChange::Added { dll: "d3d11".into(), mode: "native".into() },
+            ],
+            problem: None,
+        };
+        let preview = candidate.preview();
+        assert!(preview.contains("added from NaK's baseline:\n  - winmm"));
+        assert!(preview.contains("kept as they are:\n  + d3d11=native"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Record of what NaK applied to a prefix
+//!
+//! Writes `NaK Tools/applied_settings.txt` with the DLL overrides, Windows
+//! version, DPI, .NET installs and Proton actually found in the prefix, so
+//! bug reports and working-vs-broken comparisons don't rely on memory.
+//! Values come from the prefix's registry files, which wineserver writes
+//! when it exits.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::dotnet_check::{scan_dotnet_install, DotnetReport};
+
+/// File name inside the install's NaK Tools folder
+pub const APPLIED_SETTINGS_FILE: &str = "applied_settings.txt";
+
+/// Settings read back from a prefix
+#[derive(Debug, Clone, Default)]
+pub struct AppliedSettings {
+    /// Proton the prefix is set up with
+    pub proton: String,
+    /// Prefix-wide Windows version (winecfg code, e.g. "win11")
+    pub windows_version: Option<String>,
+    /// Windows build reported to applications
+    pub windows_build: Option<String>,
+    /// DPI from `Control Panel\Desktop\LogPixels` (None = Wine default, 96)
+    pub dpi: Option<u32>,
+    /// DLL overrides, lowercase DLL name -> mode
+    pub dll_overrides: BTreeMap<String, String>,
+    /// Per-application Windows versions, exe name -> winecfg code
+    pub app_versions: BTreeMap<String, String>,
+    /// .NET runtimes and SDKs in `Program Files/dotnet`
+    pub dotnet: DotnetReport,
+    /// winetricks verbs recorded in the prefix's winetricks.log
+    pub winetricks_verbs: Vec<String>,
+}
+
+impl AppliedSettings {
+    /// Plain-text summary, as written to applied_settings.txt
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("Proton: {}\n", self.proton));
+        out.push_str(&format!(
+            "Windows version: {}{}\n",
+            self.windows_version.as_deref().unwrap_or("Wine default"),
+            self.windows_build.as_deref().map(|b| format!(" (build {})", b)).unwrap_or_default()
+        ));
+        out.push_str(&format!(
+            "DPI: {}\n",
+            self.dpi.map(|d| format!("{} ({}%)", d, d * 100 / 96)).unwrap_or_else(|| "96 (default)".to_string())
+        ));
+        out.push_str(&format!(".NET: {}\n", self.dotnet.summary()));
+        out.push_str(&format!(
+            "winetricks: {}\n",
+            if self.winetricks_verbs.is_empty() { "none recorded".to_string() } else { self.winetricks_verbs.join(", ") }
+        ));
+
+        out.push_str(&format!("\nDLL overrides ({}):\n", self.dll_overrides.len()));
+        for (dll, mode) in &self.dll_overrides {
+            out.push_str(&format!("  {}={}\n", dll, mode));
+        }
+
+        if !self.app_versions.is_empty() {
+            out.push_str("\nPer-application Windows versions:\n");
+            for (exe, version) in &self.app_versions {
+                out.push_str(&format!("  {}: {}\n", exe, version));
+            }
+        }
+        out
+    }
+}
+
+/// Parse one section of a Wine `.reg` file into lowercase value name -> value
+///
+/// `section` is the key as written in the file, e.g. `Software\\Wine\\DllOverrides`
+/// (backslashes doubled). String values are unescaped; dwords are returned as
+/// `dword:xxxxxxxx`.
+fn parse_reg_section(content: &str, section: &str) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    let header = format!("[{}]", section).to_lowercase();
+    let mut in_section = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            // "[Software\\Wine\\DllOverrides] 1712345678"
+            in_section = line.to_lowercase().starts_with(&header);
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let Some(rest) = line.strip_prefix('"') else {
+            continue;
+        };
+        let Some((name, value)) = rest.split_once("\"=") else {
+            continue;
+        };
+        let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            Some(s) => s.replace("\\\\", "\\").replace("\\\"", "\""),
+            None => value.to_string(),
+        };
+        values.insert(name.to_lowercase(), value);
+    }
+
+    values
+}
+
+/// Sections of user.reg holding per-application settings, e.g.
+/// `Software\\Wine\\AppDefaults\\SSEEdit.exe`, with their Windows version
+fn parse_app_versions(content: &str) -> BTreeMap<String, String> {
+    const PREFIX: &str = "[software\\\\wine\\\\appdefaults\\\\";
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if !line.get(..PREFIX.len())?.eq_ignore_ascii_case(PREFIX) {
+                return None;
+            }
+            let rest = &line[PREFIX.len()..];
+            let exe = &rest[..rest.find(']')?];
+            // Only the app's own key, not subkeys like ...\\DllOverrides
+            (!exe.contains('\\')).then(|| exe.to_string())
+        })
+        .filter_map(|exe| {
+            let section = format!("Software\\\\Wine\\\\AppDefaults\\\\{}", exe);
+            parse_reg_section(content, &section).remove("version").map(|v| (exe, v))
+        })
+        .collect()
+}
+
+/// Verbs from winetricks.log (one per line, in install order, no duplicates)
+fn parse_winetricks_log(content: &str) -> Vec<String> {
+    let mut verbs: Vec<String> = Vec::new();
+    for verb in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if !verbs.iter().any(|v| v == verb) {
+            verbs.push(verb.to_string());
+        }
+    }
+    verbs
+}
+
+/// Read the applied settings back from a prefix's registry files
+pub fn read_applied_settings(prefix_root: &Path, proton: &str) -> AppliedSettings {
+    let user_reg = fs::read_to_string(prefix_root.join("user.reg")).unwrap_or_default();
+    let system_reg = fs::read_to_string(prefix_root.join("system.reg")).unwrap_or_default();
+
+    let wine = parse_reg_section(&user_reg, "Software\\\\Wine");
+    let current_version = parse_reg_section(&system_reg, "Software\\\\Microsoft\\\\Windows NT\\\\CurrentVersion");
+    let desktop = parse_reg_section(&user_reg, "Control Panel\\\\Desktop");
+
+    AppliedSettings {
+        proton: proton.to_string(),
+        windows_version: wine.get("version").cloned(),
+        windows_build: current_version.get("currentbuild").cloned(),
+        dpi: desktop
+            .get("logpixels")
+            .and_then(|v| v.strip_prefix("dword:"))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok()),
+        dll_overrides: parse_reg_section(&user_reg, "Software\\\\Wine\\\\DllOverrides"),
+        app_versions: parse_app_versions(&user_reg),
+        dotnet: scan_dotnet_install(prefix_root),
+        winetricks_verbs: fs::read_to_string(prefix_root.join("winetricks.log"))
+            .map(|log| parse_winetricks_log(&log))
+            .unwrap_or_default(),
+    }
+}
+
+/// Write `NaK Tools/applied_settings.txt` for an install
+///
+/// Returns the summary that was written.
+pub fn write_applied_settings(prefix_root: &Path, install_dir: &Path, proton: &str) -> Result<String, std::io::Error> {
+    let settings = read_applied_settings(prefix_root, proton);
+    let summary = settings.render();
+
+    let tools_dir = install_dir.join("NaK Tools");
+    fs::create_dir_all(&tools_dir)?;
+    let content = format!(
+        "# Settings NaK found in this prefix ({})\n# Prefix: {}\n\n{}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        prefix_root.display(),
+        summary
+    );
+    fs::write(applied_settings_path(install_dir), content)?;
+    Ok(summary)
+}
+
+/// Where an install's applied settings summary lives
+pub fn applied_settings_path(install_dir: &Path) -> PathBuf {
+    install_dir.join("NaK Tools").join(APPLIED_SETTINGS_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_REG: &str = r#"WINE REGISTRY Version 2
+;; All keys relative to \\User\\S-1-5-21-0-0-0-1000
+
+[Control Panel\\Desktop] 1712345678
+#time=1da8c0a1b2c3d4e
+"LogPixels"=dword:00000090
+
+[Software\\Wine] 1712345678
+"Version"="win11"
+
+[Software\\Wine\\AppDefaults\\SSEEdit.exe] 1712345678
+"Version"="winxp"
+
+[Software\\Wine\\AppDefaults\\SSEEdit.exe\\DllOverrides] 1712345678
+"d3d9"="builtin"
+
+[Software\\Wine\\DllOverrides] 1712345678
+"*d3d11"="builtin"
+"dwrite"="native,builtin"
+"#;
+
+    #[test]
+    fn test_parse_user_reg() {
+        let overrides = parse_reg_section(USER_REG, "Software\\\\Wine\\\\DllOverrides");
+        assert_eq!(overrides.get("dwrite").map(String::as_str), Some("native,builtin"));
+        assert_eq!(overrides.get("*d3d11").map(String::as_str), Some("builtin"));
+        assert_eq!(overrides.len(), 2);
+
+        let wine = parse_reg_section(USER_REG, "Software\\\\Wine");
+        assert_eq!(wine.get("version").map(String::as_str), Some("win11"));
+
+        let desktop = parse_reg_section(USER_REG, "Control Panel\\\\Desktop");
+        assert_eq!(desktop.get("logpixels").map(String::as_str), Some("dword:00000090"));
+
+        let apps = parse_app_versions(USER_REG);
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps.get("SSEEdit.exe").map(String::as_str), Some("winxp"));
+
+        assert_eq!(parse_winetricks_log("vcrun2022\nxact\nvcrun2022\n"), vec!["vcrun2022", "xact"]);
+    }
+}
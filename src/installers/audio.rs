@@ -0,0 +1,170 @@
+//! XAudio/XACT check and repair
+//!
+//! Skyrim and the other Bethesda games play voices through XACT/XAudio2.
+//! winetricks' `xact` and `xact_x64` verbs put Microsoft's DLLs in
+//! syswow64/system32 and override them to native. With one architecture
+//! missing or the overrides gone, music still plays but NPCs are silent.
+//! The game's own Steam prefix usually works, so its audio overrides are
+//! the reference when repairing a NaK prefix.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use super::dll_overrides::{apply_dll_overrides, read_user_reg_overrides};
+use crate::deps::run_winetricks;
+use crate::logging::log_install;
+use crate::steam::SteamProton;
+
+/// DLLs checked in both system32 and syswow64, each needing a native override
+pub const XAUDIO_DLLS: &[&str] = &["xaudio2_7", "xactengine3_7", "x3daudio1_7"];
+
+/// What's wrong with a prefix's XAudio setup
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioCheck {
+    /// Missing DLL files, e.g. "syswow64/xaudio2_7.dll"
+    pub missing_files: Vec<String>,
+    /// DLLs without a native override
+    pub missing_overrides: Vec<String>,
+}
+
+impl AudioCheck {
+    pub fn is_ok(&self) -> bool {
+        self.missing_files.is_empty() && self.missing_overrides.is_empty()
+    }
+
+    /// One-line description of the problems (empty if none)
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.missing_files.is_empty() {
+            parts.push(format!("missing {}", self.missing_files.join(", ")));
+        }
+        if !self.missing_overrides.is_empty() {
+            parts.push(format!("no native override for {}", self.missing_overrides.join(", ")));
+        }
+        parts.join("; ")
+    }
+}
+
+/// Whether a DLL is part of XAudio2/XACT (any version)
+fn is_audio_dll(dll: &str) -> bool {
+    ["xaudio2_", "xactengine", "x3daudio", "xapofx"].iter().any(|p| dll.starts_with(p))
+}
+
+/// Check a prefix for the XAudio DLLs (both architectures) and their overrides
+pub fn check_xaudio(prefix_root: &Path) -> AudioCheck {
+    let windows = prefix_root.join("drive_c/windows");
+    let overrides = read_user_reg_overrides(prefix_root);
+    let mut check = AudioCheck::default();
+
+    for dll in XAUDIO_DLLS {
+        for dir in ["system32", "syswow64"] {
+            if !windows.join(dir).join(format!("{}.dll", dll)).is_file() {
+                check.missing_files.push(format!("{}/{}.dll", dir, dll));
+            }
+        }
+        if !overrides.get(*dll).is_some_and(|mode| mode.starts_with("native")) {
+            check.missing_overrides.push(dll.to_string());
+        }
+    }
+    check
+}
+
+/// Audio overrides set in a game's own prefix that the NaK prefix lacks or sets differently
+pub fn game_audio_override_diff(nak_prefix: &Path, game_prefix: &Path) -> BTreeMap<String, String> {
+    let ours = read_user_reg_overrides(nak_prefix);
+    read_user_reg_overrides(game_prefix)
+        .into_iter()
+        .filter(|(dll, mode)| is_audio_dll(dll) && ours.get(dll) != Some(mode))
+        .collect()
+}
+
+/// Reinstall XACT (both architectures) and copy audio overrides from the games' prefixes
+///
+/// `game_prefixes` are the Steam prefixes of the games the NaK prefix mods;
+/// where they disagree, the first one wins. Returns notes for the user,
+/// ending with what the check still finds wrong, if anything.
+pub fn fix_audio(
+    prefix_root: &Path,
+    proton: &SteamProton,
+    game_prefixes: &[PathBuf],
+    log_callback: impl Fn(String),
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut notes = Vec::new();
+    let before = check_xaudio(prefix_root);
+    if !before.is_ok() {
+        notes.push(format!("Before: {}", before.summary()));
+    }
+
+    log_callback("Reinstalling XACT (32-bit and 64-bit)...".to_string());
+    run_winetricks(prefix_root, proton, &["--force", "xact", "xact_x64"], &log_callback)?;
+    notes.push("Reinstalled xact and xact_x64".to_string());
+
+    let mut from_games: BTreeMap<String, String> = BTreeMap::new();
+    for game_prefix in game_prefixes {
+        for (dll, mode) in game_audio_override_diff(prefix_root, game_prefix) {
+            from_games.entry(dll).or_insert(mode);
+        }
+    }
+    if !from_games.is_empty() {
+        log_callback("Copying audio overrides from the game's prefix...".to_string());
+        apply_dll_overrides(prefix_root, proton, &from_games)?;
+        let list: Vec<String> = from_games.iter().map(|(dll, mode)| format!("{}={}", dll, mode)).collect();
+        notes.push(format!("Copied from the game's prefix: {}", list.join(", ")));
+    }
+
+    let after = check_xaudio(prefix_root);
+    if after.is_ok() {
+        notes.push("XAudio check passes".to_string());
+    } else {
+        notes.push(format!("Still wrong: {}", after.summary()));
+    }
+    log_install(&format!("Fixed audio in {}: {}", prefix_root.display(), notes.join("; ")));
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_check_xaudio_and_game_diff() {
+        let dir = std::env::temp_dir().join(format!("nak_audio_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let nak = dir.join("nak");
+        let game = dir.join("game");
+        let windows = nak.join("drive_c/windows");
+        fs::create_dir_all(windows.join("system32")).unwrap();
+        fs::create_dir_all(windows.join("syswow64")).unwrap();
+        fs::create_dir_all(&game).unwrap();
+
+        // Only the 64-bit half of XACT, overrides complete except x3daudio1_7
+        for dll in XAUDIO_DLLS {
+            fs::write(windows.join("system32").join(format!("{}.dll", dll)), "").unwrap();
+        }
+        fs::write(
+            nak.join("user.reg"),
+            "[Software\\\\Wine\\\\DllOverrides] 1\n\"*xaudio2_7\"=\"native,builtin\"\n\"xactengine3_7\"=\"native\"\n",
+        )
+        .unwrap();
+        let check = check_xaudio(&nak);
+        assert_eq!(
+            check.missing_files,
+            ["syswow64/xaudio2_7.dll", "syswow64/xactengine3_7.dll", "syswow64/x3daudio1_7.dll"]
+        );
+        assert_eq!(check.missing_overrides, ["x3daudio1_7"]);
+        assert!(!check.is_ok());
+
+        fs::write(
+            game.join("user.reg"),
+            "[Software\\\\Wine\\\\DllOverrides] 1\n\"x3daudio1_7\"=\"native,builtin\"\n\
+             \"xaudio2_7\"=\"native,builtin\"\n\"d3d11\"=\"native\"\n",
+        )
+        .unwrap();
+        let diff = game_audio_override_diff(&nak, &game);
+        assert_eq!(diff, BTreeMap::from([("x3daudio1_7".to_string(), "native,builtin".to_string())]));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -10,9 +10,12 @@ use std::path::{Path, PathBuf};
 
 use crate::game_finder::detect_all_games;
 use crate::logging::{log_error, log_install, log_warning};
+use crate::steam::shell_dq_escape;
 
-use super::symlinks::{create_game_symlinks, create_nak_tools_symlinks, ensure_temp_directory};
-use super::TaskContext;
+use super::symlinks::{
+    create_game_symlinks, create_nak_tools_symlinks, ensure_temp_directory, repair_nak_tools_symlinks,
+};
+use super::{InstallPhase, TaskContext};
 
 // Re-export ManagerType from config for use in other installer modules
 pub use crate::config::ManagerType;
@@ -70,7 +73,7 @@ fn download_dxvk_conf_template() -> Result<String, Box<dyn std::error::Error>> {
     use std::io::Read;
     use std::time::Duration;
 
-    let agent = ureq::AgentBuilder::new()
+    let agent = crate::net::agent_for(DXVK_CONF_URL)
         .timeout_read(Duration::from_secs(30))
         .timeout_write(Duration::from_secs(10))
         .build();
@@ -83,11 +86,83 @@ fn download_dxvk_conf_template() -> Result<String, Box<dyn std::error::Error>> {
     Ok(content)
 }
 
+/// Return the install's dxvk.conf, creating it only if it doesn't exist yet
+///
+/// Keeps any edits the user made to an existing file.
+pub fn ensure_dxvk_conf(install_dir: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dxvk_conf_path = get_dxvk_conf_path(install_dir);
+    if dxvk_conf_path.exists() {
+        return Ok(dxvk_conf_path);
+    }
+    download_and_create_dxvk_conf(install_dir)
+}
+
+/// Replace the install's dxvk.conf with NaK's defaults
+///
+/// The previous file is kept as `dxvk.conf.bak`.
+pub fn reset_dxvk_conf(install_dir: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dxvk_conf_path = get_dxvk_conf_path(install_dir);
+    if dxvk_conf_path.exists() {
+        fs::copy(&dxvk_conf_path, dxvk_conf_path.with_extension("conf.bak"))?;
+    }
+    download_and_create_dxvk_conf(install_dir)
+}
+
 /// Get the path where dxvk.conf will be created (for use before actual creation)
 pub fn get_dxvk_conf_path(install_dir: &Path) -> PathBuf {
     install_dir.join("NaK Tools").join("dxvk.conf")
 }
 
+/// NaK's MangoHud preset: frame timing plus the DXVK/VKD3D details that matter
+/// when comparing settings
+const MANGOHUD_PRESET: &str = "\
+# NaK MangoHud preset (MANGOHUD_CONFIGFILE). Edit freely; NaK won't overwrite it.
+# Options: https://github.com/flightlessmango/MangoHud#mangohud_config-and-mangohud_configfile-environment-variables
+position=top-left
+fps
+frametime
+frame_timing
+cpu_stats
+gpu_stats
+vram
+ram
+engine_version
+wine
+fps_limit=0
+toggle_hud=Shift_R+F12
+log_duration=60
+toggle_logging=Shift_L+F2
+output_folder=/tmp/mangohud_logs
+";
+
+/// Return the install's MangoHud.conf, writing NaK's preset if it doesn't exist yet
+pub fn ensure_mangohud_conf(install_dir: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = install_dir.join("NaK Tools").join("MangoHud.conf");
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, MANGOHUD_PRESET)?;
+        log_install(&format!("Created MangoHud preset at {}", path.display()));
+    }
+    Ok(path)
+}
+
+/// Return the install's Launch Log wrapper, writing it if it doesn't exist yet
+///
+/// Instances set up before the wrapper existed only get it here or from
+/// `regenerate_nak_tools_scripts`.
+pub fn ensure_launch_log_script(manager_type: &ManagerType, install_dir: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let tools_dir = install_dir.join("NaK Tools");
+    let path = tools_dir.join(crate::config::LAUNCH_LOG_SCRIPT);
+    if !path.exists() {
+        fs::create_dir_all(&tools_dir)?;
+        write_script(&path, &generate_launch_log_script(manager_type.display_name()))?;
+        log_install(&format!("Created launch log wrapper at {}", path.display()));
+    }
+    Ok(path)
+}
+
 // ============================================================================
 // Shared Installation Errors
 // ============================================================================
@@ -147,7 +222,7 @@ impl std::error::Error for InstallError {}
 /// - Sets up MO2 Global Instance support (if MO2)
 /// - Creates NaK Tools folder with utilities
 pub fn finalize_steam_installation_with_tools(
-    manager_type: ManagerType,
+    manager_type: &ManagerType,
     prefix_path: &Path,
     install_dir: &Path,
     app_id: u32,
@@ -158,10 +233,11 @@ pub fn finalize_steam_installation_with_tools(
         return Err(InstallError::Cancelled);
     }
 
+    ctx.set_phase(InstallPhase::Finalize);
     ctx.set_status("Finalizing installation...".to_string());
 
     // Manager-specific setup
-    if manager_type == ManagerType::MO2 {
+    if *manager_type == ManagerType::MO2 {
         setup_mo2_global_instance(prefix_path, install_dir);
     }
 
@@ -175,6 +251,12 @@ pub fn finalize_steam_installation_with_tools(
         }
     }
 
+    // Record what ended up in the prefix, for bug reports
+    let proton_name = proton_path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    if let Err(e) = super::write_applied_settings(prefix_path, install_dir, &proton_name) {
+        log_warning(&format!("Failed to write applied settings summary: {}", e));
+    }
+
     log_install(&format!(
         "{} installation finalized (Steam-native)",
         manager_type.display_name()
@@ -189,7 +271,7 @@ pub fn finalize_steam_installation_with_tools(
 
 /// Sets up the symlink for MO2 Global Instance support.
 /// Symlinks `.../pfx/drive_c/users/<user>/AppData/Local/ModOrganizer` -> `install_dir/Global Instance`
-fn setup_mo2_global_instance(prefix_path: &Path, install_dir: &Path) {
+pub(super) fn setup_mo2_global_instance(prefix_path: &Path, install_dir: &Path) {
     let users_dir = prefix_path.join("drive_c/users");
     let username = find_prefix_username(&users_dir);
 
@@ -213,10 +295,18 @@ fn setup_mo2_global_instance(prefix_path: &Path, install_dir: &Path) {
         }
     }
 
-    // 3. Create symlink
-    if mo2_global_path.exists() || fs::symlink_metadata(&mo2_global_path).is_ok() {
-        let _ = fs::remove_dir_all(&mo2_global_path);
-        let _ = fs::remove_file(&mo2_global_path);
+    // 3. Create symlink, keeping instances already in a real ModOrganizer folder
+    match fs::symlink_metadata(&mo2_global_path) {
+        Ok(meta) if meta.is_dir() => {
+            if let Err(e) = merge_dir_into(&mo2_global_path, &target_global_instance) {
+                log_error(&format!("Failed to move existing ModOrganizer folder into Global Instance: {}", e));
+                return;
+            }
+        }
+        Ok(_) => {
+            let _ = fs::remove_file(&mo2_global_path);
+        }
+        Err(_) => {}
     }
 
     if let Err(e) = std::os::unix::fs::symlink(&target_global_instance, &mo2_global_path) {
@@ -239,6 +329,210 @@ fn find_prefix_username(users_dir: &Path) -> String {
     "steamuser".to_string()
 }
 
+/// `AppData/Local/ModOrganizer` of a prefix, where the Global Instance link lives
+pub(crate) fn global_instance_link(prefix_path: &Path) -> PathBuf {
+    let users_dir = prefix_path.join("drive_c/users");
+    users_dir.join(find_prefix_username(&users_dir)).join("AppData/Local/ModOrganizer")
+}
+
+/// Move a file or folder, copying when it crosses filesystems
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if fs::symlink_metadata(from)?.is_dir() {
+        copy_dir_all(from, to)?;
+        fs::remove_dir_all(from)
+    } else {
+        fs::copy(from, to)?;
+        fs::remove_file(from)
+    }
+}
+
+/// Move everything in `src` into `dst`, then remove `src`
+///
+/// Nothing is overwritten: when both sides have an entry, the newer one
+/// keeps the name and the older one is kept next to it as `<name>.nak-bak`.
+fn merge_dir_into(src: &Path, dst: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let from = entry.path();
+        let to = dst.join(entry.file_name());
+        let Ok(existing) = fs::symlink_metadata(&to) else {
+            move_path(&from, &to)?;
+            continue;
+        };
+        if entry.file_type()?.is_dir() && existing.is_dir() {
+            merge_dir_into(&from, &to)?;
+            continue;
+        }
+        let mut backup = dst.join(format!("{}.nak-bak", entry.file_name().to_string_lossy()));
+        let mut n = 1;
+        while fs::symlink_metadata(&backup).is_ok() {
+            n += 1;
+            backup = dst.join(format!("{}.nak-bak{}", entry.file_name().to_string_lossy(), n));
+        }
+        if entry.metadata()?.modified()? > existing.modified()? {
+            fs::rename(&to, &backup)?;
+            move_path(&from, &to)?;
+        } else {
+            move_path(&from, &backup)?;
+        }
+    }
+    fs::remove_dir(src)
+}
+
+/// State of a prefix's Global Instance link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlobalInstanceLink {
+    /// Points at the install folder's "Global Instance"
+    Linked,
+    /// No link: never set up, or disabled on purpose
+    Missing,
+    /// Points somewhere else, e.g. an install folder that was moved
+    WrongTarget(PathBuf),
+    /// A real folder, recreated by MO2 or Windows: instances made since
+    /// then don't reach the install folder
+    RealFolder,
+}
+
+impl GlobalInstanceLink {
+    /// User-facing warning for the prefix manager (None when nothing needs repairing)
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            Self::Linked | Self::Missing => None,
+            Self::WrongTarget(target) => Some(format!(
+                "The Global Instance link points to {} instead of this install's Global Instance folder.",
+                target.display()
+            )),
+            Self::RealFolder => Some(
+                "AppData/Local/ModOrganizer was replaced by a real folder, so Global Instances no longer \
+                 live in the install folder. Repair moves them back and restores the link."
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+/// Check that `AppData/Local/ModOrganizer` still links to `install_dir/Global Instance`
+pub fn check_mo2_global_instance(prefix_path: &Path, install_dir: &Path) -> GlobalInstanceLink {
+    let link = global_instance_link(prefix_path);
+    let Ok(meta) = fs::symlink_metadata(&link) else {
+        return GlobalInstanceLink::Missing;
+    };
+    if !meta.file_type().is_symlink() {
+        return GlobalInstanceLink::RealFolder;
+    }
+    let expected = install_dir.join("Global Instance");
+    let target = fs::read_link(&link).unwrap_or_default();
+    let same = target == expected
+        || fs::canonicalize(&link).is_ok_and(|resolved| fs::canonicalize(&expected).is_ok_and(|e| e == resolved));
+    if same {
+        GlobalInstanceLink::Linked
+    } else {
+        GlobalInstanceLink::WrongTarget(target)
+    }
+}
+
+/// Point the Global Instance link back at `install_dir/Global Instance`
+///
+/// A real folder in its place is merged into the install folder's Global
+/// Instance first, so instances MO2 wrote there are kept.
+pub fn repair_mo2_global_instance(prefix_path: &Path, install_dir: &Path) -> std::io::Result<()> {
+    let link = global_instance_link(prefix_path);
+    let target = install_dir.join("Global Instance");
+    match check_mo2_global_instance(prefix_path, install_dir) {
+        GlobalInstanceLink::Linked => return Ok(()),
+        GlobalInstanceLink::RealFolder => merge_dir_into(&link, &target)?,
+        GlobalInstanceLink::WrongTarget(_) => fs::remove_file(&link)?,
+        GlobalInstanceLink::Missing => {}
+    }
+    fs::create_dir_all(&target)?;
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    std::os::unix::fs::symlink(&target, &link)?;
+    log_install(&format!("Repaired MO2 Global Instance link in {}", prefix_path.display()));
+    Ok(())
+}
+
+/// Which instance MO2 opens when a prefix has both a Global Instance link and a portable instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mo2InstanceConflict {
+    /// `portable.txt` in the install folder forces the portable instance
+    PortableActive,
+    /// MO2 opens whichever instance was picked last in its instance manager
+    LastPicked,
+}
+
+impl Mo2InstanceConflict {
+    /// User-facing explanation for the prefix manager
+    pub fn warning(&self) -> &'static str {
+        match self {
+            Self::PortableActive => {
+                "MO2 has both a portable instance and a Global Instance. portable.txt makes MO2 always use the \
+                 portable one (in the install folder), so the Global Instance is ignored."
+            }
+            Self::LastPicked => {
+                "MO2 has both a portable instance and a Global Instance, and opens whichever was picked last - \
+                 mods can seem to disappear between launches. Disable the Global Instance link to stick to portable."
+            }
+        }
+    }
+}
+
+/// Whether a ModOrganizer.ini belongs to a set-up instance (not just a stub)
+fn is_configured_mo2_ini(ini: &Path) -> bool {
+    fs::read_to_string(ini).is_ok_and(|content| {
+        content.lines().any(|line| {
+            let key = line.split('=').next().unwrap_or("").trim();
+            key == "gameName" || key.eq_ignore_ascii_case("dataPath") || key == "base_directory"
+        })
+    })
+}
+
+/// Detect a portable MO2 instance fighting with the Global Instance symlink
+///
+/// Only a conflict when the `AppData/Local/ModOrganizer` link exists, the
+/// install folder has a configured ModOrganizer.ini and the Global Instance
+/// folder holds at least one instance of its own.
+pub fn detect_mo2_instance_conflict(prefix_path: &Path, install_dir: &Path) -> Option<Mo2InstanceConflict> {
+    let link = global_instance_link(prefix_path);
+    if !fs::symlink_metadata(&link).is_ok_and(|m| m.file_type().is_symlink()) {
+        return None;
+    }
+    if !is_configured_mo2_ini(&install_dir.join("ModOrganizer.ini")) {
+        return None;
+    }
+    let has_global_instance = fs::read_dir(&link)
+        .map(|entries| entries.flatten().any(|e| e.path().join("ModOrganizer.ini").is_file()))
+        .unwrap_or(false);
+    if !has_global_instance {
+        return None;
+    }
+
+    Some(if install_dir.join("portable.txt").exists() {
+        Mo2InstanceConflict::PortableActive
+    } else {
+        Mo2InstanceConflict::LastPicked
+    })
+}
+
+/// Remove the Global Instance symlink so MO2 only sees the portable instance
+///
+/// The instances in the install folder's "Global Instance" are left alone.
+/// Reset Prefix recreates the link.
+pub fn disable_mo2_global_instance(prefix_path: &Path) -> std::io::Result<()> {
+    let link = global_instance_link(prefix_path);
+    if !fs::symlink_metadata(&link)?.file_type().is_symlink() {
+        return Err(std::io::Error::other(format!("{} is not a Global Instance link", link.display())));
+    }
+    fs::remove_file(&link)?;
+    log_install(&format!("Disabled MO2 Global Instance link in {}", prefix_path.display()));
+    Ok(())
+}
+
 // ============================================================================
 // Disk Space Validation
 // ============================================================================
@@ -246,27 +540,77 @@ fn find_prefix_username(users_dir: &Path) -> String {
 /// Minimum required disk space for a full installation (in GB)
 pub const MIN_REQUIRED_DISK_SPACE_GB: f64 = 5.0;
 
-/// Get available disk space at the given path (in GB).
-pub fn get_available_disk_space(path: &Path) -> Option<f64> {
+/// Convert a byte count to GB (GiB, matching what `df -BG` used to report)
+fn bytes_to_gb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+}
+
+/// Bytes available to unprivileged users on the filesystem holding `path`
+#[cfg(feature = "full")]
+fn statvfs_available_bytes(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is a valid NUL-terminated string and stat is a valid out pointer
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+}
+
+/// Parse the available byte count from `df -P -B1` output
+///
+/// Uses the column before the capacity percentage rather than a fixed index,
+/// so device names containing spaces, or wrapped lines from a `df` that
+/// ignores `-P`, still parse.
+fn parse_df_available_bytes(output: &str) -> Option<u64> {
+    // Skip the header, then treat the rest as one stream of fields
+    let fields: Vec<&str> = output.lines().skip(1).flat_map(|l| l.split_whitespace()).collect();
+    let pct_idx = fields.iter().position(|f| f.ends_with('%') && f.trim_end_matches('%').parse::<u32>().is_ok())?;
+    fields.get(pct_idx.checked_sub(1)?)?.parse::<u64>().ok()
+}
+
+/// Available bytes via `df`, used only when statvfs fails
+fn df_available_bytes(path: &Path) -> Option<u64> {
     use std::process::Command;
 
     let output = Command::new("df")
-        .arg("-BG")
+        .arg("-P")
+        .arg("-B1")
         .arg(path)
+        .env("LC_ALL", "C")
         .output()
         .ok()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    for line in stdout.lines().skip(1) {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 4 {
-            let available_str = parts[3].trim_end_matches('G');
-            if let Ok(available) = available_str.parse::<f64>() {
-                return Some(available);
-            }
-        }
+    if !output.status.success() {
+        return None;
     }
-    None
+    parse_df_available_bytes(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Get available disk space at the given path (in bytes).
+///
+/// Uses statvfs, falling back to `df` if the syscall fails.
+#[cfg(feature = "full")]
+pub fn get_available_disk_bytes(path: &Path) -> Option<u64> {
+    statvfs_available_bytes(path).or_else(|| {
+        log_warning(&format!("statvfs failed for {:?}, falling back to df", path));
+        df_available_bytes(path)
+    })
+}
+
+/// Get available disk space at the given path (in bytes).
+///
+/// Installer-only builds don't link libc, so this always asks `df`.
+#[cfg(not(feature = "full"))]
+pub fn get_available_disk_bytes(path: &Path) -> Option<u64> {
+    df_available_bytes(path)
+}
+
+/// Get available disk space at the given path (in GB).
+pub fn get_available_disk_space(path: &Path) -> Option<f64> {
+    get_available_disk_bytes(path).map(bytes_to_gb)
 }
 
 /// Check if there's sufficient disk space at the given path.
@@ -283,7 +627,7 @@ pub fn check_disk_space(path: &Path, required_gb: f64) -> Result<f64, InstallErr
             }
         }
         None => {
-            log_warning("Could not check disk space - df command failed");
+            log_warning("Could not check disk space - statvfs and df both failed");
             Ok(required_gb)
         }
     }
@@ -316,7 +660,7 @@ pub fn check_cancelled(ctx: &TaskContext) -> Result<(), InstallError> {
 /// - Creates symlinks FROM NaK prefix TO game prefixes (data stays in game prefix)
 /// - Only creates essential directories (Temp)
 pub fn create_nak_tools_folder(
-    manager_type: ManagerType,
+    manager_type: &ManagerType,
     install_dir: &Path,
     prefix_path: &Path,
     app_id: u32,
@@ -345,10 +689,11 @@ pub fn create_nak_tools_folder(
     // 2. Detect all games using the game_finder module
     let scan_result = detect_all_games();
     log_install(&format!(
-        "Detected {} games (Steam: {}, Heroic: {}, Bottles: {})",
+        "Detected {} games (Steam: {}, Heroic: {}, Legendary: {}, Bottles: {})",
         scan_result.games.len(),
         scan_result.steam_count,
         scan_result.heroic_count,
+        scan_result.legendary_count,
         scan_result.bottles_count
     ));
 
@@ -369,8 +714,8 @@ pub fn create_nak_tools_folder(
 
     // === All scripts and configs go in NaK Tools folder ===
 
-    // 6. Download and create dxvk.conf (non-fatal if download fails)
-    if let Err(e) = download_and_create_dxvk_conf(install_dir) {
+    // 6. Download and create dxvk.conf unless the user already has one (non-fatal if download fails)
+    if let Err(e) = ensure_dxvk_conf(install_dir) {
         log_warning(&format!("Could not create dxvk.conf: {}", e));
     }
 
@@ -380,7 +725,7 @@ pub fn create_nak_tools_folder(
     log_install(&format!("Created Launch {} script", manager_name));
 
     // 8. Create NXM Toggle script
-    let nxm_script = generate_nxm_toggle_script(app_id, manager_name, install_dir, prefix_path, proton_path);
+    let nxm_script = generate_nxm_toggle_script(app_id, manager_type, install_dir, prefix_path, proton_path);
     write_script(&tools_dir.join("NXM Toggle.sh"), &nxm_script)?;
     log_install("Created NXM Toggle script");
 
@@ -399,22 +744,56 @@ pub fn create_nak_tools_folder(
     write_script(&tools_dir.join("Winetricks.sh"), &winetricks_script)?;
     log_install("Created Winetricks GUI script");
 
+    // 12. Create Launch Log wrapper (used when the instance's "Log launches" toggle is on)
+    let launch_log_script = generate_launch_log_script(manager_name);
+    write_script(&tools_dir.join(crate::config::LAUNCH_LOG_SCRIPT), &launch_log_script)?;
+    log_install("Created Launch Log wrapper");
+
     log_install(&format!("NaK Tools created in {:?}", install_dir));
     Ok(())
 }
 
+/// Point NaK Tools' "Wine Prefix" link at `prefix_path` if it is missing or points elsewhere
+///
+/// Returns true if the link was fixed.
+pub(crate) fn repair_wine_prefix_link(tools_dir: &Path, prefix_path: &Path) -> bool {
+    let prefix_link = tools_dir.join("Wine Prefix");
+    if fs::read_link(&prefix_link).ok().as_deref() == Some(prefix_path) {
+        return false;
+    }
+    if prefix_link.exists() || fs::symlink_metadata(&prefix_link).is_ok() {
+        let _ = fs::remove_file(&prefix_link);
+    }
+    if let Err(e) = std::os::unix::fs::symlink(prefix_path, &prefix_link) {
+        log_warning(&format!("Failed to update prefix symlink: {}", e));
+        return false;
+    }
+    log_install("Updated Wine Prefix symlink");
+    true
+}
+
+/// What `regenerate_nak_tools_scripts` changed
+#[derive(Debug, Clone, Default)]
+pub struct ScriptsRegenerated {
+    /// Scripts whose contents changed (or that were missing)
+    pub changed_scripts: Vec<String>,
+    /// Wine Prefix and convenience symlinks that were missing or pointed elsewhere
+    pub symlinks_fixed: usize,
+}
+
 /// Regenerate NaK Tools scripts for an existing instance.
 /// This updates the Wine Prefix symlink and all scripts without touching
 /// the folder structure (Prefix Documents, etc.)
 ///
 /// Used when NaK is updated to refresh scripts with bug fixes/improvements.
+/// Scripts that are already up to date are left alone.
 pub fn regenerate_nak_tools_scripts(
-    manager_type: ManagerType,
+    manager_type: &ManagerType,
     install_dir: &Path,
     prefix_path: &Path,
     app_id: u32,
     proton_path: &Path,
-) -> Result<(), InstallError> {
+) -> Result<ScriptsRegenerated, InstallError> {
     let manager_name = manager_type.display_name();
     let tools_dir = install_dir.join("NaK Tools");
 
@@ -427,47 +806,595 @@ pub fn regenerate_nak_tools_scripts(
     }
 
     log_install(&format!("Regenerating scripts for {} instance...", manager_name));
+    let mut report = ScriptsRegenerated::default();
 
     // 1. Update Wine Prefix symlink (in case prefix was moved)
-    let prefix_link = tools_dir.join("Wine Prefix");
-    if prefix_link.exists() || fs::symlink_metadata(&prefix_link).is_ok() {
-        let _ = fs::remove_file(&prefix_link);
+    if repair_wine_prefix_link(&tools_dir, prefix_path) {
+        report.symlinks_fixed += 1;
     }
-    if let Err(e) = std::os::unix::fs::symlink(prefix_path, &prefix_link) {
-        log_warning(&format!("Failed to update prefix symlink: {}", e));
-    } else {
-        log_install("Updated Wine Prefix symlink");
+    report.symlinks_fixed += repair_nak_tools_symlinks(&tools_dir, prefix_path);
+
+    let scripts = [
+        // 2. Launch script
+        (format!("Launch {}.sh", manager_name), generate_steam_launch_script(app_id, manager_name)),
+        // 3. NXM Toggle script
+        ("NXM Toggle.sh".to_string(), generate_nxm_toggle_script(app_id, manager_type, install_dir, prefix_path, proton_path)),
+        // 4. Fix Game Registry script
+        ("Fix Game Registry.sh".to_string(), generate_fix_registry_script(manager_name, prefix_path, proton_path)),
+        // 5. Import Saves script
+        ("Import Saves.sh".to_string(), generate_import_saves_script(prefix_path)),
+        // 6. Winetricks GUI script
+        ("Winetricks.sh".to_string(), generate_winetricks_gui_script(prefix_path)),
+        // 7. Launch Log wrapper
+        (crate::config::LAUNCH_LOG_SCRIPT.to_string(), generate_launch_log_script(manager_name)),
+    ];
+    for (file_name, content) in scripts {
+        let path = tools_dir.join(&file_name);
+        if fs::read_to_string(&path).ok().as_deref() == Some(content.as_str()) {
+            continue;
+        }
+        write_script(&path, &content)?;
+        log_install(&format!("Regenerated {}", file_name));
+        report.changed_scripts.push(file_name);
     }
 
-    // 2. Regenerate Launch script
-    let launch_script = generate_steam_launch_script(app_id, manager_name);
-    write_script(&tools_dir.join(format!("Launch {}.sh", manager_name)), &launch_script)?;
-    log_install(&format!("Regenerated Launch {} script", manager_name));
+    log_install(&format!(
+        "NaK Tools scripts regenerated for {:?} ({} changed, {} symlinks fixed)",
+        install_dir,
+        report.changed_scripts.len(),
+        report.symlinks_fixed
+    ));
+    Ok(report)
+}
 
-    // 3. Regenerate NXM Toggle script
-    let nxm_script = generate_nxm_toggle_script(app_id, manager_name, install_dir, prefix_path, proton_path);
-    write_script(&tools_dir.join("NXM Toggle.sh"), &nxm_script)?;
-    log_install("Regenerated NXM Toggle script");
+/// Result of regenerating one prefix's scripts in `regenerate_all_prefix_scripts`
+#[derive(Debug, Clone)]
+pub struct PrefixScriptsReport {
+    /// Instance name
+    pub name: String,
+    /// Proton recorded for the prefix before regenerating
+    pub proton_before: Option<String>,
+    /// Proton the scripts now use (None if no Proton was available)
+    pub proton_after: Option<String>,
+    /// What changed, or why regenerating failed
+    pub result: Result<ScriptsRegenerated, String>,
+}
 
-    // 4. Regenerate Fix Game Registry script
-    let registry_script = generate_fix_registry_script(manager_name, prefix_path, proton_path);
-    write_script(&tools_dir.join("Fix Game Registry.sh"), &registry_script)?;
-    log_install("Regenerated Fix Game Registry script");
+impl PrefixScriptsReport {
+    /// One-line summary for the report dialog
+    pub fn summary(&self) -> String {
+        let proton = match (&self.proton_before, &self.proton_after) {
+            (Some(before), Some(after)) if before != after => format!("Proton {} -> {}", before, after),
+            (None, Some(after)) => format!("Proton set to {}", after),
+            (_, Some(after)) => format!("Proton {}", after),
+            (_, None) => "no Proton".to_string(),
+        };
+        match &self.result {
+            Ok(r) if r.changed_scripts.is_empty() && r.symlinks_fixed == 0 => {
+                format!("{}: up to date ({})", self.name, proton)
+            }
+            Ok(r) => {
+                let mut parts = vec![proton];
+                if !r.changed_scripts.is_empty() {
+                    parts.push(format!("updated {}", r.changed_scripts.join(", ")));
+                }
+                if r.symlinks_fixed > 0 {
+                    parts.push(format!("{} symlink(s) fixed", r.symlinks_fixed));
+                }
+                format!("{}: {}", self.name, parts.join("; "))
+            }
+            Err(e) => format!("{}: FAILED - {}", self.name, e),
+        }
+    }
+}
 
-    // 5. Regenerate Import Saves script
-    let import_script = generate_import_saves_script(prefix_path);
-    write_script(&tools_dir.join("Import Saves.sh"), &import_script)?;
-    log_install("Regenerated Import Saves script");
+/// Regenerate the NaK Tools scripts of one managed prefix
+///
+/// Uses the prefix's recorded Proton if it is still installed, otherwise
+/// the first of `protons`, and saves the choice back.
+pub fn regenerate_prefix_scripts(
+    prefix: &crate::config::ManagedPrefix,
+    protons: &[crate::steam::SteamProton],
+) -> PrefixScriptsReport {
+    let proton = prefix
+        .proton_config_name
+        .as_deref()
+        .and_then(|name| protons.iter().find(|p| p.config_name == name))
+        .or_else(|| protons.first());
+
+    let result = match proton {
+        Some(proton) => regenerate_nak_tools_scripts(
+            &prefix.manager_type,
+            Path::new(&prefix.install_path),
+            Path::new(&prefix.prefix_path),
+            prefix.app_id,
+            &proton.path,
+        )
+        .map_err(|e| e.to_string()),
+        None => Err("No Proton installed".to_string()),
+    };
 
-    // 6. Regenerate Winetricks GUI script
-    let winetricks_script = generate_winetricks_gui_script(prefix_path);
-    write_script(&tools_dir.join("Winetricks.sh"), &winetricks_script)?;
-    log_install("Regenerated Winetricks GUI script");
+    if let (Ok(_), Some(proton)) = (&result, proton) {
+        crate::config::ManagedPrefixes::update_proton(prefix.app_id, &proton.config_name);
+    }
 
-    log_install(&format!("NaK Tools scripts regenerated for {:?}", install_dir));
+    let report = PrefixScriptsReport {
+        name: prefix.name.clone(),
+        proton_before: prefix.proton_config_name.clone(),
+        proton_after: proton.map(|p| p.config_name.clone()),
+        result,
+    };
+    log_install(&report.summary());
+    report
+}
+
+/// Regenerate NaK Tools scripts for every managed prefix (see `regenerate_prefix_scripts`)
+pub fn regenerate_all_prefix_scripts(protons: &[crate::steam::SteamProton]) -> Vec<PrefixScriptsReport> {
+    crate::config::ManagedPrefixes::load()
+        .prefixes
+        .iter()
+        .map(|prefix| regenerate_prefix_scripts(prefix, protons))
+        .collect()
+}
+
+// ============================================================================
+// Steam Shortcut Recovery
+// ============================================================================
+
+/// Find the mod manager executable for an existing install
+///
+/// MO2 always uses ModOrganizer.exe and generic managers record their exe.
+/// Plugin installs don't, so the first top-level .exe that isn't an
+/// uninstaller is used.
+pub fn find_manager_exe(manager_type: &ManagerType, install_dir: &Path) -> Option<PathBuf> {
+    match manager_type {
+        ManagerType::MO2 => {
+            let exe = install_dir.join("ModOrganizer.exe");
+            return exe.exists().then_some(exe);
+        }
+        ManagerType::Generic { exe_name, .. } => {
+            let exe = install_dir.join(exe_name);
+            return exe.exists().then_some(exe);
+        }
+        ManagerType::Plugin => {}
+    }
+
+    let mut exes: Vec<PathBuf> = fs::read_dir(install_dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("").to_lowercase();
+            name.ends_with(".exe") && !name.starts_with("unins") && !name.contains("uninstall")
+        })
+        .collect();
+    exes.sort();
+    exes.into_iter().next()
+}
+
+/// Point an instance at its mod manager folder after the user moved it
+///
+/// The new folder must contain the manager's executable. Updates the recorded
+/// install path, the Steam shortcut (exe, start dir, dxvk.conf) if there is
+/// one, and the NaK Tools scripts and symlinks that moved with the folder.
+pub fn relocate_install(
+    prefix: &crate::config::ManagedPrefix,
+    new_install_dir: &Path,
+    proton: &crate::steam::SteamProton,
+) -> Result<ScriptsRegenerated, Box<dyn std::error::Error>> {
+    if find_manager_exe(&prefix.manager_type, new_install_dir).is_none() {
+        return Err(format!(
+            "{} does not contain a {} executable",
+            new_install_dir.display(),
+            prefix.manager_type.display_name()
+        )
+        .into());
+    }
+    let new_path = new_install_dir.to_str().ok_or("Install path contains non-UTF-8 characters")?;
+
+    log_install(&format!("Relocating {} from {} to {}", prefix.name, prefix.install_path, new_path));
+    crate::config::ManagedPrefixes::update_install_path(prefix.app_id, new_path);
+    let moved = crate::config::ManagedPrefix {
+        install_path: new_path.to_string(),
+        ..prefix.clone()
+    };
+
+    // Only rewrite the shortcut if it's still in Steam; otherwise "Add to Steam" picks up the new path
+    let in_steam = crate::steam::ShortcutsVdf::load()
+        .map(|vdf| vdf.shortcuts.iter().any(|s| s.appid == prefix.app_id))
+        .unwrap_or(false);
+    if in_steam {
+        add_prefix_to_steam(&moved, proton)?;
+    }
+
+    let tools_dir = new_install_dir.join("NaK Tools");
+    fs::create_dir_all(&tools_dir).map_err(|e| InstallError::DirectoryCreation {
+        path: tools_dir.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(regenerate_nak_tools_scripts(
+        &prefix.manager_type,
+        new_install_dir,
+        Path::new(&prefix.prefix_path),
+        prefix.app_id,
+        &proton.path,
+    )?)
+}
+
+/// Write a registered instance's launch option toggles to its Steam shortcut
+///
+/// Installers call this after registering, so a new instance gets the
+/// protonfixes game id of its detected game (see `ManagedPrefix::effective_launch_toggles`).
+pub(crate) fn apply_instance_launch_toggles(app_id: u32) {
+    let managed = crate::config::ManagedPrefixes::load();
+    let Some(prefix) = managed.get_by_app_id(app_id) else {
+        return;
+    };
+    let toggles = prefix.effective_launch_toggles();
+    if toggles == crate::config::LaunchToggles::default() {
+        return;
+    }
+    match crate::steam::set_launch_toggles(app_id, &toggles) {
+        Ok(_) => log_install(&format!("Applied launch option toggles for {}", prefix.name)),
+        Err(e) => log_warning(&format!("Failed to set launch option toggles: {}", e)),
+    }
+}
+
+/// Apply `source`'s launch settings to the shortcut `app_id` and record them on its instance
+///
+/// Covers the launch arguments, native Wayland, sync mode, locale and launch
+/// option toggles. `source` is the instance being cloned, the instance itself
+/// when its shortcut is re-created, or a recipe's settings; the arguments
+/// replaced are the ones recorded for `app_id`. Steps that fail don't stop
+/// the rest, the returned messages list them.
+pub(crate) fn reapply_prefix_launch_settings(app_id: u32, source: &crate::config::ManagedPrefix) -> Vec<String> {
+    use crate::config::{ManagedPrefix, ManagedPrefixes};
+
+    let target = ManagedPrefixes::load().get_by_app_id(app_id).cloned();
+    let mut failures = Vec::new();
+
+    let old_args = target.as_ref().and_then(|t| t.launch_args.as_deref()).unwrap_or("");
+    let new_args = source.launch_args.as_deref().unwrap_or("");
+    match crate::steam::set_launch_args(app_id, old_args, new_args) {
+        Ok(_) => ManagedPrefixes::update_launch_args(app_id, source.launch_args.as_deref()),
+        Err(e) => failures.push(format!("Failed to set launch arguments: {}", e)),
+    }
+    match crate::steam::set_native_wayland(app_id, source.native_wayland) {
+        Ok(_) => ManagedPrefixes::update_native_wayland(app_id, source.native_wayland),
+        Err(e) => failures.push(format!("Failed to set native Wayland: {}", e)),
+    }
+    match crate::steam::set_sync_mode(app_id, source.sync_mode.as_deref().unwrap_or("default")) {
+        Ok(_) => ManagedPrefixes::update_sync_mode(app_id, source.sync_mode.as_deref()),
+        Err(e) => failures.push(format!("Failed to set sync mode: {}", e)),
+    }
+    match crate::steam::set_locale(app_id, source.locale.as_deref()) {
+        Ok(_) => ManagedPrefixes::update_locale(app_id, source.locale.as_deref()),
+        Err(e) => failures.push(format!("Failed to set locale: {}", e)),
+    }
+    // Resolve the automatic game id etc. against the target instance, not the source
+    let effective = match &target {
+        Some(target) => ManagedPrefix { launch_toggles: source.launch_toggles.clone(), ..target.clone() },
+        None => source.clone(),
+    }
+    .effective_launch_toggles();
+    match crate::steam::set_launch_toggles(app_id, &effective) {
+        Ok(_) => ManagedPrefixes::update_launch_toggles(app_id, &source.launch_toggles),
+        Err(e) => failures.push(format!("Failed to set launch option toggles: {}", e)),
+    }
+    failures
+}
+
+/// Add an existing install back to Steam (or update its shortcut in place)
+///
+/// Reuses the prefix's AppID so Steam keeps using the existing prefix.
+/// Returns the shortcut's AppID.
+pub fn add_prefix_to_steam(
+    prefix: &crate::config::ManagedPrefix,
+    proton: &crate::steam::SteamProton,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    let install_dir = Path::new(&prefix.install_path);
+    let exe_path = find_manager_exe(&prefix.manager_type, install_dir)
+        .ok_or_else(|| format!("No mod manager executable found in {:?}", install_dir))?;
+
+    let dxvk_conf_path = get_dxvk_conf_path(install_dir);
+    let result = crate::steam::restore_mod_manager_shortcut(
+        prefix.app_id,
+        &prefix.name,
+        exe_path.to_str().ok_or("Exe path contains non-UTF-8 characters")?,
+        install_dir.to_str().ok_or("Install path contains non-UTF-8 characters")?,
+        &proton.config_name,
+        dxvk_conf_path.exists().then_some(dxvk_conf_path.as_path()),
+        matches!(prefix.manager_type, ManagerType::Generic { is_electron: true, .. })
+            || crate::steam::is_electron_executable(&exe_path),
+    )?;
+
+    crate::config::ManagedPrefixes::update_proton(result.app_id, &proton.config_name);
+    // A re-created shortcut has fresh launch options, so put the user's settings back
+    for failure in reapply_prefix_launch_settings(result.app_id, prefix) {
+        log_warning(&failure);
+    }
+    // The shortcut only shows up once Steam reloads shortcuts.vdf
+    crate::steam::finish_vdf_write(result.steam_was_closed);
+    log_install(&format!("Added {} to Steam (AppID {})", prefix.name, result.app_id));
+    Ok(result.app_id)
+}
+
+// ============================================================================
+// Prefix Reset
+// ============================================================================
+
+/// Wipe a prefix and set it up again, keeping the mod manager install
+///
+/// Only the `pfx` folder is deleted. The install folder (mods, downloads,
+/// profiles) and NaK Tools are left alone; the prefix-side symlinks
+/// (Global Instance, game saves, Temp) are recreated afterwards.
+pub fn reset_prefix(
+    prefix: &crate::config::ManagedPrefix,
+    proton: &crate::steam::SteamProton,
+    ctx: &TaskContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    proton.validate_binaries()?;
+    let prefix_path = Path::new(&prefix.prefix_path);
+    let install_dir = Path::new(&prefix.install_path);
+
+    // Never delete anything that isn't a Steam compatdata pfx folder
+    if prefix_path.file_name().and_then(|n| n.to_str()) != Some("pfx") || install_dir.starts_with(prefix_path) {
+        return Err(format!("Refusing to reset unexpected prefix path {:?}", prefix_path).into());
+    }
+
+    ctx.set_status("Removing old prefix...".to_string());
+    log_install(&format!("Resetting prefix for {} at {:?}", prefix.name, prefix_path));
+    super::kill_wineserver(prefix_path, proton);
+    if prefix_path.exists() {
+        fs::remove_dir_all(prefix_path)?;
+    }
+    fs::create_dir_all(prefix_path)?;
+
+    // Wineboot, winetricks, .NET and registry settings
+    super::install_all_dependencies(prefix_path, proton, ctx, 0.0, 0.9, prefix.app_id)?;
+
+    ctx.set_status("Restoring prefix links...".to_string());
+    if prefix.manager_type == ManagerType::MO2 {
+        setup_mo2_global_instance(prefix_path, install_dir);
+    }
+    create_game_symlinks(prefix_path, &detect_all_games().games);
+    ensure_temp_directory(prefix_path);
+    let tools_dir = install_dir.join("NaK Tools");
+    if tools_dir.exists() {
+        create_nak_tools_symlinks(&tools_dir, prefix_path);
+    }
+
+    // A DXVK override lived inside the old prefix
+    crate::config::ManagedPrefixes::update_dxvk_version(prefix.app_id, None);
+
+    // Keep a Windows version the user picked for this prefix
+    if let Some(version) = prefix.windows_version.as_deref() {
+        if let Err(e) = super::set_windows_version(prefix_path, proton, version) {
+            log_warning(&format!("Failed to restore Windows version {}: {}", version, e));
+        }
+    }
+
+    ctx.set_progress(1.0);
+    log_install(&format!("Prefix for {} reset", prefix.name));
+    Ok(())
+}
+
+// ============================================================================
+// Prefix Clone
+// ============================================================================
+
+/// Recursively copy a directory tree (fallback when rename fails across filesystems)
+pub(super) fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let dst_path = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &dst_path)?;
+        } else {
+            fs::copy(entry.path(), dst_path)?;
+        }
+    }
     Ok(())
 }
 
+/// Install-folder entries that hold user data rather than setup
+/// (checked directly under the install folder and under each Global Instance)
+const CLONE_USER_DATA_DIRS: &[&str] = &["mods", "downloads", "overwrite"];
+
+/// Copy a mod manager install for `clone_prefix`
+///
+/// Symlinks are copied as links, except those inside NaK Tools, which point
+/// at the old prefix and are recreated for the new one.
+fn copy_install_for_clone(src: &Path, dst: &Path, copy_mods: bool, depth: usize) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let file_type = entry.file_type()?;
+        let src_path = entry.path();
+        let dst_path = dst.join(&name);
+
+        let name_str = name.to_string_lossy().to_lowercase();
+        // depth 0 = install root, depth 2 = Global Instance/<instance>
+        if !copy_mods && (depth == 0 || depth == 2) && CLONE_USER_DATA_DIRS.contains(&name_str.as_str()) {
+            if file_type.is_dir() {
+                fs::create_dir_all(&dst_path)?;
+            }
+            continue;
+        }
+
+        if file_type.is_symlink() {
+            if src.file_name().is_some_and(|n| n == "NaK Tools") {
+                continue;
+            }
+            std::os::unix::fs::symlink(fs::read_link(&src_path)?, &dst_path)?;
+        } else if file_type.is_dir() {
+            let in_global = depth == 0 && name == "Global Instance";
+            let next_depth = if in_global || depth == 1 { depth + 1 } else { usize::MAX };
+            copy_install_for_clone(&src_path, &dst_path, copy_mods, next_depth)?;
+        } else {
+            fs::copy(&src_path, &dst_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy a directory with `cp -a`, using reflinks where the filesystem supports them
+fn reflink_copy_dir(src: &Path, dst: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(dst)?;
+    let status = std::process::Command::new("cp")
+        .arg("-a")
+        .arg("--reflink=auto")
+        .arg(src.join("."))
+        .arg(dst)
+        .status()?;
+    if !status.success() {
+        return Err(format!("cp failed copying {:?} to {:?}", src, dst).into());
+    }
+    Ok(())
+}
+
+/// Create a second instance from an existing one, keeping its prefix setup
+///
+/// Copies the compatdata folder (reflinked where possible) and the mod
+/// manager install into a sibling folder named `new_name`, creates a new
+/// Steam shortcut/AppID for the copy and regenerates its NaK Tools scripts.
+/// Mods, downloads and overwrite are left out unless `copy_mods` is set.
+/// Returns the new AppID.
+pub fn clone_prefix(
+    source: &crate::config::ManagedPrefix,
+    new_name: &str,
+    copy_mods: bool,
+    proton: &crate::steam::SteamProton,
+    ctx: &TaskContext,
+) -> Result<u32, Box<dyn std::error::Error>> {
+    proton.validate_binaries()?;
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("Name cannot be empty".into());
+    }
+    if crate::config::ManagedPrefixes::load().prefixes.iter().any(|p| p.name == new_name) {
+        return Err(format!("A prefix named '{}' already exists", new_name).into());
+    }
+    if new_name.contains('/') {
+        return Err("Name cannot contain '/'".into());
+    }
+
+    let src_prefix = Path::new(&source.prefix_path);
+    let src_install = Path::new(&source.install_path);
+    let src_compatdata = src_prefix.parent().ok_or("Prefix path has no compatdata folder")?;
+    if !src_prefix.exists() {
+        return Err(format!("Prefix does not exist: {:?}", src_prefix).into());
+    }
+    let dst_install = src_install
+        .parent()
+        .ok_or("Install path has no parent folder")?
+        .join(new_name);
+    if dst_install.exists() {
+        return Err(format!("{:?} already exists", dst_install).into());
+    }
+
+    log_install(&format!(
+        "Cloning {} to {} (mods and downloads {})",
+        source.name,
+        new_name,
+        if copy_mods { "included" } else { "left out" }
+    ));
+
+    // Everything this creates is rolled back if a step fails
+    let mut shortcut: Option<crate::steam::SteamShortcutResult> = None;
+    let result = (|| -> Result<u32, Box<dyn std::error::Error>> {
+        // 1. Copy the install folder (the shortcut points at its exe)
+        ctx.set_status(format!("Copying {} install...", source.manager_type));
+        ctx.set_progress(0.05);
+        copy_install_for_clone(src_install, &dst_install, copy_mods, 0).map_err(|e| InstallError::Other {
+            context: format!("Copying {:?}", src_install),
+            reason: e.to_string(),
+        })?;
+
+        if ctx.is_cancelled() {
+            return Err(InstallError::Cancelled.into());
+        }
+
+        // 2. New Steam shortcut, which gives the copy its own AppID and compatdata
+        ctx.set_status("Creating Steam shortcut...".to_string());
+        ctx.set_progress(0.30);
+        let exe_path = find_manager_exe(&source.manager_type, &dst_install)
+            .ok_or_else(|| format!("No mod manager executable found in {:?}", dst_install))?;
+        let dxvk_conf_path = get_dxvk_conf_path(&dst_install);
+        let is_electron = matches!(source.manager_type, ManagerType::Generic { is_electron: true, .. })
+            || crate::steam::is_electron_executable(&exe_path);
+        let steam_result = shortcut.insert(
+            crate::steam::add_mod_manager_shortcut(
+                new_name,
+                exe_path.to_str().ok_or("Exe path contains non-UTF-8 characters")?,
+                dst_install.to_str().ok_or("Install path contains non-UTF-8 characters")?,
+                &proton.config_name,
+                Some(&dxvk_conf_path),
+                is_electron,
+            )
+            .map_err(|e| InstallError::SteamError { reason: e.to_string() })?,
+        );
+        log_install(&format!("Created Steam shortcut with AppID: {}", steam_result.app_id));
+
+        // 3. Copy the prefix (pfx plus Proton's version/tracking files beside it)
+        ctx.set_status("Copying prefix...".to_string());
+        ctx.set_progress(0.35);
+        super::kill_wineserver(src_prefix, proton);
+        let dst_compatdata = steam_result.prefix_path.parent().ok_or("New prefix path has no compatdata folder")?;
+        reflink_copy_dir(src_compatdata, dst_compatdata)?;
+
+        // 4. Point the prefix-side links at the new install and regenerate NaK Tools
+        ctx.set_progress(0.90);
+        finalize_steam_installation_with_tools(
+            &source.manager_type,
+            &steam_result.prefix_path,
+            &dst_install,
+            steam_result.app_id,
+            &proton.path,
+            ctx,
+        )?;
+
+        // 5. Track the copy, carrying over settings that live in the prefix
+        crate::config::ManagedPrefixes::register(
+            steam_result.app_id,
+            new_name,
+            steam_result.prefix_path.to_str().unwrap_or(""),
+            dst_install.to_str().unwrap_or(""),
+            source.manager_type.clone(),
+            &source.library_path,
+            Some(&proton.config_name),
+        );
+        crate::config::ManagedPrefixes::update_dxvk_version(steam_result.app_id, source.dxvk_version.as_deref());
+        crate::config::ManagedPrefixes::update_windows_version(steam_result.app_id, source.windows_version.as_deref());
+        for failure in reapply_prefix_launch_settings(steam_result.app_id, source) {
+            log_warning(&failure);
+        }
+        Ok(steam_result.app_id)
+    })();
+
+    if result.is_err() {
+        if let Some(steam_result) = &shortcut {
+            let _ = crate::steam::remove_steam_shortcut(steam_result.app_id);
+            if let Some(dst_compatdata) = steam_result.prefix_path.parent() {
+                let _ = fs::remove_dir_all(dst_compatdata);
+            }
+            crate::config::ManagedPrefixes::unregister(steam_result.app_id);
+        }
+        let _ = fs::remove_dir_all(&dst_install);
+    }
+    // Steam stays closed from the shortcut write until here
+    if let Some(steam_result) = &shortcut {
+        crate::steam::finish_vdf_write(steam_result.steam_was_closed);
+    }
+    let app_id = result?;
+
+    ctx.set_progress(1.0);
+    log_install(&format!("Cloned {} to {} (AppID {})", source.name, new_name, app_id));
+    Ok(app_id)
+}
+
 /// Write a script file with executable permissions
 fn write_script(path: &Path, content: &str) -> Result<(), InstallError> {
     let mut file = fs::File::create(path).map_err(|e| InstallError::Other {
@@ -493,24 +1420,6 @@ fn write_script(path: &Path, content: &str) -> Result<(), InstallError> {
     Ok(())
 }
 
-/// Escape a string for safe substitution inside a double-quoted bash string.
-/// Escapes backslash, dollar sign, backtick, and double-quote so that the
-/// substituted value cannot break out of the surrounding `"..."` context or
-/// trigger command substitution / variable expansion.
-fn shell_dq_escape(s: &str) -> String {
-    let mut out = String::with_capacity(s.len());
-    for c in s.chars() {
-        match c {
-            '\\' | '$' | '`' | '"' => {
-                out.push('\\');
-                out.push(c);
-            }
-            _ => out.push(c),
-        }
-    }
-    out
-}
-
 /// Generate Fix Game Registry script for Steam-native installs
 fn generate_fix_registry_script(manager_name: &str, prefix_path: &Path, proton_path: &Path) -> String {
     // Normalize paths for Bazzite/Fedora Atomic compatibility
@@ -524,25 +1433,25 @@ fn generate_fix_registry_script(manager_name: &str, prefix_path: &Path, proton_p
 }
 
 /// Generate NXM Toggle script
-fn generate_nxm_toggle_script(app_id: u32, manager_name: &str, install_dir: &Path, prefix_path: &Path, proton_path: &Path) -> String {
+fn generate_nxm_toggle_script(app_id: u32, manager_type: &ManagerType, install_dir: &Path, prefix_path: &Path, proton_path: &Path) -> String {
     // Normalize paths for Bazzite/Fedora Atomic compatibility
     // On these systems, $HOME is /var/home/user but pressure-vessel exposes /home
     let install_str = crate::config::normalize_path_for_steam(&install_dir.to_string_lossy());
     let prefix_str = crate::config::normalize_path_for_steam(&prefix_path.to_string_lossy());
     let proton_str = crate::config::normalize_path_for_steam(&proton_path.to_string_lossy());
 
-    let nxm_exe = format!("{}/nxmhandler.exe", install_str);
+    let nxm_exe = format!("{}/{}", install_str, manager_type.nxm_exe_name());
 
     include_str!("../scripts/nxm_toggle.sh")
         .replace("{{APP_ID}}", &app_id.to_string()) // u32 integer — no escaping needed
-        .replace("{{MANAGER_NAME}}", &shell_dq_escape(manager_name))
+        .replace("{{MANAGER_NAME}}", &shell_dq_escape(manager_type.display_name()))
         .replace("{{NXM_EXE}}", &shell_dq_escape(&nxm_exe))
         .replace("{{PREFIX_PATH}}", &shell_dq_escape(&prefix_str))
         .replace("{{PROTON_PATH}}", &shell_dq_escape(&proton_str))
 }
 
 /// Generate Steam launch script (for manual use outside Steam)
-fn generate_steam_launch_script(app_id: u32, manager_name: &str) -> String {
+pub(super) fn generate_steam_launch_script(app_id: u32, manager_name: &str) -> String {
     // Convert 32-bit AppID to 64-bit Game ID (required for non-Steam shortcuts)
     // Formula: (appid << 32) | 0x02000000
     let game_id: u64 = ((app_id as u64) << 32) | 0x02000000;
@@ -570,3 +1479,229 @@ fn generate_winetricks_gui_script(prefix_path: &Path) -> String {
         .replace("{{PREFIX_PATH}}", &shell_dq_escape(&prefix_str))
 }
 
+/// Launch logs kept by the Launch Log wrapper: `launch.log` plus this many older ones
+const LAUNCH_LOGS_KEPT: u32 = 3;
+
+/// Generate the Launch Log wrapper that Steam runs in front of Proton
+fn generate_launch_log_script(manager_name: &str) -> String {
+    include_str!("../scripts/launch_log.sh")
+        .replace("{{MANAGER_NAME}}", &shell_dq_escape(manager_name))
+        .replace("{{LOG_FILE}}", crate::config::LAUNCH_LOG_FILE)
+        .replace("{{KEEP_LOGS}}", &LAUNCH_LOGS_KEPT.to_string())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_posix_output() {
+        let out = "Filesystem 1-blocks Used Available Capacity Mounted on\n\
+                   /dev/nvme0n1p2 499963174912 200000000000 299963174912 41% /home\n";
+        assert_eq!(parse_df_available_bytes(out), Some(299963174912));
+    }
+
+    #[test]
+    fn test_parse_df_tricky_output() {
+        // Long device name wrapped onto its own line (df without -P)
+        let wrapped = "Filesystem     1B-blocks        Used   Available Use% Mounted on\n\
+                       /dev/mapper/luks-0c5a7e3d-very-long-volume-name\n\
+                                      1000000      400000      600000  40% /mnt/games\n";
+        assert_eq!(parse_df_available_bytes(wrapped), Some(600000));
+
+        // Device name containing spaces
+        let spaced = "Filesystem 1-blocks Used Available Capacity Mounted on\n\
+                      My Drive 2048 1024 1024 50% /mnt/My Drive\n";
+        assert_eq!(parse_df_available_bytes(spaced), Some(1024));
+
+        assert_eq!(parse_df_available_bytes(""), None);
+        assert_eq!(parse_df_available_bytes("df: /nope: No such file or directory\n"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "full")]
+    fn test_statvfs_matches_df() {
+        let dir = std::env::temp_dir();
+        let statvfs = statvfs_available_bytes(&dir).expect("statvfs should work on the temp dir");
+        assert!(bytes_to_gb(statvfs) >= 0.0);
+
+        // df may be missing in minimal containers; only compare when it runs
+        if let Some(df) = df_available_bytes(&dir) {
+            let tolerance = 256 * 1024 * 1024;
+            assert!(statvfs.abs_diff(df) < tolerance, "statvfs {} vs df {}", statvfs, df);
+        }
+    }
+
+    /// Value a generated script assigns to `var`, as bash sees it
+    fn bash_value(script: &str, var: &str) -> String {
+        let assignment = script
+            .lines()
+            .find(|l| l.starts_with(&format!("{}=", var)))
+            .unwrap_or_else(|| panic!("{} not assigned", var));
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(format!("{}\nprintf '%s' \"${}\"", assignment, var))
+            .output()
+            .expect("bash should run");
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_script_paths_with_special_characters() {
+        let install = Path::new("/home/Zoë O'Brien/Mod \"Lists\"/$HOME `id` \\ MO2");
+        let prefix = Path::new("/home/Zoë O'Brien/compat data/pfx");
+        let proton = Path::new("/opt/Proton – Experimental");
+        let manager = ManagerType::Generic { exe_name: "Mod Manager (x64).exe".to_string(), is_electron: false };
+
+        let script = generate_nxm_toggle_script(42, &manager, install, prefix, proton);
+        assert_eq!(
+            bash_value(&script, "NXM_EXE"),
+            format!("{}/Mod Manager (x64).exe", install.display())
+        );
+        assert_eq!(bash_value(&script, "PREFIX_PATH"), prefix.display().to_string());
+        assert_eq!(bash_value(&script, "DEFAULT_PROTON_PATH"), proton.display().to_string());
+
+        let script = generate_winetricks_gui_script(prefix);
+        assert_eq!(bash_value(&script, "PREFIX"), prefix.display().to_string());
+
+        // A newline in a name must not end the comment it is substituted into
+        let script = generate_steam_launch_script(42, "Evil\nrm -rf ~");
+        assert!(!script.lines().any(|l| l.starts_with("rm -rf")));
+    }
+
+    /// Checked-in copies of each manager's NaK Tools scripts, with the test's
+    /// temp dir written as {{TMP}}
+    fn golden_dir(variant: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("src/installers/testdata/golden").join(variant)
+    }
+
+    /// Regenerate with `NAK_UPDATE_GOLDEN=1 cargo test test_nak_tools_scripts_golden`
+    /// after an intended script change, and review the diff.
+    #[test]
+    fn test_nak_tools_scripts_golden() {
+        let update = std::env::var_os("NAK_UPDATE_GOLDEN").is_some();
+        let variants = [
+            ("mo2", ManagerType::MO2),
+            ("vortex", ManagerType::Generic { exe_name: "Vortex.exe".to_string(), is_electron: true }),
+        ];
+
+        for (variant, manager) in variants {
+            let dir = std::env::temp_dir().join(format!("nak_golden_{}_{}", variant, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            let install = dir.join("Mod Manager");
+            let tools_dir = install.join("NaK Tools");
+            fs::create_dir_all(&tools_dir).unwrap();
+
+            let report = regenerate_nak_tools_scripts(
+                &manager,
+                &install,
+                &dir.join("compatdata/pfx"),
+                3_000_000_000,
+                &dir.join("Proton"),
+            )
+            .unwrap();
+            assert_eq!(report.changed_scripts.len(), 6);
+
+            for name in &report.changed_scripts {
+                let path = tools_dir.join(name);
+                let syntax = std::process::Command::new("bash").arg("-n").arg(&path).output().expect("bash should run");
+                assert!(
+                    syntax.status.success(),
+                    "{}/{} has a syntax error: {}",
+                    variant,
+                    name,
+                    String::from_utf8_lossy(&syntax.stderr)
+                );
+
+                let content = fs::read_to_string(&path).unwrap().replace(&dir.display().to_string(), "{{TMP}}");
+                let golden = golden_dir(variant).join(name);
+                if update {
+                    fs::create_dir_all(golden_dir(variant)).unwrap();
+                    fs::write(&golden, &content).unwrap();
+                    continue;
+                }
+                let expected = fs::read_to_string(&golden)
+                    .unwrap_or_else(|_| panic!("missing golden file {}", golden.display()));
+                assert!(
+                    content == expected,
+                    "{}/{} differs from {}; rerun with NAK_UPDATE_GOLDEN=1 if the change is intended",
+                    variant,
+                    name,
+                    golden.display()
+                );
+            }
+
+            let _ = fs::remove_dir_all(&dir);
+        }
+    }
+
+    #[test]
+    fn test_detect_mo2_instance_conflict() {
+        let dir = std::env::temp_dir().join(format!("nak_instance_conflict_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let prefix = dir.join("pfx");
+        let install = dir.join("MO2");
+        let local = prefix.join("drive_c/users/steamuser/AppData/Local");
+        fs::create_dir_all(&local).unwrap();
+        fs::create_dir_all(install.join("Global Instance/Skyrim")).unwrap();
+        std::os::unix::fs::symlink(install.join("Global Instance"), local.join("ModOrganizer")).unwrap();
+
+        // Link alone, or a stub portable ini, is no conflict
+        assert_eq!(detect_mo2_instance_conflict(&prefix, &install), None);
+        fs::write(install.join("ModOrganizer.ini"), "[General]\n").unwrap();
+        assert_eq!(detect_mo2_instance_conflict(&prefix, &install), None);
+
+        // Configured portable instance, but the Global Instance is still empty
+        fs::write(install.join("ModOrganizer.ini"), "[General]\ngameName=Skyrim Special Edition\n").unwrap();
+        assert_eq!(detect_mo2_instance_conflict(&prefix, &install), None);
+
+        fs::write(install.join("Global Instance/Skyrim/ModOrganizer.ini"), "[General]\n").unwrap();
+        assert_eq!(detect_mo2_instance_conflict(&prefix, &install), Some(Mo2InstanceConflict::LastPicked));
+        fs::write(install.join("portable.txt"), "").unwrap();
+        assert_eq!(detect_mo2_instance_conflict(&prefix, &install), Some(Mo2InstanceConflict::PortableActive));
+
+        disable_mo2_global_instance(&prefix).unwrap();
+        assert!(install.join("Global Instance/Skyrim/ModOrganizer.ini").is_file());
+        assert_eq!(detect_mo2_instance_conflict(&prefix, &install), None);
+        assert!(disable_mo2_global_instance(&prefix).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_repair_mo2_global_instance() {
+        let dir = std::env::temp_dir().join(format!("nak_global_instance_repair_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let prefix = dir.join("pfx");
+        let install = dir.join("MO2");
+        let real = prefix.join("drive_c/users/steamuser/AppData/Local/ModOrganizer");
+        fs::create_dir_all(real.join("Skyrim")).unwrap();
+        fs::create_dir_all(real.join("Fallout 4")).unwrap();
+        fs::create_dir_all(install.join("Global Instance/Skyrim")).unwrap();
+        assert_eq!(check_mo2_global_instance(&prefix, &install), GlobalInstanceLink::RealFolder);
+
+        // MO2 kept writing to the real folder after the link was lost
+        let old_ini = install.join("Global Instance/Skyrim/ModOrganizer.ini");
+        fs::write(&old_ini, "old").unwrap();
+        let old = fs::File::options().write(true).open(&old_ini).unwrap();
+        old.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(3600)).unwrap();
+        fs::write(real.join("Skyrim/ModOrganizer.ini"), "new").unwrap();
+        fs::write(real.join("Fallout 4/ModOrganizer.ini"), "fo4").unwrap();
+
+        repair_mo2_global_instance(&prefix, &install).unwrap();
+        assert_eq!(check_mo2_global_instance(&prefix, &install), GlobalInstanceLink::Linked);
+        assert_eq!(fs::read_to_string(&old_ini).unwrap(), "new");
+        assert_eq!(fs::read_to_string(install.join("Global Instance/Skyrim/ModOrganizer.ini.nak-bak")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(real.join("Fallout 4/ModOrganizer.ini")).unwrap(), "fo4");
+
+        fs::remove_file(&real).unwrap();
+        assert_eq!(check_mo2_global_instance(&prefix, &install), GlobalInstanceLink::Missing);
+        std::os::unix::fs::symlink(dir.join("Old MO2/Global Instance"), &real).unwrap();
+        assert!(matches!(check_mo2_global_instance(&prefix, &install), GlobalInstanceLink::WrongTarget(_)));
+        repair_mo2_global_instance(&prefix, &install).unwrap();
+        assert_eq!(check_mo2_global_instance(&prefix, &install), GlobalInstanceLink::Linked);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
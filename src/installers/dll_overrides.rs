@@ -0,0 +1,317 @@
+//! Compare a prefix's DLL overrides against NaK's baseline
+//!
+//! Mods and manual tweaks often leave overrides behind (a stray
+//! `d3d11=native` breaks rendering with Proton's DXVK). This reads the live
+//! `HKCU\Software\Wine\DllOverrides` key and reports what differs from the
+//! overrides NaK applies in `WINE_SETTINGS_REG`.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use super::{prefix_env, WINE_SETTINGS_REG};
+use crate::config::AppConfig;
+use crate::runtime_wrap;
+use crate::steam::SteamProton;
+
+/// Registry key holding Wine's DLL overrides
+const DLL_OVERRIDES_KEY: &str = r"HKEY_CURRENT_USER\Software\Wine\DllOverrides";
+
+/// One difference between a prefix's overrides and the baseline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DllOverrideChange {
+    /// Override present in the prefix but not in the baseline
+    Added { dll: String, mode: String },
+    /// Baseline override missing from the prefix
+    Removed { dll: String, baseline: String },
+    /// Override present in both with a different mode
+    Changed { dll: String, baseline: String, mode: String },
+}
+
+impl fmt::Display for DllOverrideChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DllOverrideChange::Added { dll, mode } => write!(f, "+ {}={}", dll, mode),
+            DllOverrideChange::Removed { dll, baseline } => write!(f, "- {} (baseline {})", dll, baseline),
+            DllOverrideChange::Changed { dll, baseline, mode } => {
+                write!(f, "~ {}={} (baseline {})", dll, mode, baseline)
+            }
+        }
+    }
+}
+
+/// DLL overrides from `WINE_SETTINGS_REG`, keyed by lowercase DLL name
+pub fn baseline_dll_overrides() -> BTreeMap<String, String> {
+    let mut overrides = BTreeMap::new();
+    let mut in_section = false;
+
+    for line in WINE_SETTINGS_REG.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_section = line.trim_matches(|c| c == '[' || c == ']') == DLL_OVERRIDES_KEY;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        // "dwrite"="native,builtin"
+        if let Some((name, value)) = line.split_once('=') {
+            overrides.insert(
+                name.trim_matches('"').to_lowercase(),
+                value.trim_matches('"').to_string(),
+            );
+        }
+    }
+
+    overrides
+}
+
+/// Parse `wine reg query` output into lowercase DLL name -> mode
+fn parse_reg_query(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once("REG_SZ")?;
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some((name.to_lowercase(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// DLL overrides saved in a prefix's `user.reg`, keyed by lowercase DLL name
+///
+/// Reads the file instead of running `wine reg query`, so it's cheap enough
+/// for scans and works on prefixes whose Proton isn't installed. Wine's
+/// `*` prefix (override the DLL even when loaded by path) is dropped.
+pub fn read_user_reg_overrides(prefix_root: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(prefix_root.join("user.reg"))
+        .map(|reg| parse_user_reg_overrides(&reg))
+        .unwrap_or_default()
+}
+
+fn parse_user_reg_overrides(reg: &str) -> BTreeMap<String, String> {
+    let mut overrides = BTreeMap::new();
+    let mut in_section = false;
+    for line in reg.lines() {
+        if line.starts_with('[') {
+            in_section = line.starts_with("[Software\\\\Wine\\\\DllOverrides]");
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        // "*xaudio2_7"="native,builtin"
+        if let Some((name, value)) = line.split_once("\"=\"") {
+            let name = name.trim_start_matches('"').trim_start_matches('*').to_lowercase();
+            overrides.insert(name, value.trim_end_matches('"').to_string());
+        }
+    }
+    overrides
+}
+
+/// Compare two override maps (keys must be lowercase)
+pub fn compare_dll_overrides(
+    baseline: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> Vec<DllOverrideChange> {
+    let mut changes = Vec::new();
+
+    for (dll, mode) in current {
+        match baseline.get(dll) {
+            None => changes.push(DllOverrideChange::Added { dll: dll.clone(), mode: mode.clone() }),
+            Some(base) if !base.eq_ignore_ascii_case(mode) => changes.push(DllOverrideChange::Changed {
+                dll: dll.clone(),
+                baseline: base.clone(),
+                mode: mode.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (dll, base) in baseline {
+        if !current.contains_key(dll) {
+            changes.push(DllOverrideChange::Removed { dll: dll.clone(), baseline: base.clone() });
+        }
+    }
+
+    changes
+}
+
+/// Query a prefix's DLL overrides and report how they differ from the baseline
+pub fn diff_dll_overrides(
+    prefix_root: &Path,
+    proton: &SteamProton,
+) -> Result<Vec<DllOverrideChange>, Box<dyn Error>> {
+    let wine_bin = proton.wine_binary().ok_or_else(|| {
+        format!("Wine binary not found for Proton '{}'", proton.name)
+    })?;
+
+    if !prefix_root.join("user.reg").exists() {
+        return Err(format!("Prefix not initialized: {:?}", prefix_root).into());
+    }
+
+    let envs: Vec<(&str, String)> = vec![
+        ("WINEPREFIX", prefix_root.display().to_string()),
+        ("WINEDEBUG", "-all".to_string()),
+        ("PROTON_USE_XALIA", "0".to_string()),
+    ];
+    let output = runtime_wrap::build_command(&wine_bin, &envs)
+        .arg("reg")
+        .arg("query")
+        .arg(r"HKCU\Software\Wine\DllOverrides")
+        .output()?;
+
+    // `reg query` fails when the key doesn't exist, which just means no overrides
+    let current = if output.status.success() {
+        parse_reg_query(&String::from_utf8_lossy(&output.stdout))
+    } else {
+        BTreeMap::new()
+    };
+
+    Ok(compare_dll_overrides(&baseline_dll_overrides(), &current))
+}
+
+/// .reg file setting DLL overrides (existing overrides not listed are kept)
+fn dll_overrides_reg(overrides: &BTreeMap<String, String>) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut reg = String::from("Windows Registry Editor Version 5.00\n\n[HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides]\n");
+    for (dll, mode) in overrides {
+        reg.push_str(&format!("\"{}\"=\"{}\"\n", escape(dll), escape(mode)));
+    }
+    reg
+}
+
+/// Merge DLL overrides into the prefix registry
+pub(super) fn apply_dll_overrides(
+    prefix_root: &Path,
+    proton: &SteamProton,
+    overrides: &BTreeMap<String, String>,
+) -> Result<(), Box<dyn Error>> {
+    apply_reg(prefix_root, proton, "dll_overrides.reg", &dll_overrides_reg(overrides))
+}
+
+/// `WINE_SETTINGS_REG` without the DLL overrides a prefix already sets
+///
+/// For prefixes set up outside NaK: overrides in `current` (lowercase names)
+/// stay as they are and only the missing baseline overrides are added.
+#[cfg(feature = "full")]
+pub(super) fn baseline_reg_keeping_overrides(current: &BTreeMap<String, String>) -> String {
+    let mut in_section = false;
+    let mut reg = String::new();
+    for line in WINE_SETTINGS_REG.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_section = trimmed.trim_matches(|c| c == '[' || c == ']') == DLL_OVERRIDES_KEY;
+        } else if in_section {
+            if let Some((name, _)) = trimmed.split_once('=') {
+                if current.contains_key(&name.trim_matches('"').to_lowercase()) {
+                    continue;
+                }
+            }
+        }
+        reg.push_str(line);
+        reg.push('\n');
+    }
+    reg
+}
+
+/// Import a .reg file's content into the prefix registry with regedit
+pub(super) fn apply_reg(
+    prefix_root: &Path,
+    proton: &SteamProton,
+    file_name: &str,
+    reg: &str,
+) -> Result<(), Box<dyn Error>> {
+    let tmp_dir = AppConfig::get_tmp_path();
+    fs::create_dir_all(&tmp_dir)?;
+    let reg_file = tmp_dir.join(file_name);
+    fs::write(&reg_file, reg)?;
+
+    let (wine_bin, reg_envs) = prefix_env(prefix_root, proton)?;
+    let status = runtime_wrap::build_command(&wine_bin, &reg_envs)
+        .arg("regedit")
+        .arg(&reg_file)
+        .status();
+    let _ = fs::remove_file(&reg_file);
+
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => Err(format!("regedit exited with code {:?}", s.code()).into()),
+        Err(e) => Err(format!("Failed to run regedit: {}", e).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_baseline_parsed_from_registry_settings() {
+        let baseline = baseline_dll_overrides();
+        assert_eq!(baseline.get("dwrite").map(String::as_str), Some("native,builtin"));
+        assert_eq!(baseline.get("red4ext.dll").map(String::as_str), Some("native,builtin"));
+        // Values from other sections are not overrides
+        assert!(!baseline.contains_key("@"));
+    }
+
+    #[test]
+    #[cfg(feature = "full")]
+    fn test_baseline_reg_keeps_existing_overrides() {
+        let current = BTreeMap::from([("dwrite".to_string(), "builtin".to_string())]);
+        let reg = baseline_reg_keeping_overrides(&current);
+        assert!(!reg.contains("\"dwrite\""));
+        assert!(reg.contains("\"winmm\"=\"native,builtin\""));
+        // Only DLL overrides are filtered
+        assert_eq!(reg.lines().count() + 1, WINE_SETTINGS_REG.lines().count());
+    }
+
+    #[test]
+    fn test_compare_reports_added_removed_changed() {
+        let baseline = baseline_dll_overrides();
+        let mut current = baseline.clone();
+        current.remove("winmm");
+        current.insert("version".to_string(), "builtin".to_string());
+
+        let query = "\r\nHKEY_CURRENT_USER\\Software\\Wine\\DllOverrides\r\n    d3d11    REG_SZ    native\r\n";
+        current.extend(parse_reg_query(query));
+
+        let changes = compare_dll_overrides(&baseline, &current);
+        assert_eq!(changes.len(), 3);
+        assert!(changes.contains(&DllOverrideChange::Added { dll: "d3d11".into(), mode: "native".into() }));
+        assert!(changes.contains(&DllOverrideChange::Removed { dll: "winmm".into(), baseline: "native,builtin".into() }));
+        assert!(changes.contains(&DllOverrideChange::Changed {
+            dll: "version".into(),
+            baseline: "native,builtin".into(),
+            mode: "builtin".into(),
+        }));
+    }
+
+    #[test]
+    fn test_parse_user_reg_overrides() {
+        let reg = "WINE REGISTRY Version 2\n\n[Software\\\\Wine\\\\DllOverrides] 1700000000\n#time=1da\n\
+                   \"*xaudio2_7\"=\"native,builtin\"\n\"d3d11\"=\"builtin\"\n\n\
+                   [Software\\\\Wine\\\\Fonts] 1700000000\n\"LogPixels\"=dword:00000060\n";
+        let overrides = parse_user_reg_overrides(reg);
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides.get("xaudio2_7").map(String::as_str), Some("native,builtin"));
+        assert_eq!(overrides.get("d3d11").map(String::as_str), Some("builtin"));
+    }
+
+    #[test]
+    fn test_dll_overrides_reg() {
+        let overrides = BTreeMap::from([
+            ("d3d11".to_string(), "native,builtin".to_string()),
+            ("*version".to_string(), "native".to_string()),
+        ]);
+        assert_eq!(
+            dll_overrides_reg(&overrides),
+            "Windows Registry Editor Version 5.00\n\n[HKEY_CURRENT_USER\\Software\\Wine\\DllOverrides]\n\
+             \"*version\"=\"native\"\n\"d3d11\"=\"native,builtin\"\n"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! .NET verification for prefixes
+//!
+//! Failed .NET installs are silent: winetricks and the custom installers can
+//! exit cleanly without leaving a runtime behind, and the problem only shows
+//! up later as broken MO2 plugins. This asks the prefix's own `dotnet.exe`
+//! which runtimes and SDKs it can see, falling back to the install folders
+//! when the host won't run.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::runtime_wrap;
+use crate::steam::SteamProton;
+
+/// Runtimes a full NaK prefix should have: (component installing it, framework, major version)
+///
+/// 6-8 come from winetricks, 9 from the .NET 9 SDK, Desktop 10 from the
+/// custom installer.
+pub const EXPECTED_DOTNET_RUNTIMES: &[(&str, &str, u32)] = &[
+    ("dotnet6", "Microsoft.NETCore.App", 6),
+    ("dotnet7", "Microsoft.NETCore.App", 7),
+    ("dotnet8", "Microsoft.NETCore.App", 8),
+    ("dotnet9sdk", "Microsoft.NETCore.App", 9),
+    ("dotnetdesktop6", "Microsoft.WindowsDesktop.App", 6),
+    ("dotnetdesktop10", "Microsoft.WindowsDesktop.App", 10),
+];
+
+/// .NET installs found in a prefix
+#[derive(Debug, Clone, Default)]
+pub struct DotnetReport {
+    /// (framework, version), e.g. ("Microsoft.NETCore.App", "8.0.11")
+    pub runtimes: Vec<(String, String)>,
+    /// SDK versions, e.g. "9.0.310"
+    pub sdks: Vec<String>,
+    /// Whether `dotnet.exe` actually ran in the prefix
+    pub host_runs: bool,
+}
+
+impl DotnetReport {
+    /// Expected runtimes that are missing, as "Microsoft.NETCore.App 8"
+    pub fn missing_expected(&self) -> Vec<String> {
+        self.missing_where(|_| true)
+    }
+
+    /// Like `missing_expected`, but only for runtimes of the given components (e.g. "dotnet8")
+    pub fn missing_for(&self, components: &[String]) -> Vec<String> {
+        self.missing_where(|component| components.iter().any(|c| c == component))
+    }
+
+    fn missing_where(&self, wanted: impl Fn(&str) -> bool) -> Vec<String> {
+        EXPECTED_DOTNET_RUNTIMES
+            .iter()
+            .filter(|(component, _, _)| wanted(component))
+            .filter(|(_, framework, major)| {
+                !self.runtimes.iter().any(|(name, version)| {
+                    name == framework && version.split('.').next() == Some(&major.to_string())
+                })
+            })
+            .map(|(_, framework, major)| format!("{} {}", framework, major))
+            .collect()
+    }
+
+    /// One-line summary for logs and the status line
+    pub fn summary(&self) -> String {
+        let runtimes: Vec<String> = self
+            .runtimes
+            .iter()
+            .map(|(name, version)| {
+                let short = name.trim_start_matches("Microsoft.").trim_end_matches(".App");
+                format!("{} {}", short, version)
+            })
+            .collect();
+        let mut summary = if runtimes.is_empty() {
+            "no .NET runtimes".to_string()
+        } else {
+            runtimes.join(", ")
+        };
+        if !self.sdks.is_empty() {
+            summary.push_str(&format!("; SDK {}", self.sdks.join(", ")));
+        }
+        if !self.host_runs {
+            summary.push_str(" (dotnet.exe did not run)");
+        }
+        summary
+    }
+}
+
+fn dotnet_root(prefix_root: &Path) -> PathBuf {
+    prefix_root.join("drive_c/Program Files/dotnet")
+}
+
+/// Parse `dotnet --list-runtimes` lines: "Microsoft.NETCore.App 8.0.11 [C:\...]"
+fn parse_list_runtimes(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let version = parts.next()?;
+            name.starts_with("Microsoft.").then(|| (name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+/// Parse `dotnet --list-sdks` lines: "9.0.310 [C:\...]"
+fn parse_list_sdks(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|v| v.chars().next().is_some_and(|c| c.is_ascii_digit()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Versions under a folder like `shared/Microsoft.NETCore.App/<version>`
+fn version_dirs(dir: &Path) -> Vec<String> {
+    let mut versions: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    versions.sort();
+    versions
+}
+
+/// Find .NET installs by looking at `Program Files/dotnet` (no Wine needed)
+pub fn scan_dotnet_install(prefix_root: &Path) -> DotnetReport {
+    let root = dotnet_root(prefix_root);
+    let mut runtimes = Vec::new();
+    if let Ok(entries) = fs::read_dir(root.join("shared")) {
+        for entry in entries.flatten() {
+            let framework = entry.file_name().to_string_lossy().into_owned();
+            for version in version_dirs(&entry.path()) {
+                runtimes.push((framework.clone(), version));
+            }
+        }
+    }
+    runtimes.sort();
+
+    DotnetReport { runtimes, sdks: version_dirs(&root.join("sdk")), host_runs: false }
+}
+
+/// Ask the prefix's `dotnet.exe` which runtimes and SDKs it can load
+///
+/// Falls back to `scan_dotnet_install` (with `host_runs` false) if the host
+/// is missing or fails to run.
+pub fn verify_dotnet(prefix_root: &Path, proton: &SteamProton) -> Result<DotnetReport, Box<dyn Error>> {
+    let wine_bin = proton.wine_binary().ok_or_else(|| {
+        format!("Wine binary not found for Proton '{}'", proton.name)
+    })?;
+
+    if !dotnet_root(prefix_root).join("dotnet.exe").exists() {
+        return Ok(scan_dotnet_install(prefix_root));
+    }
+
+    let run = |arg: &str| -> Option<String> {
+        let envs: Vec<(&str, String)> = vec![
+            ("WINEPREFIX", prefix_root.display().to_string()),
+            ("WINEDEBUG", "-all".to_string()),
+            ("DOTNET_CLI_TELEMETRY_OPTOUT", "1".to_string()),
+            ("DOTNET_NOLOGO", "1".to_string()),
+            ("PROTON_USE_XALIA", "0".to_string()),
+        ];
+        let output = runtime_wrap::build_command(&wine_bin, &envs)
+            .arg(r"C:\Program Files\dotnet\dotnet.exe")
+            .arg(arg)
+            .output()
+            .ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+
+    let Some(runtimes_out) = run("--list-runtimes") else {
+        return Ok(scan_dotnet_install(prefix_root));
+    };
+    let mut runtimes = parse_list_runtimes(&runtimes_out);
+    runtimes.sort();
+    let sdks = run("--list-sdks").map(|out| parse_list_sdks(&out)).unwrap_or_default();
+
+    Ok(DotnetReport { runtimes, sdks, host_runs: true })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dotnet_listings() {
+        let runtimes = parse_list_runtimes(
+            "Microsoft.NETCore.App 8.0.11 [C:\\Program Files\\dotnet\\shared\\Microsoft.NETCore.App]\r\n\
+             Microsoft.WindowsDesktop.App 10.0.2 [C:\\Program Files\\dotnet\\shared\\Microsoft.WindowsDesktop.App]\r\n",
+        );
+        assert_eq!(
+            runtimes,
+            vec![
+                ("Microsoft.NETCore.App".to_string(), "8.0.11".to_string()),
+                ("Microsoft.WindowsDesktop.App".to_string(), "10.0.2".to_string()),
+            ]
+        );
+        assert_eq!(parse_list_sdks("9.0.310 [C:\\Program Files\\dotnet\\sdk]\r\n"), vec!["9.0.310"]);
+    }
+
+    #[test]
+    fn test_missing_expected_runtimes() {
+        let report = DotnetReport {
+            runtimes: EXPECTED_DOTNET_RUNTIMES
+                .iter()
+                .filter(|(_, _, major)| *major != 9)
+                .map(|(_, name, major)| (name.to_string(), format!("{}.0.1", major)))
+                .collect(),
+            sdks: Vec::new(),
+            host_runs: true,
+        };
+        assert_eq!(report.missing_expected(), vec!["Microsoft.NETCore.App 9"]);
+        assert!(report.missing_for(&["dotnet8".to_string()]).is_empty());
+        assert_eq!(report.missing_for(&["dotnet8".to_string(), "dotnet9sdk".to_string()]), vec!["Microsoft.NETCore.App 9"]);
+    }
+}
@@ -0,0 +1,237 @@
+//! DXVK version override for a prefix
+//!
+//! Some games (e.g. with ENB) need a specific DXVK release instead of the one
+//! bundled with Proton. This downloads a release from doitsujin/dxvk, copies
+//! the DLLs into the prefix and sets native DLL overrides. Replaced DLLs are
+//! backed up inside the prefix so they can be restored later.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+use crate::github::GithubRelease;
+use crate::logging::{log_download, log_install, log_warning};
+use crate::net::FetchError;
+use crate::runtime_wrap;
+use crate::steam::SteamProton;
+use crate::utils::download_file;
+
+/// GitHub repository for DXVK releases
+const DXVK_REPO: &str = "doitsujin/dxvk";
+
+/// Backup folder (inside the prefix root) for DLLs replaced by a DXVK override
+const BACKUP_DIR: &str = ".nak_dxvk_backup";
+
+/// File listing every DLL NaK copied into the prefix (relative to drive_c/windows)
+const INSTALLED_LIST: &str = "installed.txt";
+
+/// DXVK archive arch folder -> Windows system folder
+const ARCH_TARGETS: &[(&str, &str)] = &[("x64", "system32"), ("x32", "syswow64")];
+
+/// Fetch a DXVK release from GitHub (latest if no tag is given)
+fn fetch_dxvk_release(version_tag: Option<&str>) -> Result<GithubRelease, Box<dyn Error>> {
+    let url = match version_tag {
+        Some(tag) => {
+            // Accept "2.5" as well as "v2.5"
+            let tag = if tag.starts_with('v') { tag.to_string() } else { format!("v{}", tag) };
+            format!("https://api.github.com/repos/{}/releases/tags/{}", DXVK_REPO, tag)
+        }
+        None => format!("https://api.github.com/repos/{}/releases/latest", DXVK_REPO),
+    };
+
+    match (crate::github::fetch_release(&url, "NaK-Rust"), version_tag) {
+        (Ok(release), _) => Ok(release),
+        (Err(FetchError::NotFound(_)), Some(tag)) => Err(format!("No DXVK release tagged {}", tag).into()),
+        (Err(e), _) => Err(e.into()),
+    }
+}
+
+/// Find the directory with the given name anywhere under `root`
+fn find_arch_dir(root: &Path, name: &str) -> Option<PathBuf> {
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_type().is_dir() && e.file_name() == name)
+        .map(|e| e.into_path())
+}
+
+/// Set a DLL override in the prefix registry
+pub(super) fn set_dll_override(
+    wine_bin: &Path,
+    prefix_root: &Path,
+    dll: &str,
+    mode: &str,
+) -> Result<(), Box<dyn Error>> {
+    let envs: Vec<(&str, String)> = vec![
+        ("WINEPREFIX", prefix_root.display().to_string()),
+        ("PROTON_USE_XALIA", "0".to_string()),
+    ];
+    let status = runtime_wrap::build_command(wine_bin, &envs)
+        .arg("reg")
+        .arg("add")
+        .arg(r"HKCU\Software\Wine\DllOverrides")
+        .arg("/v")
+        .arg(dll)
+        .arg("/t")
+        .arg("REG_SZ")
+        .arg("/d")
+        .arg(mode)
+        .arg("/f")
+        .status()?;
+
+    if !status.success() {
+        return Err(format!("Failed to set DLL override for {}: exit code {:?}", dll, status.code()).into());
+    }
+    Ok(())
+}
+
+/// Install a specific DXVK release into a prefix
+///
+/// `version_tag` is a release tag like "v2.5" (None = latest release).
+/// Returns the installed release tag.
+pub fn install_dxvk_version(
+    prefix_root: &Path,
+    proton: &SteamProton,
+    version_tag: Option<&str>,
+) -> Result<String, Box<dyn Error>> {
+    proton.validate_binaries()?;
+    let wine_bin = proton.wine_binary().ok_or_else(|| {
+        format!("Wine binary not found for Proton '{}'", proton.name)
+    })?;
+
+    let windows_dir = prefix_root.join("drive_c/windows");
+    if !windows_dir.exists() {
+        return Err(format!("Prefix not initialized: {:?}", prefix_root).into());
+    }
+
+    // 1. Resolve the release and its archive asset
+    let release = fetch_dxvk_release(version_tag)?;
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| a.name.starts_with("dxvk-") && !a.name.contains("native") && a.name.ends_with(".tar.gz"))
+        .ok_or_else(|| format!("No DXVK archive found in release {}", release.tag_name))?;
+
+    // 2. Download and extract
+    let tmp_dir = AppConfig::get_tmp_path();
+    let archive_path = tmp_dir.join(&asset.name);
+    let extract_dir = tmp_dir.join(format!("dxvk-extract-{}", release.tag_name));
+    log_download(&format!("Downloading DXVK {}: {}", release.tag_name, asset.name));
+    download_file(&asset.browser_download_url, &archive_path)?;
+
+    let _ = fs::remove_dir_all(&extract_dir);
+    fs::create_dir_all(&extract_dir)?;
+    let file = fs::File::open(&archive_path)?;
+    let decoder = flate2::read::GzDecoder::new(file);
+    tar::Archive::new(decoder).unpack(&extract_dir)?;
+
+    // 3. Copy DLLs, backing up whatever Proton put there first
+    let backup_root = prefix_root.join(BACKUP_DIR);
+    let list_path = backup_root.join(INSTALLED_LIST);
+    let mut installed: Vec<String> = fs::read_to_string(&list_path)
+        .map(|s| s.lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default();
+    let mut dll_names: Vec<String> = Vec::new();
+
+    for (arch, target) in ARCH_TARGETS {
+        let Some(src_dir) = find_arch_dir(&extract_dir, arch) else {
+            log_warning(&format!("DXVK archive has no {} folder, skipping", arch));
+            continue;
+        };
+        let target_dir = windows_dir.join(target);
+        fs::create_dir_all(&target_dir)?;
+        fs::create_dir_all(backup_root.join(target))?;
+
+        for entry in fs::read_dir(&src_dir)?.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("dll") {
+                continue;
+            }
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+
+            let rel = format!("{}/{}", target, file_name);
+            let dest = target_dir.join(file_name);
+
+            // Only back up the first time, so repeated installs keep Proton's original
+            if !installed.contains(&rel) {
+                if dest.exists() {
+                    fs::copy(&dest, backup_root.join(&rel))?;
+                }
+                installed.push(rel);
+            }
+
+            fs::copy(&path, &dest)?;
+
+            let stem = file_name.trim_end_matches(".dll").to_string();
+            if !dll_names.contains(&stem) {
+                dll_names.push(stem);
+            }
+        }
+    }
+
+    if dll_names.is_empty() {
+        return Err(format!("No DLLs found in DXVK archive {}", asset.name).into());
+    }
+
+    fs::write(&list_path, installed.join("\n"))?;
+
+    // 4. Force the copied DLLs to load as native
+    for dll in &dll_names {
+        set_dll_override(&wine_bin, prefix_root, dll, "native")?;
+    }
+
+    let _ = fs::remove_file(&archive_path);
+    let _ = fs::remove_dir_all(&extract_dir);
+
+    log_install(&format!(
+        "Installed DXVK {} into {:?} ({})",
+        release.tag_name,
+        prefix_root,
+        dll_names.join(", ")
+    ));
+    Ok(release.tag_name)
+}
+
+/// Revert a DXVK override, restoring the DLLs Proton originally provided
+pub fn revert_dxvk(prefix_root: &Path, proton: &SteamProton) -> Result<(), Box<dyn Error>> {
+    proton.validate_binaries()?;
+    let wine_bin = proton.wine_binary().ok_or_else(|| {
+        format!("Wine binary not found for Proton '{}'", proton.name)
+    })?;
+
+    let backup_root = prefix_root.join(BACKUP_DIR);
+    let list = fs::read_to_string(backup_root.join(INSTALLED_LIST))
+        .map_err(|_| "No DXVK override recorded for this prefix")?;
+
+    let windows_dir = prefix_root.join("drive_c/windows");
+    let mut dll_names: Vec<String> = Vec::new();
+
+    for rel in list.lines().filter(|l| !l.is_empty()) {
+        let dest = windows_dir.join(rel);
+        let backup = backup_root.join(rel);
+        if backup.exists() {
+            fs::copy(&backup, &dest)?;
+        } else {
+            // DLL didn't exist before the override
+            let _ = fs::remove_file(&dest);
+        }
+
+        if let Some(stem) = Path::new(rel).file_stem().and_then(|s| s.to_str()) {
+            if !dll_names.iter().any(|d| d == stem) {
+                dll_names.push(stem.to_string());
+            }
+        }
+    }
+
+    // Back to NaK's default so Proton's own DXVK keeps loading
+    for dll in &dll_names {
+        set_dll_override(&wine_bin, prefix_root, dll, "native,builtin")?;
+    }
+
+    fs::remove_dir_all(&backup_root)?;
+    log_install(&format!("Reverted DXVK override in {:?}", prefix_root));
+    Ok(())
+}
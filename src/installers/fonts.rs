@@ -0,0 +1,153 @@
+//! Fonts for a prefix
+//!
+//! A fresh prefix only has Wine's own fonts, so mods and UIs with non-Latin
+//! text show boxes instead of glyphs. The winetricks font verbs cover the
+//! common sets; other .ttf/.otf files are copied into the prefix's Fonts
+//! folder and registered so Windows apps can find them by name.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::prefix_env;
+use crate::config::AppConfig;
+use crate::deps::run_winetricks;
+use crate::logging::{log_install, log_warning};
+use crate::runtime_wrap;
+use crate::steam::SteamProton;
+
+/// Winetricks font verbs offered in the prefix manager (verb, description)
+pub const FONT_VERBS: &[(&str, &str)] = &[
+    ("corefonts", "Microsoft core fonts (Arial, Times New Roman, Verdana...)"),
+    ("cjkfonts", "Chinese, Japanese and Korean fonts"),
+];
+
+/// File extensions accepted as custom fonts
+pub const FONT_EXTENSIONS: &[&str] = &["ttf", "ttc", "otf"];
+
+/// Install one of the `FONT_VERBS` sets into a prefix via winetricks
+pub fn install_font_verb(
+    prefix_path: &Path,
+    proton: &SteamProton,
+    verb: &str,
+    log_callback: impl Fn(String),
+) -> Result<(), Box<dyn Error>> {
+    if !FONT_VERBS.iter().any(|(v, _)| *v == verb) {
+        return Err(format!("Unknown font set '{}'", verb).into());
+    }
+    run_winetricks(prefix_path, proton, &[verb], log_callback)
+}
+
+/// Registry type suffix Windows uses for a font file ("TrueType" or "OpenType")
+fn font_kind(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "ttf" | "ttc" => Some("TrueType"),
+        "otf" => Some("OpenType"),
+        _ => None,
+    }
+}
+
+/// .reg file registering fonts (file name, kind) under both Fonts keys
+///
+/// The value name is the file stem, e.g. "NotoSansJP-Regular (OpenType)".
+/// Windows apps look fonts up through these keys; Wine also scans the
+/// Fonts folder itself, so the exact family name doesn't have to match.
+fn fonts_reg_content(fonts: &[(String, &str)]) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut values = String::new();
+    for (file_name, kind) in fonts {
+        let stem = Path::new(file_name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file_name.clone());
+        values.push_str(&format!("\"{} ({})\"=\"{}\"\n", escape(&stem), kind, escape(file_name)));
+    }
+
+    format!(
+        "Windows Registry Editor Version 5.00\n\n\
+         [HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts]\n{values}\n\
+         [HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows\\CurrentVersion\\Fonts]\n{values}"
+    )
+}
+
+/// Copy font files into the prefix's Fonts folder and register them
+///
+/// Files that aren't .ttf/.ttc/.otf are skipped with a warning, and an
+/// existing font with the same file name is replaced. Returns the file
+/// names installed.
+pub fn install_custom_fonts(
+    prefix_path: &Path,
+    proton: &SteamProton,
+    files: &[PathBuf],
+    log_callback: &impl Fn(String),
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let fonts_dir = prefix_path.join("drive_c/windows/Fonts");
+    if !fonts_dir.is_dir() {
+        return Err(format!("{} has no Fonts folder - is the prefix set up?", prefix_path.display()).into());
+    }
+
+    let mut installed = Vec::new();
+    for file in files {
+        let (Some(kind), Some(name)) = (font_kind(file), file.file_name()) else {
+            log_warning(&format!("Skipping {}: not a .ttf, .ttc or .otf font", file.display()));
+            continue;
+        };
+        let name = name.to_string_lossy().into_owned();
+        fs::copy(file, fonts_dir.join(&name))?;
+        log_callback(format!("Copied {}", name));
+        installed.push((name, kind));
+    }
+    if installed.is_empty() {
+        return Err("No font files to install".into());
+    }
+
+    let tmp_dir = AppConfig::get_tmp_path();
+    fs::create_dir_all(&tmp_dir)?;
+    let reg_file = tmp_dir.join("custom_fonts.reg");
+    fs::write(&reg_file, fonts_reg_content(&installed))?;
+
+    let (wine_bin, reg_envs) = prefix_env(prefix_path, proton)?;
+    log_callback("Registering fonts...".to_string());
+    let status = runtime_wrap::build_command(&wine_bin, &reg_envs)
+        .arg("regedit")
+        .arg(&reg_file)
+        .status();
+    let _ = fs::remove_file(&reg_file);
+
+    match status {
+        Ok(s) if s.success() => {}
+        Ok(s) => return Err(format!("regedit exited with code {:?}", s.code()).into()),
+        Err(e) => return Err(format!("Failed to run regedit: {}", e).into()),
+    }
+
+    let names: Vec<String> = installed.into_iter().map(|(name, _)| name).collect();
+    log_install(&format!("Installed fonts into {}: {}", prefix_path.display(), names.join(", ")));
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_kind() {
+        assert_eq!(font_kind(Path::new("/tmp/NotoSans.TTF")), Some("TrueType"));
+        assert_eq!(font_kind(Path::new("msgothic.ttc")), Some("TrueType"));
+        assert_eq!(font_kind(Path::new("SourceHanSans.otf")), Some("OpenType"));
+        assert_eq!(font_kind(Path::new("readme.txt")), None);
+        assert_eq!(font_kind(Path::new("noext")), None);
+    }
+
+    #[test]
+    fn test_fonts_reg_content() {
+        let reg = fonts_reg_content(&[("NotoSansJP-Regular.otf".to_string(), "OpenType")]);
+        assert!(reg.starts_with("Windows Registry Editor Version 5.00\n"));
+        assert!(reg.contains("[HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows NT\\CurrentVersion\\Fonts]\n"));
+        assert!(reg.contains("[HKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows\\CurrentVersion\\Fonts]\n"));
+        assert_eq!(reg.matches("\"NotoSansJP-Regular (OpenType)\"=\"NotoSansJP-Regular.otf\"\n").count(), 2);
+
+        let reg = fonts_reg_content(&[("Odd \"Name\".ttf".to_string(), "TrueType")]);
+        assert!(reg.contains("\"Odd \\\"Name\\\" (TrueType)\"=\"Odd \\\"Name\\\".ttf\""));
+    }
+}
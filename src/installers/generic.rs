@@ -0,0 +1,241 @@
+//! Generic mod manager installation (Steam-native)
+//!
+//! For Windows mod managers NaK has no dedicated installer for (Kortex, MO2
+//! forks, game-specific tools). The user supplies the executable name and
+//! either a download URL or a folder, and gets the same prefix, dependencies
+//! and NaK Tools scripts as an MO2 install.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::common::{
+    apply_instance_launch_toggles, check_cancelled, check_disk_space, copy_dir_all,
+    finalize_steam_installation_with_tools, get_dxvk_conf_path, InstallError, ManagerType,
+};
+use super::plugin::{extract_7z, extract_zip};
+use super::{install_all_dependencies, InstallPhase, TaskContext};
+use crate::config::{AppConfig, ManagedPrefixes};
+use crate::logging::{log_download, log_error, log_install};
+use crate::steam::{self, SteamProton};
+use crate::utils::download_file;
+
+/// Minimum disk space required for a generic manager installation (in GB)
+const MIN_DISK_SPACE_GB: f64 = 5.0;
+
+/// Where a generic mod manager's files come from
+#[derive(Debug, Clone)]
+pub enum GenericSource {
+    /// A .zip or .7z archive, or the manager's exe itself
+    Url(String),
+    /// A folder that already contains the manager. Used in place when it is
+    /// the install path, copied otherwise.
+    LocalFolder(PathBuf),
+}
+
+/// Result of a generic manager installation
+pub struct GenericInstallResult {
+    /// Steam AppID for the shortcut
+    pub app_id: u32,
+    /// Path to the Wine prefix
+    pub prefix_path: PathBuf,
+}
+
+/// Put the manager's files into `install_path` from a download
+fn install_from_url(url: &str, exe_name: &str, install_path: &Path, ctx: &TaskContext) -> Result<(), Box<dyn Error>> {
+    let filename = url
+        .split('?')
+        .next()
+        .and_then(|u| u.split('/').next_back())
+        .filter(|f| !f.is_empty())
+        .unwrap_or(exe_name);
+
+    ctx.set_phase(InstallPhase::Download);
+    ctx.set_status(format!("Downloading {}...", filename));
+    log_download(&format!("Downloading {}", url));
+
+    let tmp_dir = AppConfig::get_tmp_path();
+    fs::create_dir_all(&tmp_dir)?;
+    let download_path = tmp_dir.join(filename);
+    download_file(url, &download_path)?;
+    log_download(&format!("Downloaded to: {:?}", download_path));
+
+    check_cancelled(ctx)?;
+
+    ctx.set_phase(InstallPhase::Extract);
+    ctx.set_status(format!("Extracting {}...", filename));
+    let lower = filename.to_lowercase();
+    let result = if lower.ends_with(".zip") {
+        extract_zip(&download_path, install_path)
+    } else if lower.ends_with(".7z") {
+        extract_7z(&download_path, install_path)
+    } else if lower.ends_with(".exe") {
+        // A bare portable exe
+        fs::copy(&download_path, install_path.join(exe_name))
+            .map(|_| ())
+            .map_err(|e| e.into())
+    } else {
+        Err(InstallError::Other {
+            context: "Generic manager download".to_string(),
+            reason: format!("Unsupported file type '{}' (expected .zip, .7z or .exe)", filename),
+        }.into())
+    };
+
+    let _ = fs::remove_file(&download_path);
+    result
+}
+
+/// Install a mod manager NaK has no dedicated installer for
+///
+/// Creates the Steam shortcut and prefix, fetches the files from `source`,
+/// installs the standard dependencies and generates the NaK Tools scripts
+/// named after the executable.
+#[allow(clippy::too_many_arguments)]
+pub fn install_generic_manager(
+    install_name: &str,
+    exe_name: &str,
+    is_electron: bool,
+    source: &GenericSource,
+    install_path: PathBuf,
+    proton: &SteamProton,
+    ctx: TaskContext,
+    skip_disk_check: bool,
+) -> Result<GenericInstallResult, Box<dyn Error>> {
+    proton.validate_binaries()?;
+    let exe_name = exe_name.trim();
+    if exe_name.is_empty() || !exe_name.to_lowercase().ends_with(".exe") {
+        return Err(InstallError::Other {
+            context: "Generic manager".to_string(),
+            reason: format!("'{}' is not a Windows executable name", exe_name),
+        }.into());
+    }
+    let manager_type = ManagerType::Generic { exe_name: exe_name.to_string(), is_electron };
+
+    log_install(&format!(
+        "Starting {} installation: {} -> {:?} (source: {:?})",
+        manager_type, install_name, install_path, source
+    ));
+
+    // Get primary Steam path (where prefixes are always created)
+    let steam_path = steam::find_steam_path()
+        .ok_or_else(|| InstallError::SteamError { reason: "Steam not found".to_string() })?;
+
+    if !skip_disk_check {
+        if let Err(e) = check_disk_space(&steam_path, MIN_DISK_SPACE_GB) {
+            log_error(&format!("Disk space check failed: {}", e));
+            return Err(e.into());
+        }
+    }
+
+    // A local folder must already contain the exe, so check before touching Steam
+    if let GenericSource::LocalFolder(folder) = source {
+        if !folder.join(exe_name).exists() {
+            return Err(InstallError::ExeNotFound {
+                exe_name: exe_name.to_string(),
+                path: folder.display().to_string(),
+            }.into());
+        }
+    }
+
+    check_cancelled(&ctx)?;
+
+    // 1. Create Steam shortcut
+    ctx.set_status("Creating Steam shortcut...".to_string());
+    ctx.set_progress(0.05);
+
+    let exe_path = install_path.join(exe_name);
+    let dxvk_conf_path = get_dxvk_conf_path(&install_path);
+    let steam_result = steam::add_mod_manager_shortcut(
+        install_name,
+        exe_path.to_str().ok_or("Exe path contains non-UTF-8 characters")?,
+        install_path.to_str().ok_or("Install path contains non-UTF-8 characters")?,
+        &proton.config_name,
+        Some(&dxvk_conf_path),
+        is_electron,
+    ).map_err(|e| InstallError::SteamError { reason: e.to_string() })?;
+    // Steam only needs to stay closed for the shortcut write, not the whole install
+    steam::finish_vdf_write(steam_result.steam_was_closed);
+
+    log_install(&format!("Created Steam shortcut with AppID: {}", steam_result.app_id));
+
+    check_cancelled(&ctx)?;
+
+    // 2. Create install directory
+    ctx.set_status("Creating directories...".to_string());
+    ctx.set_progress(0.08);
+
+    fs::create_dir_all(&install_path).map_err(|e| InstallError::DirectoryCreation {
+        path: install_path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+
+    check_cancelled(&ctx)?;
+
+    // 3. Get the manager's files
+    ctx.set_progress(0.10);
+    match source {
+        GenericSource::Url(url) => install_from_url(url, exe_name, &install_path, &ctx)?,
+        GenericSource::LocalFolder(folder) => {
+            let same = fs::canonicalize(folder).ok() == fs::canonicalize(&install_path).ok();
+            if !same {
+                ctx.set_status(format!("Copying {}...", manager_type));
+                copy_dir_all(folder, &install_path).map_err(|e| InstallError::Other {
+                    context: format!("Copying {:?}", folder),
+                    reason: e.to_string(),
+                })?;
+            }
+        }
+    }
+    ctx.set_progress(0.20);
+
+    // 4. Verify executable exists
+    if !exe_path.exists() {
+        log_error(&format!("{} not found after installation", exe_name));
+        return Err(InstallError::ExeNotFound {
+            exe_name: exe_name.to_string(),
+            path: install_path.display().to_string(),
+        }.into());
+    }
+
+    check_cancelled(&ctx)?;
+
+    // 5. Initialize prefix and install dependencies
+    install_all_dependencies(&steam_result.prefix_path, proton, &ctx, 0.20, 0.90, steam_result.app_id)?;
+
+    ctx.set_progress(0.92);
+
+    check_cancelled(&ctx)?;
+
+    // 6. Finalize installation (creates NaK Tools folder)
+    finalize_steam_installation_with_tools(
+        &manager_type,
+        &steam_result.prefix_path,
+        &install_path,
+        steam_result.app_id,
+        &proton.path,
+        &ctx,
+    )?;
+
+    // 7. Register prefix for cleanup tracking
+    ctx.set_phase(InstallPhase::SteamIntegration);
+    ManagedPrefixes::register(
+        steam_result.app_id,
+        install_name,
+        steam_result.prefix_path.to_str().unwrap_or(""),
+        install_path.to_str().unwrap_or(""),
+        manager_type.clone(),
+        steam_path.to_str().unwrap_or(""),
+        Some(&proton.config_name),
+    );
+    log_install("Registered prefix for cleanup tracking");
+    apply_instance_launch_toggles(steam_result.app_id);
+
+    ctx.set_progress(1.0);
+    ctx.set_status(format!("{} Installed! Restart Steam to see it.", manager_type));
+    log_install(&format!("{} installation complete: {}", manager_type, install_name));
+
+    Ok(GenericInstallResult {
+        app_id: steam_result.app_id,
+        prefix_path: steam_result.prefix_path,
+    })
+}
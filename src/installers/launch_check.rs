@@ -0,0 +1,308 @@
+//! Static check of an instance's launch setup
+//!
+//! Clicking Play goes through several pieces NaK wrote at install time: the
+//! Steam shortcut, the Proton mapping in config.vdf, the prefix and the
+//! NaK Tools launch script. Any of them can break later (Proton removed,
+//! folder moved, shortcut deleted) and Steam only says the game failed to
+//! start. This checks each piece without launching anything, and reads
+//! Proton's own log (PROTON_LOG=1) for known failures after a launch.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::ManagedPrefix;
+use crate::steam::{dxvk_config_option, get_compat_tool, ShortcutsVdf, SteamProton};
+
+use super::common::{find_manager_exe, generate_steam_launch_script};
+use super::prefix_version_mismatch;
+
+/// A problem that will stop (or likely break) a launch
+#[derive(Debug, Clone)]
+pub struct LaunchIssue {
+    /// What is wrong
+    pub problem: String,
+    /// What to do about it
+    pub fix: String,
+}
+
+impl LaunchIssue {
+    fn new(problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { problem: problem.into(), fix: fix.into() }
+    }
+}
+
+/// Check everything a launch of `prefix` depends on
+///
+/// `protons` are the Protons Steam can currently see. Returns an empty list
+/// when the setup looks launchable.
+pub fn validate_launch_setup(prefix: &ManagedPrefix, protons: &[SteamProton]) -> Vec<LaunchIssue> {
+    let mut issues = Vec::new();
+    let install_dir = Path::new(&prefix.install_path);
+    let prefix_path = Path::new(&prefix.prefix_path);
+    let manager_name = prefix.manager_type.display_name();
+
+    // 1. Mod manager folder and executable
+    if !install_dir.is_dir() {
+        issues.push(LaunchIssue::new(
+            format!("Install folder {} does not exist", install_dir.display()),
+            "Use Relocate Install if you moved it",
+        ));
+    } else if find_manager_exe(&prefix.manager_type, install_dir).is_none() {
+        issues.push(LaunchIssue::new(
+            format!("No {} executable in {}", manager_name, install_dir.display()),
+            "Reinstall the mod manager into this folder",
+        ));
+    }
+
+    // 2. Prefix
+    if !prefix_path.join("drive_c").is_dir() {
+        issues.push(LaunchIssue::new(
+            format!("Prefix {} is missing or was never initialized", prefix_path.display()),
+            "Use Reset Prefix (keep mods) to rebuild it",
+        ));
+    }
+
+    // 3. Proton NaK set the instance up with
+    match prefix.proton_config_name.as_deref() {
+        None => issues.push(LaunchIssue::new(
+            "No Proton is recorded for this instance",
+            "Use Update Scripts to pick one",
+        )),
+        Some(name) => match protons.iter().find(|p| p.config_name == name) {
+            None => issues.push(LaunchIssue::new(
+                format!("Proton '{}' is no longer installed", name),
+                "Install it again, or use Update Scripts to switch Proton",
+            )),
+            Some(proton) => {
+                if let Err(e) = proton.validate_binaries() {
+                    issues.push(LaunchIssue::new(e, "Or use Update Scripts to switch Proton"));
+                } else if let Some(mismatch) = prefix_version_mismatch(prefix_path, proton) {
+                    issues.push(LaunchIssue::new(
+                        format!(
+                            "Prefix was last updated by {}, not {}",
+                            mismatch.prefix_build, mismatch.proton_build
+                        ),
+                        "Use Upgrade Prefix",
+                    ));
+                }
+            }
+        },
+    }
+
+    // 4. Steam shortcut
+    let shortcut = match ShortcutsVdf::load() {
+        Ok(vdf) => vdf.shortcuts.into_iter().find(|s| s.appid == prefix.app_id),
+        Err(e) => {
+            issues.push(LaunchIssue::new(format!("Could not read Steam shortcuts: {}", e), "Check that Steam is installed"));
+            None
+        }
+    };
+    match &shortcut {
+        None => issues.push(LaunchIssue::new(
+            format!("There is no Steam shortcut for AppID {}", prefix.app_id),
+            "Use Add to Steam",
+        )),
+        Some(shortcut) => {
+            let exe = shortcut.exe.trim_matches('"');
+            if !Path::new(exe).is_file() {
+                issues.push(LaunchIssue::new(
+                    format!("The Steam shortcut points at {}, which does not exist", exe),
+                    "Use Relocate Install or Add to Steam",
+                ));
+            }
+            let start_dir = shortcut.start_dir.trim_matches('"');
+            if !Path::new(start_dir).is_dir() {
+                issues.push(LaunchIssue::new(
+                    format!("The Steam shortcut starts in {}, which does not exist", start_dir),
+                    "Use Relocate Install or Add to Steam",
+                ));
+            }
+            if let Some(conf) = dxvk_config_option(&shortcut.launch_options) {
+                if !Path::new(&conf).is_file() {
+                    issues.push(LaunchIssue::new(
+                        format!("DXVK_CONFIG_FILE points at {}, which does not exist", conf),
+                        "Use Reset DXVK Config",
+                    ));
+                }
+            }
+        }
+    }
+
+    // 5. Proton Steam will actually launch with
+    if shortcut.is_some() {
+        match get_compat_tool(prefix.app_id) {
+            None => issues.push(LaunchIssue::new(
+                "Steam has no Proton set for the shortcut, so it will try to run the .exe natively",
+                "Use Add to Steam, or pick a Proton under Properties > Compatibility",
+            )),
+            Some(tool) if !protons.iter().any(|p| p.config_name == tool) => issues.push(LaunchIssue::new(
+                format!("Steam is set to launch with '{}', which is not installed", tool),
+                "Pick an installed Proton under Properties > Compatibility",
+            )),
+            Some(_) => {}
+        }
+    }
+
+    // 6. NaK Tools launch script and prefix link
+    let tools_dir = install_dir.join("NaK Tools");
+    if install_dir.is_dir() {
+        let script = tools_dir.join(format!("Launch {}.sh", manager_name));
+        match fs::read_to_string(&script) {
+            Err(_) => issues.push(LaunchIssue::new(
+                format!("{} is missing", script.display()),
+                "Use Update Scripts",
+            )),
+            Ok(content) if content != generate_steam_launch_script(prefix.app_id, manager_name) => {
+                issues.push(LaunchIssue::new(
+                    format!("{} launches a different AppID or is out of date", script.display()),
+                    "Use Update Scripts",
+                ))
+            }
+            Ok(_) => {}
+        }
+        if fs::read_link(tools_dir.join("Wine Prefix")).ok().as_deref() != Some(prefix_path) {
+            issues.push(LaunchIssue::new(
+                "NaK Tools/Wine Prefix does not point at this instance's prefix",
+                "Use Update Scripts",
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Plain-text report of `validate_launch_setup`, for the report dialog and logs
+pub fn render_launch_check(issues: &[LaunchIssue]) -> String {
+    if issues.is_empty() {
+        return "No problems found - the launch setup looks good.\n".to_string();
+    }
+    let mut out = format!("{} problem(s) found:\n", issues.len());
+    for issue in issues {
+        out.push_str(&format!("  - {}\n    Fix: {}\n", issue.problem, issue.fix));
+    }
+    out
+}
+
+// ============================================================================
+// Proton Log Diagnosis
+// ============================================================================
+
+/// Where Proton writes its log for `app_id` when launched with PROTON_LOG=1
+pub fn proton_log_path(app_id: u32) -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_default();
+    PathBuf::from(home).join(format!("steam-{}.log", app_id))
+}
+
+/// Known failure signatures: (matches a line, problem, fix)
+type LogSignature = (fn(&str) -> bool, &'static str, &'static str);
+
+const LOG_SIGNATURES: &[LogSignature] = &[
+    (
+        |line| {
+            let lower = line.to_ascii_lowercase();
+            lower.contains("import_dll") && (lower.contains("msvcp") || lower.contains("vcruntime") || lower.contains("vcomp"))
+        },
+        "The Visual C++ runtime (vcrun) is missing from the prefix",
+        "Use Reset Prefix (keep mods) to reinstall the dependencies",
+    ),
+    (
+        |line| {
+            let lower = line.to_ascii_lowercase();
+            lower.contains("d3dcompiler_4") && (lower.contains("not found") || lower.contains("failed"))
+        },
+        "d3dcompiler_47.dll failed to load",
+        "Use Reset Prefix (keep mods) to reinstall the dependencies",
+    ),
+    (
+        |line| line.contains("err:mscoree") || line.contains("CLR error"),
+        ".NET Framework is missing or broken in the prefix",
+        "Use Reset Prefix (keep mods) to reinstall the dependencies",
+    ),
+    (
+        |line| line.contains("pressure-vessel") && (line.contains("Unable to") || line.contains("not shared") || line.contains("cannot")),
+        "Steam's container could not mount a folder the instance uses",
+        "Make sure the drive holding the mods is mounted, then use Add to Steam to refresh the launch options",
+    ),
+    (
+        |line| line.contains("EBADF") || line.contains("Bad file descriptor"),
+        "A Chromium/Electron process failed with a bad file descriptor (EBADF)",
+        "Use Fix Electron Sandbox",
+    ),
+    (
+        |line| line.contains("wine: cannot find"),
+        "Wine could not find the program to start",
+        "Use Validate Launch Setup, or Relocate Install if you moved the mod manager",
+    ),
+];
+
+/// Longest log line quoted in a problem
+const MAX_QUOTED_LINE: usize = 160;
+
+/// Scan Proton log text for known failure signatures
+///
+/// Each signature is reported once, quoting the first line that matched.
+pub fn diagnose_log_text(content: &str) -> Vec<LaunchIssue> {
+    let mut issues = Vec::new();
+    for (matches, problem, fix) in LOG_SIGNATURES {
+        if let Some(line) = content.lines().find(|line| matches(line)) {
+            let mut quoted: String = line.trim().chars().take(MAX_QUOTED_LINE).collect();
+            if quoted.len() < line.trim().len() {
+                quoted.push_str("...");
+            }
+            issues.push(LaunchIssue::new(format!("{}\n    Log: {}", problem, quoted), *fix));
+        }
+    }
+    issues
+}
+
+/// Scan a Proton log file (see `proton_log_path`) for known failure signatures
+pub fn diagnose_proton_log(path: &Path) -> std::io::Result<Vec<LaunchIssue>> {
+    let bytes = fs::read(path)?;
+    Ok(diagnose_log_text(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Lines of the launch log shown in the viewer
+const LAUNCH_LOG_TAIL_LINES: usize = 200;
+
+/// The last `max_lines` lines of `content`
+fn tail_lines(content: &str, max_lines: usize) -> &str {
+    let Some((pos, _)) = content.trim_end().rmatch_indices('\n').nth(max_lines.saturating_sub(1)) else {
+        return content;
+    };
+    &content[pos + 1..]
+}
+
+/// Read the latest launch log written by NaK Tools' Launch Log wrapper
+///
+/// Returns the end of the log and the known failures found anywhere in it.
+pub fn read_launch_log(path: &Path) -> std::io::Result<(String, Vec<LaunchIssue>)> {
+    let bytes = fs::read(path)?;
+    let content = String::from_utf8_lossy(&bytes);
+    Ok((tail_lines(&content, LAUNCH_LOG_TAIL_LINES).to_string(), diagnose_log_text(&content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnose_log_text() {
+        let log = "\
+7.123:0020:0024:err:module:import_dll Library MSVCP140.dll (which is needed by L\"C:\\\\MO2\\\\ModOrganizer.exe\") not found
+7.200:0020:0024:err:module:import_dll Library MSVCP140.dll (which is needed by L\"C:\\\\MO2\\\\uibase.dll\") not found
+pressure-vessel-wrap[1234]: W: Unable to mount /mnt/games: No such file or directory
+wine: cannot find L\"C:\\\\Modding\\\\MO2\\\\ModOrganizer.exe\"
+";
+        let issues = diagnose_log_text(log);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].problem.starts_with("The Visual C++ runtime"));
+        assert!(issues[0].problem.contains("ModOrganizer.exe"));
+        assert!(issues[1].problem.contains("/mnt/games"));
+        assert!(issues[2].problem.starts_with("Wine could not find"));
+
+        assert!(diagnose_log_text("fixme:heap:RtlSetHeapInformation\n").is_empty());
+
+        assert_eq!(tail_lines("a\nb\nc\n", 2), "b\nc\n");
+        assert_eq!(tail_lines("a\nb", 5), "a\nb");
+    }
+}
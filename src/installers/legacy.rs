@@ -0,0 +1,431 @@
+//! Migration of prefixes from the old bash NaK
+//!
+//! The bash version kept its own Wine prefixes under `~/NaK/Prefixes/<name>`
+//! and ran them with the Proton in `~/NaK/ProtonGE`. The Rust version only
+//! manages Steam shortcuts whose prefix lives in `compatdata/<appid>/pfx`.
+//! A legacy prefix is migrated by creating the shortcut for its mod manager
+//! and moving the prefix into the shortcut's compatdata folder, so installed
+//! dependencies and game registry entries are kept.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::common::{
+    apply_instance_launch_toggles, finalize_steam_installation_with_tools, find_manager_exe, get_dxvk_conf_path,
+};
+use super::TaskContext;
+use crate::config::{AppConfig, ManagedPrefixes, ManagerType};
+use crate::logging::{log_install, log_warning};
+use crate::steam::{self, SteamProton};
+
+/// Symlink next to a legacy prefix pointing at its mod manager folder
+const MANAGER_LINK: &str = "manager_link";
+
+/// Folders under drive_c searched for a mod manager when `manager_link` doesn't lead to one
+const DEFAULT_SEARCH_DIRS: &[&str] = &["Program Files", "Program Files (x86)", "Modding", "Games"];
+
+/// Default depth of that search, enough for `Program Files/<Game>/Tools/MO2`
+const DEFAULT_SEARCH_DEPTH: usize = 6;
+
+/// Where to look for the mod manager of a legacy prefix without a usable `manager_link`
+#[derive(Debug, Clone)]
+pub struct ManagerSearch {
+    pub roots: Vec<PathBuf>,
+    pub max_depth: usize,
+}
+
+impl ManagerSearch {
+    /// The default drive_c folders plus `manager_search_dirs` from the config
+    pub fn for_prefix(prefix_dir: &Path, config: &AppConfig) -> Self {
+        let drive_c = prefix_dir.join("drive_c");
+        let mut roots: Vec<PathBuf> = DEFAULT_SEARCH_DIRS.iter().map(|d| drive_c.join(d)).collect();
+        for dir in config.manager_search_dirs.iter().map(|d| d.trim()).filter(|d| !d.is_empty()) {
+            // Absolute paths may point outside the prefix
+            let root = drive_c.join(dir);
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        let max_depth = match config.manager_search_depth {
+            0 => DEFAULT_SEARCH_DEPTH,
+            depth => depth,
+        };
+        Self { roots, max_depth }
+    }
+}
+
+/// Find the mod manager folder a legacy prefix belongs to
+///
+/// A `manager_link` next to the prefix wins wherever it points, including
+/// outside the prefix, as long as its target still holds a mod manager.
+/// Otherwise the search roots are walked (without following symlinks) for a
+/// folder with ModOrganizer.exe. On failure, returns the folders searched.
+pub fn find_mod_manager_install_dir(dir: &Path, search: &ManagerSearch) -> Result<PathBuf, Vec<PathBuf>> {
+    if let Some(target) = read_manager_link(dir) {
+        if target.is_dir() && manager_type_for(&target).is_some() {
+            return Ok(target);
+        }
+    }
+
+    let mut searched = Vec::new();
+    for root in search.roots.iter().filter(|r| r.is_dir()) {
+        searched.push(root.clone());
+        let found = walkdir::WalkDir::new(root)
+            .max_depth(search.max_depth)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| {
+                // Unreadable folders and symlink trouble are skipped, not fatal
+                e.map_err(|err| log_warning(&format!("Skipping while searching {}: {}", root.display(), err)))
+                    .ok()
+            })
+            .find(|e| e.file_type().is_dir() && e.path().join("ModOrganizer.exe").is_file());
+        if let Some(entry) = found {
+            return Ok(entry.into_path());
+        }
+    }
+    Err(searched)
+}
+
+/// Target of the `manager_link` in a legacy prefix folder, relative links resolved
+fn read_manager_link(dir: &Path) -> Option<PathBuf> {
+    fs::read_link(dir.join(MANAGER_LINK))
+        .ok()
+        .map(|target| if target.is_relative() { dir.join(target) } else { target })
+}
+
+/// A prefix found in the legacy `Prefixes` folder
+#[derive(Debug, Clone)]
+pub struct LegacyPrefix {
+    /// Folder name, used as the Steam shortcut name
+    pub name: String,
+    /// The Wine prefix itself (the folder holding drive_c)
+    pub prefix_dir: PathBuf,
+    /// Mod manager folder the prefix belongs to, if it could be found
+    pub install_dir: Option<PathBuf>,
+    /// Why the prefix can't be migrated (None = can be migrated)
+    pub problem: Option<String>,
+}
+
+/// What happened to each legacy prefix in `migrate_legacy_prefixes`
+#[derive(Debug, Clone, Default)]
+pub struct LegacyMigrationReport {
+    /// Migrated prefixes: (name, new AppID)
+    pub migrated: Vec<(String, u32)>,
+    /// Prefixes left in place: (name, reason)
+    pub skipped: Vec<(String, String)>,
+}
+
+impl LegacyMigrationReport {
+    /// Multi-line summary for the report dialog
+    pub fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        for (name, app_id) in &self.migrated {
+            lines.push(format!("{}: migrated (AppID {})", name, app_id));
+        }
+        for (name, reason) in &self.skipped {
+            lines.push(format!("{}: NOT migrated - {}", name, reason));
+        }
+        if lines.is_empty() {
+            lines.push("No old NaK prefixes found.".to_string());
+        }
+        if !self.skipped.is_empty() {
+            lines.push(String::new());
+            lines.push(
+                "Prefixes that weren't migrated are left where they were. Set their mod manager up again \
+                 with \"Set Up Existing MO2\" (or the matching installer) to get a new prefix."
+                    .to_string(),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+/// Whether a prefix was created as 32-bit (`WINEARCH=win32`)
+///
+/// Proton only runs 64-bit prefixes, so these can't be moved over.
+pub(super) fn is_win32_prefix(prefix_dir: &Path) -> bool {
+    crate::deps::prefix_arch(prefix_dir).as_deref() == Some("win32")
+}
+
+/// Inspect one folder of the legacy `Prefixes` folder
+fn inspect_legacy_prefix(dir: &Path, config: &AppConfig) -> Option<LegacyPrefix> {
+    let name = dir.file_name()?.to_string_lossy().into_owned();
+    // Proton-style `<name>/pfx`, or the folder itself is the Wine prefix
+    let prefix_dir = if dir.join("pfx/drive_c").is_dir() {
+        dir.join("pfx")
+    } else if dir.join("drive_c").is_dir() {
+        dir.to_path_buf()
+    } else {
+        return None;
+    };
+
+    let search = ManagerSearch::for_prefix(&prefix_dir, config);
+    let install_dir = match find_mod_manager_install_dir(dir, &search) {
+        Ok(install_dir) => Some(install_dir),
+        Err(searched) => {
+            let searched: Vec<String> = searched.iter().map(|p| p.display().to_string()).collect();
+            log_warning(&format!(
+                "No mod manager found for legacy prefix {} (searched {} levels deep in: {})",
+                name,
+                search.max_depth,
+                if searched.is_empty() { "nothing, no search folder exists".to_string() } else { searched.join(", ") }
+            ));
+            // Keep a broken link's target so the problem names it
+            read_manager_link(dir)
+        }
+    };
+
+    let problem = if is_win32_prefix(&prefix_dir) {
+        Some("32-bit prefix, which Proton can't run".to_string())
+    } else {
+        match &install_dir {
+            None => Some(
+                "no manager_link and no ModOrganizer.exe found in the prefix, so its mod manager folder is unknown"
+                    .to_string(),
+            ),
+            Some(install) if !install.is_dir() => {
+                Some(format!("mod manager folder {} no longer exists", install.display()))
+            }
+            Some(install) if manager_type_for(install).is_none() => {
+                Some(format!("no mod manager executable in {}", install.display()))
+            }
+            Some(install) if is_already_managed(install) => {
+                Some(format!("{} is already set up in this version of NaK", install.display()))
+            }
+            Some(_) => None,
+        }
+    };
+
+    Some(LegacyPrefix { name, prefix_dir, install_dir, problem })
+}
+
+/// Manager type of a legacy install: MO2, or the first executable as a generic manager
+pub(super) fn manager_type_for(install_dir: &Path) -> Option<ManagerType> {
+    if find_manager_exe(&ManagerType::MO2, install_dir).is_some() {
+        return Some(ManagerType::MO2);
+    }
+    let exe = find_manager_exe(&ManagerType::Plugin, install_dir)?;
+    Some(ManagerType::Generic {
+        exe_name: exe.file_name()?.to_string_lossy().into_owned(),
+        is_electron: steam::is_electron_executable(&exe),
+    })
+}
+
+pub(super) fn is_already_managed(install_dir: &Path) -> bool {
+    ManagedPrefixes::load()
+        .prefixes
+        .iter()
+        .any(|p| Path::new(&p.install_path) == install_dir)
+}
+
+/// Find prefixes left by the bash NaK in `<data_path>/Prefixes`
+pub fn find_legacy_prefixes(data_path: &Path) -> Vec<LegacyPrefix> {
+    let Ok(entries) = fs::read_dir(data_path.join("Prefixes")) else {
+        return Vec::new();
+    };
+    let config = AppConfig::load();
+    let mut found: Vec<LegacyPrefix> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| inspect_legacy_prefix(&e.path(), &config))
+        .collect();
+    found.sort_by(|a, b| a.name.cmp(&b.name));
+    found
+}
+
+/// Move one legacy prefix under Steam and register it, returning the new AppID
+///
+/// Creates the Steam shortcut, moves the prefix to `compatdata/<appid>/pfx`,
+/// then sets up NaK Tools like a normal install. Sets `steam_was_closed` if
+/// writing the shortcut closed Steam, even when a later step fails; pass it to
+/// `steam::finish_vdf_write` once done so Steam picks up the shortcut.
+pub fn migrate_legacy_prefix(
+    legacy: &LegacyPrefix,
+    proton: &SteamProton,
+    ctx: &TaskContext,
+    steam_was_closed: &mut bool,
+) -> Result<u32, Box<dyn Error>> {
+    if let Some(problem) = &legacy.problem {
+        return Err(problem.clone().into());
+    }
+    let install_dir = legacy.install_dir.as_deref().ok_or("Mod manager folder is unknown")?;
+    let manager_type = manager_type_for(install_dir).ok_or("No mod manager executable found")?;
+    let exe = find_manager_exe(&manager_type, install_dir).ok_or("No mod manager executable found")?;
+    let is_electron = matches!(manager_type, ManagerType::Generic { is_electron: true, .. });
+
+    ctx.set_status(format!("Migrating {}...", legacy.name));
+    log_install(&format!("Migrating legacy prefix {} ({})", legacy.name, legacy.prefix_dir.display()));
+
+    let steam_path = steam::find_steam_path().ok_or("Steam not found")?;
+    let steam_result = steam::add_mod_manager_shortcut(
+        &legacy.name,
+        exe.to_str().ok_or("Exe path contains non-UTF-8 characters")?,
+        install_dir.to_str().ok_or("Install path contains non-UTF-8 characters")?,
+        &proton.config_name,
+        Some(&get_dxvk_conf_path(install_dir)),
+        is_electron,
+    )?;
+    *steam_was_closed |= steam_result.steam_was_closed;
+
+    if let Err(e) = crate::utils::move_dir(&legacy.prefix_dir, &steam_result.prefix_path, |_, _| {}) {
+        // The legacy prefix is only deleted once fully copied, so undo the shortcut
+        if let Err(remove_err) = steam::remove_steam_shortcut(steam_result.app_id) {
+            log_warning(&format!("Failed to remove Steam shortcut: {}", remove_err));
+        }
+        if let Some(compat_dir) = steam_result.prefix_path.parent() {
+            let _ = fs::remove_dir_all(compat_dir);
+        }
+        return Err(format!("Failed to move the prefix: {}", e).into());
+    }
+
+    finalize_steam_installation_with_tools(
+        &manager_type,
+        &steam_result.prefix_path,
+        install_dir,
+        steam_result.app_id,
+        &proton.path,
+        ctx,
+    )?;
+
+    ManagedPrefixes::register(
+        steam_result.app_id,
+        &legacy.name,
+        steam_result.prefix_path.to_str().unwrap_or(""),
+        install_dir.to_str().unwrap_or(""),
+        manager_type,
+        steam_path.to_str().unwrap_or(""),
+        Some(&proton.config_name),
+    );
+    apply_instance_launch_toggles(steam_result.app_id);
+
+    // A flat legacy prefix took its link along; a `pfx` one leaves an (empty) folder behind
+    let _ = fs::remove_file(steam_result.prefix_path.join(MANAGER_LINK));
+    if let Some(legacy_dir) = legacy_folder(legacy) {
+        let _ = fs::remove_file(legacy_dir.join(MANAGER_LINK));
+        let _ = fs::remove_dir(legacy_dir);
+    }
+
+    log_install(&format!("Migrated {} to AppID {}", legacy.name, steam_result.app_id));
+    Ok(steam_result.app_id)
+}
+
+/// The `Prefixes/<name>` folder of a Proton-style legacy prefix
+fn legacy_folder(legacy: &LegacyPrefix) -> Option<&Path> {
+    (legacy.prefix_dir.file_name()? == "pfx").then(|| legacy.prefix_dir.parent()).flatten()
+}
+
+/// Migrate every legacy prefix that can be migrated
+///
+/// Prefixes with a `problem` are reported as skipped and left in place.
+pub fn migrate_legacy_prefixes(
+    data_path: &Path,
+    proton: &SteamProton,
+    ctx: &TaskContext,
+) -> LegacyMigrationReport {
+    let mut report = LegacyMigrationReport::default();
+    let legacy = find_legacy_prefixes(data_path);
+    let mut steam_was_closed = false;
+
+    for (i, prefix) in legacy.iter().enumerate() {
+        if ctx.is_cancelled() {
+            report.skipped.push((prefix.name.clone(), "cancelled".to_string()));
+            continue;
+        }
+        ctx.set_progress(i as f32 / legacy.len() as f32);
+        match migrate_legacy_prefix(prefix, proton, ctx, &mut steam_was_closed) {
+            Ok(app_id) => report.migrated.push((prefix.name.clone(), app_id)),
+            Err(e) => {
+                log_warning(&format!("Not migrating {}: {}", prefix.name, e));
+                report.skipped.push((prefix.name.clone(), e.to_string()));
+            }
+        }
+    }
+
+    // The shortcuts only show up once Steam reloads shortcuts.vdf
+    steam::finish_vdf_write(steam_was_closed);
+    ctx.set_progress(1.0);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_legacy_prefixes() {
+        let dir = std::env::temp_dir().join(format!("nak_legacy_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let prefixes = dir.join("Prefixes");
+
+        // Proton-style prefix linked to an MO2 folder
+        let mo2 = dir.join("MO2");
+        fs::create_dir_all(&mo2).unwrap();
+        fs::write(mo2.join("ModOrganizer.exe"), "").unwrap();
+        fs::create_dir_all(prefixes.join("Skyrim/pfx/drive_c")).unwrap();
+        std::os::unix::fs::symlink(&mo2, prefixes.join("Skyrim").join(MANAGER_LINK)).unwrap();
+
+        // Plain Wine prefix without a link
+        fs::create_dir_all(prefixes.join("Fallout/drive_c")).unwrap();
+
+        // 32-bit prefix
+        fs::create_dir_all(prefixes.join("Old/drive_c")).unwrap();
+        fs::write(prefixes.join("Old/system.reg"), "WINE REGISTRY Version 2\n#arch=win32\n").unwrap();
+
+        // Not a prefix at all
+        fs::create_dir_all(prefixes.join("Empty")).unwrap();
+
+        let found = find_legacy_prefixes(&dir);
+        let names: Vec<&str> = found.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Fallout", "Old", "Skyrim"]);
+
+        assert_eq!(found[0].prefix_dir, prefixes.join("Fallout"));
+        assert!(found[0].problem.as_deref().unwrap().contains("manager_link"));
+        assert!(found[1].problem.as_deref().unwrap().contains("32-bit"));
+
+        assert_eq!(found[2].prefix_dir, prefixes.join("Skyrim/pfx"));
+        assert_eq!(found[2].install_dir.as_deref(), Some(mo2.as_path()));
+        assert_eq!(manager_type_for(&mo2), Some(ManagerType::MO2));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_find_mod_manager_install_dir() {
+        let dir = std::env::temp_dir().join(format!("nak_manager_search_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let legacy = dir.join("Prefixes/Skyrim");
+        let drive_c = legacy.join("pfx/drive_c");
+        let mut config = AppConfig::default();
+
+        // Deep inside Program Files
+        let deep = drive_c.join("Program Files/Skyrim/Tools/Modding/MO2");
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(deep.join("ModOrganizer.exe"), "").unwrap();
+        let search = ManagerSearch::for_prefix(&legacy.join("pfx"), &config);
+        assert_eq!(find_mod_manager_install_dir(&legacy, &search), Ok(deep.clone()));
+
+        // Too deep for the configured depth: the searched folders are returned
+        config.manager_search_depth = 2;
+        let search = ManagerSearch::for_prefix(&legacy.join("pfx"), &config);
+        assert_eq!(find_mod_manager_install_dir(&legacy, &search), Err(vec![drive_c.join("Program Files")]));
+
+        // Extra folder from the config, relative to drive_c
+        let custom = drive_c.join("Stuff/MO2");
+        fs::create_dir_all(&custom).unwrap();
+        fs::write(custom.join("ModOrganizer.exe"), "").unwrap();
+        config.manager_search_dirs = vec!["Stuff".to_string()];
+        let search = ManagerSearch::for_prefix(&legacy.join("pfx"), &config);
+        assert_eq!(find_mod_manager_install_dir(&legacy, &search), Ok(custom));
+
+        // A manager_link outside the prefix wins over the search
+        let external = dir.join("External MO2");
+        fs::create_dir_all(&external).unwrap();
+        fs::write(external.join("ModOrganizer.exe"), "").unwrap();
+        std::os::unix::fs::symlink(&external, legacy.join(MANAGER_LINK)).unwrap();
+        assert_eq!(find_mod_manager_install_dir(&legacy, &search), Ok(external));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
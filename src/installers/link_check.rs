@@ -0,0 +1,281 @@
+//! Symlink check across managed prefixes
+//!
+//! NaK links a lot of folders together: NaK Tools' "Wine Prefix" and
+//! "Prefix ..." links, the MO2 Global Instance link and the game save links
+//! in each prefix's Documents and AppData. Moving folders, deleting a game's
+//! prefix or reorganizing drives leaves some of them broken.
+//! `check_all_links` reports them with the target each should have;
+//! `repair_all_links` recreates the ones NaK knows the target of. Links to
+//! a game prefix that is gone are only reported: there is nothing to point
+//! them at.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::common::{check_mo2_global_instance, global_instance_link, repair_mo2_global_instance, repair_wine_prefix_link};
+use super::symlinks::nak_tools_symlink_targets;
+use crate::config::{ManagedPrefix, ManagedPrefixes, ManagerType};
+use crate::logging::{log_info, log_warning};
+
+/// Where a broken link lives, which decides how it is repaired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinkKind {
+    /// NaK Tools' "Wine Prefix" link
+    WinePrefix,
+    /// NaK Tools' "Prefix Documents" / "Prefix AppData ..." links
+    NakTools,
+    /// The prefix's `AppData/Local/ModOrganizer` link to the Global Instance
+    GlobalInstance,
+    /// A save or game data link into another prefix
+    GameData,
+}
+
+/// A symlink that is missing, dangling or points at the wrong place
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// Instance name
+    pub instance: String,
+    /// The link itself
+    pub link: PathBuf,
+    /// What the link should point at (None = unknown)
+    pub intended: Option<PathBuf>,
+    /// What the link points at now (None = missing, or a real folder)
+    pub actual: Option<PathBuf>,
+    kind: LinkKind,
+}
+
+impl BrokenLink {
+    /// Whether `repair_all_links` can recreate it
+    pub fn fixable(&self) -> bool {
+        match self.kind {
+            LinkKind::GameData => false,
+            // A real folder in NaK Tools may hold the user's files; never replace it
+            LinkKind::WinePrefix | LinkKind::NakTools => {
+                let real_folder = fs::symlink_metadata(&self.link).is_ok_and(|m| !m.file_type().is_symlink());
+                self.intended.as_ref().is_some_and(|t| t.exists()) && !real_folder
+            }
+            LinkKind::GlobalInstance => true,
+        }
+    }
+
+    /// One line for the report dialog
+    pub fn describe(&self) -> String {
+        let now = match (&self.actual, fs::symlink_metadata(&self.link)) {
+            (Some(actual), _) => format!("-> {}", actual.display()),
+            (None, Ok(_)) => "is a real folder".to_string(),
+            (None, Err(_)) => "is missing".to_string(),
+        };
+        let should = match &self.intended {
+            Some(intended) if !intended.exists() => format!(" (should be {}, which doesn't exist)", intended.display()),
+            Some(intended) => format!(" (should be {})", intended.display()),
+            None => " (target is gone)".to_string(),
+        };
+        let fix = if self.fixable() { "" } else { " [can't repair]" };
+        format!("{} {}{}{}", self.link.display(), now, should, fix)
+    }
+}
+
+/// Check one link against the target it should have
+fn check_link(instance: &str, link: &Path, intended: &Path, kind: LinkKind) -> Option<BrokenLink> {
+    let actual = fs::read_link(link).ok();
+    if actual.as_deref() == Some(intended) && intended.exists() {
+        return None;
+    }
+    Some(BrokenLink {
+        instance: instance.to_string(),
+        link: link.to_path_buf(),
+        intended: Some(intended.to_path_buf()),
+        actual,
+        kind,
+    })
+}
+
+/// Symlinks directly in `dir` whose target doesn't exist
+fn dangling_links_in(instance: &str, dir: &Path) -> Vec<BrokenLink> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut links: Vec<BrokenLink> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_symlink()))
+        .filter(|entry| fs::metadata(entry.path()).is_err())
+        .map(|entry| BrokenLink {
+            instance: instance.to_string(),
+            actual: fs::read_link(entry.path()).ok(),
+            link: entry.path(),
+            intended: None,
+            kind: LinkKind::GameData,
+        })
+        .collect();
+    links.sort_by(|a, b| a.link.cmp(&b.link));
+    links
+}
+
+/// Check every link NaK made for one instance
+fn check_links(instance: &str, prefix_path: &Path, install_dir: &Path, is_mo2: bool) -> Vec<BrokenLink> {
+    let mut broken = Vec::new();
+
+    let tools_dir = install_dir.join("NaK Tools");
+    if tools_dir.is_dir() {
+        broken.extend(check_link(instance, &tools_dir.join("Wine Prefix"), prefix_path, LinkKind::WinePrefix));
+        for (link, target, _) in nak_tools_symlink_targets(&tools_dir, prefix_path) {
+            broken.extend(check_link(instance, &link, &target, LinkKind::NakTools));
+        }
+    }
+
+    if is_mo2 && install_dir.is_dir() {
+        // A missing link may have been disabled on purpose, so only a wrong one counts
+        if check_mo2_global_instance(prefix_path, install_dir).warning().is_some() {
+            let link = global_instance_link(prefix_path);
+            broken.push(BrokenLink {
+                instance: instance.to_string(),
+                actual: fs::read_link(&link).ok(),
+                link,
+                intended: Some(install_dir.join("Global Instance")),
+                kind: LinkKind::GlobalInstance,
+            });
+        }
+    }
+
+    // Save imports and game data links into other games' prefixes
+    if let Ok(users) = fs::read_dir(prefix_path.join("drive_c/users")) {
+        for user in users.flatten() {
+            for dir in ["Documents", "Documents/My Games", "AppData/Local", "AppData/Roaming"] {
+                for link in dangling_links_in(instance, &user.path().join(dir)) {
+                    if !broken.iter().any(|b| b.link == link.link) {
+                        broken.push(link);
+                    }
+                }
+            }
+        }
+    }
+
+    broken
+}
+
+/// Check every link NaK made for a managed prefix
+pub fn check_prefix_links(prefix: &ManagedPrefix) -> Vec<BrokenLink> {
+    check_links(
+        &prefix.name,
+        Path::new(&prefix.prefix_path),
+        Path::new(&prefix.install_path),
+        prefix.manager_type == ManagerType::MO2,
+    )
+}
+
+/// Check the links of every managed prefix
+pub fn check_all_links() -> Vec<BrokenLink> {
+    let broken: Vec<BrokenLink> = ManagedPrefixes::load().prefixes.iter().flat_map(check_prefix_links).collect();
+    log_info(&format!("Symlink check found {} broken link(s)", broken.len()));
+    broken
+}
+
+/// Recreate a broken link NaK knows the target of
+fn repair_link(link: &BrokenLink, prefix_path: &Path, install_dir: &Path) -> Result<(), String> {
+    let tools_dir = install_dir.join("NaK Tools");
+    match link.kind {
+        LinkKind::WinePrefix => {
+            if !repair_wine_prefix_link(&tools_dir, prefix_path) {
+                return Err(format!("Failed to recreate {}", link.link.display()));
+            }
+        }
+        LinkKind::NakTools => {
+            let target = link.intended.as_deref().ok_or("No target to link to")?;
+            if fs::symlink_metadata(&link.link).is_ok() {
+                fs::remove_file(&link.link).map_err(|e| format!("Failed to remove {}: {}", link.link.display(), e))?;
+            }
+            std::os::unix::fs::symlink(target, &link.link)
+                .map_err(|e| format!("Failed to recreate {}: {}", link.link.display(), e))?;
+        }
+        LinkKind::GlobalInstance => repair_mo2_global_instance(prefix_path, install_dir)
+            .map_err(|e| format!("Failed to repair {}: {}", link.link.display(), e))?,
+        LinkKind::GameData => return Err(format!("{} points at a folder that is gone", link.link.display())),
+    }
+    Ok(())
+}
+
+/// Recreate every fixable broken link of every managed prefix
+///
+/// Returns the links fixed and the errors of the ones that couldn't be.
+pub fn repair_all_links() -> (usize, Vec<String>) {
+    let mut fixed = 0;
+    let mut errors = Vec::new();
+    for prefix in &ManagedPrefixes::load().prefixes {
+        let prefix_path = Path::new(&prefix.prefix_path);
+        let install_dir = Path::new(&prefix.install_path);
+        for link in check_prefix_links(prefix).iter().filter(|l| l.fixable()) {
+            match repair_link(link, prefix_path, install_dir) {
+                Ok(()) => fixed += 1,
+                Err(e) => {
+                    log_warning(&format!("{}: {}", prefix.name, e));
+                    errors.push(format!("{}: {}", prefix.name, e));
+                }
+            }
+        }
+    }
+    log_info(&format!("Repaired {} symlink(s)", fixed));
+    (fixed, errors)
+}
+
+/// Plain-text report of `check_all_links`, grouped by instance
+pub fn render_link_check(links: &[BrokenLink]) -> String {
+    if links.is_empty() {
+        return "No broken symlinks found.\n".to_string();
+    }
+    let fixable = links.iter().filter(|l| l.fixable()).count();
+    let mut out = format!("{} broken symlink(s), {} can be repaired:\n", links.len(), fixable);
+    let mut instance = "";
+    for link in links {
+        if link.instance != instance {
+            instance = &link.instance;
+            out.push_str(&format!("\n{}\n", instance));
+        }
+        out.push_str(&format!("  {}\n", link.describe()));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_links() {
+        let dir = std::env::temp_dir().join(format!("nak_link_check_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let prefix = dir.join("compatdata/3000000001/pfx");
+        let user = prefix.join("drive_c/users/steamuser");
+        let install = dir.join("MO2");
+        let tools = install.join("NaK Tools");
+        for sub in ["Documents/My Games", "AppData/Local", "AppData/Roaming"] {
+            fs::create_dir_all(user.join(sub)).unwrap();
+        }
+        fs::create_dir_all(&tools).unwrap();
+        fs::create_dir_all(install.join("Global Instance")).unwrap();
+
+        // A moved prefix, a missing convenience link, a real folder and a save link to a removed game
+        std::os::unix::fs::symlink(dir.join("old/pfx"), tools.join("Wine Prefix")).unwrap();
+        std::os::unix::fs::symlink(user.join("Documents"), tools.join("Prefix Documents")).unwrap();
+        fs::create_dir_all(tools.join("Prefix AppData Roaming")).unwrap();
+        std::os::unix::fs::symlink(dir.join("gone/Skyrim Special Edition"), user.join("Documents/My Games/Skyrim Special Edition"))
+            .unwrap();
+        std::os::unix::fs::symlink(install.join("Global Instance"), user.join("AppData/Local/ModOrganizer")).unwrap();
+
+        let broken = check_links("MO2", &prefix, &install, true);
+        let names: Vec<String> = broken.iter().map(|l| l.link.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names, ["Wine Prefix", "Prefix AppData Local", "Prefix AppData Roaming", "Skyrim Special Edition"]);
+        let fixable: Vec<bool> = broken.iter().map(|l| l.fixable()).collect();
+        assert_eq!(fixable, [true, true, false, false]);
+        assert!(broken[0].describe().contains("old/pfx (should be"));
+        assert!(render_link_check(&broken).contains("4 broken symlink(s), 2 can be repaired"));
+
+        for link in broken.iter().filter(|l| l.fixable()) {
+            repair_link(link, &prefix, &install).unwrap();
+        }
+        let left = check_links("MO2", &prefix, &install, true);
+        assert_eq!(left.len(), 2);
+        assert!(left.iter().all(|l| !l.fixable()));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -4,15 +4,29 @@ use std::error::Error;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use super::common::{check_cancelled, check_disk_space, finalize_steam_installation_with_tools, get_dxvk_conf_path, InstallError, ManagerType};
-use super::{fetch_latest_mo2_release, install_all_dependencies, TaskContext};
+use super::common::{
+    apply_instance_launch_toggles, check_cancelled, check_disk_space, copy_dir_all,
+    finalize_steam_installation_with_tools, get_dxvk_conf_path, InstallError, ManagerType,
+};
+use super::{fetch_latest_mo2_release, install_dependencies_unless_complete, InstallPhase, TaskContext};
 use crate::config::{AppConfig, ManagedPrefixes};
 use crate::logging::{log_download, log_error, log_install, log_warning};
 use crate::steam::{self, SteamProton};
-use crate::utils::download_file;
+use crate::utils::download_file_pausable;
+
+/// Whether the `compatdata/<appid>` folder a shortcut for `exe_path` named
+/// `name` would use already exists (left by an earlier install of the same
+/// name and folder, which a reused install runs on)
+fn shortcut_prefix_exists(name: &str, exe_path: &str) -> bool {
+    steam::expected_shortcut_prefix(name, exe_path)
+        .is_some_and(|prefix| prefix.parent().is_some_and(Path::exists))
+}
 
 /// Clean up a partially-created Steam shortcut and prefix after a failed install.
-fn cleanup_failed_install(app_id: u32, prefix_path: &std::path::Path) {
+///
+/// With `prefix_existed` the prefix (and its registration) predates this
+/// install, so only the shortcut is removed.
+fn cleanup_failed_install(app_id: u32, prefix_path: &std::path::Path, prefix_existed: bool) {
     log_warning(&format!("Cleaning up failed installation (AppID: {})", app_id));
 
     // Remove the Steam shortcut
@@ -20,6 +34,11 @@ fn cleanup_failed_install(app_id: u32, prefix_path: &std::path::Path) {
         log_error(&format!("Failed to remove Steam shortcut during cleanup: {}", e));
     }
 
+    if prefix_existed {
+        log_warning(&format!("Keeping prefix {:?}, it was there before this install", prefix_path));
+        return;
+    }
+
     // Delete the prefix (the appid folder is the parent of pfx)
     if let Some(appid_folder) = prefix_path.parent() {
         if appid_folder.exists() {
@@ -46,11 +65,222 @@ fn save_steam_config(install_path: &std::path::Path, app_id: u32) -> Result<(),
         "steam_app_id": app_id,
         "created": chrono::Utc::now().to_rfc3339(),
     });
-    std::fs::write(&config_path, serde_json::to_string_pretty(&config)?)?;
+    crate::config::write_atomic(&config_path, serde_json::to_string_pretty(&config)?.as_bytes())?;
     log_install(&format!("Saved Steam config to {:?}", config_path));
     Ok(())
 }
 
+/// Extract an MO2 release archive into `dest`
+#[cfg(feature = "full")]
+fn extract_mo2_archive(archive_path: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    if let Err(e) = sevenz_rust::decompress_file(archive_path, dest) {
+        log_error(&format!("Failed to extract MO2 archive: {}", e));
+        return Err(InstallError::Other {
+            context: "MO2 extraction".to_string(),
+            reason: e.to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Extract an MO2 release archive into `dest`
+///
+/// Installer-only builds don't bundle a 7z decoder, so this runs the
+/// system `7z` (p7zip) instead.
+#[cfg(not(feature = "full"))]
+fn extract_mo2_archive(archive_path: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    let extracted = std::process::Command::new("7z")
+        .arg("x")
+        .arg("-y")
+        .arg(format!("-o{}", dest.display()))
+        .arg(archive_path)
+        .output();
+    let reason = match extracted {
+        Ok(out) if out.status.success() => return Ok(()),
+        Ok(out) => String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        Err(e) => format!("could not run 7z ({}); install p7zip", e),
+    };
+    log_error(&format!("Failed to extract MO2 archive: {}", reason));
+    Err(InstallError::Other {
+        context: "MO2 extraction".to_string(),
+        reason,
+    }
+    .into())
+}
+
+/// Cache folder for MO2 releases (~/.cache/nak/mo2/<version>/)
+fn mo2_cache_dir() -> PathBuf {
+    AppConfig::get_default_cache_dir().join("mo2")
+}
+
+/// Cache folder name for a release tag
+fn mo2_cache_key(version: &str) -> String {
+    // Tags come from GitHub; keep them from escaping the cache folder
+    version.replace(['/', '\\'], "_")
+}
+
+/// Remove cached MO2 versions other than `keep`
+fn prune_mo2_cache(keep: &str) {
+    let Ok(entries) = fs::read_dir(mo2_cache_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name() != keep {
+            log_install(&format!("Removing cached MO2 {}", entry.file_name().to_string_lossy()));
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Fill the MO2 cache for `version`, downloading the archive on a miss
+///
+/// The cache keeps the release archive plus an extracted `template` folder
+/// per version; new instances copy the template. Only the newest version is
+/// kept, so selecting a newer release invalidates the old entry. Returns the
+/// template folder.
+fn fill_mo2_cache(
+    version: &str,
+    asset: &crate::github::GithubAsset,
+    ctx: &TaskContext,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let key = mo2_cache_key(version);
+    let version_dir = mo2_cache_dir().join(&key);
+    let template = version_dir.join("template");
+    let archive_path = version_dir.join(&asset.name);
+
+    if template.join("ModOrganizer.exe").exists() {
+        log_install(&format!("MO2 cache hit: {} template already extracted", version));
+        return Ok(template);
+    }
+
+    prune_mo2_cache(&key);
+    fs::create_dir_all(&version_dir)?;
+
+    let cached = fs::metadata(&archive_path).map(|m| m.len() > 0).unwrap_or(false);
+    if cached {
+        log_install(&format!("MO2 cache hit: reusing downloaded {}", asset.name));
+    } else {
+        ctx.set_phase(InstallPhase::Download);
+        ctx.set_status(format!("Downloading {}...", asset.name));
+        ctx.set_progress(0.10);
+        log_download(&format!("Downloading MO2: {}", asset.name));
+        // Download beside the final name so an interrupted download is never reused
+        let partial = version_dir.join(format!("{}.part", asset.name));
+        download_file_pausable(&asset.browser_download_url, &partial, ctx)?;
+        fs::rename(&partial, &archive_path)?;
+        log_download(&format!("MO2 downloaded to: {:?}", archive_path));
+    }
+
+    check_cancelled(ctx)?;
+
+    ctx.set_phase(InstallPhase::Extract);
+    ctx.set_status("Extracting MO2...".to_string());
+    ctx.set_progress(0.15);
+    let staging = version_dir.join("template.partial");
+    let _ = fs::remove_dir_all(&staging);
+    if let Err(e) = extract_mo2_archive(&archive_path, &staging) {
+        // A corrupt archive would fail every time; drop it so the next install re-downloads
+        let _ = fs::remove_file(&archive_path);
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    let _ = fs::remove_dir_all(&template);
+    fs::rename(&staging, &template)?;
+    Ok(template)
+}
+
+/// Copy a cached MO2 template into `install_path`
+fn install_mo2_template(template: &Path, install_path: &Path, ctx: &TaskContext) -> Result<(), Box<dyn Error>> {
+    check_cancelled(ctx)?;
+
+    ctx.set_status("Copying MO2 files...".to_string());
+    ctx.set_progress(0.17);
+    copy_dir_all(template, install_path).map_err(|e| InstallError::Other {
+        context: "MO2 cache copy".to_string(),
+        reason: e.to_string(),
+    })?;
+
+    Ok(())
+}
+
+/// The MO2 version in the cache and its extracted template, if any
+pub fn cached_mo2_version() -> Option<(String, PathBuf)> {
+    fs::read_dir(mo2_cache_dir())
+        .ok()?
+        .flatten()
+        .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path().join("template")))
+        .find(|(_, template)| template.join("ModOrganizer.exe").exists())
+}
+
+/// Pick the Windows MO2 archive from a release
+fn find_mo2_asset(release: &crate::github::GithubRelease) -> Result<&crate::github::GithubAsset, InstallError> {
+    let invalid_terms = ["Linux", "pdbs", "src", "uibase", "commits"];
+    release
+        .assets
+        .iter()
+        .find(|a| {
+            a.name.starts_with("Mod.Organizer-2")
+                && a.name.ends_with(".7z")
+                && !invalid_terms.iter().any(|term| a.name.contains(term))
+        })
+        .ok_or_else(|| InstallError::Other {
+            context: "MO2 download".to_string(),
+            reason: "No valid MO2 archive found in release".to_string(),
+        })
+}
+
+/// Download and extract the latest MO2 release into the cache
+///
+/// Lets `install_mo2` run offline later. Returns the cached version.
+pub fn precache_mo2(ctx: &TaskContext) -> Result<String, Box<dyn Error>> {
+    ctx.set_status("Checking for latest MO2 version...".to_string());
+    let release = fetch_latest_mo2_release()?;
+    let asset = find_mo2_asset(&release)?;
+    fill_mo2_cache(&release.tag_name, asset, ctx)?;
+    Ok(release.tag_name)
+}
+
+/// Install MO2 from a release, through the cache when `cache_mo2` is on
+fn install_mo2_release(
+    release: &crate::github::GithubRelease,
+    install_path: &Path,
+    ctx: &TaskContext,
+) -> Result<(), Box<dyn Error>> {
+    let asset = find_mo2_asset(release)?;
+
+    // A pre-downloaded copy of this release is used even with the MO2 cache turned off
+    let precached = cached_mo2_version().is_some_and(|(version, _)| version == mo2_cache_key(&release.tag_name));
+    if AppConfig::load().cache_mo2 || precached {
+        let template = fill_mo2_cache(&release.tag_name, asset, ctx)?;
+        return install_mo2_template(&template, install_path, ctx);
+    }
+
+    ctx.set_phase(InstallPhase::Download);
+    ctx.set_status(format!("Downloading {}...", asset.name));
+    ctx.set_progress(0.10);
+    log_download(&format!("Downloading MO2: {}", asset.name));
+
+    let tmp_dir = AppConfig::get_tmp_path();
+    fs::create_dir_all(&tmp_dir)?;
+    let archive_path = tmp_dir.join(&asset.name);
+    download_file_pausable(&asset.browser_download_url, &archive_path, ctx)?;
+    log_download(&format!("MO2 downloaded to: {:?}", archive_path));
+
+    check_cancelled(ctx)?;
+
+    ctx.set_phase(InstallPhase::Extract);
+    ctx.set_status("Extracting MO2...".to_string());
+    ctx.set_progress(0.15);
+
+    extract_mo2_archive(&archive_path, install_path)?;
+
+    // Clean up archive
+    let _ = fs::remove_file(&archive_path);
+
+    Ok(())
+}
+
 /// Result of MO2 installation
 pub struct Mo2InstallResult {
     /// Steam AppID for the shortcut
@@ -62,14 +292,18 @@ pub struct Mo2InstallResult {
 /// Install MO2 using Steam-native integration
 ///
 /// This creates a Steam shortcut for MO2, sets up the prefix in Steam's
-/// compatdata directory, and configures the Proton version.
+/// compatdata directory, and configures the Proton version. With
+/// `reuse_deps`, a prefix left by an earlier install of the same name and
+/// folder keeps its dependencies if its `.nak_deps_complete` marker matches.
 pub fn install_mo2(
     install_name: &str,
     install_path: PathBuf,
     proton: &SteamProton,
     ctx: TaskContext,
     skip_disk_check: bool,
+    reuse_deps: bool,
 ) -> Result<Mo2InstallResult, Box<dyn Error>> {
+    proton.validate_binaries()?;
     log_install(&format!(
         "Starting MO2 installation: {} -> {:?}",
         install_name, install_path
@@ -99,15 +333,19 @@ pub fn install_mo2(
     ctx.set_progress(0.05);
 
     let exe_path = install_path.join("ModOrganizer.exe");
+    let exe_str = exe_path.to_str().ok_or("ModOrganizer.exe path contains non-UTF-8 characters")?;
     let dxvk_conf_path = get_dxvk_conf_path(&install_path);
+    let prefix_existed = shortcut_prefix_exists(install_name, exe_str);
     let steam_result = steam::add_mod_manager_shortcut(
         install_name,
-        exe_path.to_str().ok_or("ModOrganizer.exe path contains non-UTF-8 characters")?,
+        exe_str,
         install_path.to_str().ok_or("Install path contains non-UTF-8 characters")?,
         &proton.config_name,
         Some(&dxvk_conf_path),
         false, // MO2 is not an Electron app
     ).map_err(|e| InstallError::SteamError { reason: e.to_string() })?;
+    // Steam only needs to stay closed for the shortcut write, not the whole install
+    steam::finish_vdf_write(steam_result.steam_was_closed);
 
     log_install(&format!(
         "Created Steam shortcut with AppID: {}",
@@ -120,11 +358,11 @@ pub fn install_mo2(
 
     // From this point, if anything fails we must clean up the shortcut and prefix
     let result = do_install_mo2_inner(
-        install_name, &install_path, proton, &ctx, &steam_result, &steam_path, &exe_path,
+        install_name, &install_path, proton, &ctx, &steam_result, &steam_path, reuse_deps,
     );
 
     if result.is_err() {
-        cleanup_failed_install(steam_result.app_id, &steam_result.prefix_path);
+        cleanup_failed_install(steam_result.app_id, &steam_result.prefix_path, prefix_existed);
     }
 
     result
@@ -139,9 +377,10 @@ fn do_install_mo2_inner(
     ctx: &TaskContext,
     steam_result: &steam::SteamShortcutResult,
     steam_path: &Path,
-    exe_path: &std::path::Path,
+    reuse_deps: bool,
 ) -> Result<Mo2InstallResult, Box<dyn std::error::Error>> {
     check_cancelled(ctx)?;
+    let exe_path = install_path.join("ModOrganizer.exe");
 
     // 2. Create install directory
     ctx.set_status("Preparing installation folder...".to_string());
@@ -154,52 +393,26 @@ fn do_install_mo2_inner(
 
     check_cancelled(ctx)?;
 
-    // 3. Download MO2
+    // 3. Download MO2 (or reuse the cached release)
     ctx.set_status("Checking for latest MO2 version...".to_string());
-    let release = fetch_latest_mo2_release()?;
-
-    let invalid_terms = ["Linux", "pdbs", "src", "uibase", "commits"];
-    let asset = release
-        .assets
-        .iter()
-        .find(|a| {
-            a.name.starts_with("Mod.Organizer-2")
-                && a.name.ends_with(".7z")
-                && !invalid_terms.iter().any(|term| a.name.contains(term))
-        })
-        .ok_or_else(|| InstallError::Other {
-            context: "MO2 download".to_string(),
-            reason: "No valid MO2 archive found in release".to_string(),
-        })?;
-
-    ctx.set_status(format!("Downloading {}...", asset.name));
-    ctx.set_progress(0.10);
-    log_download(&format!("Downloading MO2: {}", asset.name));
-
-    let tmp_dir = AppConfig::get_tmp_path();
-    fs::create_dir_all(&tmp_dir)?;
-    let archive_path = tmp_dir.join(&asset.name);
-    download_file(&asset.browser_download_url, &archive_path)?;
-    log_download(&format!("MO2 downloaded to: {:?}", archive_path));
-
-    check_cancelled(ctx)?;
-
-    // 4. Extract
-    ctx.set_status("Extracting MO2...".to_string());
-    ctx.set_progress(0.15);
+    let release = match fetch_latest_mo2_release() {
+        Ok(release) => Some(release),
+        // Offline: fall back to whatever "Pre-download dependencies" (or an earlier install) cached
+        Err(e) => match cached_mo2_version() {
+            Some((version, template)) => {
+                log_warning(&format!("Could not check for MO2 updates ({}), using cached {}", e, version));
+                ctx.log(format!("Offline: installing cached MO2 {}", version));
+                install_mo2_template(&template, install_path, ctx)?;
+                None
+            }
+            None => return Err(e),
+        },
+    };
 
-    if let Err(e) = sevenz_rust::decompress_file(&archive_path, install_path) {
-        log_error(&format!("Failed to extract MO2 archive: {}", e));
-        return Err(InstallError::Other {
-            context: "MO2 extraction".to_string(),
-            reason: e.to_string(),
-        }
-        .into());
+    if let Some(release) = release {
+        install_mo2_release(&release, install_path, ctx)?;
     }
 
-    // Clean up archive
-    let _ = fs::remove_file(&archive_path);
-
     ctx.set_progress(0.20);
 
     // 5. Verify executable exists
@@ -215,7 +428,7 @@ fn do_install_mo2_inner(
     check_cancelled(ctx)?;
 
     // 6. Initialize prefix and install dependencies
-    install_all_dependencies(&steam_result.prefix_path, proton, ctx, 0.20, 0.90, steam_result.app_id)?;
+    install_dependencies_unless_complete(&steam_result.prefix_path, proton, ctx, 0.20, 0.90, steam_result.app_id, reuse_deps)?;
 
     ctx.set_progress(0.92);
 
@@ -223,7 +436,7 @@ fn do_install_mo2_inner(
 
     // 7. Finalize installation (creates NaK Tools folder)
     finalize_steam_installation_with_tools(
-        ManagerType::MO2,
+        &ManagerType::MO2,
         &steam_result.prefix_path,
         install_path,
         steam_result.app_id,
@@ -232,6 +445,7 @@ fn do_install_mo2_inner(
     )?;
 
     // 8. Save Steam integration config
+    ctx.set_phase(InstallPhase::SteamIntegration);
     if let Err(e) = save_steam_config(install_path, steam_result.app_id) {
         log_error(&format!("Warning: Failed to save Steam config: {}", e));
     }
@@ -247,6 +461,7 @@ fn do_install_mo2_inner(
         Some(&proton.config_name),
     );
     log_install("Registered prefix for cleanup tracking");
+    apply_instance_launch_toggles(steam_result.app_id);
 
     ctx.set_progress(1.0);
     ctx.set_status("MO2 Installed! Restart Steam to see it.".to_string());
@@ -259,12 +474,16 @@ fn do_install_mo2_inner(
 }
 
 /// Setup an existing MO2 installation with Steam integration
+///
+/// `reuse_deps` works as for `install_mo2`.
 pub fn setup_existing_mo2(
     install_name: &str,
     existing_path: PathBuf,
     proton: &SteamProton,
     ctx: TaskContext,
+    reuse_deps: bool,
 ) -> Result<Mo2InstallResult, Box<dyn Error>> {
+    proton.validate_binaries()?;
     // Verify MO2 exists at path
     let mo2_exe = existing_path.join("ModOrganizer.exe");
     if !mo2_exe.exists() {
@@ -297,6 +516,7 @@ pub fn setup_existing_mo2(
     ctx.set_progress(0.05);
 
     let dxvk_conf_path = get_dxvk_conf_path(&existing_path);
+    let prefix_existed = shortcut_prefix_exists(install_name, mo2_exe.to_str().unwrap_or(""));
     let steam_result = steam::add_mod_manager_shortcut(
         install_name,
         mo2_exe.to_str().unwrap_or(""),
@@ -305,6 +525,8 @@ pub fn setup_existing_mo2(
         Some(&dxvk_conf_path),
         false, // MO2 is not an Electron app
     ).map_err(|e| InstallError::SteamError { reason: e.to_string() })?;
+    // Steam only needs to stay closed for the shortcut write, not the whole install
+    steam::finish_vdf_write(steam_result.steam_was_closed);
 
     log_install(&format!(
         "Created Steam shortcut with AppID: {}",
@@ -313,11 +535,11 @@ pub fn setup_existing_mo2(
 
     // From this point, if anything fails we must clean up the shortcut and prefix
     let result = do_setup_existing_inner(
-        install_name, &existing_path, proton, &ctx, &steam_result, &steam_path,
+        install_name, &existing_path, proton, &ctx, &steam_result, &steam_path, reuse_deps,
     );
 
     if result.is_err() {
-        cleanup_failed_install(steam_result.app_id, &steam_result.prefix_path);
+        cleanup_failed_install(steam_result.app_id, &steam_result.prefix_path, prefix_existed);
     }
 
     result
@@ -331,11 +553,12 @@ fn do_setup_existing_inner(
     ctx: &TaskContext,
     steam_result: &steam::SteamShortcutResult,
     steam_path: &Path,
+    reuse_deps: bool,
 ) -> Result<Mo2InstallResult, Box<dyn Error>> {
     check_cancelled(ctx)?;
 
     // 2. Install dependencies
-    install_all_dependencies(&steam_result.prefix_path, proton, ctx, 0.10, 0.85, steam_result.app_id)?;
+    install_dependencies_unless_complete(&steam_result.prefix_path, proton, ctx, 0.10, 0.85, steam_result.app_id, reuse_deps)?;
 
     ctx.set_progress(0.90);
 
@@ -343,7 +566,7 @@ fn do_setup_existing_inner(
 
     // 3. Finalize (creates NaK Tools folder)
     finalize_steam_installation_with_tools(
-        ManagerType::MO2,
+        &ManagerType::MO2,
         &steam_result.prefix_path,
         existing_path,
         steam_result.app_id,
@@ -352,6 +575,7 @@ fn do_setup_existing_inner(
     )?;
 
     // 4. Save Steam integration config
+    ctx.set_phase(InstallPhase::SteamIntegration);
     if let Err(e) = save_steam_config(existing_path, steam_result.app_id) {
         log_error(&format!("Warning: Failed to save Steam config: {}", e));
     }
@@ -367,6 +591,7 @@ fn do_setup_existing_inner(
         Some(&proton.config_name),
     );
     log_install("Registered prefix for cleanup tracking");
+    apply_instance_launch_toggles(steam_result.app_id);
 
     ctx.set_progress(1.0);
     ctx.set_status("MO2 Setup Complete! Restart Steam to see it.".to_string());
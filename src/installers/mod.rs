@@ -1,35 +1,98 @@
 //! Mod manager installation logic (Steam-native)
 
-// Full-only modules (need github, flate2, tar, zip, etc.)
+// Full-only modules (need marketplace, flate2, tar, zip, etc.)
 #[cfg(feature = "full")]
-mod common;
+mod adopt;
 #[cfg(feature = "full")]
-mod mo2;
+mod dxvk;
+#[cfg(feature = "full")]
+mod generic;
+#[cfg(feature = "full")]
+mod launch_check;
+#[cfg(feature = "full")]
+mod legacy;
+#[cfg(feature = "full")]
+mod link_check;
+#[cfg(feature = "full")]
+mod modlist;
 #[cfg(feature = "full")]
 mod plugin;
+#[cfg(feature = "full")]
+mod recipe;
 #[cfg(any(feature = "installer", feature = "full"))]
 pub mod symlinks;
 
-// Prefix setup - available with "installer" feature (needs ureq only)
+// Prefix setup and MO2 - available with "installer" feature (needs ureq only)
+mod applied_settings;
+mod audio;
+mod common;
+mod dll_overrides;
+mod dotnet_check;
+mod fonts;
+mod mo2;
 mod prefix_setup;
+mod saves;
+mod terminal;
 
 #[cfg(feature = "full")]
-pub use common::{get_available_disk_space, regenerate_nak_tools_scripts, MIN_REQUIRED_DISK_SPACE_GB};
+pub use adopt::{adopt_prefix, find_adoptable_prefixes, inspect_adoptable_prefix, AdoptCandidate};
+#[cfg(feature = "full")]
+pub use dxvk::{install_dxvk_version, revert_dxvk};
+#[cfg(feature = "full")]
+pub use generic::{install_generic_manager, GenericInstallResult, GenericSource};
+#[cfg(feature = "full")]
+pub use launch_check::{
+    diagnose_log_text, diagnose_proton_log, proton_log_path, read_launch_log, render_launch_check, validate_launch_setup,
+    LaunchIssue,
+};
 #[cfg(feature = "full")]
-pub use mo2::{install_mo2, setup_existing_mo2};
+pub use link_check::{check_all_links, check_prefix_links, render_link_check, repair_all_links, BrokenLink};
 #[cfg(feature = "full")]
-pub use plugin::install_plugin;
+pub use legacy::{find_legacy_prefixes, migrate_legacy_prefix, migrate_legacy_prefixes, LegacyMigrationReport, LegacyPrefix};
+#[cfg(feature = "full")]
+pub use modlist::{export_modlist, import_modlist, modlist_archive_name, modlist_backup_dir, ModlistImport};
+#[cfg(feature = "full")]
+pub use plugin::{install_dll_plugin, install_plugin};
+#[cfg(feature = "full")]
+pub use recipe::{apply_recipe, export_recipe, load_recipe, recipe_dir, recipe_file_name, Recipe, RECIPE_VERSION};
+pub use applied_settings::{
+    applied_settings_path, read_applied_settings, write_applied_settings, AppliedSettings, APPLIED_SETTINGS_FILE,
+};
+pub use audio::{check_xaudio, fix_audio, game_audio_override_diff, AudioCheck, XAUDIO_DLLS};
+pub use common::{
+    add_prefix_to_steam, check_mo2_global_instance, clone_prefix, detect_mo2_instance_conflict, disable_mo2_global_instance,
+    ensure_dxvk_conf, ensure_launch_log_script, ensure_mangohud_conf, find_manager_exe, get_available_disk_space,
+    regenerate_all_prefix_scripts, regenerate_nak_tools_scripts, regenerate_prefix_scripts, relocate_install, repair_mo2_global_instance,
+    reset_dxvk_conf, reset_prefix, GlobalInstanceLink, Mo2InstanceConflict, PrefixScriptsReport, ScriptsRegenerated,
+    MIN_REQUIRED_DISK_SPACE_GB,
+};
+pub use dll_overrides::{
+    baseline_dll_overrides, compare_dll_overrides, diff_dll_overrides, read_user_reg_overrides, DllOverrideChange,
+};
+pub use dotnet_check::{scan_dotnet_install, verify_dotnet, DotnetReport, EXPECTED_DOTNET_RUNTIMES};
+pub use fonts::{install_custom_fonts, install_font_verb, FONT_EXTENSIONS, FONT_VERBS};
+pub use mo2::{cached_mo2_version, install_mo2, precache_mo2, setup_existing_mo2};
 pub use prefix_setup::{
     apply_dpi, apply_registry_for_game_path, auto_apply_game_registries, cleanup_prefix_drives,
-    install_all_dependencies, kill_wineserver, known_game_names, launch_dpi_test_app, DPI_PRESETS,
+    configured_dotnet_components, deps_marker_matches, detect_registry_games, dotnet_installers_cached,
+    dotnet_preset_components, estimate_configured_deps_size, estimate_deps_size, estimate_full_deps_size,
+    install_all_dependencies, install_dependencies_unless_complete, kill_wineserver, known_game_names,
+    launch_dpi_test_app, precache_dotnet_installers, registry_game_confirmed, set_windows_version, setup_prefix,
+    DepsMarker, DotnetSavings, PrefixSetupOptions, CUSTOM_DOTNET_DEPS, DEPS_MARKER_FILE, DOTNET_COMPONENTS,
+    DOTNET_PRESETS, DPI_PRESETS, WINDOWS_VERSIONS,
+};
+pub use saves::{
+    auto_import_game_saves, brief_launch_and_kill, prefix_init_timeout, set_up_saves, BriefLaunch,
+    PREFIX_INIT_TIMEOUT,
 };
+pub use terminal::open_prefix_terminal;
 
 use std::error::Error;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
-#[cfg(feature = "full")]
 use crate::github::GithubRelease;
 use crate::logging::log_install;
 use crate::steam::SteamProton;
@@ -38,13 +101,74 @@ use crate::steam::SteamProton;
 // Shared Types
 // ============================================================================
 
+/// Stage of an install, in the order installs go through them
+///
+/// Installs that start later (an existing MO2 has nothing to download)
+/// skip the early phases. Shortcut creation comes before the first phase;
+/// `SteamIntegration` is the Steam config and registration at the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InstallPhase {
+    Download,
+    Extract,
+    CreatePrefix,
+    Dependencies,
+    Dotnet,
+    Registry,
+    Finalize,
+    SteamIntegration,
+}
+
+impl InstallPhase {
+    pub const ALL: [InstallPhase; 8] = [
+        InstallPhase::Download,
+        InstallPhase::Extract,
+        InstallPhase::CreatePrefix,
+        InstallPhase::Dependencies,
+        InstallPhase::Dotnet,
+        InstallPhase::Registry,
+        InstallPhase::Finalize,
+        InstallPhase::SteamIntegration,
+    ];
+
+    /// Position in `ALL`, counting from 1
+    pub fn number(self) -> usize {
+        self as usize + 1
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InstallPhase::Download => "Downloading",
+            InstallPhase::Extract => "Extracting",
+            InstallPhase::CreatePrefix => "Creating prefix",
+            InstallPhase::Dependencies => "Installing dependencies",
+            InstallPhase::Dotnet => "Installing .NET",
+            InstallPhase::Registry => "Configuring registry",
+            InstallPhase::Finalize => "Finalizing",
+            InstallPhase::SteamIntegration => "Setting up Steam integration",
+        }
+    }
+}
+
+impl std::fmt::Display for InstallPhase {
+    /// "Phase 5/8: Installing .NET"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Phase {}/{}: {}", self.number(), Self::ALL.len(), self.label())
+    }
+}
+
 /// Context for background installation tasks
 #[derive(Clone)]
 pub struct TaskContext {
     pub status_callback: Arc<dyn Fn(String) + Send + Sync>,
     pub log_callback: Arc<dyn Fn(String) + Send + Sync>,
     pub progress_callback: Arc<dyn Fn(f32) + Send + Sync>,
+    /// Called on every `set_phase` (no-op unless set with `on_phase`)
+    pub phase_callback: Arc<dyn Fn(InstallPhase) + Send + Sync>,
     pub cancel_flag: Arc<AtomicBool>,
+    /// Set while the user has the task paused (see `crate::tasks`)
+    pub pause_flag: Arc<AtomicBool>,
+    /// Phase the install is in, shared by every clone of the context
+    phase: Arc<parking_lot::Mutex<Option<InstallPhase>>>,
 }
 
 impl TaskContext {
@@ -58,7 +182,41 @@ impl TaskContext {
             status_callback: Arc::new(status),
             log_callback: Arc::new(log),
             progress_callback: Arc::new(progress),
+            phase_callback: Arc::new(|_| {}),
             cancel_flag: cancel,
+            pause_flag: Arc::new(AtomicBool::new(false)),
+            phase: Arc::new(parking_lot::Mutex::new(None)),
+        }
+    }
+
+    /// Also report phase transitions to `callback`
+    pub fn on_phase(self, callback: impl Fn(InstallPhase) + Send + Sync + 'static) -> Self {
+        Self { phase_callback: Arc::new(callback), ..self }
+    }
+
+    /// Also report status and progress to a queued task's row
+    ///
+    /// The task should share this context's cancel flag (see
+    /// `TaskQueue::spawn_with_cancel`) so cancelling from the task list works.
+    /// The context takes over the task's pause flag, which makes the task
+    /// pausable from the task list.
+    pub fn reporting_to(self, task: &crate::tasks::TaskHandle) -> Self {
+        let (status, progress) = (self.status_callback, self.progress_callback);
+        let (status_task, progress_task) = (task.clone(), task.clone());
+        Self {
+            status_callback: Arc::new(move |msg: String| {
+                status_task.set_status(msg.clone());
+                status(msg);
+            }),
+            log_callback: self.log_callback,
+            progress_callback: Arc::new(move |p| {
+                progress_task.set_progress(p);
+                progress(p);
+            }),
+            phase_callback: self.phase_callback,
+            cancel_flag: self.cancel_flag,
+            pause_flag: task.pause_flag(),
+            phase: self.phase,
         }
     }
 
@@ -74,21 +232,45 @@ impl TaskContext {
         (self.progress_callback)(p);
     }
 
+    /// Enter an install phase (logged, and passed to the phase callback)
+    pub fn set_phase(&self, phase: InstallPhase) {
+        *self.phase.lock() = Some(phase);
+        log_install(&phase.to_string());
+        (self.phase_callback)(phase);
+    }
+
+    /// Phase the install is in, or failed in (None before the first phase)
+    pub fn phase(&self) -> Option<InstallPhase> {
+        *self.phase.lock()
+    }
+
     pub fn is_cancelled(&self) -> bool {
         self.cancel_flag.load(std::sync::atomic::Ordering::Relaxed)
     }
 
+    pub fn is_paused(&self) -> bool {
+        self.pause_flag.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Wrap a download so it holds off while paused and stops on cancel
+    pub fn pausable_reader<R: std::io::Read>(&self, reader: R) -> crate::tasks::PausableReader<R> {
+        crate::tasks::PausableReader::new(reader, self.pause_flag.clone(), self.cancel_flag.clone())
+    }
+
     /// Run a command that can be killed if the user cancels.
     ///
     /// Uses spawn() + try_wait() polling instead of .status() so the child
-    /// process can be killed promptly on cancellation.
+    /// process can be killed promptly on cancellation. While the task is
+    /// paused the child's process tree is stopped (SIGSTOP).
     pub fn run_cancellable(&self, mut cmd: std::process::Command) -> Result<std::process::ExitStatus, Box<dyn std::error::Error>> {
         let mut child = cmd.spawn()?;
+        let mut pause = crate::tasks::ChildPause::new(&child);
 
         loop {
             match child.try_wait()? {
                 Some(status) => return Ok(status),
                 None => {
+                    pause.sync(self.is_paused() && !self.is_cancelled());
                     if self.is_cancelled() {
                         let _ = child.kill();
                         let _ = child.wait(); // Reap the zombie
@@ -265,12 +447,56 @@ pub const WINE_SETTINGS_REG: &str = r#"Windows Registry Editor Version 5.00
 // Shared Functions
 // ============================================================================
 
+/// Environment variables `prefix_env` sets for running wine on a prefix
+pub type PrefixEnv = Vec<(&'static str, String)>;
+
+/// Wine binary and environment for running a Proton's wine directly on a prefix
+///
+/// Used for regedit, wineboot and the prefix terminal, where going through
+/// the `proton` script (and its STEAM_COMPAT_* requirements) isn't needed.
+/// PATH starts with the Proton bin directory.
+pub fn prefix_env(
+    prefix_path: &Path,
+    proton: &SteamProton,
+) -> Result<(PathBuf, PrefixEnv), String> {
+    let wine_bin = proton.wine_binary().ok_or_else(|| {
+        format!(
+            "Wine binary not found for Proton '{}' (checked files/bin/wine and dist/bin/wine)",
+            proton.name
+        )
+    })?;
+
+    let wineserver_bin = proton.wineserver_binary().unwrap_or_else(|| {
+        wine_bin.with_file_name("wineserver")
+    });
+
+    let bin_dir = proton
+        .bin_dir()
+        .ok_or_else(|| "Could not determine Proton bin directory".to_string())?;
+
+    let path_env = format!(
+        "{}:{}",
+        bin_dir.to_string_lossy(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    let envs = vec![
+        ("WINEPREFIX", prefix_path.display().to_string()),
+        ("WINE", wine_bin.display().to_string()),
+        ("WINESERVER", wineserver_bin.display().to_string()),
+        ("PATH", path_env),
+        ("WINEDLLOVERRIDES", "mshtml=d".to_string()),
+        ("PROTON_USE_XALIA", "0".to_string()),
+    ];
+    Ok((wine_bin, envs))
+}
+
 /// Apply Wine registry settings to a prefix
 ///
 /// NOTE: This should be called AFTER winetricks has initialized the prefix.
 /// Winetricks handles wineboot internally, so we just apply registry settings here.
 pub fn apply_wine_registry_settings(
-    prefix_path: &std::path::Path,
+    prefix_path: &Path,
     proton: &SteamProton,
     log_callback: &impl Fn(String),
     _app_id: Option<u32>,
@@ -287,42 +513,14 @@ pub fn apply_wine_registry_settings(
     let mut file = fs::File::create(&reg_file)?;
     file.write_all(WINE_SETTINGS_REG.as_bytes())?;
 
-    let wine_bin = proton.wine_binary().ok_or_else(|| {
-        let err_msg = format!(
-            "Wine binary not found for Proton '{}' (checked files/bin/wine and dist/bin/wine)",
-            proton.name
-        );
+    let (wine_bin, reg_envs) = prefix_env(prefix_path, proton).map_err(|err_msg| {
         log_callback(format!("Error: {}", err_msg));
         err_msg
     })?;
 
-    let wineserver_bin = proton.wineserver_binary().unwrap_or_else(|| {
-        wine_bin.with_file_name("wineserver")
-    });
-
-    let bin_dir = proton.bin_dir().ok_or_else(|| {
-        let err_msg = "Could not determine Proton bin directory";
-        log_callback(format!("Error: {}", err_msg));
-        err_msg
-    })?;
-
-    let path_env = format!(
-        "{}:{}",
-        bin_dir.to_string_lossy(),
-        std::env::var("PATH").unwrap_or_default()
-    );
-
     log_callback("Applying Wine registry settings...".to_string());
     log_install("Running wine regedit...");
 
-    let reg_envs: Vec<(&str, String)> = vec![
-        ("WINEPREFIX", prefix_path.display().to_string()),
-        ("WINE", wine_bin.display().to_string()),
-        ("WINESERVER", wineserver_bin.display().to_string()),
-        ("PATH", path_env),
-        ("WINEDLLOVERRIDES", "mshtml=d".to_string()),
-        ("PROTON_USE_XALIA", "0".to_string()),
-    ];
     let regedit_status = runtime_wrap::build_command(&wine_bin, &reg_envs)
         .arg("regedit")
         .arg(&reg_file)
@@ -351,13 +549,121 @@ pub fn apply_wine_registry_settings(
     Ok(())
 }
 
+// ============================================================================
+// Prefix Version
+// ============================================================================
+
+/// Build name from a Proton `version` file ("<timestamp> <build>")
+///
+/// Older and third-party builds sometimes write only the build name.
+fn parse_version_file(content: &str) -> Option<String> {
+    let line = content.lines().next()?.trim();
+    let build = match line.split_once(' ') {
+        Some((stamp, build)) if stamp.chars().all(|c| c.is_ascii_digit()) => build.trim(),
+        _ => line,
+    };
+    (!build.is_empty()).then(|| build.to_string())
+}
+
+/// A prefix last set up by a different Proton build than the one it now uses
+#[derive(Debug, Clone)]
+pub struct PrefixVersionMismatch {
+    /// Build that last updated the prefix
+    pub prefix_build: String,
+    /// Build of the Proton the instance is configured for
+    pub proton_build: String,
+}
+
+/// Compare the Proton build recorded in a prefix against `proton`
+///
+/// Proton copies its `version` file next to `pfx` whenever it creates or
+/// updates a prefix. Returns `None` when they match, or when either file is
+/// missing (nothing reliable to compare).
+pub fn prefix_version_mismatch(prefix_path: &Path, proton: &SteamProton) -> Option<PrefixVersionMismatch> {
+    let compat_dir = prefix_path.parent()?;
+    let prefix_raw = fs::read_to_string(compat_dir.join("version")).ok()?;
+    let proton_raw = fs::read_to_string(proton.path.join("version")).ok()?;
+    if prefix_raw.trim() == proton_raw.trim() {
+        return None;
+    }
+    Some(PrefixVersionMismatch {
+        prefix_build: parse_version_file(&prefix_raw)?,
+        proton_build: parse_version_file(&proton_raw)?,
+    })
+}
+
+/// Migrate a prefix to `proton` with `wineboot -u`
+///
+/// Same update Proton does itself on launch after a version change, run up
+/// front so it doesn't happen (or half-happen) in the middle of starting the
+/// mod manager. Records the new build so the mismatch check clears.
+pub fn upgrade_prefix(
+    prefix_path: &Path,
+    proton: &SteamProton,
+    log_callback: &impl Fn(String),
+) -> Result<(), Box<dyn Error>> {
+    use crate::runtime_wrap;
+
+    let (wine_bin, envs) = prefix_env(prefix_path, proton)?;
+
+    log_callback(format!("Updating prefix for {}...", proton.name));
+    log_install(&format!("Running wineboot -u on {} with {}", prefix_path.display(), proton.name));
+
+    let status = runtime_wrap::build_command(&wine_bin, &envs)
+        .arg("wineboot")
+        .arg("-u")
+        .status()
+        .map_err(|e| format!("Failed to run wineboot: {}", e))?;
+    if !status.success() {
+        return Err(format!("wineboot -u exited with code {:?}", status.code()).into());
+    }
+
+    // Let wineserver flush the registry before anything else touches the prefix
+    if let Some(wineserver) = proton.wineserver_binary() {
+        let _ = runtime_wrap::build_command(&wineserver, &envs).arg("-w").status();
+    }
+
+    if let Some(compat_dir) = prefix_path.parent() {
+        fs::copy(proton.path.join("version"), compat_dir.join("version"))?;
+    }
+
+    log_callback("Prefix updated".to_string());
+    log_install("Prefix updated with wineboot -u");
+    Ok(())
+}
+
 /// Fetch the latest MO2 release from GitHub
-#[cfg(feature = "full")]
 pub fn fetch_latest_mo2_release() -> Result<GithubRelease, Box<dyn Error>> {
     let url = "https://api.github.com/repos/ModOrganizer2/modorganizer/releases/latest";
-    let res = ureq::get(url)
-        .set("User-Agent", "NaK-Rust")
-        .call()?
-        .into_json()?;
-    Ok(res)
+    Ok(crate::github::fetch_release(url, "NaK-Rust")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_phases() {
+        assert_eq!(InstallPhase::Dotnet.to_string(), "Phase 5/8: Installing .NET");
+        assert_eq!(InstallPhase::SteamIntegration.number(), InstallPhase::ALL.len());
+        assert!(InstallPhase::ALL.windows(2).all(|w| w[0] < w[1]));
+
+        let seen = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let cb_seen = seen.clone();
+        let ctx = TaskContext::new(|_| {}, |_| {}, |_| {}, Arc::new(AtomicBool::new(false)))
+            .on_phase(move |phase| cb_seen.lock().push(phase));
+        assert_eq!(ctx.phase(), None);
+        ctx.clone().set_phase(InstallPhase::CreatePrefix);
+        ctx.set_phase(InstallPhase::Dependencies);
+        assert_eq!(ctx.phase(), Some(InstallPhase::Dependencies));
+        assert_eq!(*seen.lock(), [InstallPhase::CreatePrefix, InstallPhase::Dependencies]);
+    }
+
+    #[test]
+    fn test_parse_version_file() {
+        assert_eq!(parse_version_file("1712345678 proton-9.0-2\n").as_deref(), Some("proton-9.0-2"));
+        assert_eq!(parse_version_file("1700000000 GE-Proton10-4").as_deref(), Some("GE-Proton10-4"));
+        assert_eq!(parse_version_file("GE-Proton8-25\n").as_deref(), Some("GE-Proton8-25"));
+        assert_eq!(parse_version_file("").as_deref(), None);
+    }
 }
@@ -0,0 +1,217 @@
+//! Mod list backups for MO2 instances
+//!
+//! A full prefix/mods backup runs to many gigabytes. The part that takes
+//! effort to rebuild is much smaller: profiles (load order, enabled mods,
+//! INIs), each mod's meta.ini (source, version, Nexus IDs) and
+//! ModOrganizer.ini. This packs just those into a .tar.gz and restores them.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::config::{AppConfig, ManagedPrefix};
+use crate::logging::log_install;
+
+/// Result of restoring a mod list backup
+#[derive(Debug, Clone, Default)]
+pub struct ModlistImport {
+    /// Files written into the instance
+    pub restored: usize,
+    /// Mods in the backup that aren't installed (their meta.ini was skipped)
+    pub missing_mods: Vec<String>,
+}
+
+/// Folder mod list backups are offered in by default (~/.config/nak/modlist_backups/)
+pub fn modlist_backup_dir() -> PathBuf {
+    AppConfig::get_config_dir().join("modlist_backups")
+}
+
+/// Default archive name for an instance, e.g. "MO2 - Skyrim-20260101-120000.tar.gz"
+pub fn modlist_archive_name(prefix: &ManagedPrefix) -> String {
+    let name: String = prefix
+        .name
+        .chars()
+        .map(|c| if c == '/' || c.is_control() { '_' } else { c })
+        .collect();
+    format!("{}-{}.tar.gz", name.trim(), chrono::Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+/// Instance folder holding ModOrganizer.ini (portable instances only)
+fn instance_dir(prefix: &ManagedPrefix) -> Result<&Path, Box<dyn Error>> {
+    let dir = Path::new(&prefix.install_path);
+    if !dir.join("ModOrganizer.ini").is_file() {
+        return Err(format!("No portable MO2 instance (ModOrganizer.ini) in {}", dir.display()).into());
+    }
+    Ok(dir)
+}
+
+/// Whether a path (relative to the instance) belongs in a mod list backup
+fn is_modlist_path(rel: &Path) -> bool {
+    let mut parts = Vec::new();
+    for component in rel.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy()),
+            // Never follow "..", "/" or "./" out of the instance
+            _ => return false,
+        }
+    }
+    match parts.as_slice() {
+        [ini] => ini == "ModOrganizer.ini",
+        [mods, _, meta] if mods == "mods" => meta == "meta.ini",
+        [profiles, _, ..] => profiles == "profiles",
+        _ => false,
+    }
+}
+
+/// Files of an instance that go in a mod list backup (relative paths)
+fn modlist_files(instance: &Path) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from("ModOrganizer.ini")];
+
+    for entry in WalkDir::new(instance.join("profiles")).into_iter().flatten() {
+        if entry.file_type().is_file() {
+            if let Ok(rel) = entry.path().strip_prefix(instance) {
+                files.push(rel.to_path_buf());
+            }
+        }
+    }
+
+    if let Ok(mods) = fs::read_dir(instance.join("mods")) {
+        for entry in mods.flatten() {
+            let meta = entry.path().join("meta.ini");
+            if meta.is_file() {
+                files.push(PathBuf::from("mods").join(entry.file_name()).join("meta.ini"));
+            }
+        }
+    }
+
+    files
+}
+
+/// Pack an MO2 instance's profiles, mod meta.ini files and ModOrganizer.ini
+///
+/// Mod files themselves are left out. Returns the number of files written.
+pub fn export_modlist(prefix: &ManagedPrefix, archive: &Path) -> Result<usize, Box<dyn Error>> {
+    let instance = instance_dir(prefix)?;
+    if let Some(parent) = archive.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let files = modlist_files(instance);
+    let encoder = flate2::write::GzEncoder::new(fs::File::create(archive)?, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    for rel in &files {
+        builder.append_path_with_name(instance.join(rel), rel)?;
+    }
+    builder.into_inner()?.finish()?;
+
+    log_install(&format!("Exported mod list of {} ({} files) to {}", prefix.name, files.len(), archive.display()));
+    Ok(files.len())
+}
+
+/// Restore a backup made by `export_modlist` into an MO2 instance
+///
+/// Overwrites the instance's profiles and ModOrganizer.ini, so MO2 should be
+/// closed. Anything in the archive outside those files is ignored, and
+/// meta.ini files of mods that aren't installed are skipped rather than
+/// creating empty mod folders.
+pub fn import_modlist(prefix: &ManagedPrefix, archive: &Path) -> Result<ModlistImport, Box<dyn Error>> {
+    let instance = instance_dir(prefix)?;
+    let decoder = flate2::read::GzDecoder::new(fs::File::open(archive)?);
+    let mut tar = tar::Archive::new(decoder);
+    let mut report = ModlistImport::default();
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let rel = entry.path()?.into_owned();
+        if !is_modlist_path(&rel) {
+            continue;
+        }
+        if rel.starts_with("mods") {
+            let mod_dir = rel.parent().unwrap_or(&rel);
+            if !instance.join(mod_dir).is_dir() {
+                if let Some(name) = mod_dir.file_name() {
+                    report.missing_mods.push(name.to_string_lossy().into_owned());
+                }
+                continue;
+            }
+        }
+        entry.unpack_in(instance)?;
+        report.restored += 1;
+    }
+
+    if report.restored == 0 {
+        return Err(format!("{} has no MO2 profiles or settings in it", archive.display()).into());
+    }
+    log_install(&format!(
+        "Imported mod list into {} from {} ({} files, {} mods not installed)",
+        prefix.name,
+        archive.display(),
+        report.restored,
+        report.missing_mods.len()
+    ));
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ManagerType;
+
+    #[test]
+    fn test_is_modlist_path() {
+        assert!(is_modlist_path(Path::new("ModOrganizer.ini")));
+        assert!(is_modlist_path(Path::new("profiles/Default/modlist.txt")));
+        assert!(is_modlist_path(Path::new("mods/SkyUI/meta.ini")));
+        assert!(!is_modlist_path(Path::new("mods/SkyUI/SkyUI_SE.bsa")));
+        assert!(!is_modlist_path(Path::new("profiles")));
+        assert!(!is_modlist_path(Path::new("../profiles/Default/modlist.txt")));
+        assert!(!is_modlist_path(Path::new("/etc/passwd")));
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("nak_modlist_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let instance = dir.join("MO2");
+        fs::create_dir_all(instance.join("profiles/Default")).unwrap();
+        fs::create_dir_all(instance.join("mods/SkyUI")).unwrap();
+        fs::write(instance.join("ModOrganizer.ini"), "[General]\n").unwrap();
+        fs::write(instance.join("profiles/Default/modlist.txt"), "+SkyUI\n").unwrap();
+        fs::write(instance.join("mods/SkyUI/meta.ini"), "modid=12604\n").unwrap();
+        fs::write(instance.join("mods/SkyUI/SkyUI_SE.bsa"), "payload").unwrap();
+
+        let prefix = ManagedPrefix {
+            app_id: 1,
+            name: "MO2 - Test".to_string(),
+            prefix_path: String::new(),
+            install_path: instance.to_string_lossy().into_owned(),
+            manager_type: ManagerType::MO2,
+            library_path: String::new(),
+            created: chrono::Utc::now(),
+            proton_config_name: None,
+            dxvk_version: None,
+            windows_version: None,
+            launch_args: None,
+            native_wayland: false,
+            sync_mode: None,
+            locale: None,
+            launch_toggles: Default::default(),
+        };
+        let archive = dir.join("backup.tar.gz");
+        assert_eq!(export_modlist(&prefix, &archive).unwrap(), 3);
+
+        fs::write(instance.join("profiles/Default/modlist.txt"), "-SkyUI\n").unwrap();
+        fs::remove_dir_all(instance.join("mods/SkyUI")).unwrap();
+        let report = import_modlist(&prefix, &archive).unwrap();
+        assert_eq!(report.restored, 2);
+        assert_eq!(report.missing_mods, vec!["SkyUI".to_string()]);
+        assert_eq!(fs::read_to_string(instance.join("profiles/Default/modlist.txt")).unwrap(), "+SkyUI\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -2,38 +2,27 @@
 
 use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::time::Duration;
 use wait_timeout::ChildExt;
 
-use super::common::{check_cancelled, check_disk_space, finalize_steam_installation_with_tools, get_dxvk_conf_path, InstallError, ManagerType};
+use super::dxvk::set_dll_override;
+use super::common::{
+    apply_instance_launch_toggles, check_cancelled, check_disk_space, copy_dir_all,
+    finalize_steam_installation_with_tools, get_dxvk_conf_path, InstallError, ManagerType,
+};
 use super::{install_all_dependencies, TaskContext};
 use crate::config::{AppConfig, ManagedPrefixes};
 use crate::logging::{log_download, log_error, log_install};
 use crate::runtime_wrap;
-use crate::marketplace::{PluginManifest, get_plugin_download_url, get_installer_args, get_plugin_exe_name, get_plugin_install_type};
+use crate::marketplace::{PluginInstallType, PluginManifest, get_plugin_download_url, get_installer_args, get_plugin_exe_name, get_plugin_install_type};
 use crate::steam::{self, SteamProton};
 use crate::utils::download_file;
 
 /// Minimum disk space required for plugin installation (in GB)
 const MIN_DISK_SPACE_GB: f64 = 5.0;
 
-/// Recursively copy a directory tree (fallback when rename fails across filesystems)
-fn copy_dir_all(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result<()> {
-    fs::create_dir_all(dst)?;
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let dst_path = dst.join(entry.file_name());
-        if entry.file_type()?.is_dir() {
-            copy_dir_all(&entry.path(), &dst_path)?;
-        } else {
-            fs::copy(entry.path(), dst_path)?;
-        }
-    }
-    Ok(())
-}
-
 /// Timeout for installers (5 minutes)
 const INSTALLER_TIMEOUT_SECS: u64 = 300;
 
@@ -51,10 +40,28 @@ pub fn install_plugin(
     ctx: TaskContext,
     skip_disk_check: bool,
 ) -> Result<PluginInstallResult, Box<dyn Error>> {
+    proton.validate_binaries()?;
     let plugin_id = &manifest.plugin.id;
     let plugin_name = &manifest.plugin.name;
     let exe_name = get_plugin_exe_name(manifest);
-    let install_type = get_plugin_install_type(manifest);
+    let install_type = get_plugin_install_type(manifest).map_err(|reason| InstallError::Other {
+        context: format!("{} manifest", plugin_name),
+        reason,
+    })?;
+
+    // Reject unusable manifests before anything is written to Steam
+    if !install_type.has_executable() {
+        return Err(InstallError::Other {
+            context: format!("{} manifest", plugin_name),
+            reason: "dll-drop plugins are installed into an existing prefix, not as a new instance".to_string(),
+        }.into());
+    }
+    if exe_name.is_empty() {
+        return Err(InstallError::Other {
+            context: format!("{} manifest", plugin_name),
+            reason: "Missing exe_name in [install]".to_string(),
+        }.into());
+    }
 
     log_install(&format!(
         "Starting {} ({}) installation: {} -> {:?}",
@@ -62,7 +69,7 @@ pub fn install_plugin(
     ));
     log_install(&format!(
         "Install type: {}, Executable: {}",
-        install_type, exe_name
+        manifest.install.install_type, exe_name
     ));
 
     // Get primary Steam path
@@ -87,7 +94,7 @@ pub fn install_plugin(
     let dxvk_conf_path = get_dxvk_conf_path(&install_path);
 
     // Check if this is an Electron app
-    let is_electron = install_type == "electron-nsis";
+    let is_electron = install_type == PluginInstallType::ElectronNsis;
 
     let steam_result = steam::add_mod_manager_shortcut(
         install_name,
@@ -97,6 +104,8 @@ pub fn install_plugin(
         Some(&dxvk_conf_path),
         is_electron,
     ).map_err(|e| InstallError::SteamError { reason: e.to_string() })?;
+    // Steam only needs to stay closed for the shortcut write, not the whole install
+    steam::finish_vdf_write(steam_result.steam_was_closed);
 
     log_install(&format!("Created Steam shortcut with AppID: {}", steam_result.app_id));
 
@@ -136,8 +145,8 @@ pub fn install_plugin(
     ctx.set_progress(0.15);
 
     match install_type {
-        "electron-nsis" | "nsis" => {
-            run_nsis_installer(
+        PluginInstallType::Nsis | PluginInstallType::ElectronNsis | PluginInstallType::Installer => {
+            run_exe_installer(
                 &installer_path,
                 &install_path,
                 manifest,
@@ -146,80 +155,14 @@ pub fn install_plugin(
                 &primary_steam_path,
             )?;
         }
-        "archive-7z" => {
-            // Extract 7z archive.
-            // sevenz_rust doesn't expose per-entry path hooks, so we extract to a temp
-            // staging directory and then validate + move each file into install_path.
-            let staging = install_path.with_extension("_nak_staging");
-            if let Err(e) = sevenz_rust::decompress_file(&installer_path, &staging) {
-                log_error(&format!("Failed to extract archive: {}", e));
-                let _ = fs::remove_dir_all(&staging);
-                return Err(InstallError::Other {
-                    context: "Archive extraction".to_string(),
-                    reason: e.to_string(),
-                }.into());
-            }
-            // Verify no extracted file escapes the staging dir, then move into place
-            let staging_canonical = staging.canonicalize().map_err(|e| InstallError::Other {
-                context: "Archive staging".to_string(),
-                reason: e.to_string(),
-            })?;
-            for entry in walkdir::WalkDir::new(&staging).min_depth(1) {
-                let entry = entry.map_err(|e| InstallError::Other {
-                    context: "Archive traversal check".to_string(),
-                    reason: e.to_string(),
-                })?;
-                let canonical = entry.path().canonicalize().map_err(|e| InstallError::Other {
-                    context: "Archive path canonicalization".to_string(),
-                    reason: e.to_string(),
-                })?;
-                if !canonical.starts_with(&staging_canonical) {
-                    let _ = fs::remove_dir_all(&staging);
-                    return Err(InstallError::Other {
-                        context: "Archive extraction".to_string(),
-                        reason: format!("Archive entry escapes destination: {:?}", entry.path()),
-                    }.into());
-                }
-            }
-            // Move contents from staging into install_path
-            fs::rename(&staging, &install_path).or_else(|_| {
-                // rename fails across filesystems; fall back to copy + delete
-                copy_dir_all(&staging, &install_path)
-                    .and_then(|_| fs::remove_dir_all(&staging))
-            }).map_err(|e| InstallError::Other {
-                context: "Archive staging move".to_string(),
-                reason: e.to_string(),
-            })?;
+        PluginInstallType::Archive7z => {
+            extract_7z(&installer_path, &install_path)?;
         }
-        "archive-zip" => {
-            // Extract zip archive with path traversal protection.
-            // zip::ZipFile::enclosed_name() already rejects absolute paths and `..`
-            // components; we additionally verify the resolved path stays within install_path.
-            let file = fs::File::open(&installer_path)?;
-            let mut archive = zip::ZipArchive::new(file)?;
-            for i in 0..archive.len() {
-                let mut entry = archive.by_index(i)?;
-                let outpath = match entry.enclosed_name() {
-                    Some(p) => install_path.join(p),
-                    None => continue, // path traversal attempt; skip
-                };
-                // Ensure no symlink trickery places the file outside install_path
-                if let Some(parent) = outpath.parent() {
-                    if !parent.starts_with(&install_path) {
-                        continue;
-                    }
-                    fs::create_dir_all(parent)?;
-                }
-                if entry.is_dir() {
-                    fs::create_dir_all(&outpath)?;
-                } else {
-                    let mut out_file = fs::File::create(&outpath)?;
-                    std::io::copy(&mut entry, &mut out_file)?;
-                }
-            }
+        PluginInstallType::PortableZip => {
+            extract_zip(&installer_path, &install_path)?;
         }
-        other => {
-            return Err(format!("Unknown install type: {}", other).into());
+        PluginInstallType::DllDrop => {
+            return Err("dll-drop plugins cannot be installed as a new instance".into());
         }
     }
 
@@ -253,7 +196,7 @@ pub fn install_plugin(
     let manager_type = ManagerType::Plugin;
 
     finalize_steam_installation_with_tools(
-        manager_type,
+        &manager_type,
         &steam_result.prefix_path,
         &install_path,
         steam_result.app_id,
@@ -272,6 +215,7 @@ pub fn install_plugin(
         Some(&proton.config_name),
     );
     log_install("Registered prefix for cleanup tracking");
+    apply_instance_launch_toggles(steam_result.app_id);
 
     ctx.set_progress(1.0);
     ctx.set_status(format!("{} Installed! Restart Steam to see it.", plugin_name));
@@ -282,19 +226,149 @@ pub fn install_plugin(
     })
 }
 
-/// Run an NSIS installer using Proton
-fn run_nsis_installer(
-    installer_path: &std::path::Path,
-    install_path: &std::path::Path,
+/// Extract a 7z archive into `dest`
+pub(super) fn extract_7z(archive_path: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    // Extract 7z archive.
+    // sevenz_rust doesn't expose per-entry path hooks, so we extract to a temp
+    // staging directory and then validate + move each file into dest.
+    let staging = dest.with_extension("_nak_staging");
+    if let Err(e) = sevenz_rust::decompress_file(archive_path, &staging) {
+        log_error(&format!("Failed to extract archive: {}", e));
+        let _ = fs::remove_dir_all(&staging);
+        return Err(InstallError::Other {
+            context: "Archive extraction".to_string(),
+            reason: e.to_string(),
+        }.into());
+    }
+    // Verify no extracted file escapes the staging dir, then move into place
+    let staging_canonical = staging.canonicalize().map_err(|e| InstallError::Other {
+        context: "Archive staging".to_string(),
+        reason: e.to_string(),
+    })?;
+    for entry in walkdir::WalkDir::new(&staging).min_depth(1) {
+        let entry = entry.map_err(|e| InstallError::Other {
+            context: "Archive traversal check".to_string(),
+            reason: e.to_string(),
+        })?;
+        let canonical = entry.path().canonicalize().map_err(|e| InstallError::Other {
+            context: "Archive path canonicalization".to_string(),
+            reason: e.to_string(),
+        })?;
+        if !canonical.starts_with(&staging_canonical) {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(InstallError::Other {
+                context: "Archive extraction".to_string(),
+                reason: format!("Archive entry escapes destination: {:?}", entry.path()),
+            }.into());
+        }
+    }
+    // Move contents from staging into dest
+    fs::rename(&staging, dest).or_else(|_| {
+        // rename fails across filesystems; fall back to copy + delete
+        copy_dir_all(&staging, dest)
+            .and_then(|_| fs::remove_dir_all(&staging))
+    }).map_err(|e| InstallError::Other {
+        context: "Archive staging move".to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(())
+}
+
+/// Extract a zip archive into `dest`
+pub(super) fn extract_zip(archive_path: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    // Extract zip archive with path traversal protection.
+    // zip::ZipFile::enclosed_name() already rejects absolute paths and `..`
+    // components; we additionally verify the resolved path stays within dest.
+    let file = fs::File::open(archive_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let outpath = match entry.enclosed_name() {
+            Some(p) => dest.join(p),
+            None => continue, // path traversal attempt; skip
+        };
+        // Ensure no symlink trickery places the file outside dest
+        if let Some(parent) = outpath.parent() {
+            if !parent.starts_with(dest) {
+                continue;
+            }
+            fs::create_dir_all(parent)?;
+        }
+        if entry.is_dir() {
+            fs::create_dir_all(&outpath)?;
+        } else {
+            let mut out_file = fs::File::create(&outpath)?;
+            std::io::copy(&mut entry, &mut out_file)?;
+        }
+    }
+    Ok(())
+}
+
+/// Install a `dll-drop` plugin into an existing prefix
+///
+/// Downloads the DLL, copies it into `drive_c/windows/<dll_target>` and sets a
+/// `native,builtin` DLL override for it. Returns the installed DLL name.
+pub fn install_dll_plugin(
+    manifest: &PluginManifest,
+    prefix_root: &Path,
+    proton: &SteamProton,
+) -> Result<String, Box<dyn Error>> {
+    proton.validate_binaries()?;
+    let plugin_name = &manifest.plugin.name;
+    let install_type = get_plugin_install_type(manifest)?;
+    if install_type != PluginInstallType::DllDrop {
+        return Err(format!("{} is not a dll-drop plugin", plugin_name).into());
+    }
+
+    let target = manifest.install.dll_target.as_deref().unwrap_or("system32");
+    if !matches!(target, "system32" | "syswow64") {
+        return Err(format!("Invalid dll_target '{}' (expected system32 or syswow64)", target).into());
+    }
+
+    let target_dir = prefix_root.join("drive_c/windows").join(target);
+    if !target_dir.exists() {
+        return Err(format!("Prefix not initialized: {:?}", prefix_root).into());
+    }
+
+    let wine_bin = proton.wine_binary().ok_or_else(|| {
+        format!("Wine binary not found for Proton '{}'", proton.name)
+    })?;
+
+    let (download_url, version) = get_plugin_download_url(manifest)?;
+    let filename = download_url.split('/').next_back().unwrap_or_default();
+    let Some(dll_name) = filename.strip_suffix(".dll").or_else(|| filename.strip_suffix(".DLL")) else {
+        return Err(format!("dll-drop download is not a DLL: {}", filename).into());
+    };
+
+    let tmp_dir = AppConfig::get_tmp_path();
+    fs::create_dir_all(&tmp_dir)?;
+    let tmp_path = tmp_dir.join(filename);
+    log_download(&format!("Downloading {} {}: {}", plugin_name, version, filename));
+    download_file(&download_url, &tmp_path)?;
+
+    let dest = target_dir.join(format!("{}.dll", dll_name));
+    fs::copy(&tmp_path, &dest)?;
+    let _ = fs::remove_file(&tmp_path);
+
+    set_dll_override(&wine_bin, prefix_root, dll_name, "native,builtin")?;
+
+    log_install(&format!("Installed {} ({}) into {:?}", plugin_name, dll_name, dest));
+    Ok(dll_name.to_string())
+}
+
+/// Run a Windows installer exe using Proton
+fn run_exe_installer(
+    installer_path: &Path,
+    install_path: &Path,
     manifest: &PluginManifest,
     proton: &SteamProton,
-    prefix_path: &std::path::Path,
-    steam_path: &std::path::Path,
+    prefix_path: &Path,
+    steam_path: &Path,
 ) -> Result<(), Box<dyn Error>> {
     let proton_bin = proton.path.join("proton");
     let args = get_installer_args(manifest, install_path);
 
-    log_install(&format!("Running NSIS installer with args: {:?}", args));
+    log_install(&format!("Running installer with args: {:?}", args));
 
     // Get compat_data_path (parent of prefix_path)
     let compat_data_path = prefix_path.parent()
@@ -318,7 +392,7 @@ fn run_nsis_installer(
             if !status.success() {
                 log_error(&format!("Installer failed with exit code: {:?}", status.code()));
                 return Err(InstallError::Other {
-                    context: "NSIS installer".to_string(),
+                    context: "Plugin installer".to_string(),
                     reason: format!("Failed with exit code: {:?}", status.code()),
                 }.into());
             }
@@ -327,7 +401,7 @@ fn run_nsis_installer(
             let _ = child.kill();
             log_error(&format!("Installer timed out after {} seconds", INSTALLER_TIMEOUT_SECS));
             return Err(InstallError::Other {
-                context: "NSIS installer".to_string(),
+                context: "Plugin installer".to_string(),
                 reason: format!("Timed out after {} seconds", INSTALLER_TIMEOUT_SECS),
             }.into());
         }
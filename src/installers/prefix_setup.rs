@@ -1,23 +1,28 @@
 //! Unified prefix setup for MO2
 //!
-//! This module handles all the dependency installation logic.
+//! This module handles all the dependency installation logic. `setup_prefix`
+//! runs the same steps on any prefix, for the CLI and other tools.
 //!
 //! Key approach (ORDER MATTERS):
 //! 1. Install dependencies via winetricks (handles wineboot internally)
 //! 2. Install custom dotnet runtimes (dotnet9sdk, dotnetdesktop10)
+//!
+//! Which .NET components go in is selectable (`DOTNET_PRESETS`), since many
+//! setups only need .NET 8.
 //! 3. Auto-detect installed games and apply registry entries
 //! 4. Apply Wine registry settings (LAST - after prefix is fully set up)
 
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
 use std::process::Child;
 
-use super::{apply_wine_registry_settings, TaskContext};
+use super::{apply_wine_registry_settings, InstallPhase, TaskContext};
 use crate::config::AppConfig;
-use crate::deps::{install_standard_deps_cancellable, STANDARD_VERBS};
+use crate::deps::{missing_verb_dlls, prefix_arch, run_winetricks_per_verb, WineBinaries, STANDARD_VERBS};
 use crate::game_finder::{detect_all_games, known_games, Game, Launcher};
-use crate::logging::{log_install, log_warning};
+use crate::logging::{log_error, log_install, log_warning};
 use crate::runtime_wrap;
 use crate::steam::{detect_steam_path_checked, SteamProton};
 
@@ -31,9 +36,301 @@ const DOTNET9_SDK_URL: &str = "https://builds.dotnet.microsoft.com/dotnet/Sdk/9.
 /// .NET Desktop Runtime 10 download URL
 const DOTNET_DESKTOP10_URL: &str = "https://builds.dotnet.microsoft.com/dotnet/WindowsDesktop/10.0.2/windowsdesktop-runtime-10.0.2-win-x64.exe";
 
+/// Custom dotnet installers run after winetricks (not winetricks verbs)
+pub const CUSTOM_DOTNET_DEPS: &[&str] = &["dotnet9sdk", "dotnetdesktop10"];
+
+/// Installers behind `CUSTOM_DOTNET_DEPS` (component, download URL, name used in logs)
+const DOTNET_INSTALLERS: &[(&str, &str, &str)] = &[
+    ("dotnet9sdk", DOTNET9_SDK_URL, "dotnet-sdk-9"),
+    ("dotnetdesktop10", DOTNET_DESKTOP10_URL, "dotnet-desktop-10"),
+];
+
+/// Every .NET component NaK can install (winetricks verbs and `CUSTOM_DOTNET_DEPS`)
+/// with a rough install time in minutes, used to report what a smaller selection saves
+pub const DOTNET_COMPONENTS: &[(&str, u64)] = &[
+    ("dotnet6", 2),
+    ("dotnet7", 2),
+    ("dotnet8", 2),
+    ("dotnetdesktop6", 3),
+    ("dotnet9sdk", 4),
+    ("dotnetdesktop10", 2),
+];
+
+/// .NET presets offered in Settings and the CLI (id, label, components)
+pub const DOTNET_PRESETS: &[(&str, &str, &[&str])] = &[
+    ("full", "Full (.NET 6, 7, 8, 9, Desktop 6 and 10)", &[
+        "dotnet6", "dotnet7", "dotnet8", "dotnetdesktop6", "dotnet9sdk", "dotnetdesktop10",
+    ]),
+    ("mo2-minimal", "MO2 minimal (.NET 8 only)", &["dotnet8"]),
+];
+
+/// Components of a `DOTNET_PRESETS` entry
+pub fn dotnet_preset_components(preset: &str) -> Option<Vec<String>> {
+    DOTNET_PRESETS
+        .iter()
+        .find(|(id, _, _)| *id == preset)
+        .map(|(_, _, components)| components.iter().map(|c| c.to_string()).collect())
+}
+
+/// The .NET components chosen in Settings (all of them if none are set)
+///
+/// Unknown names in the config are dropped.
+pub fn configured_dotnet_components() -> Vec<String> {
+    let chosen: Vec<String> = AppConfig::load()
+        .dotnet_components
+        .into_iter()
+        .filter(|c| DOTNET_COMPONENTS.iter().any(|(name, _)| name == c))
+        .collect();
+    if chosen.is_empty() {
+        DOTNET_COMPONENTS.iter().map(|(name, _)| name.to_string()).collect()
+    } else {
+        chosen
+    }
+}
+
+/// What leaving .NET components out of a setup saves
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DotnetSavings {
+    /// Components not installed
+    pub skipped: Vec<&'static str>,
+    /// Approximate download size in MB
+    pub download_mb: u64,
+    /// Rough install time in minutes
+    pub minutes: u64,
+}
+
+impl DotnetSavings {
+    /// Savings of installing only `selected`
+    pub fn for_selection(selected: &[String]) -> Self {
+        let skipped: Vec<&'static str> = DOTNET_COMPONENTS
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| !selected.iter().any(|s| s == name))
+            .collect();
+        Self {
+            download_mb: estimate_deps_size(&skipped),
+            minutes: DOTNET_COMPONENTS.iter().filter(|(name, _)| skipped.contains(name)).map(|(_, m)| m).sum(),
+            skipped,
+        }
+    }
+
+    /// e.g. "Skipping dotnet6, dotnet7 saves ~120 MB of downloads and ~4 min" (empty if nothing is skipped)
+    pub fn summary(&self) -> String {
+        if self.skipped.is_empty() {
+            return String::new();
+        }
+        format!(
+            "Skipping {} saves ~{} MB of downloads and ~{} min",
+            self.skipped.join(", "),
+            self.download_mb,
+            self.minutes
+        )
+    }
+}
+
+/// Approximate download sizes in MB for each dependency (both architectures where applicable)
+const DEP_DOWNLOAD_SIZES_MB: &[(&str, u64)] = &[
+    ("vcrun2022", 40),
+    ("dotnet6", 60),
+    ("dotnet7", 60),
+    ("dotnet8", 60),
+    ("dotnetdesktop6", 120),
+    ("d3dcompiler_47", 6),
+    ("d3dcompiler_43", 2),
+    ("d3dx9", 95),
+    ("d3dx11_43", 2),
+    ("xact", 10),
+    ("xact_x64", 10),
+    ("dotnet9sdk", 215),
+    ("dotnetdesktop10", 60),
+];
+
+/// Fallback size for verbs missing from the table
+const DEFAULT_DEP_SIZE_MB: u64 = 10;
+
 /// Drive letters to keep in the prefix (c: is Windows root, z: maps to Linux /)
 const ALLOWED_DRIVE_LETTERS: &[&str] = &["c:", "z:"];
 
+/// Estimate the total download size in MB for the given dependencies.
+///
+/// Sizes are approximate and don't account for files already in the winetricks cache.
+pub fn estimate_deps_size(verbs: &[&str]) -> u64 {
+    verbs
+        .iter()
+        .map(|verb| {
+            DEP_DOWNLOAD_SIZES_MB
+                .iter()
+                .find(|(name, _)| name == verb)
+                .map(|(_, size)| *size)
+                .unwrap_or(DEFAULT_DEP_SIZE_MB)
+        })
+        .sum()
+}
+
+/// Estimate the download size in MB of a full `install_all_dependencies` run
+pub fn estimate_full_deps_size() -> u64 {
+    estimate_deps_size(STANDARD_VERBS) + estimate_deps_size(CUSTOM_DOTNET_DEPS)
+}
+
+/// Estimate the download size in MB of `install_all_dependencies` with the configured .NET components
+pub fn estimate_configured_deps_size() -> u64 {
+    estimate_full_deps_size() - DotnetSavings::for_selection(&configured_dotnet_components()).download_mb
+}
+
+/// What `setup_prefix` does to a prefix
+///
+/// `Default` is what NaK gives a new mod manager instance.
+#[derive(Debug, Clone)]
+pub struct PrefixSetupOptions {
+    /// Winetricks verbs, installed one at a time (default: `STANDARD_VERBS`).
+    /// `xact:32`/`xact:64` pick one architecture of a split verb.
+    /// A failing `dotnet*`/`vcrun*` verb fails the setup; others only warn.
+    pub verbs: Vec<String>,
+    /// Run NaK's own .NET installers, `CUSTOM_DOTNET_DEPS` (default: true)
+    pub dotnet_installers: bool,
+    /// .NET components from `DOTNET_COMPONENTS` to install (default: all).
+    /// Others are dropped from `verbs` and the custom installers.
+    pub dotnet: Vec<String>,
+    /// Register installed games in the prefix's registry so mod managers find them,
+    /// for the games confirmed in Settings (default: true)
+    pub register_games: bool,
+    /// Windows version code from `WINDOWS_VERSIONS`, e.g. "win10" (default: "win11"; None = Wine's default)
+    pub windows_version: Option<String>,
+    /// DPI from `DPI_PRESETS` or any value, e.g. 144 for 150% (default: None = Wine's 96)
+    pub dpi: Option<u32>,
+    /// Steam AppID of the shortcut the prefix belongs to (default: None)
+    pub app_id: Option<u32>,
+}
+
+impl Default for PrefixSetupOptions {
+    fn default() -> Self {
+        Self {
+            verbs: STANDARD_VERBS.iter().map(|v| v.to_string()).collect(),
+            dotnet_installers: true,
+            dotnet: DOTNET_COMPONENTS.iter().map(|(name, _)| name.to_string()).collect(),
+            register_games: true,
+            windows_version: Some(WINDOWS_VERSIONS[0].0.to_string()),
+            dpi: None,
+            app_id: None,
+        }
+    }
+}
+
+/// Prepare a Proton prefix for Windows mod tools, without any mod manager steps
+///
+/// Runs wineboot through Proton, trims drive letters, installs `options.verbs`
+/// and the .NET installers, registers games, applies NaK's registry settings,
+/// sets the Windows version and DPI. `prefix_path` must be the `pfx` folder
+/// inside a compatdata folder (e.g. `steamapps/compatdata/<appid>/pfx`),
+/// since Proton always creates the prefix at `<compatdata>/pfx`. Progress and
+/// status go through `ctx`, which also carries the cancel flag.
+pub fn setup_prefix(
+    prefix_path: &Path,
+    proton: &SteamProton,
+    options: &PrefixSetupOptions,
+    ctx: &TaskContext,
+) -> Result<(), Box<dyn Error>> {
+    if prefix_path.file_name() != Some("pfx".as_ref()) {
+        return Err(format!(
+            "{} is not a Proton prefix folder (it must be named 'pfx', inside its compatdata folder)",
+            prefix_path.display()
+        )
+        .into());
+    }
+    run_prefix_setup(prefix_path, proton, options, ctx, 0.0, 1.0)
+}
+
+/// Marker in the prefix folder, written when a setup finished without problems
+pub const DEPS_MARKER_FILE: &str = ".nak_deps_complete";
+
+/// What a `.nak_deps_complete` marker records
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepsMarker {
+    /// Winetricks verbs, sorted
+    pub verbs: Vec<String>,
+    /// .NET components, sorted
+    pub dotnet: Vec<String>,
+    /// Proton the dependencies were installed with
+    pub proton: String,
+    /// Its major version (None for Proton Experimental)
+    pub proton_major: Option<u64>,
+}
+
+impl DepsMarker {
+    /// Marker for a setup with `options` and `proton`
+    fn for_setup(options: &PrefixSetupOptions, proton: &SteamProton) -> Self {
+        let sorted = |list: &[String]| {
+            let mut list = list.to_vec();
+            list.sort();
+            list
+        };
+        Self {
+            verbs: sorted(&options.verbs),
+            dotnet: sorted(&options.dotnet),
+            proton: proton.name.clone(),
+            proton_major: proton.version().first().copied().filter(|_| !proton.is_experimental),
+        }
+    }
+
+    fn render(&self) -> String {
+        format!(
+            "verbs={}\ndotnet={}\nproton={}\nproton_major={}\n",
+            self.verbs.join(","),
+            self.dotnet.join(","),
+            self.proton,
+            self.proton_major.map(|m| m.to_string()).unwrap_or_default()
+        )
+    }
+
+    fn parse(content: &str) -> Option<Self> {
+        let field = |key: &str| {
+            content.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix('=')).map(str::trim)
+        };
+        let list = |value: &str| value.split(',').filter(|v| !v.is_empty()).map(String::from).collect();
+        Some(Self {
+            verbs: list(field("verbs")?),
+            dotnet: list(field("dotnet")?),
+            proton: field("proton")?.to_string(),
+            proton_major: field("proton_major")?.parse().ok(),
+        })
+    }
+
+    /// The marker in `prefix_root`, if there is a readable one
+    pub fn read(prefix_root: &Path) -> Option<Self> {
+        Self::parse(&fs::read_to_string(prefix_root.join(DEPS_MARKER_FILE)).ok()?)
+    }
+}
+
+/// Whether `prefix_root` already has what `install_all_dependencies` would install with `proton`
+///
+/// The marker must list the same verbs and .NET components as the current
+/// settings, and come from a Proton with the same major version.
+pub fn deps_marker_matches(prefix_root: &Path, proton: &SteamProton) -> bool {
+    let Some(marker) = DepsMarker::read(prefix_root) else {
+        return false;
+    };
+    let expected = DepsMarker::for_setup(&dependency_options(0), proton);
+    if marker.proton_major != expected.proton_major {
+        log_install(&format!(
+            "Dependencies in {} were installed with {}, not a Proton {} build",
+            prefix_root.display(),
+            marker.proton,
+            expected.proton_major.map(|m| m.to_string()).unwrap_or_else(|| "Experimental".to_string())
+        ));
+        return false;
+    }
+    marker.verbs == expected.verbs && marker.dotnet == expected.dotnet
+}
+
+/// Options `install_all_dependencies` runs with
+fn dependency_options(app_id: u32) -> PrefixSetupOptions {
+    PrefixSetupOptions {
+        app_id: Some(app_id),
+        dotnet: configured_dotnet_components(),
+        ..Default::default()
+    }
+}
+
 /// Install all dependencies to a prefix.
 ///
 /// Order: proton init → winetricks → custom dotnet → game detection → registry → win11 → dotnet fixes
@@ -47,6 +344,40 @@ pub fn install_all_dependencies(
     start_progress: f32,
     end_progress: f32,
     app_id: u32,
+) -> Result<(), Box<dyn Error>> {
+    run_prefix_setup(prefix_root, install_proton, &dependency_options(app_id), ctx, start_progress, end_progress)
+}
+
+/// `install_all_dependencies`, unless `reuse` is set and the prefix's marker matches
+///
+/// For reinstalling a mod manager over a prefix that was already set up.
+pub fn install_dependencies_unless_complete(
+    prefix_root: &Path,
+    install_proton: &SteamProton,
+    ctx: &TaskContext,
+    start_progress: f32,
+    end_progress: f32,
+    app_id: u32,
+    reuse: bool,
+) -> Result<(), Box<dyn Error>> {
+    if reuse && deps_marker_matches(prefix_root, install_proton) {
+        let msg = format!("Dependencies already installed in {}, skipping them", prefix_root.display());
+        ctx.log(msg.clone());
+        log_install(&msg);
+        ctx.set_progress(end_progress);
+        return Ok(());
+    }
+    install_all_dependencies(prefix_root, install_proton, ctx, start_progress, end_progress, app_id)
+}
+
+/// Shared implementation of `setup_prefix`/`install_all_dependencies`
+fn run_prefix_setup(
+    prefix_root: &Path,
+    install_proton: &SteamProton,
+    options: &PrefixSetupOptions,
+    ctx: &TaskContext,
+    start_progress: f32,
+    end_progress: f32,
 ) -> Result<(), Box<dyn Error>> {
     fs::create_dir_all(AppConfig::get_tmp_path())?;
 
@@ -56,14 +387,20 @@ pub fn install_all_dependencies(
     let dotnet_end = start_progress + (end_progress - start_progress) * 0.65;
     let games_end = start_progress + (end_progress - start_progress) * 0.75;
 
+    // Only a setup that gets through everything without a problem leaves a marker
+    let _ = fs::remove_file(prefix_root.join(DEPS_MARKER_FILE));
+    let mut complete = true;
+
     // =========================================================================
     // 0. Initialize prefix with Proton wrapper (creates proper prefix structure)
     // =========================================================================
+    ctx.set_phase(InstallPhase::CreatePrefix);
     ctx.set_status("Setting up Windows compatibility layer...".to_string());
     ctx.log("Initializing Wine prefix with Proton...".to_string());
     log_install("Running proton wineboot to initialize prefix");
 
-    if let Err(e) = initialize_prefix_with_proton(prefix_root, install_proton, app_id, ctx) {
+    if let Err(e) = initialize_prefix_with_proton(prefix_root, install_proton, options.app_id.unwrap_or(0), ctx) {
+        complete = false;
         ctx.log(format!("Warning: Proton prefix init failed: {}", e));
         log_warning(&format!("Proton prefix init failed: {}", e));
         // Continue anyway - winetricks might still work
@@ -75,6 +412,11 @@ pub fn install_all_dependencies(
         return Err("Cancelled".into());
     }
 
+    // 32-bit prefixes can't take the 64-bit half of dual-arch verbs
+    if prefix_arch(prefix_root).as_deref() == Some("win32") {
+        return Err(format!("{} is a 32-bit prefix; NaK needs a 64-bit (win64) prefix", prefix_root.display()).into());
+    }
+
     // =========================================================================
     // 0.5. Clean up unwanted drive letters (keep only C: and Z:)
     // =========================================================================
@@ -94,13 +436,25 @@ pub fn install_all_dependencies(
     // =========================================================================
     // 1. Standard Dependencies via Winetricks
     // =========================================================================
+    let is_skipped_dotnet = |component: &str| {
+        DOTNET_COMPONENTS.iter().any(|(name, _)| *name == component) && !options.dotnet.iter().any(|d| d == component)
+    };
+    let savings = DotnetSavings::for_selection(&options.dotnet);
+    if !savings.skipped.is_empty() {
+        ctx.log(savings.summary());
+        log_install(&savings.summary());
+    }
+
+    let verbs: Vec<&str> = options
+        .verbs
+        .iter()
+        .map(String::as_str)
+        .filter(|verb| !is_skipped_dotnet(verb))
+        .collect();
+    ctx.set_phase(InstallPhase::Dependencies);
     ctx.set_status("Installing required Windows components (this may take several minutes)...".to_string());
-    ctx.log(format!(
-        "Installing {} dependencies via winetricks: {}",
-        STANDARD_VERBS.len(),
-        STANDARD_VERBS.join(", ")
-    ));
-    log_install(&format!("Running winetricks with {} verbs", STANDARD_VERBS.len()));
+    ctx.log(format!("Installing {} dependencies via winetricks: {}", verbs.len(), verbs.join(", ")));
+    log_install(&format!("Running winetricks with {} verbs", verbs.len()));
 
     let winetricks_log_cb = {
         let ctx = ctx.clone();
@@ -110,10 +464,47 @@ pub fn install_all_dependencies(
         }
     };
 
-    if let Err(e) = install_standard_deps_cancellable(prefix_root, install_proton, winetricks_log_cb, &ctx.cancel_flag) {
-        let msg = format!("Winetricks installation had issues: {}", e);
-        ctx.log(format!("Warning: {}", msg));
-        log_warning(&msg);
+    // One verb at a time, so a failing optional verb doesn't stop the rest
+    let verb_results = WineBinaries::from_proton(install_proton).and_then(|wine| {
+        run_winetricks_per_verb(prefix_root, &wine, &verbs, winetricks_log_cb, &ctx.cancel_flag, &ctx.pause_flag)
+    });
+    match verb_results {
+        Ok(results) => {
+            let critical = results.critical_failures();
+            if !critical.is_empty() {
+                let msg = format!(
+                    "Required components failed to install: {}. See the install log for winetricks output.",
+                    critical.join(", ")
+                );
+                log_error(&msg);
+                return Err(msg.into());
+            }
+            let failed = results.failed();
+            if !failed.is_empty() {
+                complete = false;
+                let msg = format!("Optional components failed to install: {}", failed.join(", "));
+                ctx.log(format!("Warning: {}", msg));
+                log_warning(&msg);
+            }
+
+            // A verb can "succeed" without its DLLs, e.g. only one half of XACT
+            let installed: Vec<&str> =
+                results.results.iter().filter(|(_, r)| r.is_ok()).map(|(v, _)| v.as_str()).collect();
+            let missing = missing_verb_dlls(prefix_root, &installed);
+            if !missing.is_empty() {
+                complete = false;
+                let msg = format!("Installed components are missing DLLs: {}", missing.join(", "));
+                ctx.log(format!("Warning: {}", msg));
+                log_warning(&msg);
+            }
+        }
+        Err(e) if ctx.is_cancelled() => return Err(e),
+        Err(e) => {
+            complete = false;
+            let msg = format!("Winetricks installation had issues: {}", e);
+            ctx.log(format!("Warning: {}", msg));
+            log_warning(&msg);
+        }
     }
 
     ctx.set_progress(winetricks_end);
@@ -125,20 +516,23 @@ pub fn install_all_dependencies(
     // =========================================================================
     // 2. Custom .NET Runtimes (not in winetricks yet)
     // =========================================================================
-    ctx.set_status("Installing .NET runtime (1 of 2)...".to_string());
-    ctx.log("Installing .NET 9 SDK...".to_string());
-
-    if let Err(e) = install_dotnet_runtime(prefix_root, install_proton, DOTNET9_SDK_URL, "dotnet-sdk-9", ctx) {
-        ctx.log(format!("Warning: .NET 9 SDK install failed: {}", e));
-        log_warning(&format!(".NET 9 SDK install failed: {}", e));
-    }
-
-    ctx.set_status("Installing .NET runtime (2 of 2)...".to_string());
-    ctx.log("Installing .NET Desktop Runtime 10...".to_string());
-
-    if let Err(e) = install_dotnet_runtime(prefix_root, install_proton, DOTNET_DESKTOP10_URL, "dotnet-desktop-10", ctx) {
-        ctx.log(format!("Warning: .NET Desktop 10 install failed: {}", e));
-        log_warning(&format!(".NET Desktop 10 install failed: {}", e));
+    ctx.set_phase(InstallPhase::Dotnet);
+    if options.dotnet_installers {
+        let dotnet_proton = resolve_dotnet_proton(install_proton);
+        ctx.log(format!("Using Proton '{}' for .NET installers", dotnet_proton.name));
+
+        let installers: Vec<_> =
+            DOTNET_INSTALLERS.iter().filter(|(component, _, _)| !is_skipped_dotnet(component)).collect();
+        for (i, (_, url, name)) in installers.iter().enumerate() {
+            ctx.set_status(format!("Installing .NET runtime ({} of {})...", i + 1, installers.len()));
+            ctx.log(format!("Installing {}...", name));
+
+            if let Err(e) = install_dotnet_runtime(prefix_root, &dotnet_proton, url, name, ctx) {
+                complete = false;
+                ctx.log(format!("Warning: {} install failed: {}", name, e));
+                log_warning(&format!("{} install failed: {}", name, e));
+            }
+        }
     }
 
     ctx.set_progress(dotnet_end);
@@ -150,15 +544,18 @@ pub fn install_all_dependencies(
     // =========================================================================
     // 3. Auto-detect and register installed games
     // =========================================================================
-    ctx.set_status("Detecting your installed games...".to_string());
-    ctx.log("Auto-detecting installed Steam games...".to_string());
-    log_install("Auto-detecting installed games for registry");
-
-    let game_log_cb = {
-        let ctx = ctx.clone();
-        move |msg: String| ctx.log(msg)
-    };
-    auto_apply_game_registries(prefix_root, install_proton, &game_log_cb, Some(app_id));
+    ctx.set_phase(InstallPhase::Registry);
+    if options.register_games {
+        ctx.set_status("Detecting your installed games...".to_string());
+        ctx.log("Auto-detecting installed Steam games...".to_string());
+        log_install("Auto-detecting installed games for registry");
+
+        let game_log_cb = {
+            let ctx = ctx.clone();
+            move |msg: String| ctx.log(msg)
+        };
+        auto_apply_game_registries(prefix_root, install_proton, &game_log_cb, options.app_id);
+    }
 
     ctx.set_progress(games_end);
 
@@ -177,33 +574,161 @@ pub fn install_all_dependencies(
         let ctx = ctx.clone();
         move |msg: String| ctx.log(msg)
     };
-    apply_wine_registry_settings(prefix_root, install_proton, &log_cb, Some(app_id))?;
+    apply_wine_registry_settings(prefix_root, install_proton, &log_cb, options.app_id)?;
 
     if ctx.is_cancelled() {
         return Err("Cancelled".into());
     }
 
     // =========================================================================
-    // 5. Set Windows 11 Mode
+    // 5. Set Windows version (Windows 11 by default) and DPI
     // =========================================================================
     ctx.set_status("Finalizing compatibility settings...".to_string());
-    ctx.log("Setting Windows 11 mode...".to_string());
-    log_install("Setting Windows 11 mode via winetricks");
+    if let Some(version) = options.windows_version.as_deref() {
+        ctx.log(format!("Setting Windows version to {}...", version));
+        log_install(&format!("Setting Windows version {} via winetricks", version));
 
-    if let Err(e) = set_windows_11_mode(prefix_root, install_proton, ctx) {
-        ctx.log(format!("Warning: Failed to set Windows 11 mode: {}", e));
-        log_warning(&format!("Failed to set Windows 11 mode: {}", e));
+        if let Err(e) = set_windows_mode(prefix_root, install_proton, version, ctx) {
+            ctx.log(format!("Warning: Failed to set Windows version {}: {}", version, e));
+            log_warning(&format!("Failed to set Windows version {}: {}", version, e));
+        }
+    }
+
+    if let Some(dpi) = options.dpi {
+        ctx.log(format!("Applying DPI {}...", dpi));
+        if let Err(e) = apply_dpi(prefix_root, install_proton, dpi) {
+            ctx.log(format!("Warning: Failed to apply DPI {}: {}", dpi, e));
+            log_warning(&format!("Failed to apply DPI {}: {}", dpi, e));
+        }
     }
 
     if ctx.is_cancelled() {
         return Err("Cancelled".into());
     }
 
+    // =========================================================================
+    // 6. Verify .NET actually landed in the prefix
+    // =========================================================================
+    ctx.set_status("Verifying .NET installation...".to_string());
+    match super::verify_dotnet(prefix_root, install_proton) {
+        Ok(report) => {
+            ctx.log(format!(".NET in prefix: {}", report.summary()));
+            log_install(&format!(".NET in prefix: {}", report.summary()));
+            let missing = report.missing_for(&options.dotnet);
+            if !missing.is_empty() {
+                complete = false;
+                let msg = format!("Missing .NET runtimes: {}", missing.join(", "));
+                ctx.log(format!("Warning: {}", msg));
+                log_warning(&msg);
+            }
+        }
+        Err(e) => log_warning(&format!("Could not verify .NET: {}", e)),
+    }
+
+    if complete {
+        let marker = DepsMarker::for_setup(options, install_proton);
+        if let Err(e) = fs::write(prefix_root.join(DEPS_MARKER_FILE), marker.render()) {
+            log_warning(&format!("Failed to write {}: {}", DEPS_MARKER_FILE, e));
+        }
+    }
+
     ctx.set_progress(end_progress);
     ctx.set_status("Dependencies installed".to_string());
     Ok(())
 }
 
+/// Parse the (major, minor) version from a GE-Proton name like "GE-Proton10-18"
+fn ge_proton_version(name: &str) -> Option<(u32, u32)> {
+    let rest = name.strip_prefix("GE-Proton")?;
+    let (major, minor) = rest.split_once('-')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Pick the Proton used to run the custom .NET installers
+///
+/// Order: `dotnet_proton` from the config → newest installed GE-Proton 10+ → the instance's Proton.
+fn resolve_dotnet_proton(install_proton: &SteamProton) -> SteamProton {
+    let installed = crate::steam::find_steam_protons();
+
+    if let Some(wanted) = AppConfig::load().dotnet_proton.filter(|w| !w.is_empty()) {
+        if let Some(proton) = installed.iter().find(|p| p.config_name == wanted || p.name == wanted) {
+            log_install(&format!("Using configured Proton '{}' for .NET installers", proton.name));
+            return proton.clone();
+        }
+        log_warning(&format!("Configured .NET Proton '{}' is not installed, falling back", wanted));
+    }
+
+    let newest_ge = installed
+        .iter()
+        .filter_map(|p| ge_proton_version(&p.name).map(|v| (v, p)))
+        .filter(|((major, _), p)| *major >= 10 && p.wine_binary().is_some())
+        .max_by_key(|(version, _)| *version)
+        .map(|(_, p)| p.clone());
+
+    let proton = newest_ge.unwrap_or_else(|| install_proton.clone());
+    log_install(&format!("Using Proton '{}' for .NET installers", proton.name));
+    proton
+}
+
+/// Where a .NET installer is kept in the cache
+fn dotnet_installer_path(url: &str) -> std::path::PathBuf {
+    let filename = url.split('/').next_back().unwrap_or("dotnet-installer.exe");
+    AppConfig::load().get_cache_dir().join(filename)
+}
+
+/// Download a .NET installer into the cache unless it's already there
+///
+/// Returns the installer path and whether it had to be downloaded.
+fn cache_dotnet_installer(
+    url: &str,
+    name: &str,
+    ctx: &TaskContext,
+) -> Result<(std::path::PathBuf, bool), Box<dyn Error>> {
+    let installer_path = dotnet_installer_path(url);
+    if installer_path.exists() {
+        return Ok((installer_path, false));
+    }
+
+    fs::create_dir_all(AppConfig::load().get_cache_dir())?;
+    log_install(&format!("Downloading {}...", name));
+    let response = crate::net::get(url)
+        .set("User-Agent", "NaK-Rust")
+        .call()
+        .map_err(|e| format!("Failed to download {}: {}", name, e))?;
+
+    // Download beside the final name so an interrupted download is never reused
+    let partial = installer_path.with_extension("exe.part");
+    let mut file = fs::File::create(&partial)?;
+    std::io::copy(&mut ctx.pausable_reader(response.into_reader()), &mut file)?;
+    fs::rename(&partial, &installer_path)?;
+    Ok((installer_path, true))
+}
+
+/// Whether every custom .NET installer is already in the cache
+pub fn dotnet_installers_cached() -> bool {
+    DOTNET_INSTALLERS.iter().all(|(_, url, _)| dotnet_installer_path(url).exists())
+}
+
+/// Download the custom .NET installers into the cache without running them
+///
+/// Returns how many installers were downloaded (0 = all were cached already).
+pub fn precache_dotnet_installers(ctx: &TaskContext) -> Result<usize, Box<dyn Error>> {
+    let mut downloaded = 0;
+    for (_, url, name) in DOTNET_INSTALLERS {
+        if ctx.is_cancelled() {
+            return Err("Cancelled".into());
+        }
+        ctx.set_status(format!("Downloading {}...", name));
+        if cache_dotnet_installer(url, name, ctx)?.1 {
+            ctx.log(format!("{} downloaded", name));
+            downloaded += 1;
+        } else {
+            ctx.log(format!("{} already cached", name));
+        }
+    }
+    Ok(downloaded)
+}
+
 /// Install a .NET runtime via direct exe download and wine execution
 fn install_dotnet_runtime(
     prefix_root: &Path,
@@ -212,23 +737,7 @@ fn install_dotnet_runtime(
     name: &str,
     ctx: &TaskContext,
 ) -> Result<(), Box<dyn Error>> {
-    let cache_dir = AppConfig::get_default_cache_dir();
-    fs::create_dir_all(&cache_dir)?;
-
-    let filename = url.split('/').next_back().unwrap_or("dotnet-installer.exe");
-    let installer_path = cache_dir.join(filename);
-
-    // Download if not cached
-    if !installer_path.exists() {
-        log_install(&format!("Downloading {}...", name));
-        let response = ureq::get(url)
-            .set("User-Agent", "NaK-Rust")
-            .call()
-            .map_err(|e| format!("Failed to download {}: {}", name, e))?;
-
-        let mut file = fs::File::create(&installer_path)?;
-        std::io::copy(&mut response.into_reader(), &mut file)?;
-    }
+    let (installer_path, _) = cache_dotnet_installer(url, name, ctx)?;
 
     // Run installer with wine
     let Some(wine_bin) = proton.wine_binary() else {
@@ -471,14 +980,20 @@ pub fn cleanup_prefix_drives(
 /// Set Windows 11 mode for the prefix using winetricks
 ///
 /// This should be called AFTER all components are installed.
-/// Sets the Windows version to Windows 11 which is required for MO2 to work properly.
-fn set_windows_11_mode(
+/// New instances get Windows 11, which is required for MO2 to work properly.
+fn set_windows_mode(
     prefix_root: &Path,
     proton: &SteamProton,
+    version: &str,
     ctx: &TaskContext,
 ) -> Result<(), Box<dyn Error>> {
     use crate::deps::ensure_winetricks;
 
+    // Also keeps arbitrary winetricks verbs out of the command line
+    if !WINDOWS_VERSIONS.iter().any(|(code, _)| *code == version) {
+        return Err(format!("Unknown Windows version '{}'", version).into());
+    }
+
     let winetricks_path = ensure_winetricks()?;
 
     let Some(wine_bin) = proton.wine_binary() else {
@@ -489,7 +1004,7 @@ fn set_windows_11_mode(
         return Err("Wineserver binary not found".into());
     };
 
-    log_install("Running winetricks win11...");
+    log_install(&format!("Running winetricks {}...", version));
 
     let envs: Vec<(&str, String)> = vec![
         ("WINE", wine_bin.display().to_string()),
@@ -497,15 +1012,69 @@ fn set_windows_11_mode(
         ("WINEPREFIX", prefix_root.display().to_string()),
     ];
     let mut cmd = runtime_wrap::build_command(&winetricks_path, &envs);
-    cmd.arg("-q").arg("win11");
+    cmd.arg("-q").arg(version);
 
     let status = ctx.run_cancellable(cmd)?;
 
     if !status.success() {
-        return Err(format!("winetricks win11 failed with exit code: {:?}", status.code()).into());
+        return Err(format!("winetricks {} failed with exit code: {:?}", version, status.code()).into());
     }
 
-    log_install("Windows 11 mode set successfully");
+    log_install(&format!("Windows version {} set successfully", version));
+    Ok(())
+}
+
+// =============================================================================
+// Windows Version
+// =============================================================================
+
+/// Windows versions a prefix can report (winecfg code, label)
+///
+/// The first entry is what new prefixes get from `set_windows_mode`.
+pub const WINDOWS_VERSIONS: &[(&str, &str)] = &[
+    ("win11", "Windows 11"),
+    ("win10", "Windows 10"),
+    ("win81", "Windows 8.1"),
+    ("win7", "Windows 7"),
+    ("winxp", "Windows XP"),
+];
+
+/// Set the prefix-wide Windows version reported to applications
+///
+/// Per-application overrides (e.g. winxp for xEdit in `WINE_SETTINGS_REG`)
+/// are left alone.
+pub fn set_windows_version(
+    prefix_root: &Path,
+    proton: &SteamProton,
+    version: &str,
+) -> Result<(), Box<dyn Error>> {
+    if !WINDOWS_VERSIONS.iter().any(|(code, _)| *code == version) {
+        return Err(format!("Unknown Windows version '{}'", version).into());
+    }
+    if !prefix_root.join("system.reg").exists() {
+        return Err(format!("Prefix not initialized: {:?}", prefix_root).into());
+    }
+
+    let wine_bin = proton.wine_binary().ok_or_else(|| {
+        format!("Wine binary not found for Proton '{}'", proton.name)
+    })?;
+
+    let envs: Vec<(&str, String)> = vec![
+        ("WINEPREFIX", prefix_root.display().to_string()),
+        ("WINEDEBUG", "-all".to_string()),
+        ("PROTON_USE_XALIA", "0".to_string()),
+    ];
+    let status = runtime_wrap::build_command(&wine_bin, &envs)
+        .arg("winecfg")
+        .arg("-v")
+        .arg(version)
+        .status()?;
+
+    if !status.success() {
+        return Err(format!("winecfg -v {} failed with exit code: {:?}", version, status.code()).into());
+    }
+
+    log_install(&format!("Set Windows version of {:?} to {}", prefix_root, version));
     Ok(())
 }
 
@@ -609,31 +1178,62 @@ pub fn kill_wineserver(prefix_root: &Path, proton: &SteamProton) {
 // Game Registry Detection (uses game_finder module)
 // ============================================================================
 
-/// Auto-detect installed games and apply registry entries
+/// Detected games that have a registry entry mod tools look for
+///
+/// These are the games the prefix registry can be filled in for; the user
+/// confirms each one's path in Settings first (see `registry_game_confirmed`).
+pub fn detect_registry_games() -> Vec<Game> {
+    detect_all_games()
+        .games
+        .into_iter()
+        .filter(|g| g.registry_path.is_some() && g.registry_value.is_some())
+        .collect()
+}
+
+/// Whether the user confirmed this game at its current install path
 ///
-/// This uses the game_finder module to detect installed games across all
-/// supported launchers (Steam, Heroic, Bottles) and automatically adds
-/// the registry entries so mod managers can detect them.
+/// A confirmation is for one path: if the game moved (or a second copy is
+/// detected elsewhere) the new path has to be confirmed again.
+pub fn registry_game_confirmed(confirmed: &BTreeMap<String, String>, game: &Game) -> bool {
+    confirmed
+        .get(&game.name)
+        .is_some_and(|path| Path::new(path) == game.install_path)
+}
+
+/// Apply registry entries for the detected games the user confirmed
+///
+/// Uses the game_finder module to detect installed games across all
+/// supported launchers (Steam, Heroic, Bottles) and writes the real install
+/// path of each game confirmed in Settings (`AppConfig::registry_game_paths`)
+/// into the prefix, the same keys the Fix Game Registry script sets, so
+/// xEdit and the mod managers find the games. Unconfirmed games are skipped.
 pub fn auto_apply_game_registries(
     prefix_path: &Path,
     proton: &SteamProton,
     log_callback: &impl Fn(String),
     _app_id: Option<u32>,
 ) {
+    let confirmed = AppConfig::load().registry_game_paths;
+    if confirmed.is_empty() {
+        log_callback("No games confirmed for the registry (Settings > Game Registry), skipping".to_string());
+        return;
+    }
+
     let Some(wine_bin) = proton.wine_binary() else {
         log_warning("Wine binary not found, skipping game registry auto-detection");
         return;
     };
 
-    // Use the new game_finder module to detect all games
-    let scan_result = detect_all_games();
     let mut applied_count = 0;
 
-    for game in &scan_result.games {
-        // Only process games that have registry info
+    for game in &detect_registry_games() {
         let (Some(reg_path), Some(reg_value)) = (&game.registry_path, &game.registry_value) else {
             continue;
         };
+        if !registry_game_confirmed(&confirmed, game) {
+            log_install(&format!("Skipping registry for {} at {:?} (not confirmed)", game.name, game.install_path));
+            continue;
+        }
 
         // Apply registry for this game
         if apply_game_registry(
@@ -650,7 +1250,7 @@ pub fn auto_apply_game_registries(
 
     if applied_count > 0 {
         log_callback(format!("Auto-configured {} game(s) in registry", applied_count));
-        log_install(&format!("Auto-applied registry for {} detected game(s)", applied_count));
+        log_install(&format!("Auto-applied registry for {} confirmed game(s)", applied_count));
     }
 }
 
@@ -776,3 +1376,74 @@ fn apply_game_registry(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_deps_marker() {
+        let proton = |name: &str, is_experimental: bool| SteamProton {
+            name: name.to_string(),
+            config_name: name.to_string(),
+            path: PathBuf::from("/proton"),
+            is_steam_proton: false,
+            is_experimental,
+        };
+        let options = PrefixSetupOptions {
+            verbs: vec!["xact".to_string(), "vcrun2022".to_string()],
+            dotnet: vec!["dotnet8".to_string()],
+            ..Default::default()
+        };
+        let marker = DepsMarker::for_setup(&options, &proton("GE-Proton10-25", false));
+        assert_eq!(marker.verbs, ["vcrun2022", "xact"]);
+        assert_eq!(marker.proton_major, Some(10));
+        assert_eq!(DepsMarker::parse(&marker.render()), Some(marker));
+
+        let experimental = DepsMarker::for_setup(&options, &proton("Proton - Experimental", true));
+        assert_eq!(DepsMarker::parse(&experimental.render()).unwrap().proton_major, None);
+        assert_eq!(DepsMarker::parse("verbs=xact\n"), None);
+    }
+
+    #[test]
+    fn test_dotnet_savings() {
+        let full = dotnet_preset_components("full").unwrap();
+        assert_eq!(full.len(), DOTNET_COMPONENTS.len());
+        assert_eq!(DotnetSavings::for_selection(&full), DotnetSavings::default());
+        assert_eq!(DotnetSavings::default().summary(), "");
+
+        let minimal = dotnet_preset_components("mo2-minimal").unwrap();
+        let savings = DotnetSavings::for_selection(&minimal);
+        assert_eq!(savings.skipped, ["dotnet6", "dotnet7", "dotnetdesktop6", "dotnet9sdk", "dotnetdesktop10"]);
+        assert_eq!(savings.download_mb, 60 + 60 + 120 + 215 + 60);
+        assert_eq!(savings.minutes, 13);
+        assert!(savings.summary().starts_with("Skipping dotnet6, dotnet7, "));
+        assert!(dotnet_preset_components("huge").is_none());
+    }
+
+    #[test]
+    fn test_registry_game_confirmed() {
+        let game = Game {
+            name: "Skyrim Special Edition".to_string(),
+            app_id: "489830".to_string(),
+            install_path: PathBuf::from("/games/SteamLibrary/steamapps/common/Skyrim Special Edition"),
+            prefix_path: None,
+            launcher: Launcher::Steam { is_flatpak: false, is_snap: false },
+            my_games_folder: None,
+            appdata_local_folder: None,
+            appdata_roaming_folder: None,
+            registry_path: Some("Software\\Bethesda Softworks\\Skyrim Special Edition".to_string()),
+            registry_value: Some("Installed Path".to_string()),
+        };
+        let mut confirmed = BTreeMap::new();
+        assert!(!registry_game_confirmed(&confirmed, &game));
+
+        confirmed.insert(game.name.clone(), "/games/SteamLibrary/steamapps/common/Skyrim Special Edition/".to_string());
+        assert!(registry_game_confirmed(&confirmed, &game));
+
+        // Moved since it was confirmed
+        confirmed.insert(game.name.clone(), "/old/steamapps/common/Skyrim Special Edition".to_string());
+        assert!(!registry_game_confirmed(&confirmed, &game));
+    }
+}
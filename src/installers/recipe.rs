@@ -0,0 +1,303 @@
+//! Prefix recipes
+//!
+//! A recipe describes how a working prefix is set up without any of its
+//! files: Proton, winetricks verbs, DLL overrides, Windows version, DPI,
+//! DXVK and the Steam launch options NaK manages (env vars and arguments).
+//! It's a small JSON file that fits in a bug report or a guide, and
+//! `apply_recipe` sets another prefix up the same way.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::dll_overrides::apply_dll_overrides;
+use super::common::reapply_prefix_launch_settings;
+use super::{apply_dpi, install_dxvk_version, read_applied_settings, revert_dxvk, set_windows_version, TaskContext};
+use crate::config::{write_atomic, AppConfig, LaunchToggles, ManagedPrefix, ManagedPrefixes};
+use crate::deps::{run_winetricks_per_verb, WineBinaries};
+use crate::logging::{log_install, log_warning};
+use crate::steam::SteamProton;
+
+/// Recipe format version written by this build
+pub const RECIPE_VERSION: u32 = 1;
+
+/// Configuration of a prefix, as exported to and imported from JSON
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recipe {
+    /// Format version (`RECIPE_VERSION`)
+    pub version: u32,
+    /// NaK version that wrote the recipe
+    #[serde(default)]
+    pub nak_version: String,
+    /// Proton config name, e.g. "GE-Proton10-20" or "proton_experimental"
+    #[serde(default)]
+    pub proton: Option<String>,
+    /// winetricks verbs, in install order
+    #[serde(default)]
+    pub verbs: Vec<String>,
+    /// Prefix DLL overrides from the registry, lowercase DLL name -> mode
+    #[serde(default)]
+    pub dll_overrides: BTreeMap<String, String>,
+    /// Prefix-wide Windows version (winecfg code, e.g. "win11")
+    #[serde(default)]
+    pub windows_version: Option<String>,
+    /// DPI (None = Wine default, 96)
+    #[serde(default)]
+    pub dpi: Option<u32>,
+    /// DXVK release installed over Proton's own (None = Proton's DXVK)
+    #[serde(default)]
+    pub dxvk_version: Option<String>,
+    /// Sync mode code from `steam::SYNC_MODES` (None = Proton's default)
+    #[serde(default)]
+    pub sync_mode: Option<String>,
+    /// Locale for LANG/LC_ALL, e.g. "ja_JP.UTF-8" (None = the system locale)
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Proton's Wayland driver instead of XWayland
+    #[serde(default)]
+    pub native_wayland: bool,
+    /// Launch options builder toggles (GameMode, MangoHud, WineD3D, WINEDLLOVERRIDES, ...)
+    #[serde(default)]
+    pub launch_toggles: LaunchToggles,
+    /// Extra arguments for the mod manager
+    #[serde(default)]
+    pub launch_args: Option<String>,
+}
+
+impl Recipe {
+    /// Read the recipe of a managed prefix from its registry files and NaK's records
+    pub fn from_prefix(prefix: &ManagedPrefix) -> Self {
+        let proton = prefix.proton_config_name.clone();
+        let applied = read_applied_settings(Path::new(&prefix.prefix_path), proton.as_deref().unwrap_or(""));
+        Self {
+            version: RECIPE_VERSION,
+            nak_version: env!("CARGO_PKG_VERSION").to_string(),
+            proton,
+            verbs: applied.winetricks_verbs,
+            dll_overrides: applied.dll_overrides,
+            windows_version: prefix.windows_version.clone().or(applied.windows_version),
+            dpi: applied.dpi,
+            dxvk_version: prefix.dxvk_version.clone(),
+            sync_mode: prefix.sync_mode.clone(),
+            locale: prefix.locale.clone(),
+            native_wayland: prefix.native_wayland,
+            launch_toggles: LaunchToggles {
+                // The MangoHud preset is a file in the instance's own folder
+                mangohud_config: String::new(),
+                ..prefix.launch_toggles.clone()
+            },
+            launch_args: prefix.launch_args.clone(),
+        }
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a recipe, rejecting ones written by a newer format
+    pub fn from_json(content: &str) -> Result<Self, Box<dyn Error>> {
+        let recipe: Self = serde_json::from_str(content)?;
+        if recipe.version > RECIPE_VERSION {
+            return Err(format!(
+                "This recipe was made by a newer NaK (recipe version {}, this NaK reads up to {})",
+                recipe.version, RECIPE_VERSION
+            )
+            .into());
+        }
+        Ok(recipe)
+    }
+}
+
+/// Folder recipes are offered in by default (~/.config/nak/recipes/)
+pub fn recipe_dir() -> PathBuf {
+    AppConfig::get_config_dir().join("recipes")
+}
+
+/// Default recipe file name for an instance, e.g. "MO2 - Skyrim.recipe.json"
+pub fn recipe_file_name(prefix: &ManagedPrefix) -> String {
+    let name: String = prefix
+        .name
+        .chars()
+        .map(|c| if c == '/' || c.is_control() { '_' } else { c })
+        .collect();
+    format!("{}.recipe.json", name.trim())
+}
+
+/// Write the recipe of a managed prefix to `path`
+pub fn export_recipe(prefix: &ManagedPrefix, path: &Path) -> Result<Recipe, Box<dyn Error>> {
+    let recipe = Recipe::from_prefix(prefix);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(path, recipe.to_json()?.as_bytes())?;
+    log_install(&format!("Exported recipe of {} to {}", prefix.name, path.display()));
+    Ok(recipe)
+}
+
+/// Read a recipe file
+pub fn load_recipe(path: &Path) -> Result<Recipe, Box<dyn Error>> {
+    Recipe::from_json(&fs::read_to_string(path)?)
+}
+
+/// Set up a managed prefix the way a recipe describes
+///
+/// Installs the recipe's verbs the prefix doesn't have yet, then applies the
+/// Windows version, DPI, DLL overrides, DXVK and launch options, and records
+/// them for the prefix. `proton` is used for all of it and becomes the
+/// prefix's Proton; pass the recipe's Proton when it's installed.
+/// Steps that fail don't stop the rest; the returned notes list them (and
+/// anything that differs from the recipe) for the user.
+pub fn apply_recipe(
+    prefix: &ManagedPrefix,
+    recipe: &Recipe,
+    proton: &SteamProton,
+    ctx: &TaskContext,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let prefix_root = Path::new(&prefix.prefix_path);
+    if !prefix_root.join("system.reg").exists() {
+        return Err(format!("{} isn't an initialized prefix", prefix_root.display()).into());
+    }
+    let mut notes = Vec::new();
+    let mut note = |msg: String| {
+        log_warning(&msg);
+        notes.push(msg);
+    };
+
+    // Proton
+    if let Some(wanted) = recipe.proton.as_deref().filter(|p| *p != proton.config_name) {
+        note(format!("The recipe uses {}, which isn't installed here; used {} instead", wanted, proton.config_name));
+    }
+    if prefix.proton_config_name.as_deref() != Some(proton.config_name.as_str()) {
+        ctx.set_status(format!("Switching to {}...", proton.name));
+        match crate::steam::set_compat_tool(prefix.app_id, &proton.config_name) {
+            Ok(()) => ManagedPrefixes::update_proton(prefix.app_id, &proton.config_name),
+            Err(e) => note(format!("Failed to set Proton to {}: {}", proton.config_name, e)),
+        }
+    }
+
+    // winetricks verbs the prefix doesn't have yet
+    let have = read_applied_settings(prefix_root, &proton.config_name).winetricks_verbs;
+    let missing: Vec<&str> = recipe
+        .verbs
+        .iter()
+        .filter(|v| !have.contains(v))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        let wine = WineBinaries::from_proton(proton)?;
+        let results = run_winetricks_per_verb(
+            prefix_root,
+            &wine,
+            &missing,
+            |msg| ctx.set_status(msg),
+            &ctx.cancel_flag,
+            &ctx.pause_flag,
+        )?;
+        for verb in results.failed() {
+            note(format!("winetricks verb {} failed", verb));
+        }
+    }
+    if ctx.is_cancelled() {
+        return Err("Cancelled".into());
+    }
+
+    // Registry: Windows version, DPI, DLL overrides
+    if let Some(version) = recipe.windows_version.as_deref() {
+        ctx.set_status(format!("Setting Windows version to {}...", version));
+        match set_windows_version(prefix_root, proton, version) {
+            Ok(()) => ManagedPrefixes::update_windows_version(prefix.app_id, Some(version)),
+            Err(e) => note(format!("Failed to set Windows version {}: {}", version, e)),
+        }
+    }
+    if let Some(dpi) = recipe.dpi {
+        ctx.set_status(format!("Setting DPI to {}...", dpi));
+        if let Err(e) = apply_dpi(prefix_root, proton, dpi) {
+            note(format!("Failed to set DPI {}: {}", dpi, e));
+        }
+    }
+    if !recipe.dll_overrides.is_empty() {
+        ctx.set_status("Applying DLL overrides...".to_string());
+        if let Err(e) = apply_dll_overrides(prefix_root, proton, &recipe.dll_overrides) {
+            note(format!("Failed to apply DLL overrides: {}", e));
+        }
+    }
+
+    // DXVK
+    if recipe.dxvk_version != prefix.dxvk_version {
+        match recipe.dxvk_version.as_deref() {
+            Some(tag) => {
+                ctx.set_status(format!("Installing DXVK {}...", tag));
+                match install_dxvk_version(prefix_root, proton, Some(tag)) {
+                    Ok(installed) => ManagedPrefixes::update_dxvk_version(prefix.app_id, Some(&installed)),
+                    Err(e) => note(format!("Failed to install DXVK {}: {}", tag, e)),
+                }
+            }
+            None => {
+                ctx.set_status("Reverting to Proton's DXVK...".to_string());
+                match revert_dxvk(prefix_root, proton) {
+                    Ok(()) => ManagedPrefixes::update_dxvk_version(prefix.app_id, None),
+                    Err(e) => note(format!("Failed to revert DXVK: {}", e)),
+                }
+            }
+        }
+    }
+
+    // Steam launch options
+    ctx.set_status("Updating launch options...".to_string());
+    let settings = ManagedPrefix {
+        launch_args: recipe.launch_args.clone(),
+        native_wayland: recipe.native_wayland,
+        sync_mode: recipe.sync_mode.clone(),
+        locale: recipe.locale.clone(),
+        launch_toggles: LaunchToggles {
+            // Keep the instance's own MangoHud preset, the recipe has none
+            mangohud_config: prefix.launch_toggles.mangohud_config.clone(),
+            ..recipe.launch_toggles.clone()
+        },
+        ..prefix.clone()
+    };
+    for failure in reapply_prefix_launch_settings(prefix.app_id, &settings) {
+        note(failure);
+    }
+
+    log_install(&format!("Applied recipe to {} ({} notes)", prefix.name, notes.len()));
+    Ok(notes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipe_json_roundtrip() {
+        let recipe = Recipe {
+            version: RECIPE_VERSION,
+            nak_version: "5.0.0".to_string(),
+            proton: Some("GE-Proton10-20".to_string()),
+            verbs: vec!["vcrun2022".to_string(), "dotnet8".to_string()],
+            dll_overrides: BTreeMap::from([("dinput8".to_string(), "native,builtin".to_string())]),
+            windows_version: Some("win10".to_string()),
+            dpi: Some(144),
+            dxvk_version: None,
+            sync_mode: Some("ntsync".to_string()),
+            locale: Some("ja_JP.UTF-8".to_string()),
+            native_wayland: true,
+            launch_toggles: LaunchToggles { gamemode: true, ..Default::default() },
+            launch_args: Some("-p Default".to_string()),
+        };
+        let parsed = Recipe::from_json(&recipe.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, recipe);
+
+        // Hand-written recipes only need the version
+        let minimal = Recipe::from_json(r#"{"version": 1, "verbs": ["corefonts"]}"#).unwrap();
+        assert_eq!(minimal.verbs, vec!["corefonts".to_string()]);
+        assert_eq!(minimal.proton, None);
+
+        assert!(Recipe::from_json(r#"{"version": 99}"#).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+//! Game save import for mod manager prefixes
+//!
+//! A fresh Steam prefix has no user folder until it has been launched once.
+//! `set_up_saves` launches the mod manager through Steam, waits for
+//! `drive_c/users/<user>/Documents` to appear, stops the prefix and then links
+//! each detected game's `Documents/My Games/<folder>` into the manager prefix
+//! (the same links the interactive "Import Saves" NaK Tools script creates).
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use super::kill_wineserver;
+use crate::runtime_wrap;
+use crate::game_finder::{detect_all_games, Launcher};
+use crate::logging::{log_info, log_install, log_warning};
+use crate::config::ManagedPrefixes;
+use crate::steam::{locate_compatdata_prefix, SteamProton};
+
+/// Default time to wait for the prefix user folder before giving up
+pub const PREFIX_INIT_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Find `drive_c/users/<user>/Documents` in a prefix, if it exists yet
+fn find_documents_dir(prefix_path: &Path) -> Option<PathBuf> {
+    let users_dir = prefix_path.join("drive_c/users");
+    fs::read_dir(&users_dir)
+        .ok()?
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name();
+            name != "Public" && name != "root"
+        })
+        .map(|e| e.path().join("Documents"))
+        .find(|docs| docs.is_dir())
+}
+
+/// How long a brief launch gets to shut down cleanly before it is force-killed
+const BRIEF_LAUNCH_SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Wine's own background processes, which run in every prefix
+const WINE_SERVICE_EXES: &[&str] = &[
+    "services.exe", "winedevice.exe", "plugplay.exe", "explorer.exe", "rpcss.exe",
+    "svchost.exe", "tabtip.exe", "conhost.exe", "wineboot.exe", "steam.exe", "start.exe",
+];
+
+/// Prefix init timeout from the config (`prefix_init_timeout_secs`)
+pub fn prefix_init_timeout() -> Duration {
+    match crate::config::AppConfig::load().prefix_init_timeout_secs {
+        0 => PREFIX_INIT_TIMEOUT,
+        secs => Duration::from_secs(secs),
+    }
+}
+
+/// How a brief launch went
+#[derive(Debug, Clone)]
+pub struct BriefLaunch {
+    /// The prefix's Documents folder
+    pub documents: PathBuf,
+    /// The prefix Steam launched in (not the expected one if Steam put it in
+    /// another library)
+    pub prefix_path: PathBuf,
+    /// The prefix shut down when asked, without a force-kill
+    pub exited_normally: bool,
+    /// The launched exe quit on its own before NaK stopped it
+    pub crashed: bool,
+}
+
+impl BriefLaunch {
+    /// Warning to show the user, if the launch looked unhealthy
+    pub fn warning(&self) -> Option<String> {
+        if self.crashed {
+            Some("The mod manager closed on its own during its first launch; launching it from Steam will likely fail too. Check the prefix's dependencies.".to_string())
+        } else if !self.exited_normally {
+            Some("The mod manager did not shut down cleanly and had to be force-killed.".to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Executables running in a prefix (lowercase file names, Wine services excluded)
+///
+/// Matches processes by the WINEPREFIX in their environment. Processes of
+/// other users, or outside NaK's sandbox when running as a Flatpak, aren't
+/// visible, in which case this is empty.
+fn prefix_app_processes(prefix_path: &Path) -> Vec<String> {
+    let wanted = format!("WINEPREFIX={}", prefix_path.display());
+    let wanted_normalized = format!(
+        "WINEPREFIX={}",
+        crate::config::normalize_path_for_steam(&prefix_path.to_string_lossy())
+    );
+
+    let mut exes = Vec::new();
+    for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+        let proc_dir = entry.path();
+        let Ok(environ) = fs::read(proc_dir.join("environ")) else {
+            continue;
+        };
+        let in_prefix = environ.split(|b| *b == 0).any(|var| {
+            let var = String::from_utf8_lossy(var);
+            let var = var.trim_end_matches('/');
+            var == wanted || var == wanted_normalized
+        });
+        if !in_prefix {
+            continue;
+        }
+
+        let Ok(cmdline) = fs::read(proc_dir.join("cmdline")) else {
+            continue;
+        };
+        // Wine processes show the Windows path of the exe as argv[0]
+        let Some(exe) = cmdline
+            .split(|b| *b == 0)
+            .map(|arg| String::from_utf8_lossy(arg).to_lowercase())
+            .find(|arg| arg.ends_with(".exe"))
+        else {
+            continue;
+        };
+        let name = exe.rsplit(['\\', '/']).next().unwrap_or(&exe).to_string();
+        if !WINE_SERVICE_EXES.contains(&name.as_str()) {
+            exes.push(name);
+        }
+    }
+    exes.sort();
+    exes.dedup();
+    exes
+}
+
+/// Add newly started prefix executables to `seen`
+fn note_prefix_processes(prefix_path: &Path, seen: &mut Vec<String>) {
+    for exe in prefix_app_processes(prefix_path) {
+        if !seen.contains(&exe) {
+            log_info(&format!("Brief launch: {} running", exe));
+            seen.push(exe);
+        }
+    }
+}
+
+/// Ask the prefix to end its session, force-killing it if that doesn't work
+///
+/// Returns true if the prefix shut down without the force-kill.
+fn stop_prefix(prefix_path: &Path, proton: &SteamProton) -> bool {
+    if let Some(wine_bin) = proton.wine_binary() {
+        let envs: Vec<(&str, String)> = vec![
+            ("WINEPREFIX", prefix_path.display().to_string()),
+            ("WINEDEBUG", "-all".to_string()),
+        ];
+        match runtime_wrap::build_command(&wine_bin, &envs)
+            .arg("wineboot")
+            .arg("--end-session")
+            .status()
+        {
+            Ok(status) => log_install(&format!("wineboot --end-session exited with code {:?}", status.code())),
+            Err(e) => log_warning(&format!("Failed to run wineboot --end-session: {}", e)),
+        }
+
+        let start = Instant::now();
+        while start.elapsed() < BRIEF_LAUNCH_SHUTDOWN_GRACE {
+            if prefix_app_processes(prefix_path).is_empty() {
+                // Services go down with the wineserver; stop it the usual way
+                kill_wineserver(prefix_path, proton);
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        log_warning(&format!(
+            "Prefix did not shut down within {}s, force-killing",
+            BRIEF_LAUNCH_SHUTDOWN_GRACE.as_secs()
+        ));
+    }
+
+    kill_wineserver(prefix_path, proton);
+    false
+}
+
+/// Launch a non-Steam shortcut through Steam and wait for its prefix to be
+/// initialized, then stop the prefix.
+///
+/// The launch is ended with `wineboot --end-session` and force-killed via
+/// the wineserver if it doesn't go away, or if the prefix never initializes
+/// within `timeout`. An exe that quits on its own before being stopped is
+/// reported as crashed. Steam may create the prefix in another library than
+/// `expected_prefix`; the prefix it used is returned.
+pub fn brief_launch_and_kill(
+    app_id: u32,
+    expected_prefix: &Path,
+    proton: &SteamProton,
+    timeout: Duration,
+) -> Result<BriefLaunch, Box<dyn Error>> {
+    let mut prefix_path = locate_compatdata_prefix(app_id, expected_prefix);
+    if let Some(documents) = find_documents_dir(&prefix_path) {
+        log_info("Prefix already initialized, skipping launch");
+        return Ok(BriefLaunch { documents, prefix_path, exited_normally: true, crashed: false });
+    }
+
+    // Same launch path as the generated "Launch" script: (appid << 32) | 0x02000000
+    let game_id: u64 = ((app_id as u64) << 32) | 0x02000000;
+    log_install(&format!("Launching shortcut {} to initialize prefix", app_id));
+    let launcher_status = Command::new("xdg-open")
+        .arg(format!("steam://rungameid/{}", game_id))
+        .status()?;
+    log_install(&format!("xdg-open exited with code {:?}", launcher_status.code()));
+    if !launcher_status.success() {
+        log_warning("xdg-open reported an error; Steam may not have received the launch request");
+    }
+
+    // Remember what ran so an exe that disappears early can be told apart
+    // from one that was never seen
+    let mut seen: Vec<String> = Vec::new();
+
+    let start = Instant::now();
+    let documents = loop {
+        // Steam may create the prefix in another library than the expected one
+        if prefix_path == expected_prefix {
+            prefix_path = locate_compatdata_prefix(app_id, expected_prefix);
+            if prefix_path != expected_prefix {
+                log_warning(&format!(
+                    "Steam created the prefix at {} instead of {}",
+                    prefix_path.display(),
+                    expected_prefix.display()
+                ));
+            }
+        }
+        note_prefix_processes(&prefix_path, &mut seen);
+        if let Some(docs) = find_documents_dir(&prefix_path) {
+            break docs;
+        }
+        if start.elapsed() >= timeout {
+            log_warning(&format!(
+                "Prefix did not initialize within {}s, force-killing the launch",
+                timeout.as_secs()
+            ));
+            kill_wineserver(&prefix_path, proton);
+            return Err(format!(
+                "Timed out after {}s waiting for the prefix user folder. Is Steam running?",
+                timeout.as_secs()
+            )
+            .into());
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    };
+
+    // Give Proton a moment to finish writing the user profile before stopping it
+    for _ in 0..3 {
+        std::thread::sleep(Duration::from_secs(1));
+        note_prefix_processes(&prefix_path, &mut seen);
+    }
+
+    let crashed = !seen.is_empty() && prefix_app_processes(&prefix_path).is_empty();
+    if crashed {
+        log_warning(&format!(
+            "{} exited on its own during the brief launch - the real launch will likely fail",
+            seen.join(", ")
+        ));
+    }
+
+    let exited_normally = stop_prefix(&prefix_path, proton);
+    if !expected_prefix.join("drive_c").is_dir() {
+        log_warning(&format!(
+            "Expected prefix {} does not exist after the launch; Steam is using {}",
+            expected_prefix.display(),
+            prefix_path.display()
+        ));
+    }
+    log_install(&format!(
+        "Brief launch finished (exited normally: {}, crashed: {})",
+        exited_normally, crashed
+    ));
+    Ok(BriefLaunch { documents, prefix_path, exited_normally, crashed })
+}
+
+/// Link `Documents/My Games/<folder>` from each detected Steam game's prefix
+/// into the given mod manager prefix.
+///
+/// Existing folders in the manager prefix are left alone. Returns the names of
+/// the games that were linked.
+pub fn auto_import_game_saves(prefix_path: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let docs = find_documents_dir(prefix_path)
+        .ok_or("Prefix has no user Documents folder yet - launch the mod manager once first")?;
+    let target_root = docs.join("My Games");
+    fs::create_dir_all(&target_root)?;
+
+    let mut imported = Vec::new();
+    for game in detect_all_games().games {
+        if !matches!(game.launcher, Launcher::Steam { .. }) {
+            continue;
+        }
+        let (Some(folder), Some(source)) = (game.my_games_folder.as_ref(), game.get_prefix_my_games_path()) else {
+            continue;
+        };
+        if !source.is_dir() {
+            continue;
+        }
+
+        let target = target_root.join(folder);
+        if target.symlink_metadata().is_ok() {
+            if fs::read_link(&target).ok().as_deref() == Some(source.as_path()) {
+                continue;
+            }
+            log_warning(&format!("Skipping {}: {:?} already exists", game.name, target));
+            continue;
+        }
+
+        std::os::unix::fs::symlink(&source, &target)?;
+        log_install(&format!("Linked saves for {}: {:?} -> {:?}", game.name, target, source));
+        imported.push(game.name);
+    }
+
+    Ok(imported)
+}
+
+/// Launch the manager once so its prefix is initialized, then import game saves
+///
+/// If Steam put the prefix somewhere other than `prefix_path`, NaK's record
+/// of it is updated. Returns the linked games and how the launch went.
+pub fn set_up_saves(
+    app_id: u32,
+    prefix_path: &Path,
+    proton: &SteamProton,
+) -> Result<(Vec<String>, BriefLaunch), Box<dyn Error>> {
+    let launch = brief_launch_and_kill(app_id, prefix_path, proton, prefix_init_timeout())?;
+    if launch.prefix_path != prefix_path {
+        log_warning(&format!("Recording {} as the prefix of AppID {}", launch.prefix_path.display(), app_id));
+        ManagedPrefixes::update_prefix_path(app_id, &launch.prefix_path.to_string_lossy());
+    }
+    Ok((auto_import_game_saves(&launch.prefix_path)?, launch))
+}
@@ -15,7 +15,7 @@
 #![allow(dead_code)]
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::game_finder::{detect_all_games, Game, GameScanResult};
 use crate::logging::{log_info, log_warning};
@@ -116,6 +116,22 @@ pub fn create_game_symlinks(nak_prefix: &Path, games: &[Game]) {
     }
 }
 
+/// NaK Tools convenience links and the prefix folders they point to
+pub(crate) fn nak_tools_symlink_targets(tools_dir: &Path, prefix_path: &Path) -> [(PathBuf, PathBuf, &'static str); 3] {
+    let users_dir = prefix_path.join("drive_c/users");
+    let username = find_prefix_username(&users_dir);
+    let user_dir = users_dir.join(&username);
+
+    [
+        // NaK Tools/Prefix Documents -> prefix Documents
+        (tools_dir.join("Prefix Documents"), user_dir.join("Documents"), "Prefix Documents"),
+        // NaK Tools/Prefix AppData Local -> prefix AppData/Local
+        (tools_dir.join("Prefix AppData Local"), user_dir.join("AppData/Local"), "Prefix AppData Local"),
+        // NaK Tools/Prefix AppData Roaming -> prefix AppData/Roaming
+        (tools_dir.join("Prefix AppData Roaming"), user_dir.join("AppData/Roaming"), "Prefix AppData Roaming"),
+    ]
+}
+
 /// Create NaK Tools convenience symlinks pointing INTO the prefix
 ///
 /// Creates symlinks in NaK Tools folder for easy access:
@@ -123,28 +139,27 @@ pub fn create_game_symlinks(nak_prefix: &Path, games: &[Game]) {
 /// - NaK Tools/Prefix AppData Local -> prefix/drive_c/users/<user>/AppData/Local
 /// - NaK Tools/Prefix AppData Roaming -> prefix/drive_c/users/<user>/AppData/Roaming
 pub fn create_nak_tools_symlinks(tools_dir: &Path, prefix_path: &Path) {
-    let users_dir = prefix_path.join("drive_c/users");
-    let username = find_prefix_username(&users_dir);
-    let user_dir = users_dir.join(&username);
-
-    // Symlink: NaK Tools/Prefix Documents -> prefix Documents
-    let documents_link = tools_dir.join("Prefix Documents");
-    let documents_target = user_dir.join("Documents");
-    create_or_update_symlink(&documents_link, &documents_target, "Prefix Documents");
-
-    // Symlink: NaK Tools/Prefix AppData Local -> prefix AppData/Local
-    let appdata_local_link = tools_dir.join("Prefix AppData Local");
-    let appdata_local_target = user_dir.join("AppData/Local");
-    create_or_update_symlink(&appdata_local_link, &appdata_local_target, "Prefix AppData Local");
-
-    // Symlink: NaK Tools/Prefix AppData Roaming -> prefix AppData/Roaming
-    let appdata_roaming_link = tools_dir.join("Prefix AppData Roaming");
-    let appdata_roaming_target = user_dir.join("AppData/Roaming");
-    create_or_update_symlink(&appdata_roaming_link, &appdata_roaming_target, "Prefix AppData Roaming");
+    for (link, target, name) in nak_tools_symlink_targets(tools_dir, prefix_path) {
+        create_or_update_symlink(&link, &target, name);
+    }
 
     log_info("Created NaK Tools convenience symlinks to prefix folders");
 }
 
+/// Recreate NaK Tools convenience symlinks that are missing or point elsewhere
+///
+/// Returns how many links were fixed.
+pub fn repair_nak_tools_symlinks(tools_dir: &Path, prefix_path: &Path) -> usize {
+    let mut fixed = 0;
+    for (link, target, name) in nak_tools_symlink_targets(tools_dir, prefix_path) {
+        if fs::read_link(&link).ok().as_deref() != Some(target.as_path()) {
+            create_or_update_symlink(&link, &target, name);
+            fixed += 1;
+        }
+    }
+    fixed
+}
+
 /// Create or update a symlink
 fn create_or_update_symlink(link_path: &Path, target: &Path, name: &str) {
     // Remove existing symlink or file
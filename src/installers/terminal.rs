@@ -0,0 +1,118 @@
+//! Terminal with a prefix's Wine environment
+//!
+//! For debugging a prefix by hand: opens the configured terminal (or the
+//! first one found) running bash with WINEPREFIX, WINE, WINESERVER and the
+//! Proton bin directory on PATH, so `wine`, `wine reg` and `wineserver -k`
+//! work as they would for NaK.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use super::prefix_env;
+use crate::config::AppConfig;
+use crate::deps::check_command_available;
+use crate::logging::log_info;
+use crate::runtime_wrap;
+use crate::steam::{shell_dq_escape, SteamProton};
+
+/// Terminals tried when none is configured, with the flags that make them run a command
+const TERMINALS: &[(&str, &[&str])] = &[
+    ("ptyxis", &["--"]),
+    ("konsole", &["-e"]),
+    ("gnome-terminal", &["--"]),
+    ("xfce4-terminal", &["-x"]),
+    ("kitty", &[]),
+    ("alacritty", &["-e"]),
+    ("xterm", &["-e"]),
+];
+
+/// Command line that opens a terminal running `command`
+///
+/// `configured` is the Settings value: a terminal and the flags that make it
+/// run a command (e.g. "wezterm start --"). Empty uses the first of
+/// `TERMINALS` that `available` finds.
+fn terminal_command_line(configured: &str, available: impl Fn(&str) -> bool, command: &[String]) -> Option<Vec<String>> {
+    let mut line: Vec<String> = if configured.trim().is_empty() {
+        let (terminal, flags) = TERMINALS.iter().find(|(terminal, _)| available(terminal))?;
+        std::iter::once(*terminal).chain(flags.iter().copied()).map(String::from).collect()
+    } else {
+        configured.split_whitespace().map(String::from).collect()
+    };
+    line.extend(command.iter().cloned());
+    Some(line)
+}
+
+/// Bash rcfile that loads the user's bashrc, then the prefix environment
+///
+/// NaK's own WINEDLLOVERRIDES (no Gecko for silent regedit runs) is left
+/// out, and PATH keeps the terminal's own entries after the Proton bin dir.
+fn prefix_shell_rc(name: &str, envs: &[(&'static str, String)]) -> String {
+    let mut rc = format!("# NaK prefix shell for {}\n[ -f ~/.bashrc ] && . ~/.bashrc\n", name.replace('\n', " "));
+    for (key, value) in envs {
+        match *key {
+            "WINEDLLOVERRIDES" => {}
+            "PATH" => {
+                let bin_dir = value.split(':').next().unwrap_or_default();
+                rc.push_str(&format!("export PATH=\"{}:$PATH\"\n", shell_dq_escape(bin_dir)));
+            }
+            _ => rc.push_str(&format!("export {}=\"{}\"\n", key, shell_dq_escape(value))),
+        }
+    }
+    rc.push_str(&format!("PS1=\"(NaK: {}) $PS1\"\n", shell_dq_escape(name)));
+    rc.push_str("echo \"WINEPREFIX=$WINEPREFIX\"\n");
+    rc.push_str("echo \"Proton's wine is on PATH: try 'wine reg query HKCU\\\\Software\\\\Wine' or 'wineserver -k'.\"\n");
+    rc
+}
+
+/// Open a terminal whose shell has the prefix's Wine environment loaded
+pub fn open_prefix_terminal(name: &str, prefix_path: &Path, proton: &SteamProton) -> Result<(), String> {
+    let (_, envs) = prefix_env(prefix_path, proton)?;
+
+    // One rcfile per prefix, so opening two prefixes at once can't mix them up
+    let mut hasher = DefaultHasher::new();
+    prefix_path.hash(&mut hasher);
+    let tmp_dir = AppConfig::get_tmp_path();
+    fs::create_dir_all(&tmp_dir).map_err(|e| format!("Failed to create {}: {}", tmp_dir.display(), e))?;
+    let rc_file = tmp_dir.join(format!("prefix_shell_{:016x}.rc", hasher.finish()));
+    fs::write(&rc_file, prefix_shell_rc(name, &envs))
+        .map_err(|e| format!("Failed to write {}: {}", rc_file.display(), e))?;
+
+    let shell = ["bash".to_string(), "--rcfile".to_string(), rc_file.display().to_string()];
+    let configured = AppConfig::load().terminal_command;
+    let line = terminal_command_line(&configured, check_command_available, &shell)
+        .ok_or("No terminal found. Set one in Settings > Terminal.")?;
+
+    log_info(&format!("Opening prefix terminal for {} with {}", name, line[0]));
+    runtime_wrap::command_for(Path::new(&line[0]))
+        .args(&line[1..])
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", line[0], e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_command_line() {
+        let shell = ["bash".to_string(), "--rcfile".to_string(), "/tmp/x.rc".to_string()];
+        let line = terminal_command_line("", |t| t == "konsole" || t == "xterm", &shell).unwrap();
+        assert_eq!(line, ["konsole", "-e", "bash", "--rcfile", "/tmp/x.rc"]);
+        let line = terminal_command_line(" wezterm start -- ", |_| false, &shell).unwrap();
+        assert_eq!(line, ["wezterm", "start", "--", "bash", "--rcfile", "/tmp/x.rc"]);
+        assert_eq!(terminal_command_line("", |_| false, &shell), None);
+
+        let envs = vec![
+            ("WINEPREFIX", "/games/My \"MO2\"/pfx".to_string()),
+            ("PATH", "/proton/files/bin:/usr/bin".to_string()),
+            ("WINEDLLOVERRIDES", "mshtml=d".to_string()),
+        ];
+        let rc = prefix_shell_rc("MO2", &envs);
+        assert!(rc.contains("export WINEPREFIX=\"/games/My \\\"MO2\\\"/pfx\"\n"));
+        assert!(rc.contains("export PATH=\"/proton/files/bin:$PATH\"\n"));
+        assert!(!rc.contains("mshtml"));
+    }
+}
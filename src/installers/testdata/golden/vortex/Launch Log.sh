@@ -0,0 +1,47 @@
+#!/bin/bash
+# NaK Launch Log wrapper for Vortex
+# Steam runs this in front of Proton when "Log launches" is on, so the
+# output of the last launches survives a launch from Steam or a file manager.
+#
+# launch.log is the latest launch, launch.log.1 the one before, and so on.
+
+LOG_DIR="$(cd "$(dirname "$0")" && pwd)"
+LOG="$LOG_DIR/launch.log"
+KEEP=3
+
+if [ $# -eq 0 ]; then
+    echo "This wrapper is run by Steam (launch options: \"$0\" %command%)."
+    echo "The last launch is logged in $LOG"
+    exit 1
+fi
+
+# Rotate: launch.log -> launch.log.1 -> ... -> launch.log.$KEEP
+for ((i = KEEP - 1; i >= 1; i--)); do
+    [ -f "$LOG.$i" ] && mv -f "$LOG.$i" "$LOG.$((i + 1))"
+done
+[ -f "$LOG" ] && mv -f "$LOG" "$LOG.1"
+
+{
+    echo "=== NaK launch log for Vortex ==="
+    echo "Started: $(date '+%Y-%m-%d %H:%M:%S')"
+    echo "Prefix: ${STEAM_COMPAT_DATA_PATH:-unknown}"
+    echo "Command: $*"
+    echo
+} > "$LOG"
+
+"$@" 2>&1 | tee -a "$LOG"
+STATUS=${PIPESTATUS[0]}
+
+{
+    echo
+    echo "Exited with status $STATUS at $(date '+%Y-%m-%d %H:%M:%S')"
+    # Proton only writes its own log with PROTON_LOG=1; keep its end with this launch
+    PROTON_LOG_FILE="${PROTON_LOG_DIR:-$HOME}/steam-${SteamGameId:-0}.log"
+    if [ -n "$PROTON_LOG" ] && [ "$PROTON_LOG" != "0" ] && [ -f "$PROTON_LOG_FILE" ]; then
+        echo
+        echo "=== Last 200 lines of $PROTON_LOG_FILE ==="
+        tail -n 200 "$PROTON_LOG_FILE"
+    fi
+} >> "$LOG"
+
+exit "$STATUS"
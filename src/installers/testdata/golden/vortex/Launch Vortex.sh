@@ -0,0 +1,13 @@
+#!/bin/bash
+set -e -o pipefail
+# NaK Steam Launch Script for Vortex
+# Launches via Steam using the 64-bit game ID format for non-Steam shortcuts
+#
+# Use this to manually launch the mod manager through Steam.
+# This is equivalent to clicking Play in Steam.
+
+# 64-bit Game ID (required for non-Steam shortcuts)
+GAME_ID=12884901888033554432
+
+echo "Launching Vortex via Steam..."
+xdg-open "steam://rungameid/$GAME_ID"
@@ -6,32 +6,53 @@
 //!
 //! - `core` (always available): game detection, Proton detection, Steam paths,
 //!   config management, logging
-//! - `full` (default): adds installers, deps, marketplace, updater, nxm,
-//!   networking, archive handling, and all heavy dependencies
+//! - `installer`: prefix setup, dependencies and MO2 install/setup, with
+//!   ureq as the only networking dependency (archives via system tools)
+//! - `full` (default): adds the other installers, marketplace, updater, nxm,
+//!   archive handling, and all heavy dependencies
 
 // Core modules - always available
 pub mod config;
 pub mod game_finder;
 pub mod logging;
 pub mod steam;
+pub mod tasks;
+#[cfg(any(feature = "installer", feature = "full"))]
+pub mod net;
 #[cfg(any(feature = "installer", feature = "full"))]
 pub mod runtime_wrap;
+#[cfg(any(feature = "installer", feature = "full"))]
+pub mod telemetry;
 
 // Installer modules - available with "installer" or "full" feature
 // Provides prefix setup, winetricks, .NET installation, registry settings
 #[cfg(any(feature = "installer", feature = "full"))]
 pub mod deps;
 #[cfg(any(feature = "installer", feature = "full"))]
+pub mod github;
+#[cfg(any(feature = "installer", feature = "full"))]
 pub mod installers;
+#[cfg(any(feature = "installer", feature = "full"))]
+pub mod utils;
 
 // Full modules - only available with the "full" feature
 #[cfg(feature = "full")]
-pub mod github;
-#[cfg(feature = "full")]
 pub mod marketplace;
 #[cfg(feature = "full")]
 pub mod nxm;
 #[cfg(feature = "full")]
 pub mod updater;
-#[cfg(feature = "full")]
-pub mod utils;
+
+// Build check for `--no-default-features --features installer`: naming the
+// slim build's entry points here makes that build fail if one of them is
+// gated behind `full` again.
+#[cfg(all(feature = "installer", not(feature = "full")))]
+const _: () = {
+    let _ = installers::setup_existing_mo2;
+    let _ = installers::install_mo2;
+    let _ = installers::install_all_dependencies;
+    let _ = installers::setup_prefix;
+    let _ = installers::fetch_latest_mo2_release;
+    let _ = deps::ensure_winetricks;
+    let _ = utils::download_file;
+};
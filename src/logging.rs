@@ -5,6 +5,7 @@
 use chrono::Local;
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use parking_lot::Mutex;
 use std::sync::{Arc, OnceLock};
@@ -30,6 +31,7 @@ pub struct SystemInfo {
     pub gpu: String,
     pub glibc_version: String,
     pub disk_space_free: String,
+    pub limits: String,
 }
 
 impl SystemInfo {
@@ -46,6 +48,7 @@ impl SystemInfo {
             gpu: detect_gpu(),
             glibc_version: detect_glibc(),
             disk_space_free: detect_disk_space(),
+            limits: ResourceLimits::detect().summary(),
         }
     }
 
@@ -65,6 +68,7 @@ System Info:
   GPU:         {}
   GLIBC:       {}
   Disk Free:   {}
+  Limits:      {}
 ================================================================================
 "#,
             Local::now().format("%Y-%m-%d %H:%M:%S"),
@@ -78,7 +82,8 @@ System Info:
             self.memory_gb,
             self.gpu,
             self.glibc_version,
-            self.disk_space_free
+            self.disk_space_free,
+            self.limits
         )
     }
 }
@@ -251,6 +256,87 @@ fn detect_gpu() -> String {
     "Unknown".to_string()
 }
 
+// ============================================================================
+// Resource Limits
+// ============================================================================
+
+/// inotify watches recommended for large mod lists (the default on newer distros)
+pub const RECOMMENDED_INOTIFY_WATCHES: u64 = 524288;
+
+/// Hard open-file limit recommended for Proton (esync and USVFS hold many fds)
+pub const RECOMMENDED_NOFILE_HARD: u64 = 524288;
+
+/// Kernel/process limits that large mod lists run into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// `fs.inotify.max_user_watches`
+    pub inotify_watches: Option<u64>,
+    /// RLIMIT_NOFILE soft limit (u64::MAX = unlimited)
+    pub open_files_soft: Option<u64>,
+    /// RLIMIT_NOFILE hard limit (u64::MAX = unlimited)
+    pub open_files_hard: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn detect() -> Self {
+        let inotify_watches = std::fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        let (open_files_soft, open_files_hard) = std::fs::read_to_string("/proc/self/limits")
+            .map(|s| parse_nofile_limit(&s))
+            .unwrap_or_default();
+        Self { inotify_watches, open_files_soft, open_files_hard }
+    }
+
+    /// Limits below the recommended values, each with the command that fixes it
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if let Some(watches) = self.inotify_watches.filter(|w| *w < RECOMMENDED_INOTIFY_WATCHES) {
+            warnings.push(format!(
+                "inotify watch limit is {} (recommended {}). Fix: echo fs.inotify.max_user_watches={} | sudo tee /etc/sysctl.d/60-nak-inotify.conf && sudo sysctl --system",
+                watches, RECOMMENDED_INOTIFY_WATCHES, RECOMMENDED_INOTIFY_WATCHES
+            ));
+        }
+        if let Some(hard) = self.open_files_hard.filter(|h| *h < RECOMMENDED_NOFILE_HARD) {
+            warnings.push(format!(
+                "Open file limit is {} (recommended {}, check with ulimit -Hn). Fix: add DefaultLimitNOFILE=1024:{} to /etc/systemd/system.conf and /etc/systemd/user.conf, then reboot",
+                hard, RECOMMENDED_NOFILE_HARD, RECOMMENDED_NOFILE_HARD
+            ));
+        }
+        warnings
+    }
+
+    /// "inotify watches 8192, open files 1024 soft / 524288 hard"
+    pub fn summary(&self) -> String {
+        fn show(value: Option<u64>) -> String {
+            match value {
+                Some(u64::MAX) => "unlimited".to_string(),
+                Some(v) => v.to_string(),
+                None => "unknown".to_string(),
+            }
+        }
+        format!(
+            "inotify watches {}, open files {} soft / {} hard",
+            show(self.inotify_watches),
+            show(self.open_files_soft),
+            show(self.open_files_hard)
+        )
+    }
+}
+
+/// Soft and hard limits from the "Max open files" line of /proc/<pid>/limits
+fn parse_nofile_limit(limits: &str) -> (Option<u64>, Option<u64>) {
+    let parse = |v: &str| if v == "unlimited" { Some(u64::MAX) } else { v.parse().ok() };
+    limits
+        .lines()
+        .find_map(|line| line.strip_prefix("Max open files"))
+        .map(|rest| {
+            let mut values = rest.split_whitespace();
+            (values.next().and_then(parse), values.next().and_then(parse))
+        })
+        .unwrap_or_default()
+}
+
 // ============================================================================
 // Log Levels
 // ============================================================================
@@ -282,8 +368,38 @@ impl LogLevel {
 // NaK Logger
 // ============================================================================
 
+/// Start a new log file once the current one grows past this size
+const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// How many log files to keep, and for how long
+#[derive(Debug, Clone, Copy)]
+pub struct LogRetention {
+    /// Maximum number of log files to keep (including the current one)
+    pub max_files: usize,
+    /// Delete log files older than this many days (0 = keep regardless of age)
+    pub max_age_days: u32,
+}
+
+impl LogRetention {
+    /// Read the retention settings from the app config
+    pub fn from_config() -> Self {
+        let config = crate::config::AppConfig::load();
+        Self {
+            max_files: config.log_retention_count.max(1),
+            max_age_days: config.log_retention_days,
+        }
+    }
+}
+
 pub struct NakLogger {
     log_file: Option<File>,
+    log_path: Option<PathBuf>,
+    logs_dir: PathBuf,
+    bytes_written: u64,
+    max_file_bytes: u64,
+    retention: LogRetention,
+    /// Echo log lines to stdout (off while the terminal UI owns the screen)
+    console: bool,
 }
 
 impl Default for NakLogger {
@@ -300,23 +416,10 @@ impl NakLogger {
             .ok()
             .and_then(|p| p.parent().map(|p| p.to_path_buf()))
             .unwrap_or_else(|| {
-                std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."))
+                std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
             });
 
-        // Rotate old logs before creating a new one
-        rotate_logs(&logs_dir, 10);
-
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let log_path = logs_dir.join(format!("nak_{}.log", timestamp));
-
-        let log_file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)
-            .ok();
-
-        let has_log_file = log_file.is_some();
-        let mut logger = Self { log_file };
+        let mut logger = Self::open_in(logs_dir, LogRetention::from_config(), MAX_LOG_FILE_BYTES);
 
         // Write system info header
         let sys_info = SystemInfo::detect();
@@ -324,22 +427,71 @@ impl NakLogger {
         logger.write_raw(&header);
 
         // Log where the file is being written
-        if has_log_file {
-            eprintln!("Log file: {}", log_path.display());
+        if let Some(ref path) = logger.log_path {
+            eprintln!("Log file: {}", path.display());
         }
 
         logger
     }
 
+    /// Open a fresh log file in `logs_dir`, pruning old logs first
+    fn open_in(logs_dir: PathBuf, retention: LogRetention, max_file_bytes: u64) -> Self {
+        let mut logger = Self {
+            log_file: None,
+            log_path: None,
+            logs_dir,
+            bytes_written: 0,
+            max_file_bytes,
+            retention,
+            console: true,
+        };
+        logger.start_new_file();
+        logger
+    }
+
+    /// Prune old logs and switch to a new timestamped log file
+    fn start_new_file(&mut self) {
+        prune_logs(&self.logs_dir, self.retention);
+
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let mut log_path = self.logs_dir.join(format!("nak_{}.log", timestamp));
+        // Rolling over within the same second: add a counter so we never append to the old file
+        let mut counter = 1;
+        while log_path.exists() {
+            log_path = self.logs_dir.join(format!("nak_{}_{}.log", timestamp, counter));
+            counter += 1;
+        }
+
+        self.log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .ok();
+        self.log_path = self.log_file.as_ref().map(|_| log_path);
+        self.bytes_written = 0;
+    }
+
     fn write_raw(&mut self, msg: &str) {
         // Write to file
         if let Some(ref mut file) = self.log_file {
             let _ = writeln!(file, "{}", msg);
             let _ = file.flush();
+            self.bytes_written += msg.len() as u64 + 1;
         }
 
         // Also print to console
-        println!("{}", msg);
+        if self.console {
+            println!("{}", msg);
+        }
+
+        // Roll over to a new file when the current one gets too large
+        if self.log_file.is_some() && self.bytes_written >= self.max_file_bytes {
+            let previous = self.log_path.clone();
+            self.start_new_file();
+            if let (Some(ref mut file), Some(previous)) = (&mut self.log_file, previous) {
+                let _ = writeln!(file, "(continued from {})", previous.display());
+            }
+        }
     }
 
     pub fn log(&mut self, level: LogLevel, message: &str) {
@@ -393,13 +545,29 @@ pub fn log_error(message: &str) {
     logger().lock().log(LogLevel::Error, message);
 }
 
-/// Remove oldest log files if there are more than `max_logs` in the directory
-fn rotate_logs(logs_dir: &std::path::Path, max_logs: usize) {
+/// Turn echoing log lines to stdout on or off (the log file is always written)
+pub fn set_console_logging(enabled: bool) {
+    logger().lock().console = enabled;
+}
+
+/// Get the directory log files are written to
+pub fn logs_dir() -> PathBuf {
+    logger().lock().logs_dir.clone()
+}
+
+/// Get the path of the log file currently being written (None if logging to console only)
+pub fn current_log_path() -> Option<PathBuf> {
+    logger().lock().log_path.clone()
+}
+
+/// Remove old log files so that, after a new one is created, at most
+/// `retention.max_files` remain, and delete any older than `retention.max_age_days`
+fn prune_logs(logs_dir: &Path, retention: LogRetention) {
     let Ok(entries) = std::fs::read_dir(logs_dir) else {
         return;
     };
 
-    let mut log_files: Vec<std::path::PathBuf> = entries
+    let mut log_files: Vec<PathBuf> = entries
         .filter_map(|e| e.ok())
         .map(|e| e.path())
         .filter(|p| {
@@ -412,11 +580,102 @@ fn rotate_logs(logs_dir: &std::path::Path, max_logs: usize) {
     // Sort alphabetically — timestamp in filename means chronological order
     log_files.sort();
 
-    // Remove oldest files until we're under the limit
-    while log_files.len() >= max_logs {
-        if let Some(oldest) = log_files.first() {
-            let _ = std::fs::remove_file(oldest);
-        }
+    // Drop logs past the age limit
+    if retention.max_age_days > 0 {
+        let max_age = std::time::Duration::from_secs(u64::from(retention.max_age_days) * 24 * 60 * 60);
+        log_files.retain(|path| {
+            let expired = std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > max_age);
+            if expired {
+                let _ = std::fs::remove_file(path);
+            }
+            !expired
+        });
+    }
+
+    // Remove oldest files until there's room for the new one
+    while log_files.len() >= retention.max_files.max(1) {
+        let _ = std::fs::remove_file(&log_files[0]);
         log_files.remove(0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nak_log_test_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn log_count(dir: &Path) -> usize {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with("nak_"))
+            .count()
+    }
+
+    #[test]
+    fn test_resource_limits() {
+        let limits = "Limit                     Soft Limit           Hard Limit           Units\n\
+                      Max open files            1024                 4096                 files\n\
+                      Max locked memory         unlimited            unlimited            bytes\n";
+        assert_eq!(parse_nofile_limit(limits), (Some(1024), Some(4096)));
+        assert_eq!(parse_nofile_limit("Max open files  unlimited  unlimited  files"), (Some(u64::MAX), Some(u64::MAX)));
+        assert_eq!(parse_nofile_limit(""), (None, None));
+
+        let low = ResourceLimits { inotify_watches: Some(8192), open_files_soft: Some(1024), open_files_hard: Some(4096) };
+        let warnings = low.warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("sysctl"));
+        assert!(warnings[1].contains("DefaultLimitNOFILE"));
+
+        let fine = ResourceLimits {
+            inotify_watches: Some(RECOMMENDED_INOTIFY_WATCHES),
+            open_files_soft: Some(1024),
+            open_files_hard: Some(u64::MAX),
+        };
+        assert!(fine.warnings().is_empty());
+        assert!(ResourceLimits::default().warnings().is_empty());
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_logs() {
+        let dir = test_dir("prune");
+        for i in 0..12 {
+            std::fs::write(dir.join(format!("nak_20240101_0000{:02}.log", i)), "x").unwrap();
+        }
+        std::fs::write(dir.join("other.txt"), "x").unwrap();
+
+        prune_logs(&dir, LogRetention { max_files: 5, max_age_days: 0 });
+
+        // Room is left for the log file about to be created
+        assert_eq!(log_count(&dir), 4);
+        assert!(dir.join("nak_20240101_000011.log").exists());
+        assert!(!dir.join("nak_20240101_000000.log").exists());
+        assert!(dir.join("other.txt").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rollover_starts_new_file() {
+        let dir = test_dir("rollover");
+        let mut logger = NakLogger::open_in(dir.clone(), LogRetention { max_files: 3, max_age_days: 0 }, 64);
+        let first = logger.log_path.clone().unwrap();
+
+        logger.log(LogLevel::Info, "a message long enough to fill the tiny test log in one go");
+
+        let current = logger.log_path.clone().unwrap();
+        assert_ne!(first, current);
+        assert!(current.exists());
+        assert_eq!(log_count(&dir), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
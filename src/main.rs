@@ -12,10 +12,15 @@ use std::sync::Arc;
 use clap::{Parser, Subcommand};
 
 mod app;
+#[cfg(feature = "tui")]
+mod tui;
 mod ui;
 
 use app::MyApp;
-use nak_rust::installers::{setup_existing_mo2, TaskContext};
+use nak_rust::installers::{
+    dotnet_preset_components, install_generic_manager, setup_existing_mo2, setup_prefix, GenericSource,
+    PrefixSetupOptions, TaskContext, DOTNET_COMPONENTS,
+};
 use nak_rust::logging::{init_logger, log_info};
 use nak_rust::steam::{find_steam_protons, SteamProton};
 use nak_rust::updater::cleanup_update_backup;
@@ -23,7 +28,7 @@ use nak_rust::updater::cleanup_update_backup;
 /// NaK - Linux Mod Manager Tool
 ///
 /// Run without arguments to launch the GUI.
-/// Use subcommands for CLI automation.
+/// Use subcommands for CLI automation, or --tui for a terminal interface.
 #[derive(Parser)]
 #[command(name = "nak")]
 #[command(author = "NaK Team")]
@@ -32,6 +37,11 @@ use nak_rust::updater::cleanup_update_backup;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Run the terminal interface instead of the GUI (no X11/Wayland needed)
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    tui: bool,
 }
 
 #[derive(Subcommand)]
@@ -49,6 +59,45 @@ enum Commands {
         /// Proton version to use (name or index from --list-protons)
         #[arg(long)]
         proton: Option<String>,
+
+        /// Skip installing dependencies if the prefix already has them (same
+        /// components and Proton major version as this setup)
+        #[arg(long)]
+        reuse_deps: bool,
+    },
+
+    /// Install any other Windows mod manager (Kortex, MO2 forks, ...) with Steam/Proton integration
+    ///
+    /// Give either --url (a .zip, .7z or .exe download) or --from (a folder
+    /// that already contains the manager).
+    InstallManager {
+        /// Executable to launch, relative to the install folder (e.g. "Kortex.exe")
+        #[arg(short, long)]
+        exe: String,
+
+        /// Where to install the manager (defaults to the --from folder)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+
+        /// Download URL for the manager
+        #[arg(long, conflicts_with = "from")]
+        url: Option<String>,
+
+        /// Existing folder containing the manager
+        #[arg(long)]
+        from: Option<PathBuf>,
+
+        /// Name for the Steam shortcut (defaults to the exe name)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// The manager is an Electron app
+        #[arg(long)]
+        electron: bool,
+
+        /// Proton version to use (name or index from --list-protons)
+        #[arg(long)]
+        proton: Option<String>,
     },
 
     /// List available Proton versions
@@ -56,6 +105,54 @@ enum Commands {
 
     /// Check if Steam is properly detected
     CheckSteam,
+
+    /// Install the standard dependencies into a Bottles bottle
+    ///
+    /// Run without --bottle to list detected bottles.
+    BottleDeps {
+        /// Path to the bottle (directory containing drive_c and bottle.yml)
+        #[arg(short, long)]
+        bottle: Option<PathBuf>,
+    },
+
+    /// Set up a Proton prefix (wineboot, dependencies, registry) without a mod manager
+    ///
+    /// The prefix is created if needed. PATH must be the "pfx" folder inside
+    /// a compatdata folder, e.g. ~/Games/tool/compatdata/pfx.
+    SetupPrefix {
+        /// Path to the prefix ("pfx" folder)
+        path: PathBuf,
+
+        /// Proton version to use (name or index from --list-protons)
+        #[arg(long)]
+        proton: Option<String>,
+
+        /// Comma-separated winetricks verbs (default: NaK's standard set).
+        /// `xact:32` or `xact:64` installs one architecture only
+        #[arg(long, value_delimiter = ',')]
+        verbs: Option<Vec<String>>,
+
+        /// Skip the .NET 9 SDK / .NET Desktop 10 installers
+        #[arg(long)]
+        no_dotnet: bool,
+
+        /// .NET components to install: a preset (full, mo2-minimal) or a
+        /// comma-separated list, e.g. dotnet8,dotnetdesktop6 (default: full)
+        #[arg(long, value_delimiter = ',')]
+        dotnet: Option<Vec<String>>,
+
+        /// Don't register installed games in the prefix
+        #[arg(long)]
+        no_games: bool,
+
+        /// Windows version (win11, win10, win81, win7, winxp)
+        #[arg(long, default_value = "win11")]
+        windows_version: String,
+
+        /// DPI to apply, e.g. 144 for 150% scaling
+        #[arg(long)]
+        dpi: Option<u32>,
+    },
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -73,8 +170,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         log_info("NaK CLI mode starting...");
 
         match command {
-            Commands::SetupMo2 { path, name, proton } => {
-                setup_mo2_cli(path, name, proton);
+            Commands::SetupMo2 { path, name, proton, reuse_deps } => {
+                setup_mo2_cli(path, name, proton, reuse_deps);
+            }
+            Commands::InstallManager { exe, path, url, from, name, electron, proton } => {
+                install_manager_cli(exe, path, url, from, name, electron, proton);
             }
             Commands::ListProtons => {
                 list_protons();
@@ -82,11 +182,45 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Commands::CheckSteam => {
                 check_steam();
             }
+            Commands::BottleDeps { bottle } => {
+                bottle_deps_cli(bottle);
+            }
+            Commands::SetupPrefix { path, proton, verbs, no_dotnet, dotnet, no_games, windows_version, dpi } => {
+                let mut options = PrefixSetupOptions {
+                    dotnet_installers: !no_dotnet,
+                    register_games: !no_games,
+                    windows_version: Some(windows_version),
+                    dpi,
+                    ..Default::default()
+                };
+                if let Some(verbs) = verbs {
+                    options.verbs = verbs;
+                }
+                if let Some(dotnet) = dotnet {
+                    let preset = match dotnet.as_slice() {
+                        [one] => dotnet_preset_components(one),
+                        _ => None,
+                    };
+                    options.dotnet = preset.unwrap_or(dotnet);
+                    if let Some(unknown) =
+                        options.dotnet.iter().find(|c| !DOTNET_COMPONENTS.iter().any(|(name, _)| name == *c))
+                    {
+                        eprintln!("Error: Unknown .NET component '{}'. Use full, mo2-minimal or names like dotnet8.", unknown);
+                        std::process::exit(1);
+                    }
+                }
+                setup_prefix_cli(path, proton, options);
+            }
         }
 
         return Ok(());
     }
 
+    #[cfg(feature = "tui")]
+    if cli.tui {
+        return tui::run_tui();
+    }
+
     // No subcommand - run GUI mode
     log_info("NaK GUI starting...");
 
@@ -103,24 +237,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 // CLI Functions
 // ============================================================================
 
-fn setup_mo2_cli(path: PathBuf, name: String, proton_arg: Option<String>) {
-    println!("Setting up MO2 at: {}", path.display());
-    println!("Steam shortcut name: {}", name);
-
-    // Verify path exists
-    if !path.exists() {
-        eprintln!("Error: Path does not exist: {}", path.display());
-        std::process::exit(1);
-    }
-
-    // Verify ModOrganizer.exe exists
-    let mo2_exe = path.join("ModOrganizer.exe");
-    if !mo2_exe.exists() {
-        eprintln!("Error: ModOrganizer.exe not found at: {}", path.display());
-        eprintln!("Please provide the path to an existing MO2 installation.");
-        std::process::exit(1);
-    }
-
+/// Pick a Proton from a --proton argument (name or index), exiting on error
+fn select_proton_cli(proton_arg: Option<String>) -> SteamProton {
     // Find available Protons
     let protons = find_steam_protons();
     if protons.is_empty() {
@@ -130,7 +248,7 @@ fn setup_mo2_cli(path: PathBuf, name: String, proton_arg: Option<String>) {
     }
 
     // Select Proton
-    let selected_proton: SteamProton = match proton_arg {
+    match proton_arg {
         Some(arg) => {
             // Try to find by name first
             if let Some(p) = protons.iter().find(|p| p.name.eq_ignore_ascii_case(&arg)) {
@@ -154,12 +272,11 @@ fn setup_mo2_cli(path: PathBuf, name: String, proton_arg: Option<String>) {
             println!("No Proton specified, using: {}", protons[0].name);
             protons[0].clone()
         }
-    };
-
-    println!("Using Proton: {}", selected_proton.name);
-    println!();
+    }
+}
 
-    // Create a simple CLI task context
+/// Task context that prints progress to stdout and cancels on Ctrl+C
+fn cli_task_context() -> TaskContext {
     let cancel_flag = Arc::new(AtomicBool::new(false));
     let ctx = TaskContext::new(
         |status| println!("[STATUS] {}", status),
@@ -174,7 +291,8 @@ fn setup_mo2_cli(path: PathBuf, name: String, proton_arg: Option<String>) {
             let _ = std::io::stdout().flush();
         },
         cancel_flag.clone(),
-    );
+    )
+    .on_phase(|phase| println!("\n[PHASE] {}", phase));
 
     // Handle Ctrl+C
     let cancel_flag_ctrlc = cancel_flag.clone();
@@ -190,8 +308,35 @@ fn setup_mo2_cli(path: PathBuf, name: String, proton_arg: Option<String>) {
         force_quit_handler.store(true, std::sync::atomic::Ordering::SeqCst);
     }).expect("Failed to set Ctrl+C handler");
 
+    ctx
+}
+
+fn setup_mo2_cli(path: PathBuf, name: String, proton_arg: Option<String>, reuse_deps: bool) {
+    println!("Setting up MO2 at: {}", path.display());
+    println!("Steam shortcut name: {}", name);
+
+    // Verify path exists
+    if !path.exists() {
+        eprintln!("Error: Path does not exist: {}", path.display());
+        std::process::exit(1);
+    }
+
+    // Verify ModOrganizer.exe exists
+    let mo2_exe = path.join("ModOrganizer.exe");
+    if !mo2_exe.exists() {
+        eprintln!("Error: ModOrganizer.exe not found at: {}", path.display());
+        eprintln!("Please provide the path to an existing MO2 installation.");
+        std::process::exit(1);
+    }
+
+    let selected_proton = select_proton_cli(proton_arg);
+    println!("Using Proton: {}", selected_proton.name);
+    println!();
+
+    let ctx = cli_task_context();
+
     // Run the setup
-    match setup_existing_mo2(&name, path, &selected_proton, ctx) {
+    match setup_existing_mo2(&name, path, &selected_proton, ctx, reuse_deps) {
         Ok(result) => {
             println!();
             println!("Success! MO2 has been set up with Steam integration.");
@@ -199,7 +344,71 @@ fn setup_mo2_cli(path: PathBuf, name: String, proton_arg: Option<String>) {
             println!("Steam AppID: {}", result.app_id);
             println!("Prefix path: {}", result.prefix_path.display());
             println!();
-            println!("Please RESTART Steam to see the new shortcut in your library.");
+            if nak_rust::steam::restart_steam_after_install() {
+                println!("Steam was restarted; the new shortcut is in your library.");
+            } else {
+                println!("Please RESTART Steam to see the new shortcut in your library.");
+            }
+        }
+        Err(e) => {
+            eprintln!();
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn install_manager_cli(
+    exe: String,
+    path: Option<PathBuf>,
+    url: Option<String>,
+    from: Option<PathBuf>,
+    name: Option<String>,
+    electron: bool,
+    proton_arg: Option<String>,
+) {
+    let source = match (url, from) {
+        (Some(url), _) => GenericSource::Url(url),
+        (None, Some(folder)) => GenericSource::LocalFolder(folder),
+        (None, None) => {
+            eprintln!("Error: Give either --url or --from.");
+            std::process::exit(1);
+        }
+    };
+
+    let install_path = match (path, &source) {
+        (Some(path), _) => path,
+        (None, GenericSource::LocalFolder(folder)) => folder.clone(),
+        (None, GenericSource::Url(_)) => {
+            eprintln!("Error: --path is required when installing from --url.");
+            std::process::exit(1);
+        }
+    };
+
+    let name = name.unwrap_or_else(|| exe.trim_end_matches(".exe").to_string());
+    println!("Installing {} to: {}", exe, install_path.display());
+    println!("Steam shortcut name: {}", name);
+
+    let selected_proton = select_proton_cli(proton_arg);
+    println!("Using Proton: {}", selected_proton.name);
+    println!();
+
+    let ctx = cli_task_context();
+
+    match install_generic_manager(&name, &exe, electron, &source, install_path, &selected_proton, ctx, false) {
+        Ok(result) => {
+            println!();
+            println!("Success! {} has been set up with Steam integration.", name);
+            println!();
+            println!("Steam AppID: {}", result.app_id);
+            println!("Prefix path: {}", result.prefix_path.display());
+            println!();
+            if nak_rust::steam::restart_steam_after_install() {
+                println!("Steam was restarted; the new shortcut is in your library.");
+            } else {
+                println!("Please RESTART Steam to see the new shortcut in your library.");
+            }
         }
         Err(e) => {
             eprintln!();
@@ -240,7 +449,7 @@ fn check_steam() {
             println!("Steam found at: {}", path.display());
 
             // Check for Flatpak
-            if path.to_string_lossy().contains(".var/app/com.valvesoftware.Steam") {
+            if steam::is_flatpak_steam(&path) {
                 println!("Steam type: Flatpak");
             } else {
                 println!("Steam type: Native");
@@ -263,3 +472,68 @@ fn check_steam() {
         }
     }
 }
+
+fn bottle_deps_cli(bottle: Option<PathBuf>) {
+    let Some(bottle) = bottle else {
+        let bottles = nak_rust::game_finder::find_bottles_prefixes();
+        if bottles.is_empty() {
+            println!("No Bottles bottles found.");
+            return;
+        }
+
+        println!("Detected bottles:");
+        println!();
+        for path in bottles {
+            println!("  {}", path.display());
+        }
+        println!();
+        println!("Use --bottle <path> to install dependencies into one of them.");
+        return;
+    };
+
+    if !bottle.join("drive_c").exists() {
+        eprintln!("Error: Not a Wine prefix: {}", bottle.display());
+        std::process::exit(1);
+    }
+
+    println!("Installing dependencies into: {}", bottle.display());
+    println!("This may take a while...");
+
+    match nak_rust::deps::install_standard_deps_to_bottle(&bottle, |msg| println!("{}", msg)) {
+        Ok(()) => {
+            println!();
+            println!("Dependencies installed successfully!");
+        }
+        Err(e) => {
+            eprintln!();
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn setup_prefix_cli(path: PathBuf, proton_arg: Option<String>, options: PrefixSetupOptions) {
+    if let Err(e) = std::fs::create_dir_all(&path) {
+        eprintln!("Error: Can't create {}: {}", path.display(), e);
+        std::process::exit(1);
+    }
+
+    println!("Setting up prefix at: {}", path.display());
+    let selected_proton = select_proton_cli(proton_arg);
+    println!("Using Proton: {}", selected_proton.name);
+    println!();
+
+    let ctx = cli_task_context();
+
+    match setup_prefix(&path, &selected_proton, &options, &ctx) {
+        Ok(()) => {
+            println!();
+            println!("Prefix set up successfully!");
+        }
+        Err(e) => {
+            eprintln!();
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
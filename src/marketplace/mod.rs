@@ -18,12 +18,56 @@ pub struct RegistryEntry {
     pub name: String,
     pub description: String,
     pub folder: String,
+    /// Base URL of the registry this plugin came from (set when fetched)
+    #[serde(skip)]
+    pub registry_url: String,
 }
 
 /// The registry file containing all available plugins
 #[derive(Debug, Clone, Deserialize)]
 pub struct Registry {
     pub plugins: Vec<RegistryEntry>,
+    /// Registries that failed to fetch or validate, with the reason (set when fetched)
+    #[serde(skip)]
+    pub skipped: Vec<String>,
+}
+
+impl Registry {
+    /// Check a parsed registry.toml before its plugins are listed
+    ///
+    /// Every entry needs an id, name and folder, ids must be unique, and
+    /// folders must stay inside the registry (no absolute paths or `..`).
+    pub fn validate(&self) -> Result<(), String> {
+        let mut ids = std::collections::HashSet::new();
+        for entry in &self.plugins {
+            if entry.id.trim().is_empty() || entry.name.trim().is_empty() || entry.folder.trim().is_empty() {
+                return Err(format!("plugin '{}' is missing an id, name or folder", entry.id));
+            }
+            if entry.folder.starts_with('/') || entry.folder.split('/').any(|part| part == "..") {
+                return Err(format!("plugin '{}' has an invalid folder '{}'", entry.id, entry.folder));
+            }
+            if !ids.insert(entry.id.as_str()) {
+                return Err(format!("plugin id '{}' is listed twice", entry.id));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RegistryEntry {
+    /// Short label for the registry a plugin came from, for the plugin list
+    pub fn source_label(&self) -> String {
+        if self.registry_url == MARKETPLACE_RAW_URL {
+            return "NaK Marketplace".to_string();
+        }
+        let url = self.registry_url.trim_end_matches('/');
+        let url = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+        // raw.githubusercontent.com/<owner>/<repo>/<branch> -> <owner>/<repo>
+        match url.strip_prefix("raw.githubusercontent.com/") {
+            Some(path) => path.splitn(3, '/').take(2).collect::<Vec<_>>().join("/"),
+            None => url.to_string(),
+        }
+    }
 }
 
 /// Plugin metadata (from plugin.toml [plugin] section)
@@ -51,9 +95,54 @@ pub struct PluginSource {
 pub struct PluginInstall {
     #[serde(rename = "type")]
     pub install_type: String,
+    /// Executable to launch (not used by `dll-drop` plugins)
+    #[serde(default)]
     pub exe_name: String,
     #[serde(default)]
     pub installer_args: Vec<String>,
+    /// Windows system folder a `dll-drop` DLL is copied into (default "system32")
+    #[serde(default)]
+    pub dll_target: Option<String>,
+}
+
+/// How a plugin's download is installed (the `type` field of [install])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginInstallType {
+    /// NSIS setup exe, run silently through Proton
+    Nsis,
+    /// NSIS setup exe for an Electron app (needs the Electron launch workaround)
+    ElectronNsis,
+    /// Any other Windows installer exe, run through Proton with `installer_args`
+    Installer,
+    /// 7z archive extracted into the install folder
+    Archive7z,
+    /// Zip archive extracted into the install folder (`archive-zip` or `portable-zip`)
+    PortableZip,
+    /// Single DLL copied into an existing prefix with a native DLL override
+    DllDrop,
+}
+
+impl PluginInstallType {
+    /// Parse the manifest `type` string
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "nsis" => Ok(Self::Nsis),
+            "electron-nsis" => Ok(Self::ElectronNsis),
+            "installer" => Ok(Self::Installer),
+            "archive-7z" => Ok(Self::Archive7z),
+            "archive-zip" | "portable-zip" => Ok(Self::PortableZip),
+            "dll-drop" => Ok(Self::DllDrop),
+            other => Err(format!(
+                "Unknown install type '{}' (expected nsis, electron-nsis, installer, archive-7z, archive-zip, portable-zip or dll-drop)",
+                other
+            )),
+        }
+    }
+
+    /// Whether this type produces a launchable executable for a Steam shortcut
+    pub fn has_executable(self) -> bool {
+        self != Self::DllDrop
+    }
 }
 
 /// Complete plugin manifest (plugin.toml)
@@ -70,36 +159,82 @@ pub struct PluginManifest {
 /// HTTP timeout for marketplace requests (30 seconds)
 const MARKETPLACE_TIMEOUT: Duration = Duration::from_secs(30);
 
-/// Fetch the plugin registry from GitHub
+/// Registry base URLs to fetch, in precedence order
+///
+/// The official marketplace comes first, then any `marketplace_registries`
+/// from config.json (duplicates and blank lines dropped).
+pub fn registry_urls() -> Vec<String> {
+    let mut urls = vec![MARKETPLACE_RAW_URL.to_string()];
+    for url in crate::config::AppConfig::load().marketplace_registries {
+        let url = url.trim().trim_end_matches('/').to_string();
+        if !url.is_empty() && !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// Fetch the plugins of every configured registry
+///
+/// Plugins are merged in registry order; when two registries list the same
+/// `id`, the first one wins. A registry that fails to fetch or validate is
+/// skipped with a warning (recorded in `Registry::skipped`); this only fails
+/// if no registry could be read.
 pub fn fetch_registry() -> Result<Registry, Box<dyn Error>> {
-    let url = format!("{}/registry.toml", MARKETPLACE_RAW_URL);
+    let mut registries = Vec::new();
+    let mut skipped = Vec::new();
+    for url in registry_urls() {
+        match fetch_registry_from(&url) {
+            Ok(registry) => registries.push(registry),
+            Err(e) => {
+                crate::logging::log_warning(&format!("Skipping plugin registry {}: {}", url, e));
+                skipped.push(format!("{}: {}", url, e));
+            }
+        }
+    }
+    if registries.is_empty() {
+        return Err(skipped.join("; ").into());
+    }
+
+    let mut merged = merge_registries(registries);
+    merged.skipped = skipped;
+    Ok(merged)
+}
 
-    let response = ureq::AgentBuilder::new()
-        .timeout(MARKETPLACE_TIMEOUT)
-        .build()
-        .get(&url)
-        .set("User-Agent", "NaK-Rust")
-        .call()?;
+/// Fetch and validate one registry.toml
+fn fetch_registry_from(base_url: &str) -> Result<Registry, Box<dyn Error>> {
+    let url = format!("{}/registry.toml", base_url);
 
-    let content = response.into_string()?;
-    let registry: Registry = toml::from_str(&content)?;
+    let mut registry: Registry = crate::net::get_parsed(&url, MARKETPLACE_TIMEOUT, "NaK-Rust", |body| {
+        toml::from_str(body).map_err(|e| e.to_string())
+    })?;
+    registry.validate()?;
+    for entry in &mut registry.plugins {
+        entry.registry_url = base_url.to_string();
+    }
 
     Ok(registry)
 }
 
-/// Fetch a plugin's manifest from GitHub
-pub fn fetch_plugin_manifest(folder: &str) -> Result<PluginManifest, Box<dyn Error>> {
-    let url = format!("{}/{}/plugin.toml", MARKETPLACE_RAW_URL, folder);
+/// Combine registries in precedence order, keeping the first plugin per `id`
+fn merge_registries(registries: Vec<Registry>) -> Registry {
+    let mut plugins: Vec<RegistryEntry> = Vec::new();
+    for entry in registries.into_iter().flat_map(|r| r.plugins) {
+        if !plugins.iter().any(|p| p.id == entry.id) {
+            plugins.push(entry);
+        }
+    }
+    Registry { plugins, skipped: Vec::new() }
+}
 
-    let response = ureq::AgentBuilder::new()
-        .timeout(MARKETPLACE_TIMEOUT)
-        .build()
-        .get(&url)
-        .set("User-Agent", "NaK-Rust")
-        .call()?;
+/// Fetch a plugin's manifest from the registry that listed it
+pub fn fetch_plugin_manifest(entry: &RegistryEntry) -> Result<PluginManifest, Box<dyn Error>> {
+    let base_url = if entry.registry_url.is_empty() { MARKETPLACE_RAW_URL } else { &entry.registry_url };
+    let url = format!("{}/{}/plugin.toml", base_url, entry.folder);
 
-    let content = response.into_string()?;
-    let manifest: PluginManifest = toml::from_str(&content)?;
+    let manifest: PluginManifest = crate::net::get_parsed(&url, MARKETPLACE_TIMEOUT, "NaK-Rust", |body| {
+        toml::from_str(body).map_err(|e| e.to_string())
+    })?;
 
     Ok(manifest)
 }
@@ -110,7 +245,7 @@ pub fn check_version_compatible(min_version: &str) -> bool {
     version_compare::compare_to(current, min_version, version_compare::Cmp::Ge).unwrap_or(false)
 }
 
-use crate::github::GithubRelease;
+use crate::github::GithubAsset;
 
 // ============================================================================
 // Install Functions
@@ -128,39 +263,11 @@ pub fn get_plugin_download_url(manifest: &PluginManifest) -> Result<(String, Str
 
             // Fetch latest release from GitHub API
             let api_url = format!("https://api.github.com/repos/{}/releases/latest", repo);
-            let response: GithubRelease = ureq::AgentBuilder::new()
-                .timeout(MARKETPLACE_TIMEOUT)
-                .build()
-                .get(&api_url)
-                .set("User-Agent", "NaK-Rust")
-                .call()?
-                .into_json()?;
+            let response = crate::github::fetch_release(&api_url, "NaK-Rust")?;
 
             let version = response.tag_name.clone();
 
-            // Find matching asset using glob pattern
-            let pattern_lower = pattern.to_lowercase();
-            let asset = response.assets.iter()
-                .find(|a| {
-                    let name_lower = a.name.to_lowercase();
-                    // Simple glob matching: handle * as wildcard
-                    if pattern_lower.contains('*') {
-                        let parts: Vec<&str> = pattern_lower.split('*').collect();
-                        let mut pos = 0;
-                        for part in parts {
-                            if part.is_empty() { continue; }
-                            if let Some(found) = name_lower[pos..].find(part) {
-                                pos += found + part.len();
-                            } else {
-                                return false;
-                            }
-                        }
-                        true
-                    } else {
-                        name_lower == pattern_lower
-                    }
-                })
-                .ok_or_else(|| format!("No asset matching pattern '{}' found in release", pattern))?;
+            let asset = find_matching_asset(&response.assets, pattern)?;
 
             Ok((asset.browser_download_url.clone(), version))
         }
@@ -173,6 +280,25 @@ pub fn get_plugin_download_url(manifest: &PluginManifest) -> Result<(String, Str
     }
 }
 
+/// Find the release asset whose name matches a plugin's `asset_pattern`
+///
+/// Patterns are shell-style globs (`*`, `?`, `[...]`) matched against the
+/// whole asset name, ignoring case. The first matching asset wins.
+pub fn find_matching_asset<'a>(assets: &'a [GithubAsset], pattern: &str) -> Result<&'a GithubAsset, Box<dyn Error>> {
+    let glob = glob::Pattern::new(pattern)
+        .map_err(|e| format!("Invalid asset_pattern '{}': {}", pattern, e))?;
+    let options = glob::MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+
+    assets
+        .iter()
+        .find(|a| glob.matches_with(&a.name, options))
+        .ok_or_else(|| format!("No asset matching pattern '{}' found in release", pattern).into())
+}
+
 /// Get the installer arguments with placeholders replaced
 pub fn get_installer_args(manifest: &PluginManifest, install_path: &std::path::Path) -> Vec<String> {
     let win_path = format!("Z:{}", install_path.to_string_lossy().replace('/', "\\"));
@@ -197,6 +323,96 @@ pub fn get_plugin_exe_name(manifest: &PluginManifest) -> &str {
 }
 
 /// Get the install type for a plugin
-pub fn get_plugin_install_type(manifest: &PluginManifest) -> &str {
-    &manifest.install.install_type
+pub fn get_plugin_install_type(manifest: &PluginManifest) -> Result<PluginInstallType, String> {
+    PluginInstallType::parse(&manifest.install.install_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assets(names: &[&str]) -> Vec<GithubAsset> {
+        names
+            .iter()
+            .map(|n| GithubAsset {
+                name: n.to_string(),
+                browser_download_url: format!("https://example.com/{}", n),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_multi_star_pattern() {
+        let list = assets(&["tool-1.2-linux-x64.tar.gz", "tool-1.2-win-x64.zip", "tool-1.2-win-x64.zip.sha256"]);
+        assert_eq!(find_matching_asset(&list, "*-win-x64.*").unwrap().name, "tool-1.2-win-x64.zip");
+        assert_eq!(find_matching_asset(&list, "tool-*-linux-*.tar.gz").unwrap().name, "tool-1.2-linux-x64.tar.gz");
+        // Anchored: the checksum file must not match a pattern ending in .zip
+        let only_sums = assets(&["tool-1.2-win-x64.zip.sha256"]);
+        assert!(find_matching_asset(&only_sums, "*.zip").is_err());
+    }
+
+    #[test]
+    fn test_pattern_ignores_case() {
+        let list = assets(&["Vortex-Setup-1.13.exe"]);
+        assert!(find_matching_asset(&list, "vortex-setup-*.EXE").is_ok());
+        assert!(find_matching_asset(&list, "Vortex-Setup-1.13.exe").is_ok());
+    }
+
+    #[test]
+    fn test_parse_install_types() {
+        assert_eq!(PluginInstallType::parse("installer"), Ok(PluginInstallType::Installer));
+        assert_eq!(PluginInstallType::parse("archive-zip"), Ok(PluginInstallType::PortableZip));
+        assert_eq!(PluginInstallType::parse("portable-zip"), Ok(PluginInstallType::PortableZip));
+        assert_eq!(PluginInstallType::parse("dll-drop"), Ok(PluginInstallType::DllDrop));
+        assert!(!PluginInstallType::DllDrop.has_executable());
+        let err = PluginInstallType::parse("msi").unwrap_err();
+        assert!(err.contains("Unknown install type 'msi'"));
+    }
+
+    fn entry(id: &str, folder: &str, registry_url: &str) -> RegistryEntry {
+        RegistryEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            folder: folder.to_string(),
+            registry_url: registry_url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_merge_and_validate_registries() {
+        let official = Registry {
+            plugins: vec![entry("vortex", "vortex", MARKETPLACE_RAW_URL), entry("lml", "lml", MARKETPLACE_RAW_URL)],
+            skipped: Vec::new(),
+        };
+        let community_url = "https://raw.githubusercontent.com/someone/nak-plugins/main";
+        let community = Registry {
+            plugins: vec![entry("lml", "lml-fork", community_url), entry("bodyslide", "bodyslide", community_url)],
+            skipped: Vec::new(),
+        };
+        assert!(official.validate().is_ok());
+        assert!(community.validate().is_ok());
+
+        // Earlier registries win on duplicate ids
+        let merged = merge_registries(vec![official, community]);
+        let ids: Vec<_> = merged.plugins.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, ["vortex", "lml", "bodyslide"]);
+        assert_eq!(merged.plugins[1].folder, "lml");
+        assert_eq!(merged.plugins[0].source_label(), "NaK Marketplace");
+        assert_eq!(merged.plugins[2].source_label(), "someone/nak-plugins");
+
+        let bad = |plugins| Registry { plugins, skipped: Vec::new() }.validate();
+        assert!(bad(vec![entry("x", "../escape", "")]).is_err());
+        assert!(bad(vec![entry("x", "/abs", "")]).is_err());
+        assert!(bad(vec![entry("x", "", "")]).is_err());
+        assert!(bad(vec![entry("x", "a", ""), entry("x", "b", "")]).is_err());
+    }
+
+    #[test]
+    fn test_no_match_and_invalid_pattern_errors() {
+        let list = assets(&["tool.zip"]);
+        let err = find_matching_asset(&list, "*.7z").unwrap_err().to_string();
+        assert!(err.contains("No asset matching pattern '*.7z'"));
+        assert!(find_matching_asset(&list, "[").is_err());
+    }
 }
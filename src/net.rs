@@ -0,0 +1,326 @@
+//! Shared HTTP setup
+//!
+//! Every download goes through `agent_for`/`get`, so the proxy from Settings
+//! (or the usual `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` variables) applies to
+//! GitHub, MO2, .NET and tool downloads alike. `proxy_env` hands the same
+//! proxy to winetricks, whose wget/curl downloads don't go through ureq.
+//! `get_parsed`/`get_json` fetch release metadata and registries with
+//! retries, since a flaky connection often cuts a body off mid-read.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::config::AppConfig;
+use crate::logging::log_warning;
+
+/// Tries per `get_parsed` call
+const FETCH_ATTEMPTS: u32 = 3;
+/// Wait before the second try, doubled before each one after that
+const FETCH_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Host part of a URL, without port or credentials
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host_port = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    if let Some(bracketed) = host_port.strip_prefix('[') {
+        return bracketed.split(']').next().unwrap_or("");
+    }
+    host_port.split(':').next().unwrap_or("")
+}
+
+/// Whether a `NO_PROXY` list exempts a host
+///
+/// Entries match the host itself and its subdomains ("example.com" and
+/// ".example.com" both cover "dl.example.com"); "*" exempts everything.
+fn no_proxy_matches(no_proxy: &str, host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    no_proxy.split(',').map(str::trim).filter(|e| !e.is_empty()).any(|entry| {
+        if entry == "*" {
+            return true;
+        }
+        let entry = entry.trim_start_matches('.').to_ascii_lowercase();
+        let entry = entry.split(':').next().unwrap_or("");
+        host == entry || host.ends_with(&format!(".{}", entry))
+    })
+}
+
+/// First set variable of `names` (both spellings of proxy variables are common)
+fn first_env(env: &impl Fn(&str) -> Option<String>, names: &[&str]) -> Option<String> {
+    names.iter().filter_map(|name| env(name)).find(|v| !v.trim().is_empty())
+}
+
+/// Proxy to use for `url`: the configured one, else the standard variables
+///
+/// `NO_PROXY` is honored for both. `env` reads an environment variable.
+fn proxy_for(url: &str, configured: &str, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    if let Some(no_proxy) = first_env(&env, &["NO_PROXY", "no_proxy"]) {
+        if no_proxy_matches(&no_proxy, url_host(url)) {
+            return None;
+        }
+    }
+    if !configured.trim().is_empty() {
+        return Some(configured.trim().to_string());
+    }
+    if url.starts_with("https://") {
+        first_env(&env, &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"])
+    } else {
+        first_env(&env, &["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"])
+    }
+}
+
+/// Agent builder using `proxy`, if any
+fn builder_with_proxy(proxy: Option<String>) -> ureq::AgentBuilder {
+    let builder = ureq::AgentBuilder::new();
+    let Some(proxy) = proxy else {
+        return builder;
+    };
+    match ureq::Proxy::new(&proxy) {
+        Ok(proxy) => builder.proxy(proxy),
+        Err(e) => {
+            log_warning(&format!("Ignoring invalid proxy '{}': {}", proxy, e));
+            builder
+        }
+    }
+}
+
+/// Check a proxy URL before saving it (empty = no configured proxy)
+pub fn validate_proxy(proxy: &str) -> Result<(), String> {
+    let proxy = proxy.trim();
+    if proxy.is_empty() {
+        return Ok(());
+    }
+    ureq::Proxy::new(proxy).map(|_| ()).map_err(|e| format!("Invalid proxy '{}': {}", proxy, e))
+}
+
+/// Agent builder for requests to `url`, set up with the proxy that applies to it
+///
+/// Add timeouts as needed before `build()`.
+pub fn agent_for(url: &str) -> ureq::AgentBuilder {
+    let configured = AppConfig::load().http_proxy;
+    builder_with_proxy(proxy_for(url, &configured, |name| std::env::var(name).ok()))
+}
+
+/// GET request to `url` through the proxy that applies to it
+pub fn get(url: &str) -> ureq::Request {
+    agent_for(url).build().get(url)
+}
+
+/// Why `get_parsed` failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    /// 404: no such release, tag or file (never retried)
+    NotFound(String),
+    /// Another HTTP error retrying won't fix, e.g. 403 when rate limited
+    Http(u16, String),
+    /// Connection failure, timeout, 5xx/429, or a body cut off mid-read
+    Network(String),
+    /// The body arrived but doesn't parse (malformed or truncated)
+    Parse(String),
+}
+
+impl FetchError {
+    /// Whether another try may succeed
+    fn is_retryable(&self) -> bool {
+        matches!(self, FetchError::Network(_) | FetchError::Parse(_))
+    }
+
+    /// Classify a failed request to `url`
+    fn from_ureq(url: &str, e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(404, _) => FetchError::NotFound(url.to_string()),
+            ureq::Error::Status(code, _) if code == 429 || code >= 500 => {
+                FetchError::Network(format!("{}: HTTP {}", url, code))
+            }
+            ureq::Error::Status(code, _) => FetchError::Http(code, url.to_string()),
+            ureq::Error::Transport(t) => FetchError::Network(format!("{}: {}", url, t)),
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::NotFound(url) => write!(f, "Not found: {}", url),
+            FetchError::Http(code, url) => write!(f, "{}: HTTP {}", url, code),
+            FetchError::Network(msg) => write!(f, "Network error: {}", msg),
+            FetchError::Parse(msg) => write!(f, "Invalid response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Run `attempt` up to `attempts` times while it fails with a retryable error
+fn with_retry<T>(
+    attempts: u32,
+    backoff: Duration,
+    mut attempt: impl FnMut() -> Result<T, FetchError>,
+) -> Result<T, FetchError> {
+    let mut delay = backoff;
+    for tried in 1.. {
+        match attempt() {
+            Err(e) if tried < attempts && e.is_retryable() => {
+                log_warning(&format!("{} (try {}/{}), retrying in {}s", e, tried, attempts, delay.as_secs()));
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    unreachable!()
+}
+
+/// GET `url` and parse the body, retrying network and parse failures with backoff
+///
+/// A truncated body usually fails to parse rather than to read, so parse
+/// errors are retried too. 404 and other client errors are returned at once.
+pub fn get_parsed<T>(
+    url: &str,
+    timeout: Duration,
+    user_agent: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<T, FetchError> {
+    let agent = agent_for(url).timeout(timeout).build();
+    with_retry(FETCH_ATTEMPTS, FETCH_BACKOFF, || {
+        let response = agent
+            .get(url)
+            .set("User-Agent", user_agent)
+            .call()
+            .map_err(|e| FetchError::from_ureq(url, e))?;
+        let body = response.into_string().map_err(|e| FetchError::Network(format!("{}: {}", url, e)))?;
+        parse(&body).map_err(|e| FetchError::Parse(format!("{}: {}", url, e)))
+    })
+}
+
+/// `get_parsed` for a JSON body
+pub fn get_json<T: serde::de::DeserializeOwned>(url: &str, timeout: Duration, user_agent: &str) -> Result<T, FetchError> {
+    get_parsed(url, timeout, user_agent, |body| serde_json::from_str(body).map_err(|e| e.to_string()))
+}
+
+/// Proxy variables for child processes that download on their own (winetricks)
+///
+/// With a proxy set in Settings this exports it under both spellings;
+/// otherwise the variables NaK was started with are passed through, since
+/// Flatpak's host commands don't inherit them.
+pub fn proxy_env() -> Vec<(&'static str, String)> {
+    let configured = AppConfig::load().http_proxy;
+    let configured = configured.trim();
+    let mut envs = Vec::new();
+    for (upper, lower) in [("HTTP_PROXY", "http_proxy"), ("HTTPS_PROXY", "https_proxy")] {
+        let value = if configured.is_empty() {
+            first_env(&|name: &str| std::env::var(name).ok(), &[upper, lower])
+        } else {
+            Some(configured.to_string())
+        };
+        if let Some(value) = value {
+            envs.push((upper, value.clone()));
+            envs.push((lower, value));
+        }
+    }
+    if let Some(no_proxy) = first_env(&|name: &str| std::env::var(name).ok(), &["NO_PROXY", "no_proxy"]) {
+        envs.push(("NO_PROXY", no_proxy.clone()));
+        envs.push(("no_proxy", no_proxy));
+    }
+    envs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_proxy_for() {
+        let env = env_of(&[
+            ("HTTPS_PROXY", "http://secure:3128"),
+            ("http_proxy", "http://plain:3128"),
+            ("NO_PROXY", "localhost, .internal.lan"),
+        ]);
+        assert_eq!(proxy_for("https://github.com/x", "", &env).as_deref(), Some("http://secure:3128"));
+        assert_eq!(proxy_for("http://example.com/x", "", &env).as_deref(), Some("http://plain:3128"));
+        assert_eq!(proxy_for("http://localhost:8080/x", "", &env), None);
+        assert_eq!(proxy_for("https://dl.internal.lan/x", "", &env), None);
+
+        // The configured proxy wins over the variables, but NO_PROXY still applies
+        assert_eq!(proxy_for("https://github.com/x", " http://corp:8080 ", &env).as_deref(), Some("http://corp:8080"));
+        assert_eq!(proxy_for("https://user@internal.lan:443/x", "http://corp:8080", &env), None);
+
+        assert_eq!(proxy_for("https://github.com/x", "", env_of(&[])), None);
+        assert_eq!(proxy_for("https://github.com/x", "http://corp:8080", env_of(&[("no_proxy", "*")])), None);
+    }
+
+    #[test]
+    fn test_with_retry() {
+        // A truncated body parses on the third try
+        let mut calls = 0;
+        let result = with_retry(3, Duration::ZERO, || {
+            calls += 1;
+            if calls < 3 { Err(FetchError::Parse("EOF while parsing".into())) } else { Ok(calls) }
+        });
+        assert_eq!(result, Ok(3));
+
+        // 404 is final
+        let mut calls = 0;
+        let result: Result<(), _> = with_retry(3, Duration::ZERO, || {
+            calls += 1;
+            Err(FetchError::NotFound("https://api.github.com/x".into()))
+        });
+        assert_eq!(calls, 1);
+        assert!(matches!(result, Err(FetchError::NotFound(_))));
+
+        // The last error is returned once the tries run out
+        let mut calls = 0;
+        let result: Result<(), _> = with_retry(3, Duration::ZERO, || {
+            calls += 1;
+            Err(FetchError::Network(format!("HTTP 504 ({})", calls)))
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(result, Err(FetchError::Network("HTTP 504 (3)".into())));
+    }
+
+    /// Read one request (line and headers), returning the request line
+    fn read_request(reader: &mut impl BufRead) -> String {
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 2 {
+            line.clear();
+        }
+        request_line.trim_end().to_string()
+    }
+
+    #[test]
+    fn test_configured_proxy_is_used() {
+        // A one-shot HTTP proxy: accepts a CONNECT tunnel or a plain proxied GET
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let first = read_request(&mut reader);
+            if first.starts_with("CONNECT ") {
+                stream.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n").unwrap();
+                read_request(&mut reader);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nproxied")
+                .unwrap();
+            first
+        });
+
+        // .invalid never resolves, so only the proxy can answer
+        let url = "http://nak-proxy-test.invalid/file";
+        let proxy = proxy_for(url, &format!("http://127.0.0.1:{}", port), env_of(&[]));
+        let body = builder_with_proxy(proxy).build().get(url).call().unwrap().into_string().unwrap();
+
+        assert_eq!(body, "proxied");
+        assert!(server.join().unwrap().contains("nak-proxy-test.invalid"));
+    }
+}
@@ -6,28 +6,164 @@
 
 use std::error::Error;
 use std::fs;
-use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 
-use crate::config::AppConfig;
+use crate::config::{write_atomic, write_atomic_private, AppConfig};
 use crate::logging::{log_install, log_warning};
 
-/// Write data to a file atomically by writing to a sibling `.tmp` file and renaming.
-/// On failure, the `.tmp` file is cleaned up.
-fn atomic_write(path: &Path, data: &[u8]) -> std::io::Result<()> {
-    let tmp = path.with_extension("tmp");
-    let result = (|| {
-        let mut f = fs::File::create(&tmp)?;
-        f.write_all(data)?;
-        f.flush()?;
-        drop(f);
-        fs::rename(&tmp, path)
-    })();
-    if result.is_err() {
-        let _ = fs::remove_file(&tmp);
+/// MIME type NXM links are dispatched under
+const NXM_MIME: &str = "x-scheme-handler/nxm";
+
+/// Desktop file NaK installs for nxm:// links
+const NAK_DESKTOP_FILE: &str = "nak-nxm-handler.desktop";
+
+/// Where a handler's claim on the nxm scheme comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NxmClaimKind {
+    /// A `.desktop` file lists the scheme in `MimeType=`
+    DesktopEntry,
+    /// `[Default Applications]` in a mimeapps.list
+    Default,
+    /// `[Added Associations]` in a mimeapps.list
+    Added,
+}
+
+/// One registration claiming `x-scheme-handler/nxm`
+#[derive(Debug, Clone)]
+pub struct NxmHandlerClaim {
+    /// Desktop file id, e.g. "nak-nxm-handler.desktop"
+    pub desktop_file: String,
+    /// File the claim was found in
+    pub source: PathBuf,
+    pub kind: NxmClaimKind,
+}
+
+impl NxmHandlerClaim {
+    /// Whether this claim points at NaK's handler
+    pub fn is_nak(&self) -> bool {
+        self.desktop_file == NAK_DESKTOP_FILE
     }
-    result
+}
+
+/// Parse the desktop file ids a mimeapps.list assigns to the nxm scheme
+fn parse_mimeapps_claims(content: &str) -> Vec<(NxmClaimKind, String)> {
+    let mut claims = Vec::new();
+    let mut kind = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            kind = match line {
+                "[Default Applications]" => Some(NxmClaimKind::Default),
+                "[Added Associations]" => Some(NxmClaimKind::Added),
+                _ => None,
+            };
+            continue;
+        }
+        let Some(kind) = kind else { continue };
+        let Some((key, value)) = line.split_once('=') else { continue };
+        if key.trim() != NXM_MIME {
+            continue;
+        }
+        for desktop in value.split(';').map(str::trim).filter(|d| !d.is_empty()) {
+            claims.push((kind, desktop.to_string()));
+        }
+    }
+
+    claims
+}
+
+/// Set `mime_type=desktop_file` under `[Default Applications]` of a mimeapps.list
+///
+/// Only that section is touched: an existing entry for the type is replaced
+/// (extra ones dropped), otherwise the entry goes right below the header, and
+/// the section is appended if missing. Returns `None` if nothing changes.
+fn set_default_application(content: &str, mime_type: &str, desktop_file: &str) -> Option<String> {
+    const SECTION: &str = "[Default Applications]";
+    let entry = format!("{}={}", mime_type, desktop_file);
+    let mut lines: Vec<&str> = Vec::new();
+    let mut header_index = None;
+    let mut in_defaults = false;
+    let mut found = false;
+    let mut changed = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_defaults = trimmed == SECTION;
+            if in_defaults && header_index.is_none() {
+                header_index = Some(lines.len());
+            }
+        } else if in_defaults {
+            if let Some((_, value)) = trimmed.split_once('=').filter(|(key, _)| key.trim() == mime_type) {
+                if found {
+                    changed = true;
+                    continue;
+                }
+                found = true;
+                if value.trim().trim_end_matches(';') != desktop_file {
+                    lines.push(&entry);
+                    changed = true;
+                    continue;
+                }
+            }
+        }
+        lines.push(line);
+    }
+
+    if !found {
+        match header_index {
+            Some(i) => lines.insert(i + 1, &entry),
+            None => {
+                let existing = content.trim_end();
+                return Some(if existing.is_empty() {
+                    format!("{}\n{}\n", SECTION, entry)
+                } else {
+                    format!("{}\n\n{}\n{}\n", existing, SECTION, entry)
+                });
+            }
+        }
+        changed = true;
+    }
+
+    changed.then(|| lines.join("\n") + "\n")
+}
+
+/// Check whether a .desktop file declares the nxm scheme
+fn desktop_entry_claims_nxm(content: &str) -> bool {
+    content.lines().any(|line| {
+        line.trim()
+            .strip_prefix("MimeType=")
+            .is_some_and(|types| types.split(';').any(|t| t.trim() == NXM_MIME))
+    })
+}
+
+/// Quote a path for a .desktop `Exec=` key
+///
+/// Per the Desktop Entry spec, arguments with reserved characters are
+/// double-quoted with `"`, `` ` ``, `$` and `\` backslash-escaped, and the
+/// string-value escaping then doubles every backslash. A literal `%` is `%%`.
+/// Plain paths are left bare, since some launchers mishandle quotes.
+fn desktop_exec_quote(path: &str) -> String {
+    const RESERVED: &str = " \t\n\"'\\><~|&;$*?#()`";
+    let path = path.replace('%', "%%");
+    if !path.chars().any(|c| RESERVED.contains(c)) {
+        return path;
+    }
+    let mut out = String::from("\"");
+    for c in path.chars() {
+        match c {
+            '"' | '`' | '$' => {
+                out.push_str("\\\\");
+                out.push(c);
+            }
+            '\\' => out.push_str("\\\\\\\\"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
 }
 
 pub struct NxmHandler;
@@ -42,7 +178,7 @@ impl NxmHandler {
     pub fn setup() -> Result<(), Box<dyn Error>> {
         let home = std::env::var("HOME")?;
         let nak_dir = Self::config_dir();
-        let script_path = nak_dir.join("nxm_handler.sh");
+        let script_path = Self::script_path();
         let applications_dir = PathBuf::from(format!("{}/.local/share/applications", home));
         let desktop_path = applications_dir.join("nak-nxm-handler.desktop");
 
@@ -64,17 +200,10 @@ impl NxmHandler {
             }
         }
 
-        // Create the NXM handler script (Direct Proton launch version) — atomic write
-        let script_content = include_str!("scripts/nxm_handler.sh");
-        atomic_write(&script_path, script_content.as_bytes())?;
-        let mut perms = fs::metadata(&script_path)?.permissions();
-        perms.set_mode(0o700);
-        fs::set_permissions(&script_path, perms)?;
+        Self::install_script(&script_path)?;
 
         // Create Desktop Entry
-        // Note: According to XDG spec, Exec paths with special chars should be quoted,
-        // but some implementations have issues with quotes. Since our path is in
-        // ~/.config/nak/ (no spaces), we don't need quotes.
+        // The script lives under $HOME, which can contain spaces or quotes
         let desktop_content = format!(
             r#"[Desktop Entry]
 Type=Application
@@ -90,10 +219,10 @@ MimeType=x-scheme-handler/nxm;
 StartupNotify=false
 NoDisplay=false
 "#,
-            script_path.to_string_lossy()
+            desktop_exec_quote(&script_path.to_string_lossy())
         );
 
-        atomic_write(&desktop_path, desktop_content.as_bytes())?;
+        write_atomic(&desktop_path, desktop_content.as_bytes())?;
 
         // Update desktop database so the app appears in application pickers
         // This is required for new installations - without it, the app won't
@@ -141,59 +270,224 @@ NoDisplay=false
         Ok(())
     }
 
-    /// Add a MIME type association to a mimeapps.list file
-    fn add_mime_association(path: &PathBuf, mime_type: &str, desktop_file: &str) {
-        // Read existing content or start fresh
-        let content = fs::read_to_string(path).unwrap_or_default();
+    /// mimeapps.list files that can override the nxm default, highest priority first
+    ///
+    /// Covers the XDG user/system locations, desktop-specific variants
+    /// (e.g. `kde-mimeapps.list`), Flatpak exports and per-app Flatpak configs.
+    fn mimeapps_locations(home: &Path) -> Vec<PathBuf> {
+        let config_home = std::env::var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home.join(".config"));
+        let desktops: Vec<String> = std::env::var("XDG_CURRENT_DESKTOP")
+            .unwrap_or_default()
+            .split(':')
+            .filter(|d| !d.is_empty())
+            .map(|d| d.to_lowercase())
+            .collect();
+
+        let mut paths = Vec::new();
+        for desktop in &desktops {
+            paths.push(config_home.join(format!("{}-mimeapps.list", desktop)));
+        }
+        paths.push(config_home.join("mimeapps.list"));
+        paths.push(home.join(".local/share/applications/mimeapps.list"));
+
+        // Flatpak: exported handlers and each sandboxed app's own config
+        paths.push(home.join(".local/share/flatpak/exports/share/applications/mimeapps.list"));
+        paths.push(PathBuf::from("/var/lib/flatpak/exports/share/applications/mimeapps.list"));
+        if let Ok(apps) = fs::read_dir(home.join(".var/app")) {
+            for app in apps.flatten() {
+                paths.push(app.path().join("config/mimeapps.list"));
+            }
+        }
 
-        let entry = format!("{}={}", mime_type, desktop_file);
-        let section_header = "[Default Applications]";
+        paths.push(PathBuf::from("/etc/xdg/mimeapps.list"));
+        paths.push(PathBuf::from("/usr/share/applications/mimeapps.list"));
+        paths
+    }
 
-        // Check if already correctly set
-        if content.contains(&entry) {
-            return;
+    /// Directories searched for .desktop files
+    fn applications_dirs(home: &Path) -> Vec<PathBuf> {
+        let mut dirs = vec![
+            home.join(".local/share/applications"),
+            home.join(".local/share/flatpak/exports/share/applications"),
+            PathBuf::from("/var/lib/flatpak/exports/share/applications"),
+        ];
+        let data_dirs = std::env::var("XDG_DATA_DIRS").unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+        for dir in data_dirs.split(':').filter(|d| !d.is_empty()) {
+            let apps = PathBuf::from(dir).join("applications");
+            if !dirs.contains(&apps) {
+                dirs.push(apps);
+            }
         }
+        dirs
+    }
 
-        let new_content = if content.contains(section_header) {
-            // Section exists - check if mime type is already there
-            let mut lines: Vec<&str> = content.lines().collect();
-            let mut found = false;
-            let mut in_section = false;
-
-            for line in &mut lines {
-                if line.starts_with('[') {
-                    in_section = *line == section_header;
-                } else if in_section && line.starts_with(&format!("{}=", mime_type)) {
-                    // Update existing entry - but we can't mutate through &str
-                    found = true;
-                    break;
-                }
+    /// List every desktop entry and mimeapps.list association claiming nxm://
+    ///
+    /// mimeapps.list claims come first, in the order the system resolves them,
+    /// so the first `Default` claim is the handler that actually opens links.
+    pub fn list_registered_handlers() -> Vec<NxmHandlerClaim> {
+        let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+            return Vec::new();
+        };
+        let mut claims = Vec::new();
+
+        for path in Self::mimeapps_locations(&home) {
+            let Ok(content) = fs::read_to_string(&path) else { continue };
+            for (kind, desktop_file) in parse_mimeapps_claims(&content) {
+                claims.push(NxmHandlerClaim { desktop_file, source: path.clone(), kind });
             }
+        }
 
-            if found {
-                // Replace the existing entry
-                content
-                    .lines()
-                    .map(|line| {
-                        if line.starts_with(&format!("{}=", mime_type)) {
-                            entry.clone()
-                        } else {
-                            line.to_string()
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            } else {
-                // Add entry after section header
-                content.replace(section_header, &format!("{}\n{}", section_header, entry))
+        for dir in Self::applications_dirs(&home) {
+            let Ok(entries) = fs::read_dir(&dir) else { continue };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+                    continue;
+                }
+                let Ok(content) = fs::read_to_string(&path) else { continue };
+                if desktop_entry_claims_nxm(&content) {
+                    claims.push(NxmHandlerClaim {
+                        desktop_file: entry.file_name().to_string_lossy().into_owned(),
+                        source: path,
+                        kind: NxmClaimKind::DesktopEntry,
+                    });
+                }
             }
+        }
+
+        claims
+    }
+
+    /// Path to the installed handler script
+    fn script_path() -> PathBuf {
+        Self::config_dir().join("nxm_handler.sh")
+    }
+
+    /// Write the NXM handler script (Direct Proton launch version)
+    fn install_script(script_path: &Path) -> Result<(), Box<dyn Error>> {
+        write_atomic(script_path, include_str!("scripts/nxm_handler.sh").as_bytes())?;
+        let mut perms = fs::metadata(script_path)?.permissions();
+        perms.set_mode(0o700);
+        fs::set_permissions(script_path, perms)?;
+        Ok(())
+    }
+
+    /// Check that an nxm:// link would reach the active mod manager
+    ///
+    /// Runs the handler script in test mode: it goes through the same checks
+    /// as a real link (configured instance, exe, prefix, Proton) and starts
+    /// Proton in the prefix, but sends no link and doesn't launch the manager.
+    /// The script is refreshed first so older copies don't treat the test
+    /// flag as a real link. Returns the script's output either way.
+    pub fn test_handoff() -> Result<String, String> {
+        let script_path = Self::script_path();
+        if !script_path.exists() {
+            return Err("The NXM handler isn't set up yet. Run 'NXM Toggle.sh' in your mod manager's 'NaK Tools' folder first.".to_string());
+        }
+        Self::install_script(&script_path).map_err(|e| format!("Failed to update the NXM handler script: {}", e))?;
+
+        let output = crate::runtime_wrap::command_for(&script_path)
+            .arg("--test")
+            .stdin(std::process::Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", script_path.display(), e))?;
+
+        let text = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        if output.status.success() {
+            log_install("NXM handler test passed");
+            Ok(text)
         } else {
-            // No section - add it
-            if content.is_empty() {
-                format!("{}\n{}\n", section_header, entry)
-            } else {
-                format!("{}\n\n{}\n{}\n", content.trim_end(), section_header, entry)
+            log_warning("NXM handler test failed");
+            Err(text)
+        }
+    }
+
+    /// Check whether any handler other than NaK's claims nxm://
+    pub fn has_conflicting_handlers() -> bool {
+        Self::list_registered_handlers().iter().any(|c| !c.is_nak())
+    }
+
+    /// Make NaK's handler the default for nxm:// everywhere it could be overridden
+    ///
+    /// Rewrites the default in every user-writable mimeapps.list that already
+    /// mentions the nxm scheme (including desktop-specific and Flatpak ones),
+    /// then re-runs the normal setup. Other apps' desktop files are left alone.
+    pub fn make_authoritative() -> Result<(), Box<dyn Error>> {
+        let home = PathBuf::from(std::env::var("HOME")?);
+
+        for path in Self::mimeapps_locations(&home) {
+            if !path.starts_with(&home) {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else { continue };
+            let claims = parse_mimeapps_claims(&content);
+            let nak_is_default = claims
+                .iter()
+                .any(|(kind, desktop)| *kind == NxmClaimKind::Default && desktop == NAK_DESKTOP_FILE);
+            if !claims.is_empty() && !nak_is_default {
+                Self::add_mime_association(&path, NXM_MIME, NAK_DESKTOP_FILE);
+                log_install(&format!("Set NaK as nxm:// handler in {:?}", path));
             }
+        }
+
+        Self::setup()
+    }
+
+    /// Path to the stored Nexus Mods API key
+    fn nexus_api_key_path() -> PathBuf {
+        Self::config_dir().join("nexus_api_key")
+    }
+
+    /// Save the Nexus Mods API key used to resolve nxm links to mod/file names
+    ///
+    /// The key is written owner-readable only (0600) and is never logged.
+    /// An empty key removes it.
+    pub fn set_nexus_api_key(key: &str) -> Result<(), Box<dyn Error>> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Self::clear_nexus_api_key();
+        }
+
+        let path = Self::nexus_api_key_path();
+        fs::create_dir_all(Self::config_dir())?;
+
+        // Never world-readable, not even the temp file
+        write_atomic_private(&path, key.as_bytes())?;
+
+        log_install("Nexus Mods API key saved");
+        Ok(())
+    }
+
+    /// Remove the stored Nexus Mods API key
+    pub fn clear_nexus_api_key() -> Result<(), Box<dyn Error>> {
+        let path = Self::nexus_api_key_path();
+        if path.exists() {
+            fs::remove_file(&path)?;
+            log_install("Nexus Mods API key removed");
+        }
+        Ok(())
+    }
+
+    /// Check whether a Nexus Mods API key is stored
+    pub fn has_nexus_api_key() -> bool {
+        fs::metadata(Self::nexus_api_key_path())
+            .map(|m| m.len() > 0)
+            .unwrap_or(false)
+    }
+
+    /// Make `desktop_file` the default for `mime_type` in a mimeapps.list file
+    fn add_mime_association(path: &Path, mime_type: &str, desktop_file: &str) {
+        // Read existing content or start fresh
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let Some(new_content) = set_default_application(&content, mime_type, desktop_file) else {
+            return;
         };
 
         // Ensure parent directory exists
@@ -201,7 +495,7 @@ NoDisplay=false
             let _ = fs::create_dir_all(parent);
         }
 
-        let _ = atomic_write(path, new_content.as_bytes());
+        let _ = write_atomic(path, new_content.as_bytes());
     }
 
     /// Fix Flatpak browser permissions for NXM handling
@@ -272,3 +566,88 @@ NoDisplay=false
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mimeapps_claims() {
+        let content = "[Default Applications]\n\
+                       text/html=firefox.desktop\n\
+                       x-scheme-handler/nxm=modorganizer2-nxm-handler.desktop;\n\
+                       [Added Associations]\n\
+                       x-scheme-handler/nxm=nak-nxm-handler.desktop;vortex.desktop;\n\
+                       [Removed Associations]\n\
+                       x-scheme-handler/nxm=old.desktop\n";
+        let claims = parse_mimeapps_claims(content);
+        assert_eq!(
+            claims,
+            vec![
+                (NxmClaimKind::Default, "modorganizer2-nxm-handler.desktop".to_string()),
+                (NxmClaimKind::Added, "nak-nxm-handler.desktop".to_string()),
+                (NxmClaimKind::Added, "vortex.desktop".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_default_application() {
+        // Only the competing default is replaced, not the added association
+        let content = "[Added Associations]\n\
+                       x-scheme-handler/nxm=nak-nxm-handler.desktop;\n\
+                       \n\
+                       [Default Applications]\n\
+                       text/html=firefox.desktop\n\
+                       x-scheme-handler/nxm=modorganizer2-nxm-handler.desktop\n\
+                       x-scheme-handler/nxm=vortex.desktop\n";
+        let updated = set_default_application(content, NXM_MIME, NAK_DESKTOP_FILE).unwrap();
+        assert_eq!(
+            updated,
+            "[Added Associations]\n\
+             x-scheme-handler/nxm=nak-nxm-handler.desktop;\n\
+             \n\
+             [Default Applications]\n\
+             text/html=firefox.desktop\n\
+             x-scheme-handler/nxm=nak-nxm-handler.desktop\n"
+        );
+        assert_eq!(set_default_application(&updated, NXM_MIME, NAK_DESKTOP_FILE), None);
+
+        // Missing entry goes below the header, missing section is appended
+        assert_eq!(
+            set_default_application("[Default Applications]\ntext/html=firefox.desktop\n", NXM_MIME, NAK_DESKTOP_FILE)
+                .unwrap(),
+            "[Default Applications]\nx-scheme-handler/nxm=nak-nxm-handler.desktop\ntext/html=firefox.desktop\n"
+        );
+        assert_eq!(
+            set_default_application("[Added Associations]\nx-scheme-handler/nxm=nak-nxm-handler.desktop;\n", NXM_MIME, NAK_DESKTOP_FILE)
+                .unwrap(),
+            "[Added Associations]\nx-scheme-handler/nxm=nak-nxm-handler.desktop;\n\n\
+             [Default Applications]\nx-scheme-handler/nxm=nak-nxm-handler.desktop\n"
+        );
+        assert_eq!(
+            set_default_application("", NXM_MIME, NAK_DESKTOP_FILE).unwrap(),
+            "[Default Applications]\nx-scheme-handler/nxm=nak-nxm-handler.desktop\n"
+        );
+    }
+
+    #[test]
+    fn test_desktop_entry_claims_nxm() {
+        assert!(desktop_entry_claims_nxm("[Desktop Entry]\nMimeType=x-scheme-handler/nxm;x-scheme-handler/nxm-protocol;\n"));
+        assert!(!desktop_entry_claims_nxm("[Desktop Entry]\nMimeType=x-scheme-handler/nxm-protocol;\n"));
+        assert!(!desktop_entry_claims_nxm("[Desktop Entry]\nName=Firefox\n"));
+    }
+
+    #[test]
+    fn test_desktop_exec_quote() {
+        assert_eq!(desktop_exec_quote("/home/zoë/.config/nak/nxm_handler.sh"), "/home/zoë/.config/nak/nxm_handler.sh");
+        assert_eq!(
+            desktop_exec_quote("/home/Jo Smith/.config/nak/nxm_handler.sh"),
+            "\"/home/Jo Smith/.config/nak/nxm_handler.sh\""
+        );
+        assert_eq!(
+            desktop_exec_quote(r#"/home/O'Brien "x" $y \z 100%"#),
+            r#""/home/O'Brien \\"x\\" \\$y \\\\z 100%%""#
+        );
+    }
+}
@@ -75,6 +75,8 @@ pub fn is_flatpak() -> bool {
 ///
 /// In Flatpak mode, `flatpak-spawn --host` is used and env vars are passed as
 /// `--env=KEY=VALUE` flags (the host process does NOT inherit the sandbox env).
+/// A Proton from a Flatpak Steam (`~/.var/app/com.valvesoftware.Steam/...`) is
+/// an ordinary host path, so it runs the same way in every mode.
 /// In steam-run mode, the command is wrapped with `steam-run`.
 /// Otherwise the command runs directly.
 pub fn build_command<S: AsRef<OsStr>>(
@@ -8,9 +8,19 @@ set -e -o pipefail
 
 # Terminal auto-launch if double-clicked
 if [ ! -t 0 ]; then
-    for term in konsole gnome-terminal xfce4-terminal kitty alacritty xterm; do
+    # Atomic distros (Bazzite, Silverblue) often only have a Flatpak terminal.
+    # Ptyxis runs commands on the host, so the script still sees the real system.
+    if [ -e /run/ostree-booted ] && command -v flatpak &> /dev/null; then
+        for app in app.devsuite.Ptyxis org.gnome.Ptyxis; do
+            if flatpak info "$app" &> /dev/null; then
+                exec flatpak run "$app" -- "$0" "$@"
+            fi
+        done
+    fi
+    for term in ptyxis konsole gnome-terminal xfce4-terminal kitty alacritty xterm; do
         if command -v "$term" &> /dev/null; then
             case "$term" in
+                ptyxis) exec "$term" -- "$0" "$@" ;;
                 konsole) exec "$term" --hold -e "$0" "$@" ;;
                 gnome-terminal) exec "$term" -- "$0" "$@" ;;
                 xfce4-terminal) exec "$term" --hold -e "$0" "$@" ;;
@@ -11,6 +11,22 @@ ACTIVE_PREFIX_FILE="$NAK_CONFIG_DIR/active_nxm_prefix"
 ACTIVE_APPID_FILE="$NAK_CONFIG_DIR/active_nxm_appid"
 NXM_URL="$1"
 
+# "--test" (NaK's "Test NXM Link" button) checks the whole handoff without
+# sending a real link: errors are printed instead of shown as dialogs, the mod
+# manager isn't started, and Proton only runs a harmless command in the prefix
+NXM_TEST=0
+if [ "$NXM_URL" = "--test" ]; then
+    NXM_TEST=1
+    zenity() {
+        local arg
+        for arg in "$@"; do
+            case "$arg" in
+                --text=*) echo -e "NaK: ${arg#--text=}" ;;
+            esac
+        done
+    }
+fi
+
 # Check if handler is configured
 if [ ! -f "$ACTIVE_EXE_FILE" ] || [ ! -f "$ACTIVE_PREFIX_FILE" ]; then
     # Check if only old appid file exists (from old NXM Toggle script)
@@ -40,6 +56,49 @@ if [ ! -d "$WINEPREFIX" ]; then
     exit 1
 fi
 
+# Optional: resolve the mod/file name via the Nexus Mods API (key saved from NaK's settings).
+# Runs in the background and ignores every failure so the handoff is never delayed.
+# The key is passed through the environment so it never shows up in process lists or logs.
+NEXUS_KEY_FILE="$NAK_CONFIG_DIR/nexus_api_key"
+resolve_nxm_metadata() {
+    [ -s "$NEXUS_KEY_FILE" ] || return 0
+    command -v python3 > /dev/null 2>&1 || return 0
+
+    local info
+    info=$(NEXUS_API_KEY="$(cat "$NEXUS_KEY_FILE")" timeout 15 python3 - "$NXM_URL" 2>/dev/null << 'PYEOF'
+import json, os, sys, urllib.parse, urllib.request
+
+url = urllib.parse.urlparse(sys.argv[1])
+parts = url.path.strip("/").split("/")
+# nxm://<game>/mods/<mod_id>/files/<file_id>?key=...
+if len(parts) < 4 or parts[0] != "mods" or parts[2] != "files":
+    sys.exit(1)
+game, mod_id, file_id = url.netloc, parts[1], parts[3]
+
+def get(path):
+    req = urllib.request.Request(
+        "https://api.nexusmods.com/v1/games/%s/%s.json" % (game, path),
+        headers={"apikey": os.environ["NEXUS_API_KEY"], "User-Agent": "NaK"},
+    )
+    with urllib.request.urlopen(req, timeout=5) as resp:
+        return json.load(resp)
+
+mod = get("mods/%s" % mod_id)
+file = get("mods/%s/files/%s" % (mod_id, file_id))
+print("%s: %s (%s)" % (mod.get("name", mod_id), file.get("name", file_id), file.get("version", "?")))
+PYEOF
+    ) || return 0
+    [ -n "$info" ] || return 0
+
+    echo "NaK: Downloading $info"
+    if command -v notify-send > /dev/null 2>&1; then
+        notify-send --app-name=NaK "Nexus Mods download" "$info" || true
+    fi
+}
+if [ "$NXM_TEST" = 0 ]; then
+    resolve_nxm_metadata &
+fi
+
 # Find Steam path
 if [ -d "$HOME/.steam/steam" ]; then
     STEAM_PATH="$HOME/.steam/steam"
@@ -140,7 +199,15 @@ is_running() {
 }
 
 # If mod manager isn't running, launch it via Steam first
-if [ -n "$PROCESS_NAME" ] && ! is_running; then
+if [ "$NXM_TEST" = 1 ]; then
+    if [ -z "$PROCESS_NAME" ]; then
+        echo "NaK: $NXM_EXE is not in an MO2 folder, links are passed to it directly"
+    elif is_running; then
+        echo "NaK: $MOD_MANAGER is running"
+    else
+        echo "NaK: $MOD_MANAGER is not running (a real link starts it via Steam first)"
+    fi
+elif [ -n "$PROCESS_NAME" ] && ! is_running; then
     echo "NaK: $MOD_MANAGER is not running, launching via Steam..."
 
     # Read AppID and calculate 64-bit Game ID
@@ -181,6 +248,18 @@ export STEAM_COMPAT_DATA_PATH="${WINEPREFIX%/pfx}"
 export STEAM_COMPAT_CLIENT_INSTALL_PATH="$STEAM_PATH"
 export WINEDLLOVERRIDES="winemenubuilder.exe="
 
+if [ "$NXM_TEST" = 1 ]; then
+    echo "NaK: Starting Proton in the prefix..."
+    echo "  Proton: $PROTON_PATH"
+    echo "  Prefix: $WINEPREFIX"
+    if ! timeout 120 "$PROTON_BIN" run cmd /c ver; then
+        echo "NaK: Proton could not run a command in the prefix"
+        exit 1
+    fi
+    echo "NaK: NXM test passed - links will open $NXM_EXE"
+    exit 0
+fi
+
 # Launch mod manager with NXM link via Proton
 echo "NaK: Handling NXM link via Proton..."
 echo "  EXE: $NXM_EXE"
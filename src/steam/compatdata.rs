@@ -0,0 +1,180 @@
+//! Compatdata folders left behind by removed shortcuts
+//!
+//! Removing a non-Steam shortcut leaves its `steamapps/compatdata/<appid>`
+//! prefix on disk, often several GB. Only AppIDs in the non-Steam shortcut
+//! range (top bit set) are ever reported: a Steam game's compatdata can hold
+//! saves even after the game is uninstalled. Installed games, current
+//! shortcuts of any account and NaK's managed prefixes are always kept.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{find_steam_path, ShortcutsVdf};
+use crate::config::ManagedPrefixes;
+use crate::logging::log_info;
+
+/// A compatdata folder no shortcut or game uses any more
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanedCompatdata {
+    pub app_id: u32,
+    /// The `compatdata/<appid>` folder
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+/// Whether an AppID is in the range Steam gives non-Steam shortcuts
+pub fn is_shortcut_app_id(app_id: u32) -> bool {
+    app_id & 0x8000_0000 != 0
+}
+
+/// The primary Steam folder and every library folder
+fn steam_libraries() -> Vec<PathBuf> {
+    let mut libraries: Vec<PathBuf> = find_steam_path().into_iter().collect();
+    for library in crate::game_finder::find_steam_library_folders() {
+        if !libraries.contains(&library) {
+            libraries.push(library);
+        }
+    }
+    libraries
+}
+
+/// AppIDs with an appmanifest in any library (installed or installing games and tools)
+fn installed_app_ids(libraries: &[PathBuf]) -> HashSet<u32> {
+    libraries
+        .iter()
+        .filter_map(|library| fs::read_dir(library.join("steamapps")).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_prefix("appmanifest_")?.strip_suffix(".acf")?.parse().ok()
+        })
+        .collect()
+}
+
+/// AppIDs of the shortcuts of every Steam account on this machine
+fn all_shortcut_app_ids() -> HashSet<u32> {
+    let Some(steam_path) = find_steam_path() else {
+        return HashSet::new();
+    };
+    let Ok(accounts) = fs::read_dir(steam_path.join("userdata")) else {
+        return HashSet::new();
+    };
+    accounts
+        .flatten()
+        .filter_map(|account| ShortcutsVdf::parse(&account.path().join("config/shortcuts.vdf")).ok())
+        .flat_map(|vdf| vdf.shortcuts.into_iter().map(|s| s.appid))
+        .collect()
+}
+
+/// Numbered folders in a `steamapps/compatdata` folder
+fn compatdata_entries(compatdata: &Path) -> Vec<(u32, PathBuf)> {
+    let Ok(entries) = fs::read_dir(compatdata) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| Some((entry.file_name().to_str()?.parse().ok()?, entry.path())))
+        .collect()
+}
+
+/// Compatdata folders that nothing in `keep` uses and that belong to a shortcut AppID
+fn select_orphans(entries: Vec<(u32, PathBuf)>, keep: &HashSet<u32>) -> Vec<(u32, PathBuf)> {
+    let mut orphans: Vec<(u32, PathBuf)> = entries
+        .into_iter()
+        .filter(|(app_id, _)| is_shortcut_app_id(*app_id) && !keep.contains(app_id))
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+/// AppIDs whose compatdata must never be reported or deleted
+fn app_ids_in_use(libraries: &[PathBuf]) -> HashSet<u32> {
+    let mut keep = installed_app_ids(libraries);
+    keep.extend(all_shortcut_app_ids());
+    keep.extend(ManagedPrefixes::load().prefixes.iter().map(|p| p.app_id));
+    keep
+}
+
+/// Find compatdata folders left by removed shortcuts, in every Steam library
+///
+/// Walks each orphan for its size, so call it off the UI thread for big libraries.
+pub fn find_orphaned_compatdata() -> Vec<OrphanedCompatdata> {
+    let libraries = steam_libraries();
+    let keep = app_ids_in_use(&libraries);
+    let orphans: Vec<OrphanedCompatdata> = libraries
+        .iter()
+        .flat_map(|library| select_orphans(compatdata_entries(&library.join("steamapps/compatdata")), &keep))
+        .map(|(app_id, path)| OrphanedCompatdata {
+            size_bytes: ManagedPrefixes::get_prefix_size(&path.to_string_lossy()),
+            app_id,
+            path,
+        })
+        .collect();
+    log_info(&format!("Found {} orphaned compatdata folder(s)", orphans.len()));
+    orphans
+}
+
+/// Delete an orphaned compatdata folder
+///
+/// Checks again that the folder is a shortcut-range `compatdata/<appid>`
+/// that no shortcut, game or managed prefix uses, since a shortcut may have
+/// been added since the scan.
+pub fn delete_orphaned_compatdata(orphan: &OrphanedCompatdata) -> Result<(), String> {
+    let in_compatdata = orphan.path.parent().is_some_and(|p| p.ends_with("steamapps/compatdata"));
+    let name_matches = orphan.path.file_name().is_some_and(|n| n.to_string_lossy() == orphan.app_id.to_string());
+    if !in_compatdata || !name_matches || !is_shortcut_app_id(orphan.app_id) {
+        return Err(format!("{} is not a shortcut's compatdata folder", orphan.path.display()));
+    }
+    if app_ids_in_use(&steam_libraries()).contains(&orphan.app_id) {
+        return Err(format!("AppID {} is in use again, not deleting it", orphan.app_id));
+    }
+    fs::remove_dir_all(&orphan.path).map_err(|e| format!("Failed to delete {}: {}", orphan.path.display(), e))?;
+    log_info(&format!("Deleted orphaned compatdata {}", orphan.path.display()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_orphans() {
+        let dir = std::env::temp_dir().join(format!("nak_compatdata_orphans_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let compatdata = dir.join("steamapps/compatdata");
+        for name in ["489830", "3000000001", "3000000002", "4000000000", "pfx_backup"] {
+            fs::create_dir_all(compatdata.join(name)).unwrap();
+        }
+        fs::write(compatdata.join("3500000000"), "not a folder").unwrap();
+
+        let mut entries = compatdata_entries(&compatdata);
+        entries.sort();
+        assert_eq!(entries.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [489830, 3000000001, 3000000002, 4000000000]);
+
+        // The Steam game is never an orphan, even when it isn't installed
+        let keep = HashSet::from([3000000002]);
+        let orphans = select_orphans(entries, &keep);
+        assert_eq!(orphans, [(3000000001, compatdata.join("3000000001")), (4000000000, compatdata.join("4000000000"))]);
+
+        fs::create_dir_all(dir.join("steamapps")).unwrap();
+        fs::write(dir.join("steamapps/appmanifest_489830.acf"), "").unwrap();
+        fs::write(dir.join("steamapps/appmanifest_bad.acf"), "").unwrap();
+        assert_eq!(installed_app_ids(std::slice::from_ref(&dir)), HashSet::from([489830]));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_delete_refuses_other_folders() {
+        let orphan = OrphanedCompatdata { app_id: 3000000001, path: PathBuf::from("/tmp/3000000001"), size_bytes: 0 };
+        assert!(delete_orphaned_compatdata(&orphan).is_err());
+        let game = OrphanedCompatdata {
+            app_id: 489830,
+            path: PathBuf::from("/nonexistent/steamapps/compatdata/489830"),
+            size_bytes: 0,
+        };
+        assert!(delete_orphaned_compatdata(&game).is_err());
+    }
+}
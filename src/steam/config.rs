@@ -5,7 +5,7 @@
 use std::fs;
 use std::path::Path;
 
-use super::find_steam_path;
+use super::{finish_vdf_write, find_steam_path, guard_steam_for_vdf_write, SteamError};
 
 /// Set a Proton version as the compatibility tool for a non-Steam game
 ///
@@ -15,19 +15,27 @@ use super::find_steam_path;
 ///
 /// # Notes
 /// Steam must be restarted for changes to take effect.
-pub fn set_compat_tool(app_id: u32, proton_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+pub fn set_compat_tool(app_id: u32, proton_name: &str) -> Result<(), SteamError> {
     // Validate proton_name to prevent VDF injection
     if proton_name.is_empty() || proton_name.contains('"') || proton_name.contains('\n') || proton_name.contains('\r') {
-        return Err(format!("Invalid Proton name: {:?}", proton_name).into());
+        return Err(SteamError::CompatTool(format!("Invalid Proton name: {:?}", proton_name)));
     }
 
     let steam_path = find_steam_path()
-        .ok_or("Steam not found")?;
+        .ok_or_else(|| SteamError::NotFound("Steam not found".to_string()))?;
 
+    let steam_was_closed = guard_steam_for_vdf_write()?;
+    let result = write_compat_tool(&steam_path, app_id, proton_name);
+    finish_vdf_write(steam_was_closed);
+    result
+}
+
+/// Add or update an AppID's entry in config.vdf's CompatToolMapping
+fn write_compat_tool(steam_path: &Path, app_id: u32, proton_name: &str) -> Result<(), SteamError> {
     let config_path = steam_path.join("config/config.vdf");
 
     if !config_path.exists() {
-        return Err("Steam config.vdf not found".into());
+        return Err(SteamError::NotFound("Steam config.vdf not found".to_string()));
     }
 
     let content = fs::read_to_string(&config_path)?;
@@ -77,16 +85,16 @@ pub fn set_compat_tool(app_id: u32, proton_name: &str) -> Result<(), Box<dyn std
                 let (before, after) = content.split_at(insert_pos);
                 format!("{}\n{}\n{}", before, compat_section, after)
             } else {
-                return Err("Could not find insertion point in config.vdf".into());
+                return Err(SteamError::VdfParse("Could not find insertion point in config.vdf".to_string()));
             }
         } else {
-            return Err("Could not find Steam section in config.vdf".into());
+            return Err(SteamError::VdfParse("Could not find Steam section in config.vdf".to_string()));
         }
     };
 
     // Check if anything changed
     if new_content == content {
-        return Err("Failed to modify config.vdf - format mismatch".into());
+        return Err(SteamError::VdfParse("Failed to modify config.vdf - format mismatch".to_string()));
     }
 
     // Backup original
@@ -94,11 +102,28 @@ pub fn set_compat_tool(app_id: u32, proton_name: &str) -> Result<(), Box<dyn std
     fs::copy(&config_path, &backup_path)?;
 
     // Write new config
-    fs::write(&config_path, &new_content)?;
+    crate::config::write_atomic(&config_path, new_content.as_bytes())?;
 
     Ok(())
 }
 
+/// The compatibility tool config.vdf assigns to an AppID, if any
+pub fn get_compat_tool(app_id: u32) -> Option<String> {
+    let config_path = find_steam_path()?.join("config/config.vdf");
+    parse_compat_tool(&fs::read_to_string(config_path).ok()?, app_id)
+}
+
+/// Find an AppID's tool name in config.vdf's CompatToolMapping section
+fn parse_compat_tool(content: &str, app_id: u32) -> Option<String> {
+    let mapping = find_section_content(&content[content.find("\"CompatToolMapping\"")?..])?;
+    let entry = &mapping[mapping.find(&format!("\"{}\"", app_id))?..];
+    let entry = find_section_content(entry)?;
+    let name = &entry[entry.find("\"name\"")? + "\"name\"".len()..];
+    let name = &name[name.find('"')? + 1..];
+    let name = &name[..name.find('"')?];
+    (!name.is_empty()).then(|| name.to_string())
+}
+
 /// Find the content of a VDF section (between { and matching })
 fn find_section_content(section: &str) -> Option<&str> {
     let start = section.find('{')?;
@@ -134,7 +159,7 @@ fn update_existing_compat_entry(
     content: &str,
     app_id_str: &str,
     proton_name: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), SteamError> {
     // Find the entry and update the "name" field
     let pattern = format!("\t\t\t\t\t\"{}\"\n\t\t\t\t\t{{\n", app_id_str);
 
@@ -153,15 +178,55 @@ fn update_existing_compat_entry(
                 let new_content = content.replace(old_line, &new_line);
 
                 // Backup and write
-                let steam_path = find_steam_path().ok_or("Steam not found")?;
+                let steam_path = find_steam_path()
+                    .ok_or_else(|| SteamError::NotFound("Steam not found".to_string()))?;
                 let backup_path = steam_path.join("config/config.vdf.nak.bak");
                 fs::copy(config_path, &backup_path)?;
-                fs::write(config_path, &new_content)?;
+                crate::config::write_atomic(config_path, new_content.as_bytes())?;
 
                 return Ok(());
             }
         }
     }
 
-    Err("Could not find entry to update".into())
+    Err(SteamError::CompatTool(format!("Could not find config.vdf entry for AppID {} to update", app_id_str)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compat_tool() {
+        let config = r#""InstallConfigStore"
+{
+	"Software"
+	{
+		"Valve"
+		{
+			"Steam"
+			{
+				"CompatToolMapping"
+				{
+					"0"
+					{
+						"name"		"proton_experimental"
+					}
+					"3123456789"
+					{
+						"name"		"GE-Proton10-4"
+						"config"		""
+						"priority"		"250"
+					}
+				}
+			}
+		}
+	}
+}
+"#;
+        assert_eq!(parse_compat_tool(config, 3123456789).as_deref(), Some("GE-Proton10-4"));
+        assert_eq!(parse_compat_tool(config, 0).as_deref(), Some("proton_experimental"));
+        assert_eq!(parse_compat_tool(config, 42), None);
+        assert_eq!(parse_compat_tool("\"Steam\" {}", 0), None);
+    }
 }
@@ -0,0 +1,48 @@
+//! Error type for the Steam integration API
+
+use std::fmt;
+
+/// Errors returned by the Steam module
+///
+/// Display messages are the same user-facing strings the UI showed before,
+/// so callers can keep using `to_string()`; match on the variant for retry logic.
+#[derive(Debug)]
+pub enum SteamError {
+    /// Steam installation, userdata folder or a Steam file was not found
+    NotFound(String),
+    /// A VDF file could not be parsed or has an unexpected layout
+    VdfParse(String),
+    /// shortcuts.vdf could not be updated
+    ShortcutsWrite(String),
+    /// The Proton compatibility tool could not be set
+    CompatTool(String),
+    /// Filesystem or process error
+    Io(std::io::Error),
+}
+
+impl fmt::Display for SteamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SteamError::NotFound(msg) => write!(f, "{}", msg),
+            SteamError::VdfParse(msg) => write!(f, "{}", msg),
+            SteamError::ShortcutsWrite(msg) => write!(f, "Failed to update shortcuts.vdf: {}", msg),
+            SteamError::CompatTool(msg) => write!(f, "Failed to set compatibility tool: {}", msg),
+            SteamError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SteamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SteamError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SteamError {
+    fn from(e: std::io::Error) -> Self {
+        SteamError::Io(e)
+    }
+}
@@ -3,7 +3,10 @@
 //! Handles Steam shortcut creation, Proton compatibility settings,
 //! library folder detection, and NXM handler integration.
 
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+mod compatdata;
 mod config;
+mod error;
 mod paths;
 mod proton;
 #[cfg(any(feature = "shortcuts", feature = "full"))]
@@ -11,22 +14,28 @@ mod shortcuts;
 
 // Re-export path detection utilities
 pub use paths::{
-    detect_steam_path_checked, find_steam_path, find_userdata_path,
-    get_steam_accounts, is_valid_steam_path,
+    detect_steam_path_checked, find_steam_path, find_userdata_path, flatpak_access_warning,
+    find_compatdata_prefixes, find_userdata_path_for_account, get_steam_accounts, is_flatpak_steam,
+    is_valid_steam_path, locate_compatdata_prefix, steam_install_hint, SteamAccount, FLATPAK_STEAM_DIR,
 };
 
 // Re-export Steam integration components
-pub use config::set_compat_tool;
-pub use proton::{find_steam_protons, SteamProton};
+pub use config::{get_compat_tool, set_compat_tool};
+pub use error::SteamError;
+pub use proton::{find_steam_protons, sort_protons, ProtonKind, SteamProton};
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+pub use shortcuts::{compute_app_id, Shortcut, ShortcutsVdf};
 #[cfg(any(feature = "shortcuts", feature = "full"))]
-pub use shortcuts::{Shortcut, ShortcutsVdf};
+pub use compatdata::{
+    delete_orphaned_compatdata, find_orphaned_compatdata, is_shortcut_app_id, OrphanedCompatdata,
+};
 
 use std::fs;
 #[cfg(any(feature = "shortcuts", feature = "full"))]
 use std::path::PathBuf;
 
 /// Kill Steam process gracefully, then force if needed
-pub fn kill_steam() -> Result<(), Box<dyn std::error::Error>> {
+pub fn kill_steam() -> Result<(), SteamError> {
     use std::process::Command;
 
     // Try steam -shutdown first (graceful)
@@ -49,7 +58,7 @@ pub fn kill_steam() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 /// Start Steam in background
-pub fn start_steam() -> Result<(), Box<dyn std::error::Error>> {
+pub fn start_steam() -> Result<(), SteamError> {
     use std::process::{Command, Stdio};
 
     // Use setsid to detach Steam from our process, -silent prevents the main window from popping up
@@ -64,12 +73,90 @@ pub fn start_steam() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 /// Restart Steam (kill then start)
-pub fn restart_steam() -> Result<(), Box<dyn std::error::Error>> {
+pub fn restart_steam() -> Result<(), SteamError> {
     kill_steam()?;
     start_steam()?;
     Ok(())
 }
 
+// ============================================================================
+// Steam Running Guard
+// ============================================================================
+
+/// Check whether the Steam client is currently running
+pub fn is_steam_running() -> bool {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return false;
+    };
+
+    entries.flatten().any(|entry| {
+        fs::read_to_string(entry.path().join("comm"))
+            .map(|comm| comm.trim() == "steam")
+            .unwrap_or(false)
+    })
+}
+
+/// Make sure a running Steam won't discard the VDF edits we're about to make
+///
+/// Depending on `AppConfig::steam_running_policy` this either closes Steam
+/// or only logs a warning. Returns true if Steam was closed by this call,
+/// so callers can restart it afterwards with `finish_vdf_write`.
+pub fn guard_steam_for_vdf_write() -> Result<bool, SteamError> {
+    use crate::config::{AppConfig, SteamRunningPolicy};
+
+    if !is_steam_running() {
+        return Ok(false);
+    }
+
+    match AppConfig::load().steam_running_policy {
+        SteamRunningPolicy::CloseSteam => {
+            crate::logging::log_warning("Steam is running, closing it before editing its config files");
+            kill_steam()?;
+            Ok(true)
+        }
+        SteamRunningPolicy::Warn => {
+            crate::logging::log_warning(
+                "Steam is running! It may overwrite NaK's changes when it exits. \
+                 Close Steam before making changes, or restart it afterwards.",
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// Restart Steam after a VDF write if the guard closed it and the user wants that
+///
+/// Returns true if Steam was started again, so it has already picked up the change.
+pub fn finish_vdf_write(steam_was_closed: bool) -> bool {
+    if !steam_was_closed || !crate::config::AppConfig::load().restart_steam_after_changes {
+        return false;
+    }
+    match start_steam() {
+        Ok(_) => true,
+        Err(e) => {
+            crate::logging::log_warning(&format!("Failed to start Steam: {}", e));
+            false
+        }
+    }
+}
+
+/// Restart Steam after an install so its library shows the new shortcut
+///
+/// Only done when `restart_steam_after_changes` is set. Returns true if Steam
+/// was restarted; otherwise the user still has to restart it.
+pub fn restart_steam_after_install() -> bool {
+    if !crate::config::AppConfig::load().restart_steam_after_changes {
+        return false;
+    }
+    match restart_steam() {
+        Ok(()) => true,
+        Err(e) => {
+            crate::logging::log_warning(&format!("Failed to restart Steam automatically: {}", e));
+            false
+        }
+    }
+}
+
 // ============================================================================
 // STEAM_COMPAT_MOUNTS Detection
 // ============================================================================
@@ -85,45 +172,593 @@ const SYSTEM_DIRS: &[&str] = &[
     "proc", "sys", "dev", "boot", "root", "lost+found", "snap",
 ];
 
+/// How long a mount gets to answer before it's treated as stale
+const MOUNT_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 /// Detect directories at root that need to be added to STEAM_COMPAT_MOUNTS
 ///
 /// This finds any directories in `/` that aren't already exposed by pressure-vessel
 /// and aren't system directories, so they can be made available to the container.
+/// Directories the current user can't read, or that don't answer a stat in time
+/// (dead network mounts), are dropped: pressure-vessel fails the whole launch
+/// on a single bad mount.
 pub fn detect_extra_mounts() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/") else {
+        return Vec::new();
+    };
+
+    let candidates: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        // Skip already-exposed directories, system directories and hidden directories
+        .filter(|name| {
+            !ALREADY_EXPOSED.contains(&name.as_str())
+                && !SYSTEM_DIRS.contains(&name.as_str())
+                && !name.starts_with('.')
+        })
+        .collect();
+
+    // Probe all candidates at once so several dead mounts only cost one timeout
+    let probes: Vec<_> = candidates
+        .into_iter()
+        .map(|name| {
+            let mount = format!("/{}", name);
+            let rx = probe_mount(std::path::PathBuf::from(&mount));
+            (mount, rx)
+        })
+        .collect();
+
+    let deadline = std::time::Instant::now() + MOUNT_PROBE_TIMEOUT;
     let mut mounts = Vec::new();
+    for (mount, rx) in probes {
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        match rx.recv_timeout(remaining) {
+            // Only include actual directories
+            Ok(Ok(true)) => mounts.push(mount),
+            Ok(Ok(false)) => {}
+            Ok(Err(reason)) => crate::logging::log_warning(&format!(
+                "Not adding {} to STEAM_COMPAT_MOUNTS: {}",
+                mount, reason
+            )),
+            Err(_) => crate::logging::log_warning(&format!(
+                "Not adding {} to STEAM_COMPAT_MOUNTS: no response after {}s (stale mount?)",
+                mount,
+                MOUNT_PROBE_TIMEOUT.as_secs()
+            )),
+        }
+    }
 
-    let Ok(entries) = fs::read_dir("/") else {
-        return mounts;
+    // Sort for consistent output
+    mounts.sort();
+
+    mounts
+}
+
+/// Check a mount candidate on a separate thread, since a dead mount can block forever
+///
+/// Sends Ok(true) for a readable directory, Ok(false) for anything that isn't a
+/// directory (or doesn't exist), and Err with the reason for directories pressure-vessel can't use.
+fn probe_mount(path: std::path::PathBuf) -> std::sync::mpsc::Receiver<Result<bool, String>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let result = match fs::metadata(&path) {
+            Ok(meta) if !meta.is_dir() => Ok(false),
+            // Dangling symlink
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Ok(_) => fs::read_dir(&path)
+                .map(|_| true)
+                .map_err(|e| format!("not readable by the current user ({})", e)),
+            Err(e) => Err(format!("stat failed ({})", e)),
+        };
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+/// Flags appended after `%command%` for Electron apps (Vortex, etc.)
+pub const ELECTRON_FLAGS: &str = "--disable-gpu --no-sandbox";
+
+/// Check whether an executable is an Electron app
+///
+/// Electron apps ship their code in `resources/app.asar` (or `electron.asar`)
+/// next to the executable, which is a reliable marker regardless of the exe name.
+pub fn is_electron_executable(exe_path: &std::path::Path) -> bool {
+    let Some(dir) = exe_path.parent() else {
+        return false;
     };
 
-    for entry in entries.flatten() {
-        let name = entry.file_name().to_string_lossy().to_string();
+    let resources = dir.join("resources");
+    resources.join("app.asar").exists()
+        || resources.join("electron.asar").exists()
+        || dir.join("chrome_100_percent.pak").exists()
+}
+
+/// Append the Electron flags to a launch options string if not already present
+pub fn add_electron_flags(launch_options: &str) -> String {
+    let stripped = strip_electron_flags(launch_options);
+    if stripped.is_empty() {
+        return format!("%command% {}", ELECTRON_FLAGS);
+    }
+    if !stripped.contains("%command%") {
+        return format!("{} %command% {}", stripped, ELECTRON_FLAGS);
+    }
+    format!("{} {}", stripped, ELECTRON_FLAGS)
+}
+
+/// Remove the Electron flags from a launch options string
+pub fn strip_electron_flags(launch_options: &str) -> String {
+    launch_options
+        .split_whitespace()
+        .filter(|arg| !ELECTRON_FLAGS.split(' ').any(|flag| flag == *arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Env var that makes Proton use Wine's Wayland driver instead of XWayland
+pub const NATIVE_WAYLAND_ENV: &str = "PROTON_ENABLE_WAYLAND=1";
+
+/// Check whether the desktop session is Wayland (`XDG_SESSION_TYPE`)
+pub fn is_wayland_session() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|t| t.eq_ignore_ascii_case("wayland"))
+}
+
+/// Add or remove the native Wayland env var in a launch options string
+pub fn set_native_wayland_option(launch_options: &str, enabled: bool) -> String {
+    let (name, value) = NATIVE_WAYLAND_ENV.split_once('=').unwrap_or((NATIVE_WAYLAND_ENV, ""));
+    set_launch_env_option(launch_options, name, enabled.then_some(value))
+}
+
+/// A sync primitive Proton can use: (code, label, env vars)
+pub type SyncMode = (&'static str, &'static str, &'static [&'static str]);
+
+/// Sync primitives Proton can use
+///
+/// "default" sets nothing: Proton uses fsync when the kernel supports it
+/// (futex_waitv, Linux 5.16+) and esync otherwise. ntsync needs `/dev/ntsync`
+/// (Linux 6.14+) and a Proton that knows PROTON_USE_NTSYNC (Proton 10, GE-Proton).
+pub const SYNC_MODES: &[SyncMode] = &[
+    ("default", "Default (fsync, else esync)", &[]),
+    ("esync", "esync", &["PROTON_NO_FSYNC=1"]),
+    ("ntsync", "ntsync", &["PROTON_USE_NTSYNC=1"]),
+    ("wineserver", "None (wineserver)", &["PROTON_NO_FSYNC=1", "PROTON_NO_ESYNC=1"]),
+];
+
+/// Env vars set by any sync mode
+const SYNC_ENV_VARS: &[&str] = &["PROTON_NO_FSYNC", "PROTON_NO_ESYNC", "PROTON_USE_NTSYNC"];
+
+/// Check whether the kernel exposes ntsync
+pub fn ntsync_available() -> bool {
+    std::path::Path::new("/dev/ntsync").exists()
+}
+
+/// Sync modes this system can use (ntsync only when the kernel has it)
+pub fn available_sync_modes() -> Vec<&'static SyncMode> {
+    let ntsync = ntsync_available();
+    SYNC_MODES.iter().filter(|(code, _, _)| *code != "ntsync" || ntsync).collect()
+}
+
+/// Replace the sync env vars in a launch options string with those of `mode`
+///
+/// Unknown modes clear the vars, which is the same as "default".
+pub fn set_sync_mode_option(launch_options: &str, mode: &str) -> String {
+    let mut updated = SYNC_ENV_VARS
+        .iter()
+        .fold(launch_options.to_string(), |opts, name| set_launch_env_option(&opts, name, None));
+    let vars = SYNC_MODES.iter().find(|(code, _, _)| *code == mode).map(|(_, _, vars)| *vars).unwrap_or(&[]);
+    // Prepend in reverse so the vars keep the table's order
+    for var in vars.iter().rev() {
+        let (name, value) = var.split_once('=').unwrap_or((var, ""));
+        updated = set_launch_env_option(&updated, name, Some(value));
+    }
+    updated
+}
 
-        // Skip already-exposed directories
-        if ALREADY_EXPOSED.contains(&name.as_str()) {
+/// Set (or remove, with `None`) an env var in front of a launch options string
+///
+/// Any existing `NAME=...` token is removed first, so setting twice doesn't
+/// duplicate it. Values must not contain spaces.
+pub fn set_launch_env_option(launch_options: &str, name: &str, value: Option<&str>) -> String {
+    let key = format!("{}=", name);
+    let mut stripped = launch_options.to_string();
+    let mut search = 0;
+    while let Some(pos) = stripped[search..].find(&key).map(|p| p + search) {
+        // Only whole tokens, not e.g. MY_PROTON_NO_FSYNC= or text inside a quoted path
+        if pos > 0 && !stripped[..pos].ends_with(' ') {
+            search = pos + key.len();
             continue;
         }
+        let end = stripped[pos..].find(' ').map(|e| pos + e + 1).unwrap_or(stripped.len());
+        stripped.replace_range(pos..end, "");
+        search = pos;
+    }
+    let stripped = stripped.trim();
 
-        // Skip system directories
-        if SYSTEM_DIRS.contains(&name.as_str()) {
-            continue;
+    let Some(value) = value else {
+        return stripped.to_string();
+    };
+    let var = format!("{}{}", key, value);
+    if stripped.is_empty() {
+        return format!("{} %command%", var);
+    }
+    if !stripped.contains("%command%") {
+        return format!("{} %command% {}", var, stripped);
+    }
+    format!("{} {}", var, stripped)
+}
+
+/// Check whether a wrapper command is installed (on `PATH`)
+pub fn wrapper_available(command: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(command).is_file()))
+        .unwrap_or(false)
+}
+
+/// Locales offered for a prefix: (locale, label); "" = the system locale
+///
+/// Wine picks the prefix's ANSI/OEM codepage from `LC_ALL`/`LANG` when it
+/// starts, so setting them in the launch options also sets the codepage.
+pub const PREFIX_LOCALES: &[(&str, &str)] = &[
+    ("", "System default"),
+    ("en_US.UTF-8", "English (US)"),
+    ("ja_JP.UTF-8", "Japanese"),
+    ("zh_CN.UTF-8", "Chinese (Simplified)"),
+    ("zh_TW.UTF-8", "Chinese (Traditional)"),
+    ("ko_KR.UTF-8", "Korean"),
+    ("ru_RU.UTF-8", "Russian"),
+    ("pl_PL.UTF-8", "Polish"),
+    ("de_DE.UTF-8", "German"),
+    ("fr_FR.UTF-8", "French"),
+];
+
+/// Check a locale name (e.g. "ja_JP.UTF-8") before it goes into the launch options
+pub fn validate_locale(locale: &str) -> Result<(), String> {
+    if locale.is_empty() || !locale.chars().all(|c| c.is_ascii_alphanumeric() || "_.-@".contains(c)) {
+        return Err(format!("'{}' is not a locale name like ja_JP.UTF-8", locale));
+    }
+    Ok(())
+}
+
+/// Set `LANG` and `LC_ALL` in a launch options string (or remove them, with `None`)
+///
+/// `LC_ALL` overrides any other `LC_*` the session sets, so the whole process
+/// tree, Wine included, uses the prefix's locale.
+pub fn set_locale_option(launch_options: &str, locale: Option<&str>) -> String {
+    let locale = locale.filter(|l| validate_locale(l).is_ok());
+    let updated = set_launch_env_option(launch_options, "LC_ALL", locale);
+    set_launch_env_option(&updated, "LANG", locale)
+}
+
+/// Add or remove a wrapper command right before `%command%`
+///
+/// Env vars stay in front of the wrappers, so `NAME=1 gamemoderun %command%`.
+pub fn set_wrapper_option(launch_options: &str, command: &str, enabled: bool) -> String {
+    let options = launch_options.trim();
+    let Some(command_pos) = options.find("%command%") else {
+        if !enabled {
+            return options.to_string();
         }
+        return format!("{} %command% {}", command, options).trim_end().to_string();
+    };
 
-        // Skip hidden directories
-        if name.starts_with('.') {
-            continue;
+    // Only whole words before %command%, so paths and arguments are never touched
+    let mut before = format!(" {} ", options[..command_pos].trim());
+    let word = format!(" {} ", command);
+    while let Some(pos) = before.find(&word) {
+        before.replace_range(pos..pos + word.len(), " ");
+    }
+
+    let mut parts = Vec::new();
+    if !before.trim().is_empty() {
+        parts.push(before.trim());
+    }
+    if enabled {
+        parts.push(command);
+    }
+    parts.push(&options[command_pos..]);
+    parts.join(" ")
+}
+
+/// Steam Deck screen size, the default gamescope window
+pub const DECK_RESOLUTION: (u32, u32) = (1280, 800);
+
+/// Put the command in a gamescope window (or take it out, with `None`)
+///
+/// Written as `gamescope -W <w> -H <h> -- ` right before `%command%`, so env
+/// vars still apply to gamescope and anything after it runs inside.
+pub fn set_gamescope_option(launch_options: &str, size: Option<(u32, u32)>) -> String {
+    let options = launch_options.trim();
+    let (before, rest) = options.split_at(options.find("%command%").unwrap_or(options.len()));
+    let mut before = format!(" {} ", before.trim());
+    if let Some(start) = before.find(" gamescope ") {
+        if let Some(end) = before[start..].find(" -- ").map(|i| start + i + " --".len()) {
+            before.replace_range(start..end, "");
         }
+    }
+    let options = format!("{} {}", before.trim(), rest).trim().to_string();
+    match size {
+        Some((width, height)) => set_wrapper_option(&options, &format!("gamescope -W {} -H {} --", width, height), true),
+        None => options,
+    }
+}
 
-        // Only include actual directories
-        if entry.path().is_dir() {
-            mounts.push(format!("/{}", name));
+/// Put NaK's launch log wrapper in front of the command (or take it out, with `None`)
+///
+/// Written double-quoted right before `%command%`, outside the other wrappers
+/// when it is applied first. Any quoted token ending in `LAUNCH_LOG_SCRIPT` is
+/// removed first, so a relocated install doesn't keep the old path.
+pub fn set_launch_log_option(launch_options: &str, script: Option<&std::path::Path>) -> String {
+    let options = launch_options.trim();
+    let (before, rest) = options.split_at(options.find("%command%").unwrap_or(options.len()));
+    let mut before = format!(" {}", before.trim());
+    let suffix = format!("/{}\"", crate::config::LAUNCH_LOG_SCRIPT);
+    while let Some(end) = before.find(&suffix).map(|i| i + suffix.len()) {
+        let start = before[..end - suffix.len()].rfind(" \"").unwrap_or(0);
+        before.replace_range(start..end, "");
+    }
+    let options = format!("{} {}", before.trim(), rest).trim().to_string();
+    match script {
+        Some(script) => {
+            let quoted = format!(
+                "\"{}\"",
+                shell_dq_escape(&crate::config::normalize_path_for_steam(&script.to_string_lossy()))
+            );
+            set_wrapper_option(&options, &quoted, true)
         }
+        None => options,
     }
+}
 
-    // Sort for consistent output
-    mounts.sort();
+/// Check a `WINEDLLOVERRIDES` value before it goes into the launch options
+///
+/// It is written double-quoted, so only DLL names, `=`, `,` and `;` are allowed.
+pub fn validate_dll_overrides(value: &str) -> Result<(), String> {
+    match value.chars().find(|c| !(c.is_ascii_alphanumeric() || "=,;._-*".contains(*c))) {
+        Some(c) if c.is_whitespace() => Err("DLL overrides can't contain spaces".to_string()),
+        Some(c) => Err(format!("DLL overrides can't contain '{}'", c)),
+        None => Ok(()),
+    }
+}
 
-    mounts
+/// Apply the launch options builder toggles to a launch options string
+///
+/// Each toggle is set or cleared, so applying the same toggles twice (or after
+/// the options were regenerated by `generate_launch_options`) gives the same result.
+pub fn apply_launch_toggles(launch_options: &str, toggles: &crate::config::LaunchToggles) -> String {
+    let script = toggles.launch_log_script.as_deref().filter(|_| toggles.launch_log);
+    let mut options = set_launch_log_option(launch_options, script);
+    options = set_gamescope_option(&options, toggles.gamescope);
+    for (command, enabled) in [("gamemoderun", toggles.gamemode), ("mangohud", toggles.mangohud)] {
+        options = set_wrapper_option(&options, command, enabled);
+    }
+    // protonfixes looks up its game fixes by SteamAppId (Steam's own value is the shortcut's id)
+    let game_id = toggles.game_id.filter(|id| *id != 0).map(|id| id.to_string());
+    options = set_launch_env_option(&options, "SteamAppId", game_id.as_deref());
+    let mangohud_config = toggles.mangohud_config.trim();
+    options = set_path_env_option(
+        &options,
+        "MANGOHUD_CONFIGFILE",
+        (toggles.mangohud && !mangohud_config.is_empty()).then(|| std::path::Path::new(mangohud_config)),
+    );
+    options = set_launch_env_option(&options, "PROTON_USE_WINED3D", toggles.wined3d.then_some("1"));
+    let overrides = toggles.dll_overrides.trim();
+    let quoted = format!("\"{}\"", overrides);
+    set_launch_env_option(
+        &options,
+        "WINEDLLOVERRIDES",
+        (!overrides.is_empty() && validate_dll_overrides(overrides).is_ok()).then_some(quoted.as_str()),
+    )
+}
+
+/// Escape a string for safe substitution inside a double-quoted bash string.
+/// Escapes backslash, dollar sign, backtick, and double-quote so that the
+/// substituted value cannot break out of the surrounding `"..."` context or
+/// trigger command substitution / variable expansion. Control characters
+/// (newlines in a name pasted from elsewhere) become spaces, since values
+/// also end up in script comments and single-line launch options.
+pub fn shell_dq_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' | '$' | '`' | '"' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if c.is_control() => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Split user-entered launch arguments into words, shell style
+///
+/// Supports single quotes, double quotes and backslash escapes. Rejects
+/// control characters (a newline would end the launch options), unbalanced
+/// quotes and `%command%`, which Steam would expand a second time.
+pub fn parse_launch_args(input: &str) -> Result<Vec<String>, String> {
+    if input.chars().any(|c| c.is_control()) {
+        return Err("Launch arguments can't contain newlines or control characters".to_string());
+    }
+    if input.contains("%command%") {
+        return Err("Launch arguments can't contain %command%".to_string());
+    }
+
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            '\'' => {
+                let word = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("Unbalanced ' in launch arguments".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("Unbalanced \" in launch arguments".to_string()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("Unbalanced \" in launch arguments".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => current.get_or_insert_with(String::new).push(c),
+                None => return Err("Launch arguments can't end with \\".to_string()),
+            },
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(arg) = current {
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+/// Quote one argument for the launch options (and the shell Steam runs them in)
+pub fn quote_launch_arg(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Render user-entered launch arguments the way they go after `%command%`
+pub fn render_launch_args(input: &str) -> Result<String, String> {
+    Ok(parse_launch_args(input)?
+        .iter()
+        .map(|arg| quote_launch_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+/// Replace the user's launch arguments in a launch options string
+///
+/// `old_args` and `new_args` are already rendered with `render_launch_args`.
+/// The old arguments are removed from after `%command%` (where NaK put them)
+/// and the new ones appended after `%command%` and any Electron flags.
+pub fn set_launch_args_option(launch_options: &str, old_args: &str, new_args: &str) -> String {
+    let mut options = launch_options.trim().to_string();
+    if !old_args.is_empty() {
+        // Only look after %command% so an env var can never match
+        let command_end = options.find("%command%").map(|i| i + "%command%".len()).unwrap_or(0);
+        if let Some(pos) = options.rfind(old_args).filter(|pos| *pos >= command_end) {
+            options.replace_range(pos..pos + old_args.len(), "");
+            options = options.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+    }
+    if !options.contains("%command%") {
+        options = if options.is_empty() {
+            "%command%".to_string()
+        } else {
+            format!("{} %command%", options)
+        };
+    }
+    if new_args.is_empty() {
+        options
+    } else {
+        format!("{} {}", options, new_args)
+    }
+}
+
+/// Set (or remove, with `None`) an env var holding a path in front of a launch options string
+///
+/// The path is written double-quoted and escaped, so it may contain spaces.
+/// An existing assignment (quoted or not) is replaced, leaving the rest of
+/// the launch options untouched.
+pub fn set_path_env_option(launch_options: &str, name: &str, path: Option<&std::path::Path>) -> String {
+    let key = format!("{}=", name);
+    let mut rest = launch_options.trim().to_string();
+    if let Some(start) = rest.find(&key) {
+        let value_start = start + key.len();
+        let end = if rest[value_start..].starts_with('"') {
+            closing_quote(&rest[value_start + 1..]).map(|i| value_start + i + 2).unwrap_or(rest.len())
+        } else {
+            rest[value_start..].find(char::is_whitespace).map(|i| value_start + i).unwrap_or(rest.len())
+        };
+        rest.replace_range(start..end, "");
+        rest = rest.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+
+    let Some(path) = path else {
+        return rest;
+    };
+    let assignment = format!(
+        "{}\"{}\"",
+        key,
+        shell_dq_escape(&crate::config::normalize_path_for_steam(&path.to_string_lossy()))
+    );
+    if rest.is_empty() {
+        format!("{} %command%", assignment)
+    } else {
+        format!("{} {}", assignment, rest)
+    }
+}
+
+/// Point a shortcut's launch options at a dxvk.conf
+///
+/// Replaces an existing `DXVK_CONFIG_FILE=` assignment (quoted or not) or
+/// prepends one, leaving the rest of the launch options untouched.
+pub fn set_dxvk_config_option(launch_options: &str, dxvk_conf_path: &std::path::Path) -> String {
+    set_path_env_option(launch_options, "DXVK_CONFIG_FILE", Some(dxvk_conf_path))
+}
+
+/// The dxvk.conf path a shortcut's launch options point at, if any
+///
+/// Reverses the escaping done by `set_dxvk_config_option`.
+pub fn dxvk_config_option(launch_options: &str) -> Option<String> {
+    const KEY: &str = "DXVK_CONFIG_FILE=";
+    let value = &launch_options[launch_options.find(KEY)? + KEY.len()..];
+    if let Some(quoted) = value.strip_prefix('"') {
+        let raw = &quoted[..closing_quote(quoted)?];
+        let mut path = String::with_capacity(raw.len());
+        let mut chars = raw.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => path.extend(chars.next()),
+                _ => path.push(c),
+            }
+        }
+        Some(path)
+    } else {
+        value.split_whitespace().next().map(str::to_string)
+    }
+}
+
+/// Byte index of the `"` closing a double-quoted string, skipping `\"` escapes
+fn closing_quote(s: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(i),
+            _ => {}
+        }
+    }
+    None
 }
 
 /// Generate launch options string with DXVK config file and STEAM_COMPAT_MOUNTS
@@ -140,16 +775,16 @@ pub fn generate_launch_options(dxvk_conf_path: Option<&std::path::Path>, is_elec
         // Normalize path for Bazzite/Fedora Atomic compatibility
         Some(path) => format!(
             "DXVK_CONFIG_FILE=\"{}\"",
-            crate::config::normalize_path_for_steam(&path.to_string_lossy())
+            shell_dq_escape(&crate::config::normalize_path_for_steam(&path.to_string_lossy()))
         ),
         None => String::new(),
     };
 
     // Electron apps need these flags to avoid EBADF errors with stdout/stderr under Wine
     let electron_flags = if is_electron_app {
-        " --disable-gpu --no-sandbox"
+        format!(" {}", ELECTRON_FLAGS)
     } else {
-        ""
+        String::new()
     };
 
     match (dxvk_part.is_empty(), mounts.is_empty()) {
@@ -160,6 +795,45 @@ pub fn generate_launch_options(dxvk_conf_path: Option<&std::path::Path>, is_elec
     }
 }
 
+/// Put NaK's launch options into ones the user may have edited
+///
+/// Sets DXVK_CONFIG_FILE, STEAM_COMPAT_MOUNTS (when there are mounts) and the
+/// Electron flags like `generate_launch_options`, keeping every other token.
+/// Empty launch options get the generated ones.
+pub fn merge_launch_options(
+    launch_options: &str,
+    dxvk_conf_path: Option<&std::path::Path>,
+    is_electron_app: bool,
+) -> String {
+    if launch_options.trim().is_empty() {
+        return generate_launch_options(dxvk_conf_path, is_electron_app);
+    }
+    merge_launch_options_with_mounts(launch_options, &detect_extra_mounts(), dxvk_conf_path, is_electron_app)
+}
+
+/// `merge_launch_options` with the mounts already detected
+fn merge_launch_options_with_mounts(
+    launch_options: &str,
+    mounts: &[String],
+    dxvk_conf_path: Option<&std::path::Path>,
+    is_electron_app: bool,
+) -> String {
+    let mut options = launch_options.trim().to_string();
+    if !options.contains("%command%") {
+        options = format!("{} %command%", options);
+    }
+    if !mounts.is_empty() {
+        options = set_launch_env_option(&options, "STEAM_COMPAT_MOUNTS", Some(&mounts.join(":")));
+    }
+    if let Some(path) = dxvk_conf_path {
+        options = set_dxvk_config_option(&options, path);
+    }
+    if is_electron_app {
+        options = add_electron_flags(&options);
+    }
+    options
+}
+
 #[cfg(test)]
 mod mount_tests {
     use super::*;
@@ -182,6 +856,19 @@ mod mount_tests {
         assert!(!mounts.contains(&"/boot".to_string()));
     }
 
+    #[test]
+    fn test_probe_mount() {
+        let dir = std::env::temp_dir().join(format!("nak-mount-probe-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("file"), "").unwrap();
+
+        assert_eq!(probe_mount(dir.clone()).recv().unwrap(), Ok(true));
+        assert_eq!(probe_mount(dir.join("file")).recv().unwrap(), Ok(false));
+        assert_eq!(probe_mount(dir.join("missing")).recv().unwrap(), Ok(false));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_generate_launch_options() {
         // Test without dxvk.conf (non-Electron app)
@@ -205,6 +892,186 @@ mod mount_tests {
         assert!(options.contains("--disable-gpu"));
         assert!(options.contains("--no-sandbox"));
     }
+
+    #[test]
+    fn test_merge_launch_options() {
+        let conf = std::path::Path::new("/games/MO2/dxvk.conf");
+        let mounts = vec!["/mnt/games".to_string()];
+
+        // The user's own tokens survive; NaK's are replaced, not duplicated
+        let edited = "gamemoderun STEAM_COMPAT_MOUNTS=/old DXVK_CONFIG_FILE=\"/old/dxvk.conf\" %command% -q";
+        assert_eq!(
+            merge_launch_options_with_mounts(edited, &mounts, Some(conf), false),
+            "DXVK_CONFIG_FILE=\"/games/MO2/dxvk.conf\" STEAM_COMPAT_MOUNTS=/mnt/games gamemoderun %command% -q"
+        );
+
+        // Without mounts an existing STEAM_COMPAT_MOUNTS is left alone
+        assert_eq!(
+            merge_launch_options_with_mounts("STEAM_COMPAT_MOUNTS=/data %command%", &[], None, true),
+            "STEAM_COMPAT_MOUNTS=/data %command% --disable-gpu --no-sandbox"
+        );
+        assert_eq!(merge_launch_options_with_mounts("PROTON_LOG=1", &[], None, false), "PROTON_LOG=1 %command%");
+    }
+
+    #[test]
+    fn test_set_dxvk_config_option() {
+        let conf = std::path::Path::new("/games/MO2/NaK Tools/dxvk.conf");
+        let expected = "DXVK_CONFIG_FILE=\"/games/MO2/NaK Tools/dxvk.conf\"";
+
+        assert_eq!(set_dxvk_config_option("", conf), format!("{} %command%", expected));
+        assert_eq!(
+            set_dxvk_config_option("STEAM_COMPAT_MOUNTS=/mnt %command%", conf),
+            format!("{} STEAM_COMPAT_MOUNTS=/mnt %command%", expected)
+        );
+        // Quoted value with spaces and an unquoted value are both replaced
+        assert_eq!(
+            set_dxvk_config_option("DXVK_CONFIG_FILE=\"/old path/dxvk.conf\" %command% --no-sandbox", conf),
+            format!("{} %command% --no-sandbox", expected)
+        );
+        assert_eq!(
+            set_dxvk_config_option("DXVK_CONFIG_FILE=/old/dxvk.conf %command%", conf),
+            format!("{} %command%", expected)
+        );
+
+        // Quotes, dollars and unicode are escaped, and an escaped value is replaced whole
+        let odd = std::path::Path::new("/games/Zoë's \"MO2\" $dir/dxvk.conf");
+        let escaped = set_dxvk_config_option("%command%", odd);
+        assert_eq!(escaped, "DXVK_CONFIG_FILE=\"/games/Zoë's \\\"MO2\\\" \\$dir/dxvk.conf\" %command%");
+        assert_eq!(set_dxvk_config_option(&escaped, conf), format!("{} %command%", expected));
+
+        // ...and read back unescaped
+        assert_eq!(dxvk_config_option(&escaped).as_deref(), odd.to_str());
+        assert_eq!(dxvk_config_option("DXVK_CONFIG_FILE=/old/dxvk.conf %command%").as_deref(), Some("/old/dxvk.conf"));
+        assert_eq!(dxvk_config_option("%command%"), None);
+    }
+
+    #[test]
+    fn test_electron_flags_roundtrip() {
+        let base = "DXVK_CONFIG_FILE=\"/a/dxvk.conf\" %command%";
+        let with_flags = add_electron_flags(base);
+        assert_eq!(with_flags, format!("{} {}", base, ELECTRON_FLAGS));
+
+        // Adding twice must not duplicate flags
+        assert_eq!(add_electron_flags(&with_flags), with_flags);
+
+        assert_eq!(strip_electron_flags(&with_flags), base);
+        assert_eq!(add_electron_flags(""), format!("%command% {}", ELECTRON_FLAGS));
+    }
+
+    #[test]
+    fn test_native_wayland_option() {
+        let base = "DXVK_CONFIG_FILE=\"/a b/dxvk.conf\" %command% --no-sandbox";
+        let enabled = set_native_wayland_option(base, true);
+        assert_eq!(enabled, format!("{} {}", NATIVE_WAYLAND_ENV, base));
+
+        // Enabling twice must not duplicate the variable
+        assert_eq!(set_native_wayland_option(&enabled, true), enabled);
+
+        assert_eq!(set_native_wayland_option(&enabled, false), base);
+        assert_eq!(set_native_wayland_option("", true), format!("{} %command%", NATIVE_WAYLAND_ENV));
+        assert_eq!(set_native_wayland_option(&format!("{} %command%", NATIVE_WAYLAND_ENV), false), "%command%");
+    }
+
+    #[test]
+    fn test_launch_toggles() {
+        let base = "DXVK_CONFIG_FILE=\"/a b/dxvk.conf\" %command% --no-sandbox";
+        let gamemode = set_wrapper_option(base, "gamemoderun", true);
+        assert_eq!(gamemode, "DXVK_CONFIG_FILE=\"/a b/dxvk.conf\" gamemoderun %command% --no-sandbox");
+        assert_eq!(set_wrapper_option(&gamemode, "gamemoderun", true), gamemode);
+        assert_eq!(set_wrapper_option(&gamemode, "gamemoderun", false), base);
+        assert_eq!(set_wrapper_option("", "mangohud", true), "mangohud %command%");
+
+        let toggles = crate::config::LaunchToggles {
+            gamemode: true,
+            mangohud: true,
+            mangohud_config: "/a b/NaK Tools/MangoHud.conf".to_string(),
+            wined3d: true,
+            dll_overrides: "dinput8=n,b;d3d11=n".to_string(),
+            gamescope: Some(DECK_RESOLUTION),
+            game_id: Some(489830),
+            launch_log: true,
+            launch_log_script: Some(std::path::PathBuf::from("/a b/NaK Tools/Launch Log.sh")),
+        };
+        let built = apply_launch_toggles(base, &toggles);
+        assert_eq!(
+            built,
+            "WINEDLLOVERRIDES=\"dinput8=n,b;d3d11=n\" PROTON_USE_WINED3D=1 MANGOHUD_CONFIGFILE=\"/a b/NaK Tools/MangoHud.conf\" \
+             SteamAppId=489830 DXVK_CONFIG_FILE=\"/a b/dxvk.conf\" \"/a b/NaK Tools/Launch Log.sh\" \
+             gamescope -W 1280 -H 800 -- gamemoderun mangohud %command% --no-sandbox"
+        );
+        assert_eq!(apply_launch_toggles(&built, &toggles), built);
+        assert_eq!(apply_launch_toggles(&built, &Default::default()), base);
+
+        // A moved install replaces the wrapper instead of adding a second one
+        let moved = set_launch_log_option(&built, Some(std::path::Path::new("/c/NaK Tools/Launch Log.sh")));
+        assert!(moved.contains("\"/c/NaK Tools/Launch Log.sh\" %command%"));
+        assert!(!moved.contains("/a b/NaK Tools/Launch Log.sh"));
+        assert_eq!(set_launch_log_option("\"/c/NaK Tools/Launch Log.sh\" %command%", None), "%command%");
+
+        let resized = set_gamescope_option(&built, Some((1920, 1080)));
+        assert!(resized.contains("gamescope -W 1920 -H 1080 -- %command%"));
+        assert!(!resized.contains("1280"));
+        assert_eq!(set_gamescope_option("gamescope -W 1 -H 1 -- %command%", None), "%command%");
+
+        assert!(validate_dll_overrides("dinput8=n,b;d3d11=n").is_ok());
+        assert!(validate_dll_overrides("a=n b=n").is_err());
+        assert!(validate_dll_overrides("a=n\"").is_err());
+    }
+
+    #[test]
+    fn test_sync_mode_option() {
+        let base = "DXVK_CONFIG_FILE=\"/a  b/dxvk.conf\" %command%";
+        let esync = set_sync_mode_option(base, "esync");
+        assert_eq!(esync, format!("PROTON_NO_FSYNC=1 {}", base));
+
+        let none = set_sync_mode_option(&esync, "wineserver");
+        assert_eq!(none, format!("PROTON_NO_FSYNC=1 PROTON_NO_ESYNC=1 {}", base));
+        assert_eq!(set_sync_mode_option(&none, "wineserver"), none);
+
+        let ntsync = set_sync_mode_option(&none, "ntsync");
+        assert_eq!(ntsync, format!("PROTON_USE_NTSYNC=1 {}", base));
+        assert_eq!(set_sync_mode_option(&ntsync, "default"), base);
+
+        // Only whole tokens are touched
+        let other = "MY_PROTON_NO_FSYNC=1 %command%";
+        assert_eq!(set_sync_mode_option(other, "default"), other);
+    }
+
+    #[test]
+    fn test_locale_option() {
+        let base = "DXVK_CONFIG_FILE=\"/a b/dxvk.conf\" %command%";
+        let japanese = set_locale_option(base, Some("ja_JP.UTF-8"));
+        assert_eq!(japanese, format!("LANG=ja_JP.UTF-8 LC_ALL=ja_JP.UTF-8 {}", base));
+        assert_eq!(set_locale_option(&japanese, Some("ja_JP.UTF-8")), japanese);
+        assert_eq!(set_locale_option(&japanese, None), base);
+        // Nothing that could break out of the launch options
+        assert_eq!(set_locale_option(base, Some("ja_JP.UTF-8 rm")), base);
+        assert!(validate_locale("sr_RS.UTF-8@latin").is_ok());
+        assert!(validate_locale("").is_err());
+    }
+
+    #[test]
+    fn test_launch_args() {
+        assert_eq!(
+            parse_launch_args(r#"-p "My Profile" --logs 'it''s' a\ b"#).unwrap(),
+            vec!["-p", "My Profile", "--logs", "its", "a b"]
+        );
+        assert!(parse_launch_args("-p \"open").is_err());
+        assert!(parse_launch_args("-a\n-b").is_err());
+        assert!(parse_launch_args("%command% -x").is_err());
+
+        assert!(render_launch_args("-i it's").unwrap_err().contains('\''));
+        let rendered = render_launch_args(r#"-p "Bob's Profile""#).unwrap();
+        assert_eq!(rendered, r#"-p 'Bob'\''s Profile'"#);
+
+        let base = format!("STEAM_COMPAT_MOUNTS=/mnt %command% {}", ELECTRON_FLAGS);
+        let with_args = set_launch_args_option(&base, "", &rendered);
+        assert_eq!(with_args, format!("{} {}", base, rendered));
+        // Replacing and clearing only touch NaK's suffix
+        assert_eq!(set_launch_args_option(&with_args, &rendered, "-q"), format!("{} -q", base));
+        assert_eq!(set_launch_args_option(&with_args, &rendered, ""), base);
+        assert_eq!(set_launch_args_option("", "", "-q"), "%command% -q");
+    }
 }
 
 // ============================================================================
@@ -219,17 +1086,241 @@ pub struct SteamShortcutResult {
     pub app_id: u32,
     /// Path to the prefix (in steamapps/compatdata/<appid>/pfx)
     pub prefix_path: PathBuf,
+    /// Whether Steam was closed to write the shortcut; pass it to `finish_vdf_write`
+    /// once done with Steam's files (right away if nothing else is written)
+    pub steam_was_closed: bool,
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Make sure a shortcut's launch options point DXVK_CONFIG_FILE at `dxvk_conf_path`
+///
+/// Returns whether the launch options were changed.
+pub fn ensure_dxvk_config_option(
+    app_id: u32,
+    dxvk_conf_path: &std::path::Path,
+) -> Result<LaunchOptionsUpdate, SteamError> {
+    update_shortcut_launch_options(app_id, |options| set_dxvk_config_option(options, dxvk_conf_path))
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Result of rewriting an existing shortcut's launch options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchOptionsUpdate {
+    /// The launch options were already as requested, nothing was written
+    Unchanged,
+    /// The launch options were written; `steam_restarted` is true if
+    /// `finish_vdf_write` started Steam again, so it already uses them
+    Updated { steam_restarted: bool },
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+impl LaunchOptionsUpdate {
+    /// Whether the launch options were changed
+    pub fn changed(self) -> bool {
+        matches!(self, LaunchOptionsUpdate::Updated { .. })
+    }
+
+    /// Whether the running Steam still has to be restarted to use the change
+    pub fn needs_steam_restart(self) -> bool {
+        matches!(self, LaunchOptionsUpdate::Updated { steam_restarted: false }) && is_steam_running()
+    }
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Rewrite an existing shortcut's launch options with `update`
+///
+/// Steam is only closed when `update` changes something, and
+/// `finish_vdf_write` runs however the write ends. `update` is applied again
+/// to the reloaded file, since closing Steam may have rewritten it.
+fn update_shortcut_launch_options(
+    app_id: u32,
+    update: impl Fn(&str) -> String,
+) -> Result<LaunchOptionsUpdate, SteamError> {
+    let not_found = || SteamError::NotFound(format!("No Steam shortcut found for AppID {}", app_id));
+    let vdf = ShortcutsVdf::load()?;
+    let shortcut = vdf.shortcuts.iter().find(|s| s.appid == app_id).ok_or_else(not_found)?;
+    if update(&shortcut.launch_options) == shortcut.launch_options {
+        return Ok(LaunchOptionsUpdate::Unchanged);
+    }
+
+    let steam_was_closed = guard_steam_for_vdf_write()?;
+    let result = (|| -> Result<bool, SteamError> {
+        let mut vdf = ShortcutsVdf::load()?;
+        let shortcut = vdf.shortcuts.iter_mut().find(|s| s.appid == app_id).ok_or_else(not_found)?;
+        let updated = update(&shortcut.launch_options);
+        if updated == shortcut.launch_options {
+            return Ok(false);
+        }
+        crate::logging::log_info(&format!(
+            "Updating launch options for AppID {}: {}",
+            app_id, updated
+        ));
+        shortcut.launch_options = updated;
+        vdf.save()?;
+        Ok(true)
+    })();
+    let steam_restarted = finish_vdf_write(steam_was_closed);
+    result.map(|changed| {
+        if changed {
+            LaunchOptionsUpdate::Updated { steam_restarted }
+        } else {
+            LaunchOptionsUpdate::Unchanged
+        }
+    })
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Copy shortcuts from another Steam account into the selected account
+///
+/// Only shortcuts whose AppID is in `app_ids` are copied (NaK passes its
+/// managed prefixes), and ones already present in the target are skipped.
+/// Keeping the AppID means the existing compatdata prefix is reused.
+/// Returns the number of shortcuts imported.
+pub fn import_shortcuts_from_account(source_account_id: &str, app_ids: &[u32]) -> Result<usize, SteamError> {
+    let source = ShortcutsVdf::load_for_account(source_account_id)?;
+    let target_path = find_userdata_path()
+        .ok_or_else(|| SteamError::NotFound("Could not find Steam userdata path".to_string()))?;
+    if target_path.file_name().and_then(|n| n.to_str()) == Some(source_account_id) {
+        return Err(SteamError::NotFound("Source account is the selected account".to_string()));
+    }
+
+    let steam_was_closed = guard_steam_for_vdf_write()?;
+    let result = (|| -> Result<usize, SteamError> {
+        let mut target = ShortcutsVdf::load()?;
+        let mut imported = 0;
+        for shortcut in source.shortcuts.iter().filter(|s| app_ids.contains(&s.appid)) {
+            if target.shortcuts.iter().any(|t| t.appid == shortcut.appid) {
+                continue;
+            }
+            crate::logging::log_info(&format!(
+                "Importing shortcut '{}' ({}) from account {}",
+                shortcut.app_name, shortcut.appid, source_account_id
+            ));
+            target.shortcuts.push(shortcut.clone());
+            imported += 1;
+        }
+        if imported > 0 {
+            target.save()?;
+        }
+        Ok(imported)
+    })();
+    finish_vdf_write(steam_was_closed);
+    result
 }
 
 #[cfg(any(feature = "shortcuts", feature = "full"))]
 /// Remove a non-Steam game shortcut by AppID
-pub fn remove_steam_shortcut(app_id: u32) -> Result<(), Box<dyn std::error::Error>> {
-    let mut vdf = ShortcutsVdf::load()?;
-    if vdf.remove_shortcut_by_app_id(app_id) {
+pub fn remove_steam_shortcut(app_id: u32) -> Result<(), SteamError> {
+    let steam_was_closed = guard_steam_for_vdf_write()?;
+    let result = (|| -> Result<(), SteamError> {
+        let mut vdf = ShortcutsVdf::load()?;
+        if vdf.remove_shortcut_by_app_id(app_id) {
+            vdf.save()?;
+            crate::logging::log_info(&format!("Removed Steam shortcut for AppID {}", app_id));
+        }
+        Ok(())
+    })();
+    finish_vdf_write(steam_was_closed);
+    result
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Rename a non-Steam game shortcut, keeping its AppID (and therefore its prefix)
+pub fn rename_steam_shortcut(app_id: u32, new_name: &str) -> Result<(), SteamError> {
+    let steam_was_closed = guard_steam_for_vdf_write()?;
+    let result = (|| -> Result<(), SteamError> {
+        let mut vdf = ShortcutsVdf::load()?;
+
+        // add_shortcut() replaces shortcuts by name, so names must stay unique
+        if vdf.shortcuts.iter().any(|s| s.appid != app_id && s.app_name == new_name) {
+            return Err(SteamError::ShortcutsWrite(format!(
+                "A Steam shortcut named '{}' already exists",
+                new_name
+            )));
+        }
+
+        let shortcut = vdf
+            .shortcuts
+            .iter_mut()
+            .find(|s| s.appid == app_id)
+            .ok_or_else(|| SteamError::NotFound(format!("No Steam shortcut found for AppID {}", app_id)))?;
+        shortcut.app_name = new_name.to_string();
         vdf.save()?;
-        crate::logging::log_info(&format!("Removed Steam shortcut for AppID {}", app_id));
+
+        crate::logging::log_info(&format!("Renamed Steam shortcut {} to '{}'", app_id, new_name));
+        Ok(())
+    })();
+    finish_vdf_write(steam_was_closed);
+    result
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Add or remove the Electron flags on an existing shortcut's launch options
+///
+/// Fixes EBADF stdout/stderr errors for Electron tools (Vortex, etc.) that were
+/// installed before NaK added the flags automatically, without a reinstall.
+/// Returns whether the launch options were changed.
+pub fn set_electron_flags(app_id: u32, enabled: bool) -> Result<LaunchOptionsUpdate, SteamError> {
+    update_shortcut_launch_options(app_id, |options| {
+        if enabled {
+            add_electron_flags(options)
+        } else {
+            strip_electron_flags(options)
+        }
+    })
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Turn Proton's native Wayland driver on or off for an existing shortcut
+///
+/// Returns whether the launch options were changed.
+pub fn set_native_wayland(app_id: u32, enabled: bool) -> Result<LaunchOptionsUpdate, SteamError> {
+    update_shortcut_launch_options(app_id, |options| set_native_wayland_option(options, enabled))
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Set the sync primitive (a `SYNC_MODES` code) for an existing shortcut
+///
+/// Returns whether the launch options were changed.
+pub fn set_sync_mode(app_id: u32, mode: &str) -> Result<LaunchOptionsUpdate, SteamError> {
+    update_shortcut_launch_options(app_id, |options| set_sync_mode_option(options, mode))
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Set the locale (see `PREFIX_LOCALES`) for an existing shortcut, None = the system locale
+///
+/// Returns whether the launch options were changed.
+pub fn set_locale(app_id: u32, locale: Option<&str>) -> Result<LaunchOptionsUpdate, SteamError> {
+    if let Some(locale) = locale {
+        validate_locale(locale).map_err(SteamError::ShortcutsWrite)?;
     }
-    Ok(())
+    update_shortcut_launch_options(app_id, |options| set_locale_option(options, locale))
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Apply the launch options builder toggles to an existing shortcut
+///
+/// Returns whether the launch options were changed.
+pub fn set_launch_toggles(app_id: u32, toggles: &crate::config::LaunchToggles) -> Result<LaunchOptionsUpdate, SteamError> {
+    validate_dll_overrides(toggles.dll_overrides.trim()).map_err(SteamError::ShortcutsWrite)?;
+
+    update_shortcut_launch_options(app_id, |options| apply_launch_toggles(options, toggles))
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Replace the user's launch arguments on an existing shortcut
+///
+/// `old_args`/`new_args` are the raw strings stored with the prefix; both are
+/// validated and quoted with `render_launch_args`. Returns whether the launch
+/// options were changed.
+pub fn set_launch_args(app_id: u32, old_args: &str, new_args: &str) -> Result<LaunchOptionsUpdate, SteamError> {
+    let new_rendered = render_launch_args(new_args).map_err(SteamError::ShortcutsWrite)?;
+    // Stored args were validated when saved; if they no longer parse there is nothing to strip
+    let old_rendered = render_launch_args(old_args).unwrap_or_default();
+
+    update_shortcut_launch_options(app_id, |options| {
+        set_launch_args_option(options, &old_rendered, &new_rendered)
+    })
 }
 
 #[cfg(any(feature = "shortcuts", feature = "full"))]
@@ -257,14 +1348,93 @@ pub fn add_mod_manager_shortcut(
     proton_name: &str,
     dxvk_conf_path: Option<&std::path::Path>,
     is_electron_app: bool,
-) -> Result<SteamShortcutResult, Box<dyn std::error::Error>> {
+) -> Result<SteamShortcutResult, SteamError> {
+    write_mod_manager_shortcut(None, name, exe_path, start_dir, proton_name, dxvk_conf_path, is_electron_app)
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Prefix `add_mod_manager_shortcut` would use for `exe_path` named `name`
+///
+/// The AppID comes from the exe and name, so installing again with the same
+/// name and folder lands in the same `compatdata/<appid>` prefix.
+pub fn expected_shortcut_prefix(name: &str, exe_path: &str) -> Option<PathBuf> {
+    let exe_path = crate::config::normalize_path_for_steam(exe_path);
+    let app_id = compute_app_id(&exe_path, name);
+    Some(find_steam_path()?.join("steamapps/compatdata").join(app_id.to_string()).join("pfx"))
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Re-create (or update in place) the Steam shortcut for an existing install
+///
+/// Keeps the given AppID so Steam reuses the existing `compatdata/<appid>` prefix.
+/// If a shortcut with that AppID still exists, its name, exe and start dir are
+/// updated, NaK's launch options are merged into the user's (see
+/// `merge_launch_options`) and everything else (play time, tags) is kept.
+pub fn restore_mod_manager_shortcut(
+    app_id: u32,
+    name: &str,
+    exe_path: &str,
+    start_dir: &str,
+    proton_name: &str,
+    dxvk_conf_path: Option<&std::path::Path>,
+    is_electron_app: bool,
+) -> Result<SteamShortcutResult, SteamError> {
+    write_mod_manager_shortcut(Some(app_id), name, exe_path, start_dir, proton_name, dxvk_conf_path, is_electron_app)
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// Shared implementation of `add_mod_manager_shortcut`/`restore_mod_manager_shortcut`
+fn write_mod_manager_shortcut(
+    existing_app_id: Option<u32>,
+    name: &str,
+    exe_path: &str,
+    start_dir: &str,
+    proton_name: &str,
+    dxvk_conf_path: Option<&std::path::Path>,
+    is_electron_app: bool,
+) -> Result<SteamShortcutResult, SteamError> {
     // Normalize paths for Bazzite/Fedora Atomic compatibility
     // On these systems, $HOME is /var/home/user but /home is a symlink to /var/home
     // Pressure-vessel exposes /home but may not properly handle /var/home paths
     let exe_path = crate::config::normalize_path_for_steam(exe_path);
     let start_dir = crate::config::normalize_path_for_steam(start_dir);
 
-    // 1. Load existing shortcuts
+    // 1. Make sure Steam won't overwrite our changes on exit
+    // On success the caller restarts Steam, so it can batch further VDF writes
+    let steam_was_closed = guard_steam_for_vdf_write()?;
+    let result = write_shortcut_and_compat_tool(
+        existing_app_id,
+        name,
+        &exe_path,
+        &start_dir,
+        proton_name,
+        dxvk_conf_path,
+        is_electron_app,
+    );
+    if result.is_err() {
+        finish_vdf_write(steam_was_closed);
+    }
+    let (app_id, prefix_path) = result?;
+    Ok(SteamShortcutResult {
+        app_id,
+        prefix_path,
+        steam_was_closed,
+    })
+}
+
+#[cfg(any(feature = "shortcuts", feature = "full"))]
+/// The part of `write_mod_manager_shortcut` that runs with Steam closed
+///
+/// Returns the AppID and prefix path.
+fn write_shortcut_and_compat_tool(
+    existing_app_id: Option<u32>,
+    name: &str,
+    exe_path: &str,
+    start_dir: &str,
+    proton_name: &str,
+    dxvk_conf_path: Option<&std::path::Path>,
+    is_electron_app: bool,
+) -> Result<(u32, PathBuf), SteamError> {
     let mut vdf = ShortcutsVdf::load()?;
 
     // 2. Generate launch options with DXVK_CONFIG_FILE and STEAM_COMPAT_MOUNTS
@@ -274,15 +1444,34 @@ pub fn add_mod_manager_shortcut(
         crate::logging::log_install(&format!("Setting launch options: {}", launch_options));
     }
 
-    // 3. Create the shortcut
-    let shortcut = Shortcut::new(name, &exe_path, &start_dir)
-        .with_tag("NaK")
-        .with_launch_options(&launch_options);
-
-    let app_id = shortcut.appid;
+    // 3. Create the shortcut, or update the existing one in place
+    let existing = existing_app_id
+        .and_then(|id| vdf.shortcuts.iter_mut().find(|s| s.appid == id));
+    let app_id = match existing {
+        Some(shortcut) => {
+            let updated = Shortcut::new(name, exe_path, start_dir);
+            shortcut.app_name = updated.app_name;
+            shortcut.exe = updated.exe;
+            shortcut.start_dir = updated.start_dir;
+            // Keep what the user added to the launch options
+            shortcut.launch_options = merge_launch_options(&shortcut.launch_options, dxvk_conf_path, is_electron_app);
+            if !shortcut.tags.iter().any(|t| t == "NaK") {
+                shortcut.tags.push("NaK".to_string());
+            }
+            shortcut.appid
+        }
+        None => {
+            let mut shortcut = Shortcut::new(name, exe_path, start_dir)
+                .with_tag("NaK")
+                .with_launch_options(&launch_options);
+            if let Some(id) = existing_app_id {
+                shortcut.appid = id;
+            }
 
-    // 4. Add/update in shortcuts.vdf
-    vdf.add_shortcut(shortcut);
+            // 4. Add to shortcuts.vdf
+            vdf.add_shortcut(shortcut)
+        }
+    };
     vdf.save()?;
 
     // 5. Set Proton compatibility tool
@@ -291,20 +1480,25 @@ pub fn add_mod_manager_shortcut(
     // 6. Calculate prefix paths
     // IMPORTANT: Steam creates non-Steam game prefixes in the PRIMARY Steam folder,
     // regardless of where the executable is located. We must match this behavior.
+    // For Flatpak Steam that folder is inside its sandbox (~/.var/app/...), and it
+    // is the same Steam whose shortcuts.vdf was just written.
     let primary_steam = find_steam_path()
-        .ok_or("Could not find Steam installation")?;
+        .ok_or_else(|| SteamError::NotFound("Could not find Steam installation".to_string()))?;
     let compat_data_path = primary_steam
         .join("steamapps/compatdata")
         .join(app_id.to_string());
     let prefix_path = compat_data_path.join("pfx");
+    if is_flatpak_steam(&primary_steam) {
+        crate::logging::log_install(&format!("Flatpak Steam: prefix goes in its sandbox at {}", prefix_path.display()));
+        if let Some(warning) = flatpak_access_warning(&primary_steam, std::path::Path::new(start_dir)) {
+            crate::logging::log_warning(&warning);
+        }
+    }
 
     // 7. Ensure compat data directory exists
     fs::create_dir_all(&compat_data_path)?;
 
-    Ok(SteamShortcutResult {
-        app_id,
-        prefix_path,
-    })
+    Ok((app_id, prefix_path))
 }
 
 
@@ -28,18 +28,119 @@ pub fn find_steam_path() -> Option<PathBuf> {
     }
 
     let home = std::env::var("HOME").ok()?;
+    pick_steam_path(steam_path_candidates(Path::new(&home)))
+}
+
+/// Folder of the Flatpak Steam app, relative to $HOME
+///
+/// Flatpak Steam runs with this folder as its HOME, so its Steam folder and
+/// every compatdata prefix it creates live under it.
+pub const FLATPAK_STEAM_DIR: &str = ".var/app/com.valvesoftware.Steam";
+
+/// Whether a Steam folder belongs to Flatpak Steam
+pub fn is_flatpak_steam(steam_path: &Path) -> bool {
+    steam_path.to_string_lossy().contains(FLATPAK_STEAM_DIR)
+}
+
+/// Steam folders looked for under `home`: native, Flatpak, then Snap
+fn steam_path_candidates(home: &Path) -> Vec<PathBuf> {
+    let flatpak = home.join(FLATPAK_STEAM_DIR);
+    vec![
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+        flatpak.join(".steam/steam"),
+        flatpak.join(".local/share/Steam"),
+        flatpak.join("data/Steam"),
+        home.join("snap/steam/common/.steam/steam"),
+    ]
+}
+
+/// The Steam folder to use out of `candidates`
+///
+/// With both a native and a Flatpak Steam on disk (often a leftover native
+/// install), the one logged into last wins, going by when its
+/// `loginusers.vdf` was written. Otherwise the first valid candidate is used.
+fn pick_steam_path(candidates: Vec<PathBuf>) -> Option<PathBuf> {
+    let last_login = |path: &PathBuf| fs::metadata(path.join("config/loginusers.vdf")).and_then(|m| m.modified()).ok();
+    // max_by_key keeps the last of equal keys, so go backwards to prefer earlier candidates
+    candidates
+        .into_iter()
+        .filter(|p| is_valid_steam_path(p))
+        .rev()
+        .max_by_key(last_login)
+}
 
-    let steam_paths = [
-        format!("{}/.steam/steam", home),
-        format!("{}/.local/share/Steam", home),
-        format!("{}/.var/app/com.valvesoftware.Steam/.steam/steam", home),
-        format!("{}/snap/steam/common/.steam/steam", home),
+/// Folders Flatpak Steam was given access to, from its metadata and overrides
+///
+/// Returns the `filesystems=` entries (without `:ro`/`:rw`), or None when no
+/// Flatpak metadata could be read.
+fn flatpak_steam_filesystems(home: &Path) -> Option<Vec<String>> {
+    let app = "com.valvesoftware.Steam";
+    let user_flatpak = home.join(".local/share/flatpak");
+    let system_flatpak = PathBuf::from("/var/lib/flatpak");
+    let files = [
+        system_flatpak.join("app").join(app).join("current/active/metadata"),
+        user_flatpak.join("app").join(app).join("current/active/metadata"),
+        system_flatpak.join("overrides/global"),
+        system_flatpak.join("overrides").join(app),
+        user_flatpak.join("overrides/global"),
+        user_flatpak.join("overrides").join(app),
     ];
+    let contents: Vec<String> = files.iter().filter_map(|f| fs::read_to_string(f).ok()).collect();
+    if contents.is_empty() {
+        return None;
+    }
+    Some(
+        contents
+            .iter()
+            .flat_map(|c| c.lines())
+            .filter_map(|line| line.trim().strip_prefix("filesystems="))
+            .flat_map(|list| list.split(';'))
+            .filter(|entry| !entry.is_empty() && !entry.starts_with('!'))
+            .map(|entry| entry.split(':').next().unwrap_or(entry).to_string())
+            .collect(),
+    )
+}
 
-    steam_paths
-        .iter()
-        .map(PathBuf::from)
-        .find(|p| is_valid_steam_path(p.as_path()))
+/// Whether `path` is inside a folder Flatpak Steam can see
+///
+/// Understands `host`, `home`, `~/...` and absolute entries; `xdg-*` entries
+/// are not resolved.
+fn flatpak_path_visible(path: &Path, home: &Path, filesystems: &[String]) -> bool {
+    if path.starts_with(home.join(FLATPAK_STEAM_DIR)) {
+        return true;
+    }
+    filesystems.iter().any(|entry| match entry.as_str() {
+        "host" => true,
+        "home" => path.starts_with(home),
+        _ => match entry.strip_prefix("~/") {
+            Some(rel) => path.starts_with(home.join(rel)),
+            None => entry.starts_with('/') && path.starts_with(entry),
+        },
+    })
+}
+
+/// Warning for a mod manager folder Flatpak Steam can't see, if it is one
+///
+/// Flatpak Steam only sees its own folder plus what its permissions allow,
+/// so a shortcut to anything else fails to launch. None for native Steam or
+/// when the permissions can't be read.
+pub fn flatpak_access_warning(steam_path: &Path, install_dir: &Path) -> Option<String> {
+    if !is_flatpak_steam(steam_path) {
+        return None;
+    }
+    let home = PathBuf::from(std::env::var("HOME").ok()?);
+    let filesystems = flatpak_steam_filesystems(&home)?;
+    // Compare real paths: on Fedora Atomic $HOME is /var/home/<user> but Steam paths use /home
+    let real = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    if flatpak_path_visible(&real(install_dir), &real(&home), &filesystems) {
+        return None;
+    }
+    Some(format!(
+        "Flatpak Steam may not be able to see {}. Allow it with: flatpak override --user --filesystem=\"{}\" com.valvesoftware.Steam",
+        install_dir.display(),
+        install_dir.display()
+    ))
 }
 
 /// Check whether a directory looks like a Steam installation.
@@ -294,6 +395,105 @@ pub fn find_userdata_path_for_account(account_id: &str) -> Option<PathBuf> {
     }
 }
 
+// ============================================================================
+// Compatdata Location
+// ============================================================================
+
+/// Every `steamapps/compatdata/<app_id>/pfx` that exists, in any Steam library
+///
+/// The primary Steam folder comes first.
+pub fn find_compatdata_prefixes(app_id: u32) -> Vec<PathBuf> {
+    let mut libraries: Vec<PathBuf> = find_steam_path().into_iter().collect();
+    for library in crate::game_finder::find_steam_library_folders() {
+        if !libraries.contains(&library) {
+            libraries.push(library);
+        }
+    }
+    libraries
+        .iter()
+        .map(|library| library.join("steamapps/compatdata").join(app_id.to_string()).join("pfx"))
+        .filter(|pfx| pfx.is_dir())
+        .collect()
+}
+
+/// The prefix Steam actually runs a shortcut in
+///
+/// NaK records (and prepares) the prefix in the primary Steam folder, which is
+/// where Steam normally puts non-Steam shortcuts. With a custom
+/// STEAM_COMPAT_DATA_PATH or some library setups (Deck with an SD card) Steam
+/// creates it in another library instead. Returns such an initialized prefix
+/// if there is one, otherwise `expected`.
+pub fn locate_compatdata_prefix(app_id: u32, expected: &Path) -> PathBuf {
+    pick_compatdata_prefix(expected, &find_compatdata_prefixes(app_id))
+}
+
+/// First initialized prefix in `found` that isn't `expected` (symlinks resolved)
+fn pick_compatdata_prefix(expected: &Path, found: &[PathBuf]) -> PathBuf {
+    let expected_real = fs::canonicalize(expected).unwrap_or_else(|_| expected.to_path_buf());
+    found
+        .iter()
+        .find(|pfx| {
+            let real = fs::canonicalize(pfx).unwrap_or_else(|_| pfx.to_path_buf());
+            real != expected_real && pfx.join("drive_c").is_dir()
+        })
+        .cloned()
+        .unwrap_or_else(|| expected.to_path_buf())
+}
+
+// ============================================================================
+// Steam Not Installed
+// ============================================================================
+
+/// How to get Steam working on this system, for when `find_steam_path` fails
+///
+/// Uses /etc/os-release for the distro's package command, and notices a
+/// Steam that is installed but was never started (no `steamapps` yet).
+pub fn steam_install_hint() -> String {
+    let os_release = fs::read_to_string("/etc/os-release").unwrap_or_default();
+    let steam_on_path = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join("steam").is_file()))
+        .unwrap_or(false);
+    install_hint(&os_release, steam_on_path)
+}
+
+fn install_hint(os_release: &str, steam_on_path: bool) -> String {
+    if steam_on_path {
+        return "Steam is installed but has not been set up yet. Start Steam once, log in and \
+                let it finish updating, then click Recheck."
+            .to_string();
+    }
+
+    let field = |key: &str| {
+        os_release
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(|v| v.trim_matches('"').to_lowercase())
+            .unwrap_or_default()
+    };
+    let ids = format!("{} {}", field("ID"), field("ID_LIKE"));
+    let has = |id: &str| ids.split_whitespace().any(|i| i == id);
+
+    let package = if has("steamos") || has("bazzite") {
+        "Steam ships with this system - start it from the app menu once."
+    } else if has("arch") {
+        "Enable the multilib repository in /etc/pacman.conf, then run: sudo pacman -S steam"
+    } else if has("ubuntu") || has("debian") {
+        "Run: sudo apt install steam-installer (on Debian, enable the contrib/non-free components first)"
+    } else if has("fedora") {
+        "Enable the RPM Fusion nonfree repository, then run: sudo dnf install steam"
+    } else if has("opensuse") || has("suse") {
+        "Run: sudo zypper install steam"
+    } else {
+        "Install Steam from your distribution's package manager."
+    };
+
+    format!(
+        "{}\nOr install the Flatpak: flatpak install flathub com.valvesoftware.Steam\n\
+         Start Steam once and log in, then click Recheck. If Steam is in a custom folder, use Set Steam Path.",
+        package
+    )
+}
+
 // ============================================================================
 // Convenience Wrappers (for backwards compatibility)
 // ============================================================================
@@ -317,3 +517,68 @@ pub fn detect_steam_path_checked() -> Option<String> {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_hint() {
+        let cachy = "NAME=\"CachyOS Linux\"\nID=cachyos\nID_LIKE=arch\n";
+        assert!(install_hint(cachy, false).contains("pacman -S steam"));
+        let mint = "ID=linuxmint\nID_LIKE=\"ubuntu debian\"\n";
+        assert!(install_hint(mint, false).contains("apt install steam-installer"));
+        assert!(install_hint("ID=fedora\n", false).contains("dnf install steam"));
+        // ID_LIKE must not match on a prefix of another key
+        assert!(install_hint("VERSION_ID=39\n", false).contains("package manager"));
+        assert!(install_hint(cachy, true).contains("not been set up yet"));
+    }
+
+    #[test]
+    fn test_pick_steam_path() {
+        let home = std::env::temp_dir().join(format!("nak_steam_path_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let native = home.join(".local/share/Steam");
+        let flatpak = home.join(FLATPAK_STEAM_DIR).join(".local/share/Steam");
+        fs::create_dir_all(flatpak.join("steamapps")).unwrap();
+        fs::create_dir_all(flatpak.join("config")).unwrap();
+        assert_eq!(pick_steam_path(steam_path_candidates(&home)), Some(flatpak.clone()));
+        assert!(is_flatpak_steam(&flatpak));
+
+        // A native Steam no one logged into doesn't win over the Flatpak one
+        fs::create_dir_all(native.join("steamapps")).unwrap();
+        fs::write(flatpak.join("config/loginusers.vdf"), "").unwrap();
+        assert_eq!(pick_steam_path(steam_path_candidates(&home)), Some(flatpak.clone()));
+        fs::remove_file(flatpak.join("config/loginusers.vdf")).unwrap();
+        assert_eq!(pick_steam_path(steam_path_candidates(&home)), Some(native.clone()));
+        assert!(!is_flatpak_steam(&native));
+
+        let filesystems = vec!["xdg-music".to_string(), "~/Games".to_string(), "/mnt/ssd".to_string()];
+        assert!(flatpak_path_visible(&home.join("Games/MO2"), &home, &filesystems));
+        assert!(flatpak_path_visible(Path::new("/mnt/ssd/MO2"), &home, &filesystems));
+        assert!(flatpak_path_visible(&flatpak.join("MO2"), &home, &filesystems));
+        assert!(!flatpak_path_visible(&home.join("Modding/MO2"), &home, &filesystems));
+        assert!(flatpak_path_visible(&home.join("Modding/MO2"), &home, &["home".to_string()]));
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn test_pick_compatdata_prefix() {
+        let dir = std::env::temp_dir().join(format!("nak_pick_compatdata_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let expected = dir.join("primary/steamapps/compatdata/42/pfx");
+        let sd_card = dir.join("sdcard/steamapps/compatdata/42/pfx");
+        fs::create_dir_all(expected.join("drive_c")).unwrap();
+        fs::create_dir_all(&sd_card).unwrap();
+
+        // Not initialized by Steam yet
+        let found = vec![expected.clone(), sd_card.clone()];
+        assert_eq!(pick_compatdata_prefix(&expected, &found), expected);
+
+        fs::create_dir_all(sd_card.join("drive_c")).unwrap();
+        assert_eq!(pick_compatdata_prefix(&expected, &found), sd_card);
+        assert_eq!(pick_compatdata_prefix(&expected, std::slice::from_ref(&expected)), expected);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,12 +1,40 @@
 //! Steam Proton detection and management
 //!
 //! Finds Protons that Steam can see and use for non-Steam games.
-//! This includes Steam's built-in Protons and custom Protons in compatibilitytools.d.
+//! This includes Steam's built-in Protons and custom Protons in compatibilitytools.d,
+//! for both the detected Steam and a Flatpak Steam installed alongside it.
 
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-use super::find_steam_path;
+use parking_lot::Mutex;
+
+use super::{find_steam_path, is_flatpak_steam, FLATPAK_STEAM_DIR};
+
+/// Install sizes already walked, by Proton folder
+static INSTALL_SIZE_CACHE: OnceLock<Mutex<HashMap<PathBuf, u64>>> = OnceLock::new();
+
+/// Family a Proton build belongs to, in the order the picker lists them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProtonKind {
+    Ge,
+    CachyOs,
+    Valve,
+    Custom,
+}
+
+impl ProtonKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ProtonKind::Ge => "GE",
+            ProtonKind::CachyOs => "CachyOS",
+            ProtonKind::Valve => "Valve",
+            ProtonKind::Custom => "Custom",
+        }
+    }
+}
 
 /// Information about an installed Proton version
 #[derive(Debug, Clone)]
@@ -43,10 +71,104 @@ impl SteamProton {
         paths.into_iter().find(|p| p.exists())
     }
 
+    /// Files an install needs that are missing from this Proton
+    ///
+    /// Checks the `proton` script and wine/wineserver (in `files/bin` or
+    /// `dist/bin`). Empty if the build is complete.
+    pub fn missing_binaries(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if !self.path.join("proton").exists() {
+            missing.push("proton");
+        }
+        if self.wine_binary().is_none() {
+            missing.push("files/bin/wine");
+        }
+        if self.wineserver_binary().is_none() {
+            missing.push("files/bin/wineserver");
+        }
+        missing
+    }
+
+    /// Check the Proton is complete before an install uses it
+    ///
+    /// Incompletely downloaded or non-standard builds otherwise fail halfway
+    /// through an install; the error says what's missing and how to fix it.
+    pub fn validate_binaries(&self) -> Result<(), String> {
+        let missing = self.missing_binaries();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let fix = if self.is_steam_proton {
+            "Uninstall and reinstall it from the Steam library (Tools)"
+        } else {
+            "Delete its folder in compatibilitytools.d and download it again"
+        };
+        Err(format!(
+            "Proton '{}' is incomplete (missing {} in {}). {}, or pick another Proton.",
+            self.name,
+            missing.join(", "),
+            self.path.display(),
+            fix
+        ))
+    }
+
     /// Get the bin directory containing wine executables.
     pub fn bin_dir(&self) -> Option<PathBuf> {
         self.wine_binary().and_then(|p| p.parent().map(|p| p.to_path_buf()))
     }
+
+    pub fn kind(&self) -> ProtonKind {
+        if self.name.starts_with("GE-Proton") {
+            ProtonKind::Ge
+        } else if self.name.to_ascii_lowercase().contains("cachyos") {
+            ProtonKind::CachyOs
+        } else if self.is_steam_proton {
+            ProtonKind::Valve
+        } else {
+            ProtonKind::Custom
+        }
+    }
+
+    /// Numbers in the name, for numeric sorting
+    ///
+    /// "GE-Proton10-3" gives [10, 3], so it sorts after "GE-Proton9-20" ([9, 20]).
+    pub fn version(&self) -> Vec<u64> {
+        self.name
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|part| part.parse().ok())
+            .collect()
+    }
+
+    /// Size of the Proton folder in bytes
+    ///
+    /// Walks the folder the first time, so call it off the UI thread;
+    /// `cached_install_size` reads the result without walking.
+    pub fn install_size(&self) -> u64 {
+        if let Some(size) = self.cached_install_size() {
+            return size;
+        }
+        let size = crate::config::ManagedPrefixes::get_prefix_size(&self.path.to_string_lossy());
+        INSTALL_SIZE_CACHE.get_or_init(Default::default).lock().insert(self.path.clone(), size);
+        size
+    }
+
+    /// Install size if it was already walked
+    pub fn cached_install_size(&self) -> Option<u64> {
+        INSTALL_SIZE_CACHE.get()?.lock().get(&self.path).copied()
+    }
+}
+
+/// Sort Protons for picking: GE, CachyOS, Valve, then custom builds, newest first
+///
+/// Experimental leads the Valve Protons since it tracks the newest release.
+pub fn sort_protons(protons: &mut [SteamProton]) {
+    protons.sort_by(|a, b| {
+        a.kind()
+            .cmp(&b.kind())
+            .then(b.is_experimental.cmp(&a.is_experimental))
+            .then_with(|| b.version().cmp(&a.version()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
 }
 
 /// Find all Protons that Steam can use (Proton 10+ only)
@@ -57,13 +179,16 @@ pub fn find_steam_protons() -> Vec<SteamProton> {
         return protons;
     };
 
-    let is_flatpak = steam_path.to_string_lossy().contains(".var/app/com.valvesoftware.Steam");
-
-    // 1. Steam's built-in Protons (steamapps/common/Proton*)
-    protons.extend(find_builtin_protons(&steam_path));
+    let is_flatpak = is_flatpak_steam(&steam_path);
 
-    // 2. Custom Protons in user's compatibilitytools.d
-    protons.extend(find_custom_protons(&steam_path));
+    // 1 + 2. Built-in Protons (steamapps/common/Proton*) and custom Protons
+    // (compatibilitytools.d), from the detected Steam first and then from a
+    // Flatpak Steam install (where Flatpak ProtonUp-Qt puts GE-Proton)
+    let mut roots = vec![steam_path];
+    if let Some(home) = std::env::var_os("HOME") {
+        roots.extend(flatpak_steam_roots(Path::new(&home)));
+    }
+    protons.extend(find_protons_in_roots(&roots));
 
     // 3. System-level Protons in /usr/share/steam/compatibilitytools.d/
     // Skip for Flatpak Steam - system protons won't work properly with Flatpak
@@ -88,14 +213,7 @@ pub fn find_steam_protons() -> Vec<SteamProton> {
         has_wine
     });
 
-    // Sort: Experimental first, then by name descending (newest first)
-    protons.sort_by(|a, b| {
-        if a.is_experimental != b.is_experimental {
-            return b.is_experimental.cmp(&a.is_experimental);
-        }
-        b.name.cmp(&a.name)
-    });
-
+    sort_protons(&mut protons);
     protons
 }
 
@@ -156,8 +274,47 @@ fn is_proton_10_or_newer(proton: &SteamProton) -> bool {
     true
 }
 
+/// Steam folders of a Flatpak Steam install under `home`
+///
+/// `.steam/steam` is normally a symlink to `.local/share/Steam`; older
+/// installs used `data/Steam`. Only existing folders are returned.
+fn flatpak_steam_roots(home: &Path) -> Vec<PathBuf> {
+    let app_dir = home.join(FLATPAK_STEAM_DIR);
+    [".steam/steam", ".local/share/Steam", "data/Steam"]
+        .iter()
+        .map(|rel| app_dir.join(rel))
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+/// Built-in and custom Protons from several Steam folders
+///
+/// Roots that resolve to the same folder are scanned once, and a Proton
+/// whose config name was already found in an earlier root is skipped, so
+/// the first root (the Steam NaK talks to) wins.
+fn find_protons_in_roots(roots: &[PathBuf]) -> Vec<SteamProton> {
+    let mut found: Vec<SteamProton> = Vec::new();
+    let mut scanned: Vec<PathBuf> = Vec::new();
+
+    for root in roots {
+        let resolved = fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+        if scanned.contains(&resolved) {
+            continue;
+        }
+        scanned.push(resolved);
+
+        for proton in find_builtin_protons(root).into_iter().chain(find_custom_protons(root)) {
+            if !found.iter().any(|p| p.config_name == proton.config_name) {
+                found.push(proton);
+            }
+        }
+    }
+
+    found
+}
+
 /// Find Steam's built-in Proton versions
-fn find_builtin_protons(steam_path: &std::path::Path) -> Vec<SteamProton> {
+fn find_builtin_protons(steam_path: &Path) -> Vec<SteamProton> {
     let mut found = Vec::new();
     let common_dir = steam_path.join("steamapps/common");
 
@@ -201,7 +358,7 @@ fn find_builtin_protons(steam_path: &std::path::Path) -> Vec<SteamProton> {
 }
 
 /// Find custom Protons in compatibilitytools.d
-fn find_custom_protons(steam_path: &std::path::Path) -> Vec<SteamProton> {
+fn find_custom_protons(steam_path: &Path) -> Vec<SteamProton> {
     let mut found = Vec::new();
     let compat_dir = steam_path.join("compatibilitytools.d");
 
@@ -277,6 +434,107 @@ fn find_system_protons() -> Vec<SteamProton> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_binaries() {
+        let dir = std::env::temp_dir().join(format!("nak_proton_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("files/bin")).unwrap();
+        fs::write(dir.join("files/bin/wine"), "").unwrap();
+
+        let proton = SteamProton {
+            name: "GE-Proton10-1".to_string(),
+            config_name: "GE-Proton10-1".to_string(),
+            path: dir.clone(),
+            is_steam_proton: false,
+            is_experimental: false,
+        };
+        assert_eq!(proton.missing_binaries(), vec!["proton", "files/bin/wineserver"]);
+        let err = proton.validate_binaries().unwrap_err();
+        assert!(err.contains("proton, files/bin/wineserver"));
+        assert!(err.contains("compatibilitytools.d"));
+
+        fs::write(dir.join("proton"), "").unwrap();
+        fs::write(dir.join("files/bin/wineserver"), "").unwrap();
+        assert!(proton.validate_binaries().is_ok());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_sort_protons() {
+        let proton = |name: &str, is_steam_proton: bool| SteamProton {
+            name: name.to_string(),
+            config_name: name.to_string(),
+            path: PathBuf::from(name),
+            is_steam_proton,
+            is_experimental: name == "Proton - Experimental",
+        };
+        let mut protons = vec![
+            proton("Proton 10.0", true),
+            proton("my-proton", false),
+            proton("GE-Proton9-20", false),
+            proton("proton-cachyos-10.0-20250714-slr", false),
+            proton("Proton - Experimental", true),
+            proton("GE-Proton10-3", false),
+            proton("GE-Proton10-25", false),
+        ];
+        sort_protons(&mut protons);
+        let names: Vec<&str> = protons.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "GE-Proton10-25",
+                "GE-Proton10-3",
+                "GE-Proton9-20",
+                "proton-cachyos-10.0-20250714-slr",
+                "Proton - Experimental",
+                "Proton 10.0",
+                "my-proton",
+            ]
+        );
+        assert_eq!(protons[0].version(), [10, 25]);
+        assert_eq!(protons[6].kind(), ProtonKind::Custom);
+    }
+
+    #[test]
+    fn test_flatpak_protonup_qt_ge_proton_detected() {
+        let home = std::env::temp_dir().join(format!("nak_flatpak_proton_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&home);
+        let native = home.join(".local/share/Steam");
+        fs::create_dir_all(native.join("compatibilitytools.d/GE-Proton10-20")).unwrap();
+        fs::write(native.join("compatibilitytools.d/GE-Proton10-20/proton"), "").unwrap();
+
+        // Where Flatpak ProtonUp-Qt installs GE-Proton for Flatpak Steam
+        let flatpak = home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam");
+        for name in ["GE-Proton10-25", "GE-Proton10-20"] {
+            let dir = flatpak.join("compatibilitytools.d").join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("compatibilitytool.vdf"), "").unwrap();
+        }
+        fs::create_dir_all(flatpak.join("steamapps/common/Proton 10.0")).unwrap();
+        fs::write(flatpak.join("steamapps/common/Proton 10.0/proton"), "").unwrap();
+        // .steam/steam points at the same Steam folder
+        fs::create_dir_all(home.join(".var/app/com.valvesoftware.Steam/.steam")).unwrap();
+        std::os::unix::fs::symlink(&flatpak, home.join(".var/app/com.valvesoftware.Steam/.steam/steam")).unwrap();
+
+        let roots = flatpak_steam_roots(&home);
+        assert_eq!(roots.len(), 2);
+
+        let mut all_roots = vec![native.clone()];
+        all_roots.extend(roots);
+        let protons = find_protons_in_roots(&all_roots);
+        let mut names: Vec<&str> = protons.iter().map(|p| p.config_name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["GE-Proton10-20", "GE-Proton10-25", "proton_10"]);
+
+        // The native copy wins when both Steams have the same Proton
+        let ge20 = protons.iter().find(|p| p.config_name == "GE-Proton10-20").unwrap();
+        assert!(ge20.path.starts_with(&native));
+        let ge25 = protons.iter().find(|p| p.config_name == "GE-Proton10-25").unwrap();
+        assert!(ge25.path.to_string_lossy().contains(".var/app/com.valvesoftware.Steam"));
+
+        let _ = fs::remove_dir_all(&home);
+    }
+
     #[test]
     fn test_find_protons() {
         // This test will only work on a system with Steam installed
@@ -4,12 +4,36 @@
 
 use std::collections::HashSet;
 use std::fs;
-use std::io::{Read, Write};
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use rand::Rng;
 
-use super::find_userdata_path;
+use super::{find_userdata_path, find_userdata_path_for_account, SteamError};
+
+/// AppID Steam gives a non-Steam game shortcut for this exe and name
+///
+/// Steam takes the CRC-32 (IEEE, as in zlib) of the quoted exe path
+/// followed by the shortcut name, and sets the high bit (0x80000000) to
+/// mark it as non-Steam. `exe` may be given with or without the quotes.
+/// `Shortcut::new` uses this, so the AppID (and the compatdata folder) of a
+/// shortcut can be predicted before it's created.
+pub fn compute_app_id(exe: &str, name: &str) -> u32 {
+    let quoted = if exe.starts_with('"') { exe.to_string() } else { format!("\"{}\"", exe) };
+    crc32(format!("{}{}", quoted, name).as_bytes()) | 0x80000000
+}
+
+/// CRC-32 (IEEE 802.3 polynomial, reflected), the checksum Steam uses for shortcut AppIDs
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
 
 /// Generate a random AppID for a non-Steam game shortcut
 /// High bit (0x80000000) is set to mark as non-Steam game
@@ -25,6 +49,12 @@ pub fn get_shortcuts_vdf_path() -> Option<PathBuf> {
     Some(userdata.join("config/shortcuts.vdf"))
 }
 
+/// Get path to shortcuts.vdf for a specific Steam account (userdata folder name)
+pub fn get_shortcuts_vdf_path_for_account(account_id: &str) -> Option<PathBuf> {
+    let userdata = find_userdata_path_for_account(account_id)?;
+    Some(userdata.join("config/shortcuts.vdf"))
+}
+
 
 /// A Steam non-Steam game shortcut
 #[derive(Clone, Debug)]
@@ -74,9 +104,10 @@ impl Default for Shortcut {
 
 impl Shortcut {
     /// Create a new shortcut with the given name and exe path
-    /// AppID is generated randomly (collision check happens in add_shortcut)
+    /// AppID is computed the way Steam does (`compute_app_id`); add_shortcut
+    /// falls back to a random one on a collision
     pub fn new(app_name: &str, exe_path: &str, start_dir: &str) -> Self {
-        let appid = generate_random_app_id();
+        let appid = compute_app_id(exe_path, app_name);
         Shortcut {
             appid,
             app_name: app_name.to_string(),
@@ -112,14 +143,21 @@ impl ShortcutsVdf {
     }
 
     /// Load shortcuts from the default Steam location
-    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn load() -> Result<Self, SteamError> {
         let path = get_shortcuts_vdf_path()
-            .ok_or("Could not find Steam userdata path")?;
+            .ok_or_else(|| SteamError::NotFound("Could not find Steam userdata path".to_string()))?;
+        Self::parse(&path)
+    }
+
+    /// Load shortcuts from a specific Steam account instead of the selected one
+    pub fn load_for_account(account_id: &str) -> Result<Self, SteamError> {
+        let path = get_shortcuts_vdf_path_for_account(account_id)
+            .ok_or_else(|| SteamError::NotFound(format!("No Steam userdata folder for account {}", account_id)))?;
         Self::parse(&path)
     }
 
     /// Parse shortcuts.vdf binary format
-    pub fn parse(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn parse(path: &Path) -> Result<Self, SteamError> {
         if !path.exists() {
             return Ok(ShortcutsVdf::new());
         }
@@ -268,14 +306,14 @@ impl ShortcutsVdf {
     }
 
     /// Save shortcuts to the default Steam location
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn save(&self) -> Result<(), SteamError> {
         let path = get_shortcuts_vdf_path()
-            .ok_or("Could not find Steam userdata path")?;
+            .ok_or_else(|| SteamError::NotFound("Could not find Steam userdata path".to_string()))?;
         self.write(&path)
     }
 
     /// Write shortcuts.vdf binary format
-    pub fn write(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn write(&self, path: &Path) -> Result<(), SteamError> {
         let mut data = Vec::new();
 
         // Header
@@ -326,13 +364,14 @@ impl ShortcutsVdf {
         data.push(0x08); // End shortcuts section
         data.push(0x08); // End root map (required!)
 
+        let write_err = |e: std::io::Error| SteamError::ShortcutsWrite(format!("{}: {}", path.display(), e));
+
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
+            fs::create_dir_all(parent).map_err(write_err)?;
         }
 
-        let mut file = fs::File::create(path)?;
-        file.write_all(&data)?;
+        crate::config::write_atomic(path, &data).map_err(write_err)?;
 
         Ok(())
     }
@@ -385,3 +424,26 @@ impl Default for ShortcutsVdf {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_compute_app_id() {
+        let app_id = compute_app_id("/home/deck/Games/MO2/ModOrganizer.exe", "Skyrim MO2");
+        assert_eq!(app_id, 3417451986);
+        assert_eq!(compute_app_id("\"/home/deck/Games/MO2/ModOrganizer.exe\"", "Skyrim MO2"), app_id);
+        assert_eq!(compute_app_id("/usr/bin/foo", "Foo"), 3810698013);
+        assert_ne!(app_id & 0x80000000, 0);
+
+        let shortcut = Shortcut::new("Skyrim MO2", "/home/deck/Games/MO2/ModOrganizer.exe", "/home/deck/Games/MO2");
+        assert_eq!(shortcut.appid, app_id);
+    }
+}
@@ -0,0 +1,552 @@
+//! Background task queue
+//!
+//! Installs, downloads and prefix resets/copies each used to start their own
+//! thread, so several could run at once and fight over the disk and network.
+//! Tasks spawned here still get their own thread, but wait (in order) for a
+//! free slot before doing any work. The number of slots is
+//! `max_parallel_tasks` in config.json (default 1, i.e. one at a time).
+//!
+//! The queue also keeps a status/progress row per task, which the UI lists,
+//! and a cancel flag and a pause flag per task. Pausing is cooperative: the
+//! task's download and child-process loops wait (and stop their child
+//! processes) while the flag is set.
+
+use std::io::Read;
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use parking_lot::{Condvar, Mutex};
+
+/// Finished tasks kept in the list after they end
+const MAX_FINISHED_TASKS: usize = 10;
+
+/// Where a task is in its life
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    /// Waiting for a free slot
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl TaskState {
+    /// Whether the task has ended
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Done | Self::Failed | Self::Cancelled)
+    }
+
+    /// Lowercase name for the UI
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Done => "done",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// A task's row in the task list
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub id: u64,
+    /// What the task does, e.g. "Install MO2 (Skyrim)"
+    pub label: String,
+    /// Latest status message, or the result once finished
+    pub status: String,
+    /// 0.0 - 1.0
+    pub progress: f32,
+    pub state: TaskState,
+    /// Whether the task's work watches its pause flag (see `TaskHandle::pause_flag`)
+    pub pausable: bool,
+    pub paused: bool,
+}
+
+struct Entry {
+    info: TaskInfo,
+    cancel: Arc<AtomicBool>,
+    pause: Arc<AtomicBool>,
+}
+
+struct QueueState {
+    /// Tasks allowed to run at once
+    limit: usize,
+    running: usize,
+    next_id: u64,
+    entries: Vec<Entry>,
+}
+
+struct Shared {
+    state: Mutex<QueueState>,
+    /// Signalled whenever a slot frees up or a queued task is cancelled
+    changed: Condvar,
+}
+
+impl Shared {
+    fn update(&self, id: u64, f: impl FnOnce(&mut TaskInfo)) {
+        if let Some(entry) = self.state.lock().entries.iter_mut().find(|e| e.info.id == id) {
+            f(&mut entry.info);
+        }
+    }
+}
+
+/// Runs tasks on background threads, at most `limit` at a time
+#[derive(Clone)]
+pub struct TaskQueue {
+    shared: Arc<Shared>,
+}
+
+impl TaskQueue {
+    /// Create a queue running up to `limit` tasks at once (at least 1)
+    pub fn new(limit: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(QueueState {
+                    limit: limit.max(1),
+                    running: 0,
+                    next_id: 1,
+                    entries: Vec::new(),
+                }),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// The app-wide queue, sized from `max_parallel_tasks` in config.json
+    pub fn global() -> &'static TaskQueue {
+        static QUEUE: OnceLock<TaskQueue> = OnceLock::new();
+        QUEUE.get_or_init(|| TaskQueue::new(crate::config::AppConfig::load().max_parallel_tasks))
+    }
+
+    /// Change how many tasks may run at once (queued tasks start if allowed)
+    pub fn set_limit(&self, limit: usize) {
+        self.shared.state.lock().limit = limit.max(1);
+        self.shared.changed.notify_all();
+    }
+
+    /// Queue a task with its own cancel flag, returning its id
+    ///
+    /// `work` runs on a new thread once a slot is free; its result becomes the
+    /// task's final status.
+    pub fn spawn<F>(&self, label: impl Into<String>, work: F) -> u64
+    where
+        F: FnOnce(&TaskHandle) -> Result<String, String> + Send + 'static,
+    {
+        self.spawn_with_cancel(label, Arc::new(AtomicBool::new(false)), work)
+    }
+
+    /// Queue a task that uses an existing cancel flag
+    pub fn spawn_with_cancel<F>(&self, label: impl Into<String>, cancel: Arc<AtomicBool>, work: F) -> u64
+    where
+        F: FnOnce(&TaskHandle) -> Result<String, String> + Send + 'static,
+    {
+        let pause = Arc::new(AtomicBool::new(false));
+        let id = {
+            let mut state = self.shared.state.lock();
+            let id = state.next_id;
+            state.next_id += 1;
+            state.entries.push(Entry {
+                info: TaskInfo {
+                    id,
+                    label: label.into(),
+                    status: "Waiting for other tasks to finish...".to_string(),
+                    progress: 0.0,
+                    state: TaskState::Queued,
+                    pausable: false,
+                    paused: false,
+                },
+                cancel: cancel.clone(),
+                pause: pause.clone(),
+            });
+            prune_finished(&mut state.entries);
+            id
+        };
+
+        let handle = TaskHandle { id, shared: self.shared.clone(), cancel, pause };
+        std::thread::spawn(move || {
+            if !handle.wait_for_slot() {
+                return;
+            }
+            let mut slot = RunningSlot { handle: &handle, result: None };
+            slot.result = Some(work(&handle));
+        });
+        id
+    }
+
+    /// All tasks, oldest first
+    pub fn snapshot(&self) -> Vec<TaskInfo> {
+        self.shared.state.lock().entries.iter().map(|e| e.info.clone()).collect()
+    }
+
+    /// One task's row, if it is still listed
+    pub fn get(&self, id: u64) -> Option<TaskInfo> {
+        self.shared.state.lock().entries.iter().find(|e| e.info.id == id).map(|e| e.info.clone())
+    }
+
+    /// Whether any task is queued or running
+    pub fn is_busy(&self) -> bool {
+        self.shared.state.lock().entries.iter().any(|e| !e.info.state.is_finished())
+    }
+
+    /// Ask a task to stop (a queued task is dropped before it starts)
+    pub fn cancel(&self, id: u64) {
+        if let Some(entry) = self.shared.state.lock().entries.iter().find(|e| e.info.id == id) {
+            entry.cancel.store(true, Ordering::Relaxed);
+        }
+        self.shared.changed.notify_all();
+    }
+
+    /// Pause or resume a running task that supports it
+    pub fn set_paused(&self, id: u64, paused: bool) {
+        let mut state = self.shared.state.lock();
+        let Some(entry) = state.entries.iter_mut().find(|e| e.info.id == id) else {
+            return;
+        };
+        if entry.info.pausable && entry.info.state == TaskState::Running {
+            entry.pause.store(paused, Ordering::Relaxed);
+            entry.info.paused = paused;
+        }
+    }
+
+    /// Remove finished tasks from the list
+    pub fn clear_finished(&self) {
+        self.shared.state.lock().entries.retain(|e| !e.info.state.is_finished());
+    }
+}
+
+/// A running task's slot, given back (with the task's result) when dropped
+///
+/// Dropping also happens while unwinding, so a task that panics still frees
+/// its slot and ends up Failed instead of Running forever.
+struct RunningSlot<'a> {
+    handle: &'a TaskHandle,
+    /// None until `work` returns
+    result: Option<Result<String, String>>,
+}
+
+impl Drop for RunningSlot<'_> {
+    fn drop(&mut self) {
+        let result = self.result.take().unwrap_or_else(|| Err("Task crashed unexpectedly".to_string()));
+        let mut state = self.handle.shared.state.lock();
+        state.running -= 1;
+        if let Some(entry) = state.entries.iter_mut().find(|e| e.info.id == self.handle.id) {
+            let (task_state, status) = match result {
+                Ok(msg) => (TaskState::Done, msg),
+                Err(msg) if self.handle.is_cancelled() => (TaskState::Cancelled, msg),
+                Err(msg) => (TaskState::Failed, msg),
+            };
+            entry.info.state = task_state;
+            entry.info.status = status;
+            entry.info.paused = false;
+            if task_state == TaskState::Done {
+                entry.info.progress = 1.0;
+            }
+        }
+        drop(state);
+        self.handle.shared.changed.notify_all();
+    }
+}
+
+/// Drop the oldest finished tasks beyond `MAX_FINISHED_TASKS`
+fn prune_finished(entries: &mut Vec<Entry>) {
+    let finished = entries.iter().filter(|e| e.info.state.is_finished()).count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED_TASKS);
+    entries.retain(|e| {
+        if excess > 0 && e.info.state.is_finished() {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+/// Passed to a running task to report status/progress and check for cancel
+#[derive(Clone)]
+pub struct TaskHandle {
+    id: u64,
+    shared: Arc<Shared>,
+    cancel: Arc<AtomicBool>,
+    pause: Arc<AtomicBool>,
+}
+
+impl TaskHandle {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn set_status(&self, msg: impl Into<String>) {
+        let msg = msg.into();
+        self.shared.update(self.id, |info| info.status = msg);
+    }
+
+    pub fn set_progress(&self, progress: f32) {
+        self.shared.update(self.id, |info| info.progress = progress.clamp(0.0, 1.0));
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// The task's cancel flag, for code that takes an `Arc<AtomicBool>`
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancel.clone()
+    }
+
+    /// The task's pause flag; taking it marks the task as pausable in the list
+    pub fn pause_flag(&self) -> Arc<AtomicBool> {
+        self.shared.update(self.id, |info| info.pausable = true);
+        self.pause.clone()
+    }
+
+    /// Block until this task may run; false if it was cancelled while queued
+    ///
+    /// Tasks start in the order they were queued.
+    fn wait_for_slot(&self) -> bool {
+        let mut state = self.shared.state.lock();
+        loop {
+            if self.is_cancelled() {
+                if let Some(entry) = state.entries.iter_mut().find(|e| e.info.id == self.id) {
+                    entry.info.state = TaskState::Cancelled;
+                    entry.info.status = "Cancelled before it started".to_string();
+                }
+                drop(state);
+                // Tasks queued behind this one may be next now
+                self.shared.changed.notify_all();
+                return false;
+            }
+            let first_queued = state
+                .entries
+                .iter()
+                .find(|e| e.info.state == TaskState::Queued && !e.cancel.load(Ordering::Relaxed))
+                .map(|e| e.info.id);
+            if state.running < state.limit && first_queued == Some(self.id) {
+                state.running += 1;
+                if let Some(entry) = state.entries.iter_mut().find(|e| e.info.id == self.id) {
+                    entry.info.state = TaskState::Running;
+                    entry.info.status = "Starting...".to_string();
+                }
+                return true;
+            }
+            // Timed so a cancel flag set from outside (not via `cancel`) is noticed
+            self.shared.changed.wait_for(&mut state, Duration::from_millis(250));
+        }
+    }
+}
+
+/// Block while `pause` is set; false if the task was cancelled meanwhile
+pub fn wait_while_paused(pause: &AtomicBool, cancel: &AtomicBool) -> bool {
+    while pause.load(Ordering::Relaxed) && !cancel.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(250));
+    }
+    !cancel.load(Ordering::Relaxed)
+}
+
+/// Reader that holds off while its task is paused and fails once it's cancelled
+///
+/// Wrap a download's response reader in this so pausing stops the transfer
+/// (the server keeps the connection open for a while) instead of restarting it.
+pub struct PausableReader<R> {
+    inner: R,
+    pause: Arc<AtomicBool>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl<R: Read> PausableReader<R> {
+    pub fn new(inner: R, pause: Arc<AtomicBool>, cancel: Arc<AtomicBool>) -> Self {
+        Self { inner, pause, cancel }
+    }
+}
+
+impl<R: Read> Read for PausableReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if !wait_while_paused(&self.pause, &self.cancel) {
+            return Err(std::io::Error::other("Cancelled"));
+        }
+        self.inner.read(buf)
+    }
+}
+
+/// Stops and continues a child process (and everything it started) to follow a pause flag
+///
+/// Winetricks and the .NET installers run as a tree of wine processes, so
+/// SIGSTOP goes to every descendant found in /proc, not just the child.
+pub struct ChildPause {
+    pid: u32,
+    stopped: bool,
+}
+
+impl ChildPause {
+    pub fn new(child: &Child) -> Self {
+        Self { pid: child.id(), stopped: false }
+    }
+
+    /// Stop or continue the process tree if `paused` changed
+    pub fn sync(&mut self, paused: bool) {
+        if paused != self.stopped {
+            signal_process_tree(self.pid, if paused { "STOP" } else { "CONT" });
+            self.stopped = paused;
+        }
+    }
+
+    /// Continue the tree if stopped (before killing it or returning)
+    pub fn resume(&mut self) {
+        self.sync(false);
+    }
+}
+
+/// Send a signal (e.g. "STOP") to a process and all its descendants
+fn signal_process_tree(pid: u32, signal: &str) {
+    let parents: Vec<(u32, u32)> = std::fs::read_dir("/proc")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let pid: u32 = e.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(e.path().join("stat")).ok()?;
+            Some((pid, parse_stat_ppid(&stat)?))
+        })
+        .collect();
+    let pids: Vec<String> = process_tree(pid, &parents).iter().map(u32::to_string).collect();
+    let _ = std::process::Command::new("kill").arg(format!("-{}", signal)).args(&pids).status();
+}
+
+/// Parent PID from a `/proc/<pid>/stat` line
+///
+/// The command name is in parentheses and may itself contain spaces and
+/// parentheses, so fields are counted from the last ')'.
+fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// `root` and all its descendants from (pid, parent pid) pairs
+fn process_tree(root: u32, parents: &[(u32, u32)]) -> Vec<u32> {
+    let mut tree = vec![root];
+    let mut i = 0;
+    while i < tree.len() {
+        let parent = tree[i];
+        for (pid, _) in parents.iter().filter(|(_, ppid)| *ppid == parent) {
+            if !tree.contains(pid) {
+                tree.push(*pid);
+            }
+        }
+        i += 1;
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn wait_until(queue: &TaskQueue, id: u64, state: TaskState) {
+        for _ in 0..200 {
+            if queue.get(id).map(|t| t.state) == Some(state) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("task {} never reached {:?}", id, state);
+    }
+
+    #[test]
+    fn test_queue_serializes_and_cancels() {
+        let queue = TaskQueue::new(1);
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let first = queue.spawn("first", move |task| {
+            task.set_progress(0.5);
+            release_rx.recv().map_err(|e| e.to_string())?;
+            Ok("first done".to_string())
+        });
+        let second = queue.spawn("second", |_| Ok("second done".to_string()));
+        let third = queue.spawn("third", |_| Err("should not run".to_string()));
+
+        wait_until(&queue, first, TaskState::Running);
+        // Only one slot, so the others wait in order
+        assert_eq!(queue.get(second).unwrap().state, TaskState::Queued);
+        assert_eq!(queue.get(first).unwrap().progress, 0.5);
+        assert!(queue.is_busy());
+
+        queue.cancel(third);
+        release_tx.send(()).unwrap();
+        wait_until(&queue, first, TaskState::Done);
+        wait_until(&queue, second, TaskState::Done);
+        wait_until(&queue, third, TaskState::Cancelled);
+        assert_eq!(queue.get(second).unwrap().status, "second done");
+        assert!(!queue.is_busy());
+
+        queue.clear_finished();
+        assert!(queue.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_panicking_task_frees_its_slot() {
+        let queue = TaskQueue::new(1);
+        let crashed = queue.spawn("crashes", |_| panic!("boom"));
+        let next = queue.spawn("next", |_| Ok("next done".to_string()));
+
+        wait_until(&queue, crashed, TaskState::Failed);
+        wait_until(&queue, next, TaskState::Done);
+        assert!(!queue.is_busy());
+    }
+
+    #[test]
+    fn test_pause_only_pausable_running_tasks() {
+        let queue = TaskQueue::new(1);
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let (flag_tx, flag_rx) = mpsc::channel();
+
+        let pausable = queue.spawn("pausable", move |task| {
+            flag_tx.send(task.pause_flag()).map_err(|e| e.to_string())?;
+            release_rx.recv().map_err(|e| e.to_string())?;
+            Ok("done".to_string())
+        });
+        let pause = flag_rx.recv().unwrap();
+        queue.set_paused(pausable, true);
+        assert!(pause.load(Ordering::Relaxed));
+        assert!(queue.get(pausable).unwrap().paused);
+
+        // Queued tasks can't be paused
+        let queued = queue.spawn("queued", |_| Ok("done".to_string()));
+        queue.set_paused(queued, true);
+        assert!(!queue.get(queued).unwrap().paused);
+
+        queue.set_paused(pausable, false);
+        assert!(!pause.load(Ordering::Relaxed));
+        release_tx.send(()).unwrap();
+        wait_until(&queue, queued, TaskState::Done);
+    }
+
+    #[test]
+    fn test_process_tree() {
+        assert_eq!(parse_stat_ppid("1234 (wine) S 1200 1234 1200 0 -1"), Some(1200));
+        assert_eq!(parse_stat_ppid("99 (odd (name) here) R 7 99 7"), Some(7));
+        assert_eq!(parse_stat_ppid("garbage"), None);
+
+        let parents = [(10, 1), (11, 10), (12, 11), (13, 1), (14, 10)];
+        assert_eq!(process_tree(10, &parents), vec![10, 11, 14, 12]);
+        assert_eq!(process_tree(13, &parents), vec![13]);
+    }
+
+    #[test]
+    fn test_pausable_reader_cancelled() {
+        let pause = Arc::new(AtomicBool::new(false));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let mut reader = PausableReader::new(&b"data"[..], pause.clone(), cancel.clone());
+        let mut out = String::new();
+        reader.read_to_string(&mut out).unwrap();
+        assert_eq!(out, "data");
+
+        cancel.store(true, Ordering::Relaxed);
+        pause.store(true, Ordering::Relaxed);
+        let mut reader = PausableReader::new(&b"data"[..], pause, cancel);
+        assert!(reader.read(&mut [0u8; 4]).is_err());
+    }
+}
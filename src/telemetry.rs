@@ -0,0 +1,137 @@
+//! Opt-in install failure reports
+//!
+//! Off unless the user turns it on (first-run screen or Settings) and sets an
+//! endpoint. A report holds only the category of the failing step, the Proton
+//! family and major version, and NaK's version: no paths, usernames, instance
+//! names or error text leave the machine.
+
+use serde::Serialize;
+
+use crate::config::AppConfig;
+use crate::logging::log_info;
+use crate::steam::SteamProton;
+
+/// Step of an install that failed, as far as the error message tells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureCategory {
+    Download,
+    Extract,
+    Dotnet,
+    Winetricks,
+    Steam,
+    Other,
+}
+
+impl FailureCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FailureCategory::Download => "download",
+            FailureCategory::Extract => "extract",
+            FailureCategory::Dotnet => "dotnet",
+            FailureCategory::Winetricks => "winetricks",
+            FailureCategory::Steam => "steam",
+            FailureCategory::Other => "other",
+        }
+    }
+
+    /// Classify an install error message
+    ///
+    /// The more specific steps are checked first: a failed .NET installer
+    /// download counts as "dotnet", not "download".
+    pub fn from_error(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| error.contains(w));
+        if has(&[".net", "dotnet"]) {
+            FailureCategory::Dotnet
+        } else if has(&["winetricks", "verb"]) {
+            FailureCategory::Winetricks
+        } else if has(&["extract", "archive", "7z", "unzip"]) {
+            FailureCategory::Extract
+        } else if has(&["download", "http", "network", "connection", "timed out"]) {
+            FailureCategory::Download
+        } else if has(&["steam", "shortcut", "compatdata", "appid"]) {
+            FailureCategory::Steam
+        } else {
+            FailureCategory::Other
+        }
+    }
+}
+
+/// Everything a failure report contains
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FailureReport {
+    pub category: &'static str,
+    /// "GE", "CachyOS", "Valve" or "Custom"
+    pub proton_kind: &'static str,
+    /// None for builds without a version in their name (Proton Experimental)
+    pub proton_major: Option<u64>,
+    pub nak_version: &'static str,
+}
+
+impl FailureReport {
+    pub fn new(error: &str, proton: &SteamProton) -> Self {
+        Self {
+            category: FailureCategory::from_error(error).as_str(),
+            proton_kind: proton.kind().label(),
+            proton_major: proton.version().first().copied().filter(|_| !proton.is_experimental),
+            nak_version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+/// Send a failure report in the background, if the user opted in
+///
+/// Cancelled installs aren't failures and are never reported. Sending
+/// problems are only logged; they never affect the install.
+pub fn report_install_failure(error: &str, proton: &SteamProton) {
+    if error.contains("Cancelled") {
+        return;
+    }
+    let config = AppConfig::load();
+    let endpoint = config.telemetry_endpoint.trim().to_string();
+    if !config.telemetry_enabled || endpoint.is_empty() {
+        return;
+    }
+
+    let report = FailureReport::new(error, proton);
+    std::thread::spawn(move || {
+        let sent = crate::net::agent_for(&endpoint)
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .post(&endpoint)
+            .send_json(&report);
+        match sent {
+            Ok(_) => log_info(&format!("Sent anonymous failure report ({})", report.category)),
+            Err(e) => log_info(&format!("Could not send failure report: {}", e)),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_failure_report() {
+        assert_eq!(FailureCategory::from_error("Failed to download .NET 8 installer"), FailureCategory::Dotnet);
+        assert_eq!(FailureCategory::from_error("winetricks verb vcrun2022 failed"), FailureCategory::Winetricks);
+        assert_eq!(FailureCategory::from_error("Failed to extract MO2 archive"), FailureCategory::Extract);
+        assert_eq!(FailureCategory::from_error("Download failed: connection reset"), FailureCategory::Download);
+        assert_eq!(FailureCategory::from_error("Could not write shortcuts.vdf"), FailureCategory::Steam);
+        assert_eq!(FailureCategory::from_error("Out of disk space"), FailureCategory::Other);
+
+        let proton = SteamProton {
+            name: "GE-Proton10-25".to_string(),
+            config_name: "GE-Proton10-25".to_string(),
+            path: PathBuf::from("/home/someone/.steam/compatibilitytools.d/GE-Proton10-25"),
+            is_steam_proton: false,
+            is_experimental: false,
+        };
+        let report = FailureReport::new("Failed to extract /home/someone/MO2.7z", &proton);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"category\":\"extract\""));
+        assert!(json.contains("\"proton_major\":10"));
+        assert!(!json.contains("someone"));
+    }
+}
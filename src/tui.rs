@@ -0,0 +1,504 @@
+//! Terminal UI (`nak --tui`)
+//!
+//! Keyboard-driven front end for SSH sessions and desktop-less Steam Decks.
+//! Covers the core flows (Proton versions, MO2 install, prefix management)
+//! over the same library API as the GUI, with progress reported through
+//! `TaskContext`.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use nak_rust::config::ManagedPrefixes;
+use nak_rust::installers::{install_mo2, regenerate_all_prefix_scripts, regenerate_nak_tools_scripts, TaskContext};
+use nak_rust::logging::{log_action, log_info, set_console_logging};
+use nak_rust::steam::{find_steam_protons, SteamProton};
+
+/// Lines of task log kept for the progress screen
+const MAX_LOG_LINES: usize = 200;
+
+const MENU_ITEMS: &[&str] = &["Proton versions", "Install MO2", "Manage prefixes", "Quit"];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Screen {
+    Menu,
+    Protons,
+    Install,
+    Prefixes,
+    Task,
+}
+
+/// Which field of the install form has focus
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum InstallField {
+    Name,
+    Path,
+}
+
+/// Background task state, filled in by the `TaskContext` callbacks
+#[derive(Default)]
+struct TaskState {
+    title: String,
+    status: String,
+    progress: f32,
+    log: Vec<String>,
+    /// Set once the task has finished
+    outcome: Option<Result<String, String>>,
+}
+
+struct Tui {
+    screen: Screen,
+    menu: ListState,
+    protons: Vec<SteamProton>,
+    proton_list: ListState,
+    /// Proton used for installs and script updates
+    selected_proton: usize,
+    install_name: String,
+    install_path: String,
+    install_field: InstallField,
+    prefixes: ManagedPrefixes,
+    prefix_list: ListState,
+    /// One-line feedback shown in the footer
+    message: String,
+    task: Arc<Mutex<TaskState>>,
+    cancel: Arc<AtomicBool>,
+    quit: bool,
+}
+
+/// Run the terminal UI until the user quits
+pub fn run_tui() -> Result<(), Box<dyn std::error::Error>> {
+    log_info("NaK TUI starting...");
+    // Log lines on stdout would draw over the UI; they still go to the log file
+    set_console_logging(false);
+
+    let mut terminal = ratatui::init();
+    let result = Tui::new().run(&mut terminal);
+    ratatui::restore();
+
+    set_console_logging(true);
+    result
+}
+
+impl Tui {
+    fn new() -> Self {
+        let protons = find_steam_protons();
+        let default_path = dirs::home_dir()
+            .map(|h| h.join("Modding/MO2").display().to_string())
+            .unwrap_or_default();
+
+        Self {
+            screen: Screen::Menu,
+            menu: ListState::default().with_selected(Some(0)),
+            proton_list: ListState::default().with_selected((!protons.is_empty()).then_some(0)),
+            protons,
+            selected_proton: 0,
+            install_name: "Mod Organizer 2".to_string(),
+            install_path: default_path,
+            install_field: InstallField::Name,
+            prefixes: ManagedPrefixes::load(),
+            prefix_list: ListState::default(),
+            message: String::new(),
+            task: Arc::new(Mutex::new(TaskState::default())),
+            cancel: Arc::new(AtomicBool::new(false)),
+            quit: false,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Box<dyn std::error::Error>> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Poll so the progress screen keeps updating while a task runs
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key.code);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn task_running(&self) -> bool {
+        let task = self.task.lock();
+        !task.title.is_empty() && task.outcome.is_none()
+    }
+
+    fn selected_proton(&self) -> Option<&SteamProton> {
+        self.protons.get(self.selected_proton)
+    }
+
+    // ========================================================================
+    // Input
+    // ========================================================================
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match self.screen {
+            Screen::Menu => self.menu_key(code),
+            Screen::Protons => self.protons_key(code),
+            Screen::Install => self.install_key(code),
+            Screen::Prefixes => self.prefixes_key(code),
+            Screen::Task => self.task_key(code),
+        }
+    }
+
+    fn menu_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.menu.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.menu.select_next(),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Enter => {
+                self.message.clear();
+                match self.menu.selected().unwrap_or(0) {
+                    0 => self.screen = Screen::Protons,
+                    1 => self.screen = Screen::Install,
+                    2 => {
+                        self.prefixes = ManagedPrefixes::load();
+                        let first = (!self.prefixes.prefixes.is_empty()).then_some(0);
+                        self.prefix_list.select(first);
+                        self.screen = Screen::Prefixes;
+                    }
+                    _ => self.quit = true,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn protons_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.proton_list.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.proton_list.select_next(),
+            KeyCode::Enter => {
+                if let Some(idx) = self.proton_list.selected().filter(|i| *i < self.protons.len()) {
+                    self.selected_proton = idx;
+                    self.message = format!("Using {}", self.protons[idx].name);
+                }
+            }
+            KeyCode::Char('r') => {
+                self.protons = find_steam_protons();
+                self.selected_proton = 0;
+                self.proton_list.select((!self.protons.is_empty()).then_some(0));
+                self.message = format!("Found {} Proton versions", self.protons.len());
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.screen = Screen::Menu,
+            _ => {}
+        }
+    }
+
+    fn install_key(&mut self, code: KeyCode) {
+        let field = match self.install_field {
+            InstallField::Name => &mut self.install_name,
+            InstallField::Path => &mut self.install_path,
+        };
+        match code {
+            KeyCode::Char(c) => field.push(c),
+            KeyCode::Backspace => {
+                field.pop();
+            }
+            KeyCode::Tab | KeyCode::Up | KeyCode::Down => {
+                self.install_field = match self.install_field {
+                    InstallField::Name => InstallField::Path,
+                    InstallField::Path => InstallField::Name,
+                };
+            }
+            KeyCode::Enter => self.start_mo2_install(),
+            KeyCode::Esc => self.screen = Screen::Menu,
+            _ => {}
+        }
+    }
+
+    fn prefixes_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.prefix_list.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.prefix_list.select_next(),
+            KeyCode::Char('u') => self.update_selected_scripts(),
+            KeyCode::Char('a') => self.update_all_scripts(),
+            KeyCode::Char('r') => {
+                self.prefixes = ManagedPrefixes::load();
+                self.message = format!("{} managed prefixes", self.prefixes.prefixes.len());
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.screen = Screen::Menu,
+            _ => {}
+        }
+    }
+
+    fn task_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('c') if self.task_running() => {
+                self.cancel.store(true, Ordering::SeqCst);
+                self.task.lock().status = "Cancelling...".to_string();
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') if !self.task_running() => {
+                self.screen = Screen::Menu;
+            }
+            _ => {}
+        }
+    }
+
+    // ========================================================================
+    // Actions
+    // ========================================================================
+
+    /// Task context that feeds the progress screen
+    fn task_context(&mut self, title: &str) -> TaskContext {
+        *self.task.lock() = TaskState { title: title.to_string(), ..Default::default() };
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.screen = Screen::Task;
+
+        let status = self.task.clone();
+        let log = self.task.clone();
+        let progress = self.task.clone();
+        TaskContext::new(
+            move |msg| status.lock().status = msg,
+            move |msg| {
+                let mut task = log.lock();
+                task.log.push(msg);
+                if task.log.len() > MAX_LOG_LINES {
+                    task.log.remove(0);
+                }
+            },
+            move |p| progress.lock().progress = p,
+            self.cancel.clone(),
+        )
+    }
+
+    fn start_mo2_install(&mut self) {
+        let name = self.install_name.trim().to_string();
+        let path = self.install_path.trim().to_string();
+        if name.is_empty() || path.is_empty() {
+            self.message = "Enter a name and an install path".to_string();
+            return;
+        }
+        let Some(proton) = self.selected_proton().cloned() else {
+            self.message = "No Proton 10+ found. Install Proton Experimental or GE-Proton10+".to_string();
+            return;
+        };
+        let install_path = match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|h| h.join(rest)).unwrap_or_else(|| PathBuf::from(&path)),
+            None => PathBuf::from(&path),
+        };
+
+        log_action(&format!("TUI: Install MO2 '{}' to {:?} with {}", name, install_path, proton.name));
+        let ctx = self.task_context(&format!("Installing MO2: {}", name));
+        let task = self.task.clone();
+        thread::spawn(move || {
+            let outcome = install_mo2(&name, install_path, &proton, ctx, false, false)
+                .map(|r| {
+                    if nak_rust::steam::restart_steam_after_install() {
+                        format!("Installed! AppID {}. Steam was restarted to show it.", r.app_id)
+                    } else {
+                        format!("Installed! AppID {}. Restart Steam to see it.", r.app_id)
+                    }
+                })
+                .map_err(|e| e.to_string());
+            task.lock().outcome = Some(outcome);
+        });
+    }
+
+    fn update_selected_scripts(&mut self) {
+        let Some(prefix) = self.prefix_list.selected().and_then(|i| self.prefixes.prefixes.get(i)) else {
+            return;
+        };
+        let proton = prefix
+            .proton_config_name
+            .as_deref()
+            .and_then(|name| self.protons.iter().find(|p| p.config_name == name))
+            .or_else(|| self.selected_proton());
+        let Some(proton) = proton else {
+            self.message = "No Proton available".to_string();
+            return;
+        };
+
+        log_action(&format!("TUI: Update scripts for {}", prefix.name));
+        let message = match regenerate_nak_tools_scripts(
+            &prefix.manager_type,
+            std::path::Path::new(&prefix.install_path),
+            std::path::Path::new(&prefix.prefix_path),
+            prefix.app_id,
+            &proton.path,
+        ) {
+            Ok(_) => {
+                ManagedPrefixes::update_proton(prefix.app_id, &proton.config_name);
+                format!("Updated scripts for {} using {}", prefix.name, proton.name)
+            }
+            Err(e) => format!("Failed to update scripts: {}", e),
+        };
+        self.message = message;
+    }
+
+    fn update_all_scripts(&mut self) {
+        log_action("TUI: Update all prefix scripts");
+        let protons = self.protons.clone();
+        let ctx = self.task_context("Updating all prefix scripts");
+        let task = self.task.clone();
+        thread::spawn(move || {
+            let reports = regenerate_all_prefix_scripts(&protons);
+            for report in &reports {
+                ctx.log(report.summary());
+            }
+            let failed = reports.iter().filter(|r| r.result.is_err()).count();
+            task.lock().outcome = Some(if failed == 0 {
+                Ok(format!("Checked {} prefixes", reports.len()))
+            } else {
+                Err(format!("{} of {} prefixes failed", failed, reports.len()))
+            });
+        });
+    }
+
+    // ========================================================================
+    // Drawing
+    // ========================================================================
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [body, footer] = Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).areas(frame.area());
+
+        let hint = match self.screen {
+            Screen::Menu => "Up/Down: move  Enter: open  q: quit",
+            Screen::Protons => "Enter: use for installs  r: rescan  Esc: back",
+            Screen::Install => "Tab: next field  Enter: install  Esc: back",
+            Screen::Prefixes => "u: update scripts  a: update all  r: reload  Esc: back",
+            Screen::Task if self.task_running() => "c: cancel",
+            Screen::Task => "Enter: back to menu",
+        };
+        let footer_text = if self.message.is_empty() {
+            hint.to_string()
+        } else {
+            format!("{}  |  {}", self.message, hint)
+        };
+        frame.render_widget(Paragraph::new(footer_text).block(Block::default().borders(Borders::ALL)), footer);
+
+        match self.screen {
+            Screen::Menu => self.draw_menu(frame, body),
+            Screen::Protons => self.draw_protons(frame, body),
+            Screen::Install => self.draw_install(frame, body),
+            Screen::Prefixes => self.draw_prefixes(frame, body),
+            Screen::Task => self.draw_task(frame, body),
+        }
+    }
+
+    fn draw_menu(&mut self, frame: &mut Frame, area: Rect) {
+        let proton = self.selected_proton().map(|p| p.name.as_str()).unwrap_or("none found");
+        let title = format!(" NaK {} - Proton: {} ", env!("CARGO_PKG_VERSION"), proton);
+        let items: Vec<ListItem> = MENU_ITEMS.iter().map(|i| ListItem::new(*i)).collect();
+        frame.render_stateful_widget(selectable_list(items, &title), area, &mut self.menu);
+    }
+
+    fn draw_protons(&mut self, frame: &mut Frame, area: Rect) {
+        if self.protons.is_empty() {
+            let text = "No compatible Proton versions found.\nNaK requires Proton 10 or newer (GE-Proton10+, Proton Experimental, etc.)";
+            frame.render_widget(Paragraph::new(text).block(titled(" Proton versions ")), area);
+            return;
+        }
+        let items: Vec<ListItem> = self
+            .protons
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let marker = if i == self.selected_proton { "*" } else { " " };
+                let source = if p.is_steam_proton { "Steam" } else { "Custom" };
+                let experimental = if p.is_experimental { " [Experimental]" } else { "" };
+                ListItem::new(format!("{} {} ({}){}", marker, p.name, source, experimental))
+            })
+            .collect();
+        frame.render_stateful_widget(selectable_list(items, " Proton versions "), area, &mut self.proton_list);
+    }
+
+    fn draw_install(&mut self, frame: &mut Frame, area: Rect) {
+        let block = titled(" Install MO2 ");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [name, path, info] = Layout::vertical([Constraint::Length(3), Constraint::Length(3), Constraint::Min(0)])
+            .areas(inner);
+        let field = |text: &str, title: &'static str, focused: bool| {
+            let style = if focused { Style::default().fg(Color::Cyan) } else { Style::default() };
+            let cursor = if focused { "_" } else { "" };
+            Paragraph::new(format!("{}{}", text, cursor))
+                .block(Block::default().borders(Borders::ALL).title(title).border_style(style))
+        };
+        frame.render_widget(field(&self.install_name, " Instance name ", self.install_field == InstallField::Name), name);
+        frame.render_widget(field(&self.install_path, " Install path ", self.install_field == InstallField::Path), path);
+
+        let proton = self.selected_proton().map(|p| p.name.as_str()).unwrap_or("none found");
+        let text = format!(
+            "Proton: {} (change it under Proton versions)\n\nDownloads the latest MO2, creates a Steam shortcut and prefix, and installs the dependencies.",
+            proton
+        );
+        frame.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }), info);
+    }
+
+    fn draw_prefixes(&mut self, frame: &mut Frame, area: Rect) {
+        if self.prefixes.prefixes.is_empty() {
+            frame.render_widget(Paragraph::new("No managed prefixes yet.").block(titled(" Prefixes ")), area);
+            return;
+        }
+        let items: Vec<ListItem> = self
+            .prefixes
+            .prefixes
+            .iter()
+            .map(|p| {
+                let missing = if std::path::Path::new(&p.prefix_path).exists() { "" } else { " [prefix missing]" };
+                ListItem::new(vec![
+                    Line::from(format!("{} ({}, AppID {}){}", p.name, p.manager_type, p.app_id, missing)),
+                    Line::from(format!("  {}", p.install_path)).style(Style::default().fg(Color::DarkGray)),
+                ])
+            })
+            .collect();
+        frame.render_stateful_widget(selectable_list(items, " Prefixes "), area, &mut self.prefix_list);
+    }
+
+    fn draw_task(&mut self, frame: &mut Frame, area: Rect) {
+        let task = self.task.lock();
+        let block = titled(" Task ");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [title, gauge, status, log] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Min(0),
+        ])
+        .areas(inner);
+
+        frame.render_widget(Paragraph::new(task.title.as_str()).style(Style::default().add_modifier(Modifier::BOLD)), title);
+        frame.render_widget(
+            Gauge::default().gauge_style(Style::default().fg(Color::Cyan)).ratio(task.progress.clamp(0.0, 1.0) as f64),
+            gauge,
+        );
+        let (status_text, status_style) = match &task.outcome {
+            Some(Ok(msg)) => (msg.as_str(), Style::default().fg(Color::Green)),
+            Some(Err(msg)) => (msg.as_str(), Style::default().fg(Color::Red)),
+            None => (task.status.as_str(), Style::default()),
+        };
+        frame.render_widget(Paragraph::new(status_text).style(status_style).wrap(Wrap { trim: true }), status);
+
+        let visible = log.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = task.log.iter().skip(task.log.len().saturating_sub(visible)).map(|l| Line::from(l.as_str())).collect();
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::TOP).title(" Log ")), log);
+    }
+}
+
+fn titled(title: &str) -> Block<'_> {
+    Block::default().borders(Borders::ALL).title(title)
+}
+
+fn selectable_list<'a>(items: Vec<ListItem<'a>>, title: &'a str) -> List<'a> {
+    List::new(items)
+        .block(titled(title))
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+        .highlight_symbol("> ")
+}
@@ -9,16 +9,18 @@ use std::time::Duration;
 use std::rc::Rc;
 use std::cell::RefCell;
 
-use slint::{ComponentHandle, ModelRc, SharedString, VecModel};
+use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel};
 
 use crate::app::{InstallWizard, MyApp, Page, WizardStep};
-use nak_rust::config::ManagedPrefixes;
+use nak_rust::config::{ManagedPrefix, ManagedPrefixes};
 use nak_rust::installers::{
-    apply_dpi, get_available_disk_space, install_mo2, kill_wineserver,
-    launch_dpi_test_app, setup_existing_mo2, TaskContext, MIN_REQUIRED_DISK_SPACE_GB,
+    apply_dpi, estimate_configured_deps_size, get_available_disk_space, install_mo2, kill_wineserver,
+    launch_dpi_test_app, setup_existing_mo2, DepsMarker, TaskContext, MIN_REQUIRED_DISK_SPACE_GB,
 };
 use nak_rust::logging::{log_action, log_error, log_info, log_warning};
-use nak_rust::steam::{get_steam_accounts, ShortcutsVdf};
+use nak_rust::nxm::NxmHandler;
+use nak_rust::steam::{get_steam_accounts, LaunchOptionsUpdate, ShortcutsVdf, SteamProton};
+use nak_rust::tasks::TaskQueue;
 
 // Include the generated Slint code
 slint::include_modules!();
@@ -60,32 +62,405 @@ fn wizard_step_to_int(step: WizardStep) -> i32 {
 }
 
 /// Build prefix info for Slint
-fn build_prefix_info(app: &MyApp) -> ModelRc<PrefixInfo> {
+/// Prefix manager row built off the UI thread (PrefixInfo holds Slint types)
+pub(crate) struct PrefixRow {
+    name: String,
+    app_id: u32,
+    prefix_path: String,
+    manager_type: String,
+    is_active: bool,
+    prefix_exists: bool,
+    proton_name: String,
+    dxvk_version: String,
+    warning: String,
+    is_electron: bool,
+    has_electron_flags: bool,
+    windows_version_index: i32,
+    launch_args: String,
+    disk_usage: String,
+    native_wayland: bool,
+    sync_mode_index: i32,
+    locale_index: i32,
+    proton_mismatch: String,
+    instance_conflict: String,
+    global_instance: String,
+    game_id_index: i32,
+    detected_game: String,
+    launch_toggles: nak_rust::config::LaunchToggles,
+    launch_options: String,
+}
+
+/// Read managed prefixes, Steam shortcuts and prefix health (filesystem heavy)
+fn scan_prefix_rows(protons: &[SteamProton]) -> Vec<PrefixRow> {
     let managed = ManagedPrefixes::load();
     let active_app_ids = get_active_shortcut_app_ids();
+    let shortcuts = ShortcutsVdf::load().unwrap_or_default();
 
-    let prefixes: Vec<PrefixInfo> = managed.prefixes.iter().map(|prefix| {
+    managed.prefixes.iter().map(|prefix| {
         let is_active = active_app_ids.contains(&prefix.app_id);
+        let shortcut = shortcuts.shortcuts.iter().find(|s| s.appid == prefix.app_id);
+        let is_electron = shortcut
+            .map(|s| nak_rust::steam::is_electron_executable(std::path::Path::new(s.exe.trim_matches('"'))))
+            .unwrap_or(false);
+        let warning = if std::path::Path::new(&prefix.install_path).exists() {
+            prefix_health_warning(std::path::Path::new(&prefix.prefix_path))
+        } else {
+            format!(
+                "Mod manager folder not found at {}. If you moved it, use Relocate Install.",
+                prefix.install_path
+            )
+        };
+        let has_electron_flags = shortcut
+            .map(|s| s.launch_options.contains("--no-sandbox"))
+            .unwrap_or(false);
         let prefix_exists = std::path::Path::new(&prefix.prefix_path).exists();
-        let proton_name = prefix.proton_config_name.as_deref()
-            .and_then(|name| app.steam_protons.iter().find(|p| p.config_name == name))
+        let proton = prefix.proton_config_name.as_deref()
+            .and_then(|name| protons.iter().find(|p| p.config_name == name));
+        let proton_name = proton
             .map(|p| p.name.clone())
             .unwrap_or_else(|| "Unknown".to_string());
-
-        PrefixInfo {
-            name: prefix.name.clone().into(),
-            app_id: prefix.app_id.to_string().into(),
-            prefix_path: prefix.prefix_path.clone().into(),
-            manager_type: format!("{}", prefix.manager_type).into(),
+        let proton_mismatch = proton
+            .and_then(|p| nak_rust::installers::prefix_version_mismatch(std::path::Path::new(&prefix.prefix_path), p))
+            .map(|m| format!(
+                "Prefix was last updated by {} but this instance uses {}. Upgrade it before launching.",
+                m.prefix_build, m.proton_build
+            ))
+            .unwrap_or_default();
+        let (instance_conflict, global_instance) = if prefix.manager_type == nak_rust::config::ManagerType::MO2 {
+            let prefix_path = std::path::Path::new(&prefix.prefix_path);
+            let install_path = std::path::Path::new(&prefix.install_path);
+            (
+                nak_rust::installers::detect_mo2_instance_conflict(prefix_path, install_path)
+                    .map(|conflict| conflict.warning().to_string())
+                    .unwrap_or_default(),
+                nak_rust::installers::check_mo2_global_instance(prefix_path, install_path)
+                    .warning()
+                    .unwrap_or_default(),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+        let windows_version_index = prefix.windows_version.as_deref()
+            .and_then(|v| nak_rust::installers::WINDOWS_VERSIONS.iter().position(|(code, _)| *code == v))
+            .unwrap_or(0) as i32;
+
+        let sync_mode_index = prefix.sync_mode.as_deref()
+            .and_then(|mode| nak_rust::steam::available_sync_modes().iter().position(|(code, _, _)| *code == mode))
+            .unwrap_or(0) as i32;
+
+        let locale_index = nak_rust::steam::PREFIX_LOCALES
+            .iter()
+            .position(|(locale, _)| *locale == prefix.locale.as_deref().unwrap_or(""))
+            .unwrap_or(0) as i32;
+
+        let game_id_index = game_id_choices()
+            .iter()
+            .position(|(id, _)| *id == prefix.launch_toggles.game_id)
+            .unwrap_or(0) as i32;
+        let detected_game = prefix.associated_game()
+            .map(|game| format!("{} ({})", game.name, game.steam_app_id))
+            .unwrap_or_default();
+
+        PrefixRow {
+            name: prefix.name.clone(),
+            app_id: prefix.app_id,
+            prefix_path: prefix.prefix_path.clone(),
+            manager_type: format!("{}", prefix.manager_type),
             is_active,
             prefix_exists,
-            proton_name: proton_name.into(),
+            proton_name,
+            dxvk_version: prefix.dxvk_version.clone().unwrap_or_default(),
+            warning,
+            is_electron,
+            has_electron_flags,
+            windows_version_index,
+            launch_args: prefix.launch_args.clone().unwrap_or_default(),
+            disk_usage: prefix.cached_disk_usage().map(|u| u.summary()).unwrap_or_default(),
+            native_wayland: prefix.native_wayland,
+            sync_mode_index,
+            locale_index,
+            proton_mismatch,
+            instance_conflict,
+            global_instance,
+            game_id_index,
+            detected_game,
+            launch_toggles: prefix.launch_toggles.clone(),
+            launch_options: shortcut.map(|s| s.launch_options.clone()).unwrap_or_default(),
         }
+    }).collect()
+}
+
+/// protonfixes game id choices: automatic, none, then every known game
+fn game_id_choices() -> Vec<(Option<u32>, String)> {
+    let mut choices = vec![
+        (None, "Auto (detected game)".to_string()),
+        (Some(0), "None (non-steam-game)".to_string()),
+    ];
+    choices.extend(nak_rust::game_finder::KNOWN_GAMES.iter().filter_map(|game| {
+        let id = game.steam_app_id.parse().ok()?;
+        Some((Some(id), format!("{} ({})", game.name, id)))
+    }));
+    choices
+}
+
+fn prefix_rows_to_model(rows: Vec<PrefixRow>) -> ModelRc<PrefixInfo> {
+    let prefixes: Vec<PrefixInfo> = rows.into_iter().map(|row| PrefixInfo {
+        name: row.name.into(),
+        app_id: row.app_id.to_string().into(),
+        prefix_path: row.prefix_path.into(),
+        manager_type: row.manager_type.into(),
+        is_active: row.is_active,
+        prefix_exists: row.prefix_exists,
+        proton_name: row.proton_name.into(),
+        dxvk_version: row.dxvk_version.into(),
+        warning: row.warning.into(),
+        is_electron: row.is_electron,
+        has_electron_flags: row.has_electron_flags,
+        windows_version_index: row.windows_version_index,
+        launch_args: row.launch_args.into(),
+        disk_usage: row.disk_usage.into(),
+        native_wayland: row.native_wayland,
+        sync_mode_index: row.sync_mode_index,
+        locale_index: row.locale_index,
+        proton_mismatch: row.proton_mismatch.into(),
+        instance_conflict: row.instance_conflict.into(),
+        global_instance: row.global_instance.into(),
+        game_id_index: row.game_id_index,
+        detected_game: row.detected_game.into(),
+        gamemode: row.launch_toggles.gamemode,
+        mangohud: row.launch_toggles.mangohud,
+        mangohud_preset: !row.launch_toggles.mangohud_config.is_empty(),
+        wined3d: row.launch_toggles.wined3d,
+        dll_overrides: row.launch_toggles.dll_overrides.into(),
+        gamescope: row.launch_toggles.gamescope.is_some(),
+        gamescope_width: row.launch_toggles.gamescope.unwrap_or(nak_rust::steam::DECK_RESOLUTION).0.to_string().into(),
+        gamescope_height: row.launch_toggles.gamescope.unwrap_or(nak_rust::steam::DECK_RESOLUTION).1.to_string().into(),
+        launch_log: row.launch_toggles.launch_log,
+        launch_options: row.launch_options.into(),
     }).collect();
 
     ModelRc::new(VecModel::from(prefixes))
 }
 
+/// Refresh the prefix list on a background thread
+///
+/// The poll timer picks up `prefix_scan_result`. If a scan is already running,
+/// it is flagged to run once more so the latest changes are always shown.
+fn request_prefix_scan(app: &MyApp) {
+    {
+        let mut scanning = app.is_scanning_prefixes.lock();
+        if *scanning {
+            *app.prefix_rescan_requested.lock() = true;
+            return;
+        }
+        *scanning = true;
+    }
+
+    let protons = app.steam_protons.clone();
+    let result = app.prefix_scan_result.clone();
+    let scanning = app.is_scanning_prefixes.clone();
+    let rescan = app.prefix_rescan_requested.clone();
+    let sizes = app.prefix_sizes_result.clone();
+    thread::spawn(move || {
+        loop {
+            *rescan.lock() = false;
+            let rows = scan_prefix_rows(&protons);
+            let missing_sizes = rows.iter().any(|row| row.disk_usage.is_empty());
+            *result.lock() = Some(rows);
+            // Sizes take a while on big mod lists, so they follow the rows
+            if missing_sizes {
+                *sizes.lock() = Some(scan_prefix_sizes());
+            }
+            // Hold the scanning lock while checking so a new request can't slip between
+            let mut scanning = scanning.lock();
+            if !*rescan.lock() {
+                *scanning = false;
+                break;
+            }
+        }
+    });
+}
+
+/// Disk usage summary per AppID (walks every prefix and install folder)
+fn scan_prefix_sizes() -> Vec<(u32, String)> {
+    ManagedPrefixes::load()
+        .prefixes
+        .iter()
+        .map(|prefix| (prefix.app_id, prefix.disk_usage().summary()))
+        .collect()
+}
+
+/// Check a prefix for known problems, returning a user-facing warning (empty if healthy)
+fn prefix_health_warning(prefix_path: &std::path::Path) -> String {
+    if !prefix_path.exists() {
+        return String::new();
+    }
+
+    if let Some(fs_type) = nak_rust::game_finder::windows_filesystem_for(prefix_path) {
+        return format!(
+            "Prefix is on a {} drive. Wine prefixes on NTFS/exFAT can corrupt - move your Steam library to ext4/btrfs.",
+            fs_type
+        );
+    }
+
+    // Only judge prefixes that got past Wine initialization
+    if prefix_path.join("drive_c/windows").exists() {
+        let missing = nak_rust::installers::scan_dotnet_install(prefix_path)
+            .missing_for(&nak_rust::installers::configured_dotnet_components());
+        if !missing.is_empty() {
+            return format!(
+                "Missing .NET runtimes ({}). MO2 plugins may fail - try Reset Prefix (keep mods).",
+                missing.join(", ")
+            );
+        }
+
+        // The usual cause of silent NPCs (only for prefixes NaK installed dependencies into)
+        let audio = nak_rust::installers::check_xaudio(prefix_path);
+        if prefix_path.join("winetricks.log").exists() && !audio.is_ok() {
+            return format!("XAudio/XACT incomplete ({}). NPC voices may be missing - try Fix Audio.", audio.summary());
+        }
+    }
+
+    // System-wide, but shown per prefix since it's where USVFS crashes get reported
+    static LIMIT_WARNINGS: std::sync::OnceLock<Vec<String>> = std::sync::OnceLock::new();
+    LIMIT_WARNINGS
+        .get_or_init(|| nak_rust::logging::ResourceLimits::detect().warnings())
+        .join("\n")
+}
+
+/// Index into `DOTNET_PRESETS` matching the configured .NET components
+///
+/// A list edited by hand that matches no preset is shown as an extra "Custom" entry.
+fn dotnet_preset_index(config: &nak_rust::config::AppConfig) -> Option<usize> {
+    if config.dotnet_components.is_empty() {
+        return Some(0);
+    }
+    nak_rust::installers::DOTNET_PRESETS.iter().position(|(_, _, components)| {
+        components.len() == config.dotnet_components.len()
+            && components.iter().all(|c| config.dotnet_components.iter().any(|d| d == c))
+    })
+}
+
+/// Fill the .NET preset picker and what the selection saves
+fn refresh_dotnet_preset(window: &MainWindow, config: &nak_rust::config::AppConfig) {
+    let mut options: Vec<SharedString> =
+        nak_rust::installers::DOTNET_PRESETS.iter().map(|(_, label, _)| SharedString::from(*label)).collect();
+    let index = dotnet_preset_index(config).unwrap_or_else(|| {
+        options.push(format!("Custom ({})", config.dotnet_components.join(", ")).into());
+        options.len() - 1
+    });
+    window.set_dotnet_preset_options(ModelRc::new(VecModel::from(options)));
+    window.set_dotnet_preset_index(index as i32);
+    let savings = nak_rust::installers::DotnetSavings::for_selection(&nak_rust::installers::configured_dotnet_components());
+    window.set_dotnet_savings(savings.summary().into());
+}
+
+/// Which proxy downloads use, for the Network setting
+fn proxy_status_text(configured: &str) -> String {
+    if !configured.trim().is_empty() {
+        return format!("Downloads go through {}", configured.trim());
+    }
+    match ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    {
+        Some(proxy) => format!("Using {} from the environment", proxy),
+        None => String::new(),
+    }
+}
+
+/// Detect Steam (and its Protons) again and update the window
+fn refresh_steam_detection(app_ref: &mut MyApp, window: &MainWindow) {
+    let steam_path = nak_rust::steam::detect_steam_path_checked();
+    let steam_detected = steam_path.is_some();
+    app_ref.steam_detected = steam_detected;
+    app_ref.steam_path = steam_path;
+    app_ref.steam_protons = nak_rust::steam::find_steam_protons();
+
+    window.set_steam_detected(steam_detected);
+    if let Some(ref p) = app_ref.steam_path {
+        window.set_steam_path(p.clone().into());
+    }
+    window.set_steam_install_hint(if steam_detected {
+        SharedString::new()
+    } else {
+        nak_rust::steam::steam_install_hint().into()
+    });
+    let proton_names: Vec<SharedString> = app_ref.steam_protons.iter()
+        .map(|p| SharedString::from(p.name.clone()))
+        .collect();
+    window.set_proton_options(ModelRc::new(VecModel::from(proton_names)));
+    measure_proton_sizes(&app_ref.steam_protons);
+    refresh_proton_picker(window, &app_ref.steam_protons);
+    request_prefix_scan(app_ref);
+}
+
+/// Walk the Protons' folders in the background so the picker can show their sizes
+fn measure_proton_sizes(protons: &[SteamProton]) {
+    let protons = protons.to_vec();
+    thread::spawn(move || {
+        for proton in &protons {
+            proton.install_size();
+        }
+    });
+}
+
+/// Fill the wizard's Proton picker with the Protons matching its filter
+///
+/// Each row shows the Proton's family, its size once measured, and whether
+/// Steam uses it as the default compatibility tool.
+fn refresh_proton_picker(window: &MainWindow, protons: &[SteamProton]) {
+    let filter = window.get_proton_filter().trim().to_lowercase();
+    let steam_default = nak_rust::steam::get_compat_tool(0);
+    let rows: Vec<ProtonRow> = protons
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.name.to_lowercase().contains(&filter) || p.kind().label().to_lowercase() == filter)
+        .map(|(idx, p)| {
+            let mut detail = vec![p.kind().label().to_string()];
+            if let Some(size) = p.cached_install_size() {
+                detail.push(ManagedPrefixes::format_size(size));
+            }
+            if steam_default.as_deref() == Some(p.config_name.as_str()) {
+                detail.push("Steam default".to_string());
+            }
+            ProtonRow {
+                name: p.name.clone().into(),
+                detail: detail.join(" · ").into(),
+                index: idx as i32,
+            }
+        })
+        .collect();
+    window.set_proton_rows(ModelRc::new(VecModel::from(rows)));
+}
+
+/// Find the Proton recorded for a prefix, falling back to the first available
+fn proton_for_prefix(app: &MyApp, prefix: &ManagedPrefix) -> Option<SteamProton> {
+    prefix.proton_config_name.as_deref()
+        .and_then(|name| app.steam_protons.iter().find(|p| p.config_name == name))
+        .or_else(|| app.steam_protons.first())
+        .cloned()
+}
+
+/// Report a finished background download with a desktop notification and an in-app toast
+fn notify_download_finished(toast: &Arc<Mutex<Option<(String, bool)>>>, result: &Result<String, String>) {
+    let (summary, message, is_error) = match result {
+        Ok(msg) => ("NaK: Download finished", msg, false),
+        Err(msg) => ("NaK: Download failed", msg, true),
+    };
+    nak_rust::utils::send_desktop_notification(summary, message, is_error);
+    *toast.lock() = Some((message.clone(), is_error));
+}
+
+/// Tail for a launch options status message: whether Steam still needs a restart
+fn steam_apply_hint(update: LaunchOptionsUpdate) -> &'static str {
+    match update {
+        LaunchOptionsUpdate::Updated { steam_restarted: true } => " Steam was restarted to apply it.",
+        _ if update.needs_steam_restart() => " Restart Steam to apply.",
+        _ => "",
+    }
+}
+
 /// Get all AppIDs currently in Steam's shortcuts.vdf
 fn get_active_shortcut_app_ids() -> std::collections::HashSet<u32> {
     let mut app_ids = std::collections::HashSet::new();
@@ -131,6 +506,8 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
         window.set_steam_detected(app_ref.steam_detected);
         if let Some(ref path) = app_ref.steam_path {
             window.set_steam_path(path.clone().into());
+        } else {
+            window.set_steam_install_hint(nak_rust::steam::steam_install_hint().into());
         }
 
         // Steam accounts
@@ -139,12 +516,20 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
             .map(|a| SharedString::from(a.persona_name.clone()))
             .collect();
         window.set_steam_accounts(ModelRc::new(VecModel::from(account_names)));
+        // Show the account shortcuts are written to (the selected one, else the most recent)
+        let active_account = nak_rust::steam::find_userdata_path()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()));
+        if let Some(idx) = accounts.iter().position(|a| Some(&a.account_id) == active_account.as_ref()) {
+            window.set_selected_account_index(idx as i32);
+        }
 
         // Proton options
         let proton_names: Vec<SharedString> = app_ref.steam_protons.iter()
             .map(|p| SharedString::from(p.name.clone()))
             .collect();
         window.set_proton_options(ModelRc::new(VecModel::from(proton_names)));
+        measure_proton_sizes(&app_ref.steam_protons);
+        refresh_proton_picker(&window, &app_ref.steam_protons);
 
         // Missing deps
         let missing = app_ref.missing_deps.lock();
@@ -155,10 +540,50 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
 
         // Migration popup
         window.set_show_migration_popup(app_ref.show_steam_migration_popup);
+        window.set_show_atomic_note(app_ref.show_atomic_distro_note);
+        if let Some(notes) = &app_ref.whats_new {
+            window.set_whats_new_text(notes.clone().into());
+            window.set_whats_new_visible(true);
+        }
         window.set_legacy_path(app_ref.config.get_data_path().to_string_lossy().to_string().into());
 
         // Prefixes
-        window.set_prefixes(build_prefix_info(&app_ref));
+        request_prefix_scan(&app_ref);
+        let windows_versions: Vec<SharedString> = nak_rust::installers::WINDOWS_VERSIONS
+            .iter()
+            .map(|(_, label)| SharedString::from(*label))
+            .collect();
+        window.set_windows_version_options(ModelRc::new(VecModel::from(windows_versions)));
+        window.set_nexus_key_set(NxmHandler::has_nexus_api_key());
+        window.set_wayland_session(nak_rust::steam::is_wayland_session());
+        let sync_modes: Vec<SharedString> = nak_rust::steam::available_sync_modes()
+            .iter()
+            .map(|(_, label, _)| SharedString::from(*label))
+            .collect();
+        window.set_sync_mode_options(ModelRc::new(VecModel::from(sync_modes)));
+        let locales: Vec<SharedString> = nak_rust::steam::PREFIX_LOCALES
+            .iter()
+            .map(|(locale, label)| if locale.is_empty() { SharedString::from(*label) } else { format!("{} ({})", label, locale).into() })
+            .collect();
+        window.set_locale_options(ModelRc::new(VecModel::from(locales)));
+        let game_ids: Vec<SharedString> = game_id_choices().into_iter().map(|(_, label)| label.into()).collect();
+        window.set_game_id_options(ModelRc::new(VecModel::from(game_ids)));
+        window.set_gamemode_available(nak_rust::steam::wrapper_available("gamemoderun"));
+        window.set_mangohud_available(nak_rust::steam::wrapper_available("mangohud"));
+        window.set_gamescope_available(nak_rust::steam::wrapper_available("gamescope"));
+        window.set_precache_summary(nak_rust::deps::precache::get_cache_status().summary().into());
+        window.set_deps_cache_dir(nak_rust::deps::precache::get_winetricks_cache_dir().display().to_string().into());
+        refresh_dotnet_preset(&window, &app_ref.config);
+        window.set_proxy_text(app_ref.config.http_proxy.clone().into());
+        window.set_proxy_status(proxy_status_text(&app_ref.config.http_proxy).into());
+        window.set_terminal_text(app_ref.config.terminal_command.clone().into());
+        window.set_telemetry_enabled(app_ref.config.telemetry_enabled);
+        window.set_telemetry_endpoint(app_ref.config.telemetry_endpoint.clone().into());
+        window.set_skip_dpi_setup(app_ref.config.skip_dpi_setup);
+        window.set_close_steam_for_changes(
+            app_ref.config.steam_running_policy == nak_rust::config::SteamRunningPolicy::CloseSteam,
+        );
+        window.set_restart_steam_after_changes(app_ref.config.restart_steam_after_changes);
     }
 
     // Setup navigation callback
@@ -171,8 +596,8 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
                 let rust_page = slint_to_page(page);
                 log_action(&format!("Navigate to {:?}", rust_page));
 
-                // Handle first run completion
-                if app_ref.current_page == Page::FirstRunSetup && rust_page == Page::GettingStarted {
+                // Handle first run completion (shown again next start until Steam is found)
+                if app_ref.current_page == Page::FirstRunSetup && rust_page == Page::GettingStarted && app_ref.steam_detected {
                     app_ref.config.first_run_completed = true;
                     app_ref.config.save();
                 }
@@ -211,6 +636,9 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
     // Marketplace callbacks
     setup_marketplace_callbacks(&window, &app);
 
+    // Background task list callbacks
+    setup_task_callbacks(&window);
+
     // Settings/Prefix callbacks
     setup_settings_callbacks(&window, &app);
 
@@ -235,21 +663,8 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
                         log_info(&format!("Custom Steam path set to: {}", path.display()));
 
                         // Re-detect Steam with the new custom path
-                        let steam_path = nak_rust::steam::detect_steam_path_checked();
-                        let steam_detected = steam_path.is_some();
-                        app_ref.steam_detected = steam_detected;
-                        app_ref.steam_path = steam_path;
-                        app_ref.steam_protons = nak_rust::steam::find_steam_protons();
-
                         if let Some(window) = window_weak.upgrade() {
-                            window.set_steam_detected(steam_detected);
-                            if let Some(ref p) = app_ref.steam_path {
-                                window.set_steam_path(p.clone().into());
-                            }
-                            let proton_names: Vec<SharedString> = app_ref.steam_protons.iter()
-                                .map(|p| SharedString::from(p.name.clone()))
-                                .collect();
-                            window.set_proton_options(ModelRc::new(VecModel::from(proton_names)));
+                            refresh_steam_detection(&mut app_ref, &window);
                         }
                     }
                 } else {
@@ -262,19 +677,34 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
         });
     }
 
+    // Look for Steam again (after the user installed or first started it)
+    {
+        let app_weak = Rc::downgrade(&app);
+        let window_weak = window.as_weak();
+        window.on_recheck_steam(move || {
+            log_action("Recheck Steam clicked");
+            if let (Some(app_rc), Some(window)) = (app_weak.upgrade(), window_weak.upgrade()) {
+                refresh_steam_detection(&mut app_rc.borrow_mut(), &window);
+            }
+        });
+    }
+
     // Setup polling timer for state synchronization (100ms)
     let app_poll = Rc::clone(&app);
     let window_weak = window.as_weak();
+    let toast_expires: std::cell::Cell<Option<std::time::Instant>> = std::cell::Cell::new(None);
+    let shown_tasks: RefCell<Vec<TaskRow>> = RefCell::new(Vec::new());
     let timer = slint::Timer::default();
     timer.start(slint::TimerMode::Repeated, Duration::from_millis(100), move || {
         if let Some(window) = window_weak.upgrade() {
             // First pass: read installation state
-            let (is_installing, status, progress, current_step);
+            let (is_installing, status, progress, phase, current_step);
             {
                 let app_ref = app_poll.borrow();
                 is_installing = *app_ref.is_installing_manager.lock();
                 status = app_ref.install_status.lock().clone();
                 progress = *app_ref.install_progress.lock();
+                phase = *app_ref.install_phase.lock();
                 current_step = app_ref.install_wizard.step;
             }
 
@@ -282,6 +712,8 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
             window.set_is_installing(is_installing);
             window.set_install_status(status.clone().into());
             window.set_install_progress(progress);
+            let phase_text = phase.map(|p| format!("{} ({:.0}%)", p, progress * 100.0)).unwrap_or_default();
+            window.set_install_phase(phase_text.into());
             window.set_wizard_step(wizard_step_to_int(current_step));
 
             // Check for install completion state changes (needs mutable access)
@@ -301,9 +733,16 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
                         let prefix_path_result = app_mut.install_result_prefix_path.lock().clone();
                         app_mut.install_wizard.installed_app_id = app_id_result;
                         app_mut.install_wizard.installed_prefix_path = prefix_path_result;
-                        // Move to DPI setup
-                        app_mut.install_wizard.step = WizardStep::DpiSetup;
-                        window.set_wizard_step(4);
+                        if app_mut.config.skip_dpi_setup {
+                            log_info("Skipping DPI setup (default 96 DPI)");
+                            app_mut.install_wizard.selected_dpi = 96;
+                            handle_confirm_dpi(&mut app_mut);
+                            window.set_wizard_step(5);
+                        } else {
+                            // Move to DPI setup
+                            app_mut.install_wizard.step = WizardStep::DpiSetup;
+                            window.set_wizard_step(4);
+                        }
                     }
                 }
             }
@@ -329,6 +768,103 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
                 }
             }
 
+            // Prefix manager background action results
+            {
+                let (status, result) = {
+                    let app_ref = app_poll.borrow();
+                    let status = app_ref.prefix_action_status.lock().clone();
+                    let result = app_ref.prefix_action_result.lock().take();
+                    (status, result)
+                };
+                window.set_prefix_status(status.into());
+
+                let precache = app_poll.borrow().precache_task.and_then(|id| TaskQueue::global().get(id));
+                let precaching = precache.as_ref().is_some_and(|t| !t.state.is_finished());
+                let precache_progress = precache.map(|t| t.progress).unwrap_or(0.0);
+                if window.get_precache_running() && !precaching {
+                    window.set_precache_summary(nak_rust::deps::precache::get_cache_status().summary().into());
+                }
+                window.set_precache_running(precaching);
+                window.set_precache_progress(precache_progress);
+
+                if let Some(result) = result {
+                    match result {
+                        Ok(msg) => log_info(&msg),
+                        Err(e) => log_error(&e),
+                    }
+                    request_prefix_scan(&app_poll.borrow());
+                }
+            }
+
+            // Background prefix scan results
+            {
+                let (rows, scanning) = {
+                    let app_ref = app_poll.borrow();
+                    let rows = app_ref.prefix_scan_result.lock().take();
+                    let scanning = *app_ref.is_scanning_prefixes.lock();
+                    (rows, scanning)
+                };
+                if let Some(rows) = rows {
+                    window.set_prefixes(prefix_rows_to_model(rows));
+                }
+                window.set_prefixes_scanning(scanning);
+
+                let sizes = app_poll.borrow().prefix_sizes_result.lock().take();
+                if let Some(sizes) = sizes {
+                    let model = window.get_prefixes();
+                    for i in 0..model.row_count() {
+                        let Some(mut info) = model.row_data(i) else { continue };
+                        if let Some((_, usage)) = sizes.iter().find(|(id, _)| info.app_id == id.to_string().as_str()) {
+                            info.disk_usage = usage.as_str().into();
+                            model.set_row_data(i, info);
+                        }
+                    }
+                }
+            }
+
+            // Background task list (only pushed to the UI when it changes)
+            {
+                let rows: Vec<TaskRow> = TaskQueue::global()
+                    .snapshot()
+                    .into_iter()
+                    .map(|task| TaskRow {
+                        id: task.id as i32,
+                        label: task.label.into(),
+                        status: task.status.into(),
+                        progress: task.progress,
+                        state: task.state.as_str().into(),
+                        pausable: task.pausable,
+                        paused: task.paused,
+                    })
+                    .collect();
+                if *shown_tasks.borrow() != rows {
+                    window.set_tasks(ModelRc::new(VecModel::from(rows.clone())));
+                    *shown_tasks.borrow_mut() = rows;
+                }
+            }
+
+            // Reports from background checks
+            {
+                let report = app_poll.borrow().report_result.lock().take();
+                if let Some((title, text)) = report {
+                    window.set_report_title(title.into());
+                    window.set_report_text(text.into());
+                }
+            }
+
+            // Download toasts (shown for 6 seconds)
+            {
+                let toast = app_poll.borrow().toast.lock().take();
+                if let Some((message, is_error)) = toast {
+                    window.set_toast_message(message.into());
+                    window.set_toast_is_error(is_error);
+                    toast_expires.set(Some(std::time::Instant::now() + Duration::from_secs(6)));
+                } else if toast_expires.get().is_some_and(|t| std::time::Instant::now() >= t) {
+                    window.set_toast_message("".into());
+                    toast_expires.set(None);
+                }
+            }
+
             // Third pass: marketplace async results
             // Take all results with a single short-lived borrow, then process
             let (registry_result, detail_result, install_result) = {
@@ -348,17 +884,28 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
                         let descs: Vec<SharedString> = registry.plugins.iter()
                             .map(|p| SharedString::from(p.description.clone()))
                             .collect();
+                        let sources: Vec<SharedString> = registry.plugins.iter()
+                            .map(|p| SharedString::from(p.source_label()))
+                            .collect();
+                        let warning = if registry.skipped.is_empty() {
+                            String::new()
+                        } else {
+                            format!("Some plugin registries were skipped:\n{}", registry.skipped.join("\n"))
+                        };
 
                         window.set_plugin_names(ModelRc::new(VecModel::from(names)));
                         window.set_plugin_descriptions(ModelRc::new(VecModel::from(descs)));
+                        window.set_plugin_sources(ModelRc::new(VecModel::from(sources)));
                         window.set_marketplace_loading(false);
                         window.set_marketplace_error("".into());
+                        window.set_marketplace_warning(warning.into());
 
                         // Reset detail properties when registry refreshes
                         window.set_selected_plugin_index(-1);
                         window.set_plugin_detail_author("".into());
                         window.set_plugin_detail_version("".into());
                         window.set_plugin_detail_compatible(false);
+                        window.set_plugin_detail_dll_drop(false);
 
                         if let Ok(mut app_mut) = app_poll.try_borrow_mut() {
                             if app_mut.marketplace_state.is_none() {
@@ -388,6 +935,18 @@ pub fn run_slint_ui(app: Rc<RefCell<MyApp>>) -> Result<(), slint::PlatformError>
                         window.set_plugin_detail_author(manifest.plugin.author.clone().into());
                         window.set_plugin_detail_version(manifest.plugin.min_nak_version.clone().into());
                         window.set_plugin_detail_compatible(compatible);
+                        let dll_drop = nak_rust::marketplace::get_plugin_install_type(&manifest)
+                            == Ok(nak_rust::marketplace::PluginInstallType::DllDrop);
+                        window.set_plugin_detail_dll_drop(dll_drop);
+                        if dll_drop {
+                            let names: Vec<SharedString> = ManagedPrefixes::load()
+                                .prefixes
+                                .iter()
+                                .map(|p| SharedString::from(p.name.as_str()))
+                                .collect();
+                            window.set_plugin_target_prefixes(ModelRc::new(VecModel::from(names)));
+                            window.set_plugin_target_prefix(0);
+                        }
                         window.set_marketplace_loading(false);
 
                         if let Ok(mut app_mut) = app_poll.try_borrow_mut() {
@@ -464,6 +1023,17 @@ fn setup_mo2_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
         });
     }
 
+    // Filter the Proton picker
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_mo2_filter_protons(move |_| {
+            if let (Some(window), Some(app_rc)) = (window_weak.upgrade(), app_weak.upgrade()) {
+                refresh_proton_picker(&window, &app_rc.borrow().steam_protons);
+            }
+        });
+    }
+
     // Go back
     {
         let app_weak = Rc::downgrade(app);
@@ -527,6 +1097,8 @@ fn setup_mo2_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
                         if app_ref.install_wizard.low_disk_space && !app_ref.install_wizard.disk_space_override {
                             return;
                         }
+                        app_ref.install_wizard.existing_deps = existing_deps_text(&app_ref.install_wizard);
+                        app_ref.install_wizard.reuse_deps = true;
                         WizardStep::ProtonSelect
                     }
                     _ => current,
@@ -542,6 +1114,13 @@ fn setup_mo2_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
                     );
                     window.set_low_disk_space(app_ref.install_wizard.low_disk_space);
                     window.set_available_disk_gb(app_ref.install_wizard.available_disk_gb as f32);
+                    if new_step == WizardStep::ProtonSelect {
+                        window.set_download_estimate(download_estimate_text(&app_ref.install_wizard).into());
+                        window.set_existing_deps(app_ref.install_wizard.existing_deps.clone().unwrap_or_default().into());
+                        window.set_reuse_deps(app_ref.install_wizard.reuse_deps);
+                        window.set_proton_recommendation(proton_recommendation_text(&app_ref.install_wizard).into());
+                        refresh_proton_picker(&window, &app_ref.steam_protons);
+                    }
                 }
             }
         });
@@ -583,6 +1162,7 @@ fn setup_mo2_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
                     let mut app_ref = app_rc.borrow_mut();
                     app_ref.install_wizard.name = window.get_instance_name().to_string();
                     app_ref.install_wizard.path = window.get_install_path().to_string();
+                    app_ref.install_wizard.reuse_deps = window.get_reuse_deps();
 
                     if let Ok(proton_idx) = usize::try_from(window.get_selected_proton_index()) {
                         if proton_idx < app_ref.steam_protons.len() {
@@ -609,6 +1189,38 @@ fn setup_mo2_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
         });
     }
 
+    // Retry a failed install with the same inputs
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_mo2_retry_install(move || {
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            {
+                let mut app_ref = app_rc.borrow_mut();
+                log_action("MO2: Retry installation");
+                let failed_phase = *app_ref.install_phase.lock();
+                let resumed = app_ref.install_wizard.prepare_retry(failed_phase);
+                let note = if resumed {
+                    "--- Retrying: MO2 is already extracted, setting up the prefix again ---"
+                } else {
+                    "--- Retrying installation ---"
+                };
+                log_info(note);
+                app_ref.logs.lock().push(note.to_string());
+                *app_ref.install_status.lock() = String::new();
+
+                if let Some(window) = window_weak.upgrade() {
+                    window.set_install_type(app_ref.install_wizard.install_type.clone().into());
+                    window.set_last_error("".into());
+                    window.set_wizard_step(wizard_step_to_int(WizardStep::ProtonSelect));
+                }
+            }
+            start_installation(app_rc);
+        });
+    }
+
     // Reset wizard
     {
         let app_weak = Rc::downgrade(app);
@@ -622,6 +1234,7 @@ fn setup_mo2_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
                 app_ref.install_wizard.manager_type = "MO2".to_string();
                 *app_ref.install_status.lock() = String::new();
                 *app_ref.install_progress.lock() = 0.0;
+                *app_ref.install_phase.lock() = None;
                 app_ref.logs.lock().clear();
                 app_ref.dpi_test_processes.lock().clear();
                 app_ref.cancel_install.store(false, Ordering::Relaxed);
@@ -741,6 +1354,22 @@ fn setup_getting_started_callbacks(window: &MainWindow) {
     });
 }
 
+fn setup_task_callbacks(window: &MainWindow) {
+    window.on_cancel_task(|id| {
+        log_action(&format!("Tasks: Cancel task {}", id));
+        TaskQueue::global().cancel(id as u64);
+    });
+
+    window.on_pause_task(|id, paused| {
+        log_action(&format!("Tasks: {} task {}", if paused { "Pause" } else { "Resume" }, id));
+        TaskQueue::global().set_paused(id as u64, paused);
+    });
+
+    window.on_clear_finished_tasks(|| {
+        TaskQueue::global().clear_finished();
+    });
+}
+
 fn setup_marketplace_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
     // Refresh marketplace
     {
@@ -751,6 +1380,7 @@ fn setup_marketplace_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
             if let Some(window) = window_weak.upgrade() {
                 window.set_marketplace_loading(true);
                 window.set_marketplace_error("".into());
+                window.set_marketplace_warning("".into());
             }
 
             if let Some(app_rc) = app_weak.upgrade() {
@@ -777,13 +1407,13 @@ fn setup_marketplace_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
             if let Some(app_rc) = app_weak.upgrade() {
                 let app_ref = app_rc.borrow();
 
-                // Get the folder name from the registry
-                let folder = app_ref.marketplace_state.as_ref()
+                // Get the entry (folder and source registry) from the registry
+                let entry = app_ref.marketplace_state.as_ref()
                     .and_then(|s| s.registry.as_ref())
                     .and_then(|r| r.plugins.get(idx as usize))
-                    .map(|p| p.folder.clone());
+                    .cloned();
 
-                if let Some(folder) = folder {
+                if let Some(entry) = entry {
                     let result_arc = app_ref.marketplace_async.detail_result.clone();
                     *result_arc.lock() = None;
 
@@ -793,7 +1423,7 @@ fn setup_marketplace_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
 
                     let plugin_idx = idx as usize;
                     thread::spawn(move || {
-                        let result = nak_rust::marketplace::fetch_plugin_manifest(&folder)
+                        let result = nak_rust::marketplace::fetch_plugin_manifest(&entry)
                             .map_err(|e| e.to_string());
                         *result_arc.lock() = Some((plugin_idx, result));
                     });
@@ -814,119 +1444,2277 @@ fn setup_marketplace_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
             }
         });
     }
-}
 
-fn setup_settings_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
-    // Open folder
+    // dll-drop plugins: copy the DLL into an existing prefix instead of a new instance
     {
-        window.on_prefix_open_folder(move |idx| {
-            log_action(&format!("Settings: Open folder for prefix {}", idx));
-            let managed = ManagedPrefixes::load();
-            if let Some(prefix) = managed.prefixes.get(idx as usize) {
-                let _ = std::process::Command::new("xdg-open")
-                    .arg(&prefix.prefix_path)
-                    .spawn();
-            }
+        let app_weak = Rc::downgrade(app);
+        window.on_marketplace_install_dll(move |idx, prefix_idx| {
+            log_action(&format!("Marketplace: Install DLL plugin {} into prefix {}", idx, prefix_idx));
+            let Some(prefix) = ManagedPrefixes::load().prefixes.get(prefix_idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(manifest) = app_ref.marketplace_state.as_ref()
+                .and_then(|s| s.manifests.get(&(idx as usize)).cloned())
+            else {
+                log_error("No manifest loaded for this plugin. Load details first.");
+                return;
+            };
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to install the plugin");
+                return;
+            };
+            let toast = app_ref.toast.clone();
+
+            let label = format!("Install {} ({})", manifest.plugin.name, prefix.name);
+            TaskQueue::global().spawn(label, move |task| {
+                task.set_status(format!("Installing {} into {}...", manifest.plugin.name, prefix.name));
+                let outcome = nak_rust::installers::install_dll_plugin(
+                    &manifest,
+                    std::path::Path::new(&prefix.prefix_path),
+                    &proton,
+                )
+                .map(|dll| format!("Installed {} ({}.dll) into {}", manifest.plugin.name, dll, prefix.name))
+                .map_err(|e| format!("Failed to install {}: {}", manifest.plugin.name, e));
+                *toast.lock() = Some(match &outcome {
+                    Ok(msg) => (msg.clone(), false),
+                    Err(msg) => (msg.clone(), true),
+                });
+                outcome
+            });
         });
     }
+}
 
-    // Update scripts
-    {
-        let app_weak = Rc::downgrade(app);
-        let window_weak = window.as_weak();
-        window.on_prefix_update_scripts(move |idx| {
-            log_action(&format!("Settings: Update scripts for prefix {}", idx));
-            let managed = ManagedPrefixes::load();
-            if let Some(prefix) = managed.prefixes.get(idx as usize) {
-                if let Some(app_rc) = app_weak.upgrade() {
-                    let app_ref = app_rc.borrow();
-
-                    let proton = prefix.proton_config_name.as_deref()
-                        .and_then(|name| app_ref.steam_protons.iter().find(|p| p.config_name == name))
-                        .or_else(|| app_ref.steam_protons.first());
-
-                    if let Some(proton) = proton {
-                        let install_path = std::path::Path::new(&prefix.install_path);
-                        let prefix_path = std::path::Path::new(&prefix.prefix_path);
-
-                        match nak_rust::installers::regenerate_nak_tools_scripts(
-                            prefix.manager_type,
-                            install_path,
-                            prefix_path,
-                            prefix.app_id,
-                            &proton.path,
-                        ) {
-                            Ok(_) => {
-                                log_info(&format!("Updated scripts for {} using {}", prefix.name, proton.name));
-                                ManagedPrefixes::update_proton(prefix.app_id, &proton.config_name);
-                            }
-                            Err(e) => {
-                                log_error(&format!("Failed to update scripts: {}", e));
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Refresh prefixes
-            if let Some(app_rc) = app_weak.upgrade() {
-                if let Some(window) = window_weak.upgrade() {
-                    window.set_prefixes(build_prefix_info(&app_rc.borrow()));
-                }
-            }
-        });
+/// Change a prefix's launch options builder toggles and apply them to its shortcut
+fn spawn_launch_toggles_update(
+    app_weak: &std::rc::Weak<RefCell<MyApp>>,
+    idx: i32,
+    edit: impl FnOnce(&mut nak_rust::config::LaunchToggles),
+) {
+    let managed = ManagedPrefixes::load();
+    let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+        return;
+    };
+    let Some(app_rc) = app_weak.upgrade() else {
+        return;
+    };
+    let app_ref = app_rc.borrow();
+    let status = app_ref.prefix_action_status.clone();
+    let result = app_ref.prefix_action_result.clone();
+
+    let mut toggles = prefix.launch_toggles.clone();
+    edit(&mut toggles);
+    if let Err(e) = nak_rust::steam::validate_dll_overrides(&toggles.dll_overrides) {
+        *status.lock() = e.clone();
+        *result.lock() = Some(Err(e));
+        return;
     }
+    *status.lock() = format!("Updating launch options for {}...", prefix.name);
+    let effective = ManagedPrefix { launch_toggles: toggles.clone(), ..prefix.clone() }.effective_launch_toggles();
+
+    TaskQueue::global().spawn(format!("Update launch options ({})", prefix.name), move |_| {
+        let outcome = match nak_rust::steam::set_launch_toggles(prefix.app_id, &effective) {
+            Ok(update) => {
+                ManagedPrefixes::update_launch_toggles(prefix.app_id, &toggles);
+                Ok(format!("Launch options for {} updated.{}", prefix.name, steam_apply_hint(update)))
+            }
+            Err(e) => Err(format!("Failed to update launch options: {}", e)),
+        };
+        *status.lock() = match &outcome {
+            Ok(msg) | Err(msg) => msg.clone(),
+        };
+        *result.lock() = Some(outcome.clone());
+        outcome
+    });
+}
 
-    // Delete prefix (now just sets confirm-delete-index in Slint, actual deletion via confirm)
-    // The on_prefix_delete callback is no longer used for actual deletion — Slint handles it.
+/// Open (creating if missing) or reset a prefix's `NaK Tools/dxvk.conf`
+///
+/// Also makes sure the shortcut's DXVK_CONFIG_FILE still points at it.
+fn spawn_dxvk_conf_action(app_weak: &std::rc::Weak<RefCell<MyApp>>, idx: i32, reset: bool) {
+    let managed = ManagedPrefixes::load();
+    let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+        return;
+    };
+    let Some(app_rc) = app_weak.upgrade() else {
+        return;
+    };
+    let app_ref = app_rc.borrow();
+    let status = app_ref.prefix_action_status.clone();
+    let result = app_ref.prefix_action_result.clone();
+    *status.lock() = format!("Preparing dxvk.conf for {}...", prefix.name);
+
+    TaskQueue::global().spawn(format!("{} dxvk.conf ({})", if reset { "Reset" } else { "Open" }, prefix.name), move |_| {
+        let install_dir = std::path::Path::new(&prefix.install_path);
+        let conf = if reset {
+            nak_rust::installers::reset_dxvk_conf(install_dir)
+        } else {
+            nak_rust::installers::ensure_dxvk_conf(install_dir)
+        };
 
-    // Confirm delete prefix (actual deletion)
-    {
-        let app_weak = Rc::downgrade(app);
-        let window_weak = window.as_weak();
-        window.on_prefix_confirm_delete(move |idx| {
-            log_action(&format!("Settings: Confirmed delete prefix {}", idx));
-            let managed = ManagedPrefixes::load();
-            if let Some(prefix) = managed.prefixes.get(idx as usize) {
-                match ManagedPrefixes::delete_prefix(prefix.app_id) {
-                    Ok(_) => log_info(&format!("Deleted prefix with AppID: {}", prefix.app_id)),
-                    Err(e) => log_error(&format!("Failed to delete prefix: {}", e)),
+        let outcome = match conf {
+            Ok(path) => {
+                if let Err(e) = nak_rust::steam::ensure_dxvk_config_option(prefix.app_id, &path) {
+                    log_warning(&format!("Could not check DXVK_CONFIG_FILE launch option: {}", e));
                 }
-            }
-
-            // Refresh prefixes
-            if let Some(app_rc) = app_weak.upgrade() {
-                if let Some(window) = window_weak.upgrade() {
-                    window.set_prefixes(build_prefix_info(&app_rc.borrow()));
+                if reset {
+                    Ok(format!("Reset dxvk.conf for {} to NaK defaults (old file saved as dxvk.conf.bak)", prefix.name))
+                } else {
+                    let _ = std::process::Command::new("xdg-open").arg(&path).spawn();
+                    Ok(format!("Opened {}", path.display()))
                 }
             }
-        });
+            Err(e) => Err(format!("Failed to prepare dxvk.conf: {}", e)),
+        };
+        *status.lock() = match &outcome {
+            Ok(msg) | Err(msg) => msg.clone(),
+        };
+        *result.lock() = Some(outcome.clone());
+        outcome
+    });
+}
+
+/// Point the dependency cache at `location` ("" = ~/.cache/nak) and move what's cached there
+fn change_deps_cache(app_weak: &std::rc::Weak<RefCell<MyApp>>, window: &MainWindow, location: String) {
+    use nak_rust::deps::precache::{migrate_cache, validate_cache_dir};
+
+    let Some(app_rc) = app_weak.upgrade() else {
+        return;
+    };
+    let mut app_ref = app_rc.borrow_mut();
+    let queue = TaskQueue::global();
+    let status = app_ref.prefix_action_status.clone();
+    let result = app_ref.prefix_action_result.clone();
+    if app_ref.precache_task.and_then(|id| queue.get(id)).is_some_and(|t| !t.state.is_finished()) {
+        *result.lock() = Some(Err("Wait for the current dependency download or move to finish".to_string()));
+        return;
     }
 
-    // Cancel delete prefix
-    {
-        window.on_prefix_cancel_delete(|| {
-            log_action("Settings: Cancelled prefix deletion");
-        });
+    let old_dir = app_ref.config.get_cache_dir();
+    let new_dir = if location.is_empty() {
+        nak_rust::config::AppConfig::get_default_cache_dir()
+    } else {
+        PathBuf::from(&location)
+    };
+    if new_dir == old_dir {
+        return;
+    }
+    if let Err(e) = validate_cache_dir(&new_dir) {
+        log_error(&e);
+        *status.lock() = e.clone();
+        *result.lock() = Some(Err(e));
+        return;
+    }
+
+    app_ref.config.cache_location = location;
+    app_ref.config.save();
+    window.set_deps_cache_dir(new_dir.display().to_string().into());
+    log_info(&format!("Dependency cache moved to {}", new_dir.display()));
+
+    app_ref.precache_task = Some(queue.spawn("Move dependency cache", move |task| {
+        *status.lock() = format!("Moving cached dependencies to {}...", new_dir.display());
+        let outcome = match migrate_cache(&old_dir, &new_dir, |done, total| {
+            task.set_progress(done as f32 / total.max(1) as f32);
+        }) {
+            Ok(moved) => Ok(format!("Dependency cache is now {} ({} entries moved)", new_dir.display(), moved)),
+            Err(e) => Err(format!(
+                "Could not move everything from {}: {} (missing files will be downloaded again)",
+                old_dir.display(),
+                e
+            )),
+        };
+        *status.lock() = match &outcome {
+            Ok(msg) | Err(msg) => msg.clone(),
+        };
+        *result.lock() = Some(outcome.clone());
+        outcome
+    }));
+}
+
+/// Show every nxm:// handler registration in the Settings page
+fn refresh_nxm_handlers(window: &MainWindow) {
+    use nak_rust::nxm::NxmClaimKind;
+
+    let claims = NxmHandler::list_registered_handlers();
+    let lines: Vec<SharedString> = claims
+        .iter()
+        .map(|c| {
+            let kind = match c.kind {
+                NxmClaimKind::Default => "default",
+                NxmClaimKind::Added => "associated",
+                NxmClaimKind::DesktopEntry => "desktop entry",
+            };
+            format!("{} ({}) - {}", c.desktop_file, kind, c.source.display()).into()
+        })
+        .collect();
+
+    if claims.is_empty() {
+        log_warning("No nxm:// handlers are registered");
+    }
+    for line in &lines {
+        log_info(&format!("NXM handler: {}", line));
+    }
+
+    window.set_nxm_conflict(claims.iter().any(|c| !c.is_nak()));
+    window.set_nxm_handlers(ModelRc::new(VecModel::from(lines)));
+}
+
+fn setup_settings_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
+    // Nexus Mods API key (never logged)
+    {
+        let window_weak = window.as_weak();
+        window.on_save_nexus_api_key(move |key| {
+            log_action("Settings: Save Nexus Mods API key");
+            if let Err(e) = NxmHandler::set_nexus_api_key(&key) {
+                log_error(&format!("Failed to save Nexus Mods API key: {}", e));
+            }
+            if let Some(window) = window_weak.upgrade() {
+                window.set_nexus_key_set(NxmHandler::has_nexus_api_key());
+            }
+        });
+    }
+    {
+        let window_weak = window.as_weak();
+        window.on_clear_nexus_api_key(move || {
+            log_action("Settings: Remove Nexus Mods API key");
+            if let Err(e) = NxmHandler::clear_nexus_api_key() {
+                log_error(&format!("Failed to remove Nexus Mods API key: {}", e));
+            }
+            if let Some(window) = window_weak.upgrade() {
+                window.set_nexus_key_set(NxmHandler::has_nexus_api_key());
+            }
+        });
+    }
+
+    // NXM handler conflicts
+    {
+        let window_weak = window.as_weak();
+        window.on_check_nxm_handlers(move || {
+            log_action("Settings: Check NXM handlers");
+            if let Some(window) = window_weak.upgrade() {
+                refresh_nxm_handlers(&window);
+            }
+        });
+    }
+    {
+        let window_weak = window.as_weak();
+        window.on_make_nxm_authoritative(move || {
+            log_action("Settings: Make NaK the NXM handler");
+            match NxmHandler::make_authoritative() {
+                Ok(()) => log_info("NaK is now the nxm:// handler"),
+                Err(e) => log_error(&format!("Failed to register NaK as nxm:// handler: {}", e)),
+            }
+            if let Some(window) = window_weak.upgrade() {
+                refresh_nxm_handlers(&window);
+            }
+        });
+    }
+
+    // Games whose install path goes into new prefixes' registry (opt-in per game)
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_detect_registry_games(move || {
+            log_action("Settings: Detect games for the prefix registry");
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let confirmed = app_rc.borrow().config.registry_game_paths.clone();
+            let rows: Vec<RegistryGame> = nak_rust::installers::detect_registry_games()
+                .iter()
+                .map(|game| RegistryGame {
+                    name: game.name.clone().into(),
+                    path: game.install_path.display().to_string().into(),
+                    confirmed: nak_rust::installers::registry_game_confirmed(&confirmed, game),
+                })
+                .collect();
+            log_info(&format!("Found {} game(s) with registry entries", rows.len()));
+            if let Some(window) = window_weak.upgrade() {
+                window.set_registry_games(ModelRc::new(VecModel::from(rows)));
+            }
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_set_registry_game(move |idx, checked| {
+            let (Some(window), Some(app_rc)) = (window_weak.upgrade(), app_weak.upgrade()) else {
+                return;
+            };
+            let games = window.get_registry_games();
+            let Some(mut game) = games.row_data(idx as usize) else {
+                return;
+            };
+            log_action(&format!("Settings: {} registry entry for {}", if checked { "Enable" } else { "Disable" }, game.name));
+
+            let mut app_ref = app_rc.borrow_mut();
+            if checked {
+                app_ref.config.registry_game_paths.insert(game.name.to_string(), game.path.to_string());
+            } else {
+                app_ref.config.registry_game_paths.remove(game.name.as_str());
+            }
+            app_ref.config.save();
+
+            game.confirmed = checked;
+            games.set_row_data(idx as usize, game);
+        });
+    }
+
+    // Compatdata folders left behind by removed non-Steam shortcuts
+    {
+        let window_weak = window.as_weak();
+        window.on_find_orphaned_compatdata(move || {
+            log_action("Settings: Find orphaned compatdata");
+            let rows: Vec<OrphanedCompatdata> = nak_rust::steam::find_orphaned_compatdata()
+                .iter()
+                .map(|orphan| OrphanedCompatdata {
+                    app_id: orphan.app_id.to_string().into(),
+                    path: orphan.path.display().to_string().into(),
+                    size: ManagedPrefixes::format_size(orphan.size_bytes).into(),
+                })
+                .collect();
+            if let Some(window) = window_weak.upgrade() {
+                window.set_orphaned_compatdata(ModelRc::new(VecModel::from(rows)));
+                window.set_orphans_scanned(true);
+            }
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_delete_orphaned_compatdata(move |idx| {
+            let (Some(window), Some(app_rc)) = (window_weak.upgrade(), app_weak.upgrade()) else {
+                return;
+            };
+            let orphans = window.get_orphaned_compatdata();
+            let Some(row) = orphans.row_data(idx as usize) else {
+                return;
+            };
+            log_action(&format!("Settings: Confirmed delete orphaned compatdata {}", row.path));
+            let Ok(app_id) = row.app_id.parse() else {
+                return;
+            };
+            let orphan = nak_rust::steam::OrphanedCompatdata {
+                app_id,
+                path: PathBuf::from(row.path.as_str()),
+                size_bytes: 0,
+            };
+
+            let app_ref = app_rc.borrow();
+            *app_ref.prefix_action_status.lock() = match nak_rust::steam::delete_orphaned_compatdata(&orphan) {
+                Ok(()) => {
+                    let remaining: Vec<OrphanedCompatdata> =
+                        orphans.iter().enumerate().filter(|(i, _)| *i != idx as usize).map(|(_, r)| r).collect();
+                    window.set_orphaned_compatdata(ModelRc::new(VecModel::from(remaining)));
+                    format!("Deleted compatdata for AppID {} ({} freed)", row.app_id, row.size)
+                }
+                Err(e) => {
+                    log_error(&format!("Failed to delete orphaned compatdata: {}", e));
+                    e
+                }
+            };
+            request_prefix_scan(&app_ref);
+        });
+    }
+
+    // Hand prefixes set up outside NaK (e.g. with protontricks) over to NaK
+    let adoptable: Rc<RefCell<Vec<u32>>> = Rc::new(RefCell::new(Vec::new()));
+    {
+        let adoptable = adoptable.clone();
+        let window_weak = window.as_weak();
+        window.on_find_adoptable_prefixes(move || {
+            log_action("Settings: Find prefixes to adopt");
+            let Some(window) = window_weak.upgrade() else {
+                return;
+            };
+            let found = nak_rust::installers::find_adoptable_prefixes();
+            let options: Vec<SharedString> =
+                found.iter().map(|(app_id, name)| format!("{} ({})", name, app_id).into()).collect();
+            *adoptable.borrow_mut() = found.iter().map(|(app_id, _)| *app_id).collect();
+            window.set_adopt_index(0);
+            window.set_adopt_ready(false);
+            window.set_adopt_preview(if found.is_empty() {
+                "No Steam shortcuts with a prefix that NaK doesn't manage yet.".into()
+            } else {
+                SharedString::new()
+            });
+            window.set_adopt_options(ModelRc::new(VecModel::from(options)));
+        });
+    }
+    {
+        let adoptable = adoptable.clone();
+        let window_weak = window.as_weak();
+        window.on_preview_adopt(move |idx| {
+            let Some(window) = window_weak.upgrade() else {
+                return;
+            };
+            let Some(app_id) = adoptable.borrow().get(idx as usize).copied() else {
+                return;
+            };
+            log_action(&format!("Settings: Preview adopting AppID {}", app_id));
+            let (preview, ready) = match nak_rust::installers::inspect_adoptable_prefix(app_id) {
+                Ok(candidate) => (candidate.preview(), candidate.problem.is_none()),
+                Err(e) => (e, false),
+            };
+            window.set_adopt_preview(preview.into());
+            window.set_adopt_ready(ready);
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_adopt_prefix(move |idx| {
+            let (Some(window), Some(app_rc)) = (window_weak.upgrade(), app_weak.upgrade()) else {
+                return;
+            };
+            let Some(app_id) = adoptable.borrow().get(idx as usize).copied() else {
+                return;
+            };
+            log_action(&format!("Settings: Adopt prefix of AppID {}", app_id));
+            window.set_adopt_preview(SharedString::new());
+            window.set_adopt_ready(false);
+
+            let app_ref = app_rc.borrow();
+            // Keep the Proton Steam already runs the shortcut with, if NaK can see it
+            let proton = nak_rust::steam::get_compat_tool(app_id)
+                .and_then(|name| app_ref.steam_protons.iter().find(|p| p.config_name == name))
+                .or_else(|| app_ref.steam_protons.first())
+                .cloned();
+            let Some(proton) = proton else {
+                *app_ref.prefix_action_status.lock() = "No Proton 10+ found to adopt the prefix with".to_string();
+                request_prefix_scan(&app_ref);
+                return;
+            };
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            TaskQueue::global().spawn(format!("Adopt prefix {}", app_id), move |task| {
+                let status_cb = status.clone();
+                let ctx = TaskContext::new(
+                    move |msg| *status_cb.lock() = msg,
+                    |msg| log_info(&msg),
+                    |_| {},
+                    task.cancel_flag(),
+                )
+                .reporting_to(task);
+                // Inspect again: the prefix may have changed since the preview
+                let outcome = nak_rust::installers::inspect_adoptable_prefix(app_id)
+                    .and_then(|candidate| {
+                        nak_rust::installers::adopt_prefix(&candidate, &proton, &ctx)
+                            .map(|_| format!("Adopted {} - it's now managed by NaK", candidate.name))
+                            .map_err(|e| format!("Failed to adopt {}: {}", candidate.name, e))
+                    });
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Send a test handoff through the NXM handler script
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_test_nxm_link(move || {
+            log_action("Settings: Test NXM link");
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let report = app_rc.borrow().report_result.clone();
+            TaskQueue::global().spawn("Test NXM link", move |task| {
+                task.set_status("Running the NXM handler in test mode...".to_string());
+                let outcome = NxmHandler::test_handoff();
+                let title = if outcome.is_ok() { "NXM Link Test Passed" } else { "NXM Link Test Failed" };
+                let text = match &outcome {
+                    Ok(output) | Err(output) => output.trim().to_string(),
+                };
+                *report.lock() = Some((title.to_string(), text));
+                outcome
+                    .map(|_| "NXM handler test passed".to_string())
+                    .map_err(|_| "NXM handler test failed".to_string())
+            });
+        });
+    }
+
+    // Open folder
+    {
+        window.on_prefix_open_folder(move |idx| {
+            log_action(&format!("Settings: Open folder for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            if let Some(prefix) = managed.prefixes.get(idx as usize) {
+                let _ = std::process::Command::new("xdg-open")
+                    .arg(&prefix.prefix_path)
+                    .spawn();
+            }
+        });
+    }
+
+    // Open a terminal with the prefix's Wine environment
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_open_terminal(move |idx| {
+            log_action(&format!("Settings: Open terminal for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let outcome = match proton_for_prefix(&app_ref, prefix) {
+                Some(proton) => nak_rust::installers::open_prefix_terminal(
+                    &prefix.name,
+                    std::path::Path::new(&prefix.prefix_path),
+                    &proton,
+                )
+                .map(|_| format!("Opened a terminal for {} ({})", prefix.name, proton.name)),
+                None => Err("No Proton available for this prefix".to_string()),
+            };
+            if let Err(e) = &outcome {
+                log_error(e);
+            }
+            *app_ref.prefix_action_status.lock() = match outcome {
+                Ok(msg) | Err(msg) => msg,
+            };
+            request_prefix_scan(&app_ref);
+        });
+    }
+
+    // Update one prefix's NaK Tools scripts
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_prefix_update_scripts(move |idx| {
+            log_action(&format!("Settings: Update scripts for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+
+            let report = nak_rust::installers::regenerate_prefix_scripts(prefix, &app_rc.borrow().steam_protons);
+            match &report.result {
+                Ok(_) => log_info(&format!("Updated scripts for {}", prefix.name)),
+                Err(e) => log_error(&format!("Failed to update scripts: {}", e)),
+            }
+
+            if let Some(window) = window_weak.upgrade() {
+                window.set_report_title("Scripts Updated".into());
+                window.set_report_text(report.summary().into());
+            }
+            request_prefix_scan(&app_rc.borrow());
+        });
+    }
+
+    // Delete prefix (now just sets confirm-delete-index in Slint, actual deletion via confirm)
+    // The on_prefix_delete callback is no longer used for actual deletion — Slint handles it.
+
+    // Confirm delete prefix (actual deletion)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_confirm_delete(move |idx| {
+            log_action(&format!("Settings: Confirmed delete prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            if let Some(prefix) = managed.prefixes.get(idx as usize) {
+                ManagedPrefix::forget_disk_usage(prefix.app_id);
+                match ManagedPrefixes::delete_prefix(prefix.app_id) {
+                    Ok(_) => log_info(&format!("Deleted prefix with AppID: {}", prefix.app_id)),
+                    Err(e) => log_error(&format!("Failed to delete prefix: {}", e)),
+                }
+            }
+
+            // Refresh prefixes
+            if let Some(app_rc) = app_weak.upgrade() {
+                request_prefix_scan(&app_rc.borrow());
+            }
+        });
+    }
+
+    // Cancel delete prefix
+    {
+        window.on_prefix_cancel_delete(|| {
+            log_action("Settings: Cancelled prefix deletion");
+        });
     }
 
     // Remove entry
     {
         let app_weak = Rc::downgrade(app);
-        let window_weak = window.as_weak();
-        window.on_prefix_remove_entry(move |idx| {
-            log_action(&format!("Settings: Remove entry for prefix {}", idx));
+        window.on_prefix_remove_entry(move |idx| {
+            log_action(&format!("Settings: Remove entry for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            if let Some(prefix) = managed.prefixes.get(idx as usize) {
+                ManagedPrefixes::unregister(prefix.app_id);
+            }
+
+            // Refresh prefixes
+            if let Some(app_rc) = app_weak.upgrade() {
+                request_prefix_scan(&app_rc.borrow());
+            }
+        });
+    }
+
+    // Install a specific DXVK version into the prefix
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_install_dxvk(move |idx, version| {
+            log_action(&format!("Settings: Install DXVK {} for prefix {}", version, idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to install DXVK");
+                return;
+            };
+
+            let version = version.trim().to_string();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            let toast = app_ref.toast.clone();
+
+            TaskQueue::global().spawn(format!("Install DXVK ({})", prefix.name), move |task| {
+                let message = format!("Installing DXVK {} into {}...",
+                    if version.is_empty() { "latest" } else { version.as_str() }, prefix.name);
+                task.set_status(message.clone());
+                *status.lock() = message;
+                let tag = (!version.is_empty()).then_some(version.as_str());
+                let outcome = match nak_rust::installers::install_dxvk_version(
+                    std::path::Path::new(&prefix.prefix_path), &proton, tag,
+                ) {
+                    Ok(installed) => {
+                        ManagedPrefixes::update_dxvk_version(prefix.app_id, Some(&installed));
+                        Ok(format!("Installed DXVK {} for {}", installed, prefix.name))
+                    }
+                    Err(e) => Err(format!("Failed to install DXVK: {}", e)),
+                };
+                notify_download_finished(&toast, &outcome);
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Regenerate scripts for every prefix and show what changed
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_prefix_update_all_scripts(move || {
+            log_action("Settings: Update scripts for all prefixes");
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let reports = nak_rust::installers::regenerate_all_prefix_scripts(&app_rc.borrow().steam_protons);
+            let failed = reports.iter().filter(|r| r.result.is_err()).count();
+            let summary: Vec<String> = reports.iter().map(|r| r.summary()).collect();
+            if failed > 0 {
+                log_warning(&format!("Failed to update scripts for {} of {} prefixes", failed, reports.len()));
+            } else {
+                log_info(&format!("Updated scripts for {} prefixes", reports.len()));
+            }
+
+            if let Some(window) = window_weak.upgrade() {
+                window.set_report_title("Scripts Updated".into());
+                window.set_report_text(summary.join("\n").into());
+            }
+            request_prefix_scan(&app_rc.borrow());
+        });
+    }
+
+    // Report broken symlinks of every prefix (NaK Tools, Global Instance, save links)
+    {
+        let window_weak = window.as_weak();
+        window.on_prefix_verify_symlinks(move || {
+            log_action("Settings: Verify symlinks for all prefixes");
+            let broken = nak_rust::installers::check_all_links();
+            for link in &broken {
+                log_warning(&format!("{}: {}", link.instance, link.describe()));
+            }
+            if let Some(window) = window_weak.upgrade() {
+                window.set_report_title("Symlink Check".into());
+                window.set_report_text(nak_rust::installers::render_link_check(&broken).into());
+            }
+        });
+    }
+
+    // Recreate every broken symlink NaK knows the target of
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_prefix_repair_symlinks(move || {
+            log_action("Settings: Repair symlinks for all prefixes");
+            let (fixed, errors) = nak_rust::installers::repair_all_links();
+            let left = nak_rust::installers::check_all_links();
+            let mut text = format!("Repaired {} symlink(s).\n", fixed);
+            for error in &errors {
+                text.push_str(&format!("  {}\n", error));
+            }
+            if !left.is_empty() {
+                text.push('\n');
+                text.push_str(&nak_rust::installers::render_link_check(&left));
+            }
+            if let Some(window) = window_weak.upgrade() {
+                window.set_report_title("Symlinks Repaired".into());
+                window.set_report_text(text.into());
+            }
+            if let Some(app_rc) = app_weak.upgrade() {
+                request_prefix_scan(&app_rc.borrow());
+            }
+        });
+    }
+
+    // Download everything an install needs so it can run offline later
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_precache_deps(move || {
+            log_action("Settings: Pre-download dependencies");
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let mut app_ref = app_rc.borrow_mut();
+            let queue = TaskQueue::global();
+            if app_ref.precache_task.and_then(|id| queue.get(id)).is_some_and(|t| !t.state.is_finished()) {
+                return;
+            }
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+
+            app_ref.precache_task = Some(queue.spawn("Pre-download dependencies", move |task| {
+                *status.lock() = "Pre-downloading dependencies...".to_string();
+                let status_cb = status.clone();
+                let ctx = TaskContext::new(
+                    move |msg| *status_cb.lock() = msg,
+                    |msg| log_info(&msg),
+                    |_| {},
+                    task.cancel_flag(),
+                )
+                .reporting_to(task);
+                let outcome = match nak_rust::deps::precache::precache_all(&ctx) {
+                    Ok(0) => Ok("All dependencies were already downloaded".to_string()),
+                    Ok(_) => Ok("Dependencies pre-downloaded; installs can now run offline".to_string()),
+                    Err(e) => Err(format!("Pre-download failed: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            }));
+        });
+    }
+
+    // Move the winetricks/.NET download cache to another folder
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_browse_deps_cache(move || {
+            log_action("Settings: Change dependency cache folder");
+            let Some(path) = rfd::FileDialog::new().pick_folder() else {
+                return;
+            };
+            if let Some(window) = window_weak.upgrade() {
+                change_deps_cache(&app_weak, &window, path.to_string_lossy().into_owned());
+            }
+        });
+    }
+
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_reset_deps_cache(move || {
+            log_action("Settings: Reset dependency cache folder");
+            if let Some(window) = window_weak.upgrade() {
+                change_deps_cache(&app_weak, &window, String::new());
+            }
+        });
+    }
+
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_set_dotnet_preset(move |idx| {
+            let Some((id, _, components)) = nak_rust::installers::DOTNET_PRESETS.get(idx as usize) else {
+                // The "Custom" entry: keep the hand-edited list
+                return;
+            };
+            log_action(&format!("Settings: .NET preset {}", id));
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let mut app_ref = app_rc.borrow_mut();
+            app_ref.config.dotnet_components = components.iter().map(|c| c.to_string()).collect();
+            app_ref.config.save();
+            if let Some(window) = window_weak.upgrade() {
+                refresh_dotnet_preset(&window, &app_ref.config);
+            }
+        });
+    }
+
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_save_proxy(move |proxy| {
+            let proxy = proxy.trim().to_string();
+            log_action(&format!("Settings: Set HTTP proxy to '{}'", proxy));
+            let Some(window) = window_weak.upgrade() else {
+                return;
+            };
+            if let Err(e) = nak_rust::net::validate_proxy(&proxy) {
+                log_error(&e);
+                window.set_proxy_status(e.into());
+                return;
+            }
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.config.http_proxy = proxy.clone();
+                app_ref.config.save();
+            }
+            window.set_proxy_status(proxy_status_text(&proxy).into());
+        });
+    }
+
+    // Terminal for "Open Terminal" (empty = auto-detect)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_save_terminal(move |terminal| {
+            let terminal = terminal.trim().to_string();
+            log_action(&format!("Settings: Set terminal to '{}'", terminal));
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.config.terminal_command = terminal;
+                app_ref.config.save();
+            }
+        });
+    }
+
+    // Opt-in failure reports (first-run screen and Settings)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_set_telemetry(move |enabled| {
+            log_action(&format!("Settings: {} failure reports", if enabled { "Enable" } else { "Disable" }));
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.config.telemetry_enabled = enabled;
+                app_ref.config.save();
+            }
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_save_telemetry_endpoint(move |endpoint| {
+            let endpoint = endpoint.trim().to_string();
+            log_action(&format!("Settings: Set failure report endpoint to '{}'", endpoint));
+            if !endpoint.is_empty() && !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+                log_error("Failure report endpoint must start with https:// or http://");
+                return;
+            }
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.config.telemetry_endpoint = endpoint.clone();
+                app_ref.config.save();
+            }
+            if let Some(window) = window_weak.upgrade() {
+                window.set_telemetry_endpoint(endpoint.into());
+            }
+        });
+    }
+
+    // Skip the wizard's DPI step (Configure DPI on a prefix reopens it)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_set_skip_dpi_setup(move |enabled| {
+            log_action(&format!("Settings: {} DPI setup step", if enabled { "Skip" } else { "Show" }));
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.config.skip_dpi_setup = enabled;
+                app_ref.config.save();
+            }
+        });
+    }
+
+    // Steam running policy and restart after NaK edits its files
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_set_close_steam_for_changes(move |enabled| {
+            log_action(&format!("Settings: {} Steam before changing its files", if enabled { "Close" } else { "Don't close" }));
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.config.steam_running_policy = if enabled {
+                    nak_rust::config::SteamRunningPolicy::CloseSteam
+                } else {
+                    nak_rust::config::SteamRunningPolicy::Warn
+                };
+                app_ref.config.save();
+            }
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_set_restart_steam_after_changes(move |enabled| {
+            log_action(&format!("Settings: {} Steam after changes", if enabled { "Restart" } else { "Don't restart" }));
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.config.restart_steam_after_changes = enabled;
+                app_ref.config.save();
+            }
+        });
+    }
+
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_cancel_precache(move || {
+            log_action("Settings: Cancel pre-download");
+            if let Some(id) = app_weak.upgrade().and_then(|app_rc| app_rc.borrow().precache_task) {
+                TaskQueue::global().cancel(id);
+            }
+        });
+    }
+
+    // Summarize what's applied in a prefix and refresh applied_settings.txt
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_prefix_show_applied_settings(move |idx| {
+            log_action(&format!("Settings: Show applied settings for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let proton_name = prefix.proton_config_name.as_deref()
+                .and_then(|name| app_rc.borrow().steam_protons.iter().find(|p| p.config_name == name).map(|p| p.name.clone()))
+                .or_else(|| prefix.proton_config_name.clone())
+                .unwrap_or_else(|| "Unknown".to_string());
+
+            let prefix_path = std::path::Path::new(&prefix.prefix_path);
+            let install_dir = std::path::Path::new(&prefix.install_path);
+            let summary = match nak_rust::installers::write_applied_settings(prefix_path, install_dir, &proton_name) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    log_warning(&format!("Failed to write applied settings summary: {}", e));
+                    nak_rust::installers::read_applied_settings(prefix_path, &proton_name).render()
+                }
+            };
+
+            // Include the launch check so the exported file covers why Play might fail
+            let issues = nak_rust::installers::validate_launch_setup(prefix, &app_rc.borrow().steam_protons);
+            let launch = format!("\nLaunch setup:\n{}", nak_rust::installers::render_launch_check(&issues));
+            let settings_file = nak_rust::installers::applied_settings_path(install_dir);
+            if settings_file.exists() {
+                let appended = std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(&settings_file)
+                    .and_then(|mut f| std::io::Write::write_all(&mut f, launch.as_bytes()));
+                if let Err(e) = appended {
+                    log_warning(&format!("Failed to add launch check to applied settings: {}", e));
+                }
+            }
+            let summary = summary + &launch;
+
+            if let Some(window) = window_weak.upgrade() {
+                window.set_report_title(format!("Applied Settings: {}", prefix.name).into());
+                window.set_report_text(summary.into());
+            }
+        });
+    }
+
+    // Reopen the install wizard's DPI step for an existing prefix
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_prefix_configure_dpi(move |idx| {
+            log_action(&format!("Settings: Configure DPI for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let mut app_ref = app_rc.borrow_mut();
+            if *app_ref.is_installing_manager.lock() {
+                log_error("Wait for the current install to finish before configuring DPI");
+                return;
+            }
+
+            let proton = prefix.proton_config_name.clone()
+                .filter(|name| app_ref.steam_protons.iter().any(|p| &p.config_name == name))
+                .or_else(|| app_ref.steam_protons.first().map(|p| p.config_name.clone()));
+            if proton.is_none() {
+                log_error("No Proton available to apply the DPI with");
+                return;
+            }
+
+            app_ref.install_wizard = InstallWizard::default();
+            app_ref.install_wizard.manager_type = prefix.manager_type.to_string();
+            app_ref.install_wizard.name = prefix.name.clone();
+            app_ref.install_wizard.path = prefix.install_path.clone();
+            app_ref.install_wizard.installed_app_id = Some(prefix.app_id);
+            app_ref.install_wizard.installed_prefix_path = Some(PathBuf::from(&prefix.prefix_path));
+            app_ref.install_wizard.selected_proton = proton;
+            app_ref.install_wizard.selected_dpi = 96;
+            app_ref.install_wizard.step = WizardStep::DpiSetup;
+            app_ref.current_page = Page::ModManagers;
+            *app_ref.install_status.lock() = String::new();
+
+            if let Some(window) = window_weak.upgrade() {
+                window.set_current_page(PageType::MO2);
+                window.set_selected_dpi(96);
+                window.set_wizard_step(wizard_step_to_int(WizardStep::DpiSetup));
+            }
+        });
+    }
+
+    // Check everything a launch depends on without starting anything
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_prefix_validate_launch(move |idx| {
+            log_action(&format!("Settings: Validate launch setup for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+
+            let issues = nak_rust::installers::validate_launch_setup(prefix, &app_rc.borrow().steam_protons);
+            for issue in &issues {
+                log_warning(&format!("{}: {} ({})", prefix.name, issue.problem, issue.fix));
+            }
+            if issues.is_empty() {
+                log_info(&format!("{}: launch setup looks good", prefix.name));
+            }
+
+            if let Some(window) = window_weak.upgrade() {
+                window.set_report_title(format!("Launch Setup: {}", prefix.name).into());
+                window.set_report_text(nak_rust::installers::render_launch_check(&issues).into());
+            }
+        });
+    }
+
+    // Fonts: winetricks font sets and custom font files
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_install_font_set(move |idx, verb| {
+            log_action(&format!("Settings: Install fonts {} for prefix {}", verb, idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to install fonts");
+                return;
+            };
+
+            let verb = verb.to_string();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+
+            TaskQueue::global().spawn(format!("Install {} ({})", verb, prefix.name), move |task| {
+                let status_cb = status.clone();
+                let report = |msg: String| {
+                    task.set_status(msg.clone());
+                    log_info(&msg);
+                    *status_cb.lock() = msg;
+                };
+                report(format!("Installing {} into {}...", verb, prefix.name));
+                let outcome = match nak_rust::installers::install_font_verb(
+                    std::path::Path::new(&prefix.prefix_path), &proton, &verb, report,
+                ) {
+                    Ok(()) => Ok(format!("Installed {} for {}", verb, prefix.name)),
+                    Err(e) => Err(format!("Failed to install {}: {}", verb, e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_install_custom_fonts(move |idx| {
+            log_action(&format!("Settings: Install custom fonts for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(files) = rfd::FileDialog::new()
+                .set_title(format!("Fonts to add to {}", prefix.name))
+                .add_filter("Fonts", nak_rust::installers::FONT_EXTENSIONS)
+                .pick_files()
+            else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to install fonts");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+
+            TaskQueue::global().spawn(format!("Add fonts ({})", prefix.name), move |task| {
+                let status_cb = status.clone();
+                let report = |msg: String| {
+                    task.set_status(msg.clone());
+                    *status_cb.lock() = msg;
+                };
+                let outcome = match nak_rust::installers::install_custom_fonts(
+                    std::path::Path::new(&prefix.prefix_path), &proton, &files, &report,
+                ) {
+                    Ok(names) => Ok(format!("Added {} font(s) to {}", names.len(), prefix.name)),
+                    Err(e) => Err(format!("Failed to add fonts: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Reinstall XACT and copy the game prefix's audio overrides
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_fix_audio(move |idx| {
+            log_action(&format!("Settings: Fix audio for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to fix audio");
+                return;
+            };
+
+            let confirmed = app_ref.config.registry_game_paths.clone();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            let report = app_ref.report_result.clone();
+
+            TaskQueue::global().spawn(format!("Fix audio ({})", prefix.name), move |task| {
+                // Compare against the games set up for the prefix registry, else every detected game
+                let games = nak_rust::installers::detect_registry_games();
+                let confirmed_games: Vec<_> = games
+                    .iter()
+                    .filter(|g| nak_rust::installers::registry_game_confirmed(&confirmed, g))
+                    .collect();
+                let reference = if confirmed_games.is_empty() { games.iter().collect() } else { confirmed_games };
+                let game_prefixes: Vec<std::path::PathBuf> =
+                    reference.iter().filter_map(|g| g.prefix_path.clone()).collect();
+
+                let status_cb = status.clone();
+                let progress = |msg: String| {
+                    task.set_status(msg.clone());
+                    *status_cb.lock() = msg;
+                };
+                let outcome = match nak_rust::installers::fix_audio(
+                    std::path::Path::new(&prefix.prefix_path), &proton, &game_prefixes, progress,
+                ) {
+                    Ok(notes) => {
+                        *report.lock() = Some((format!("Fix Audio: {}", prefix.name), notes.join("\n")));
+                        Ok(format!("Fixed audio for {}", prefix.name))
+                    }
+                    Err(e) => Err(format!("Failed to fix audio: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Look through the prefix's PROTON_LOG output for known failures
+    {
+        let window_weak = window.as_weak();
+        window.on_prefix_diagnose_proton_log(move |idx| {
+            log_action(&format!("Settings: Diagnose Proton log for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+
+            let log_path = nak_rust::installers::proton_log_path(prefix.app_id);
+            let text = match nak_rust::installers::diagnose_proton_log(&log_path) {
+                Ok(issues) if issues.is_empty() => format!(
+                    "No known failure signatures in {}.\n\nIf the game still fails, the end of that log usually shows why.\n",
+                    log_path.display()
+                ),
+                Ok(issues) => {
+                    for issue in &issues {
+                        log_warning(&format!("{}: {} ({})", prefix.name, issue.problem, issue.fix));
+                    }
+                    format!("{}\n{}", log_path.display(), nak_rust::installers::render_launch_check(&issues))
+                }
+                Err(_) => format!(
+                    "No Proton log at {}.\n\nTo create one, set the shortcut's launch options in Steam to\n  PROTON_LOG=1 %command%\nlaunch it once, then run this again.\n",
+                    log_path.display()
+                ),
+            };
+
+            if let Some(window) = window_weak.upgrade() {
+                window.set_report_title(format!("Proton Log: {}", prefix.name).into());
+                window.set_report_text(text.into());
+            }
+        });
+    }
+
+    // Show the latest launch log written by the Launch Log wrapper
+    {
+        let window_weak = window.as_weak();
+        window.on_prefix_view_launch_log(move |idx| {
+            log_action(&format!("Settings: View launch log for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+
+            let log_path = prefix.launch_log_dir().join(nak_rust::config::LAUNCH_LOG_FILE);
+            let text = match nak_rust::installers::read_launch_log(&log_path) {
+                Ok((tail, issues)) if issues.is_empty() => format!("{}\n\n{}", log_path.display(), tail),
+                Ok((tail, issues)) => format!(
+                    "{}\n{}\n{}",
+                    log_path.display(),
+                    nak_rust::installers::render_launch_check(&issues),
+                    tail
+                ),
+                Err(_) if !prefix.launch_toggles.launch_log => format!(
+                    "No launch log yet.\n\nTurn on \"Log launches\" in the launch options, launch {} from Steam once, then look again.\n",
+                    prefix.name
+                ),
+                Err(_) => format!("No launch log at {}.\n\nLaunch {} from Steam once, then look again.\n", log_path.display(), prefix.name),
+            };
+
+            if let Some(window) = window_weak.upgrade() {
+                window.set_report_title(format!("Launch Log: {}", prefix.name).into());
+                window.set_report_text(text.into());
+            }
+        });
+    }
+
+    // Run wineboot -u on a prefix left behind by a Proton update
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_upgrade(move |idx| {
+            log_action(&format!("Settings: Upgrade prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to upgrade the prefix");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+
+            TaskQueue::global().spawn(format!("Upgrade prefix ({})", prefix.name), move |task| {
+                let status_cb = status.clone();
+                let report = |msg: String| {
+                    task.set_status(msg.clone());
+                    log_info(&msg);
+                    *status_cb.lock() = msg;
+                };
+                let outcome = match nak_rust::installers::upgrade_prefix(
+                    std::path::Path::new(&prefix.prefix_path), &proton, &report,
+                ) {
+                    Ok(()) => Ok(format!("Prefix for {} upgraded to {}", prefix.name, proton.name)),
+                    Err(e) => Err(format!("Failed to upgrade prefix for {}: {}", prefix.name, e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Drop the Global Instance link when it conflicts with a portable instance
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_disable_global_instance(move |idx| {
+            log_action(&format!("Settings: Disable Global Instance for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            *app_ref.prefix_action_status.lock() =
+                match nak_rust::installers::disable_mo2_global_instance(std::path::Path::new(&prefix.prefix_path)) {
+                    Ok(()) => format!("Global Instance disabled for {} - MO2 will use the portable instance", prefix.name),
+                    Err(e) => {
+                        log_error(&format!("Failed to disable Global Instance: {}", e));
+                        format!("Failed to disable Global Instance: {}", e)
+                    }
+                };
+            request_prefix_scan(&app_ref);
+        });
+    }
+
+    // Restore the Global Instance link, moving instances from a real folder back
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_repair_global_instance(move |idx| {
+            log_action(&format!("Settings: Repair Global Instance for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            *app_ref.prefix_action_status.lock() = match nak_rust::installers::repair_mo2_global_instance(
+                std::path::Path::new(&prefix.prefix_path),
+                std::path::Path::new(&prefix.install_path),
+            ) {
+                Ok(()) => format!("Global Instance link restored for {}", prefix.name),
+                Err(e) => {
+                    log_error(&format!("Failed to repair Global Instance: {}", e));
+                    format!("Failed to repair Global Instance: {}", e)
+                }
+            };
+            request_prefix_scan(&app_ref);
+        });
+    }
+
+    // Launch the manager once to initialize its prefix, then link game saves
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_up_saves(move |idx| {
+            log_action(&format!("Settings: Set up saves for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to set up saves");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+
+            TaskQueue::global().spawn(format!("Set up saves ({})", prefix.name), move |task| {
+                let message = format!("Launching {} to set up saves...", prefix.name);
+                task.set_status(message.clone());
+                *status.lock() = message;
+                let outcome = match nak_rust::installers::set_up_saves(
+                    prefix.app_id, std::path::Path::new(&prefix.prefix_path), &proton,
+                ) {
+                    Ok((games, launch)) => {
+                        let mut msg = if games.is_empty() {
+                            format!("No new game saves to link for {}", prefix.name)
+                        } else {
+                            format!("Linked saves for {}: {}", prefix.name, games.join(", "))
+                        };
+                        if let Some(warning) = launch.warning() {
+                            log_warning(&warning);
+                            msg = format!("{}. Warning: {}", msg, warning);
+                        }
+                        if launch.prefix_path.to_string_lossy() != prefix.prefix_path {
+                            msg = format!(
+                                "{}. Steam keeps this prefix at {}; NaK now uses it (run Update Scripts to repoint NaK Tools)",
+                                msg,
+                                launch.prefix_path.display()
+                            );
+                        }
+                        Ok(msg)
+                    }
+                    Err(e) => Err(format!("Failed to set up saves: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Import NaK shortcuts that were written to another Steam account
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_import_account_shortcuts(move |idx| {
+            let accounts = get_steam_accounts();
+            let Some(account) = accounts.get(idx as usize) else {
+                return;
+            };
+            log_action(&format!("Settings: Import shortcuts from Steam account {}", account.persona_name));
+            let app_ids: Vec<u32> = ManagedPrefixes::load().prefixes.iter().map(|p| p.app_id).collect();
+            match nak_rust::steam::import_shortcuts_from_account(&account.account_id, &app_ids) {
+                Ok(0) => log_info(&format!("No NaK shortcuts to import from {}", account.persona_name)),
+                Ok(n) => log_info(&format!(
+                    "Imported {} shortcut(s) from {}. Restart Steam to see them.",
+                    n, account.persona_name
+                )),
+                Err(e) => log_error(&format!("Failed to import shortcuts: {}", e)),
+            }
+
+            if let Some(app_rc) = app_weak.upgrade() {
+                request_prefix_scan(&app_rc.borrow());
+            }
+        });
+    }
+
+    // Prefix-wide Windows version
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_windows_version(move |idx, version_idx| {
+            let Some((version, label)) = nak_rust::installers::WINDOWS_VERSIONS.get(version_idx as usize).copied() else {
+                return;
+            };
+            log_action(&format!("Settings: Set Windows version for prefix {} to {}", idx, version));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to set the Windows version");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Setting {} to {}...", prefix.name, label);
+
+            TaskQueue::global().spawn(format!("Set Windows version ({})", prefix.name), move |_| {
+                let outcome = match nak_rust::installers::set_windows_version(
+                    std::path::Path::new(&prefix.prefix_path), &proton, version,
+                ) {
+                    Ok(()) => {
+                        ManagedPrefixes::update_windows_version(prefix.app_id, Some(version));
+                        Ok(format!("{} now reports {}", prefix.name, label))
+                    }
+                    Err(e) => Err(format!("Failed to set Windows version: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Launch arguments for the mod manager
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_launch_args(move |idx, args| {
+            log_action(&format!("Settings: Set launch arguments for prefix {} to '{}'", idx, args));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+
+            let args = args.trim().to_string();
+            if let Err(e) = nak_rust::steam::render_launch_args(&args) {
+                *status.lock() = e.clone();
+                *result.lock() = Some(Err(e));
+                return;
+            }
+            *status.lock() = format!("Updating launch arguments for {}...", prefix.name);
+
+            TaskQueue::global().spawn(format!("Update launch arguments ({})", prefix.name), move |_| {
+                let old_args = prefix.launch_args.clone().unwrap_or_default();
+                let outcome = match nak_rust::steam::set_launch_args(prefix.app_id, &old_args, &args) {
+                    Ok(update) => {
+                        ManagedPrefixes::update_launch_args(prefix.app_id, Some(&args));
+                        Ok(format!("Updated launch arguments for {}.{}", prefix.name, steam_apply_hint(update)))
+                    }
+                    Err(e) => Err(format!("Failed to update launch arguments: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Native Wayland toggle (PROTON_ENABLE_WAYLAND in the launch options)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_native_wayland(move |idx, enabled| {
+            log_action(&format!("Settings: Set native Wayland for prefix {} to {}", idx, enabled));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Updating launch options for {}...", prefix.name);
+
+            TaskQueue::global().spawn(format!("Set native Wayland ({})", prefix.name), move |_| {
+                let outcome = match nak_rust::steam::set_native_wayland(prefix.app_id, enabled) {
+                    Ok(update) => {
+                        ManagedPrefixes::update_native_wayland(prefix.app_id, enabled);
+                        if enabled {
+                            log_warning("Native Wayland is experimental; turn it off if the mod manager fails to start");
+                        }
+                        Ok(format!(
+                            "Native Wayland {} for {}.{}",
+                            if enabled { "enabled" } else { "disabled" },
+                            prefix.name,
+                            steam_apply_hint(update)
+                        ))
+                    }
+                    Err(e) => Err(format!("Failed to update launch options: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Launch options builder (wrappers and env vars in front of %command%)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_launch_toggles(move |idx, gamemode, mangohud, wined3d, dll_overrides| {
+            log_action(&format!(
+                "Settings: Set launch options for prefix {} (gamemode: {}, mangohud: {}, wined3d: {}, overrides: '{}')",
+                idx, gamemode, mangohud, wined3d, dll_overrides
+            ));
+            spawn_launch_toggles_update(&app_weak, idx, |toggles| {
+                toggles.gamemode = gamemode;
+                toggles.mangohud = mangohud;
+                toggles.wined3d = wined3d;
+                toggles.dll_overrides = dll_overrides.trim().to_string();
+            });
+        });
+    }
+
+    // Game id for protonfixes (Auto = the instance's detected game)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_game_id(move |idx, choice| {
+            let Some((game_id, label)) = game_id_choices().into_iter().nth(choice as usize) else {
+                return;
+            };
+            log_action(&format!("Settings: Set protonfixes game for prefix {} to {}", idx, label));
+            spawn_launch_toggles_update(&app_weak, idx, |toggles| {
+                toggles.game_id = game_id;
+            });
+        });
+    }
+
+    // Gamescope window (experimental, mainly for the Deck's desktop mode)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_gamescope(move |idx, enabled, width, height| {
+            log_action(&format!("Settings: Set gamescope for prefix {} to {} ({}x{})", idx, enabled, width, height));
+            let size = match (width.trim().parse::<u32>(), height.trim().parse::<u32>()) {
+                (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+                _ if !enabled => nak_rust::steam::DECK_RESOLUTION,
+                _ => {
+                    log_warning(&format!("Invalid gamescope size '{}x{}'", width, height));
+                    return;
+                }
+            };
+            spawn_launch_toggles_update(&app_weak, idx, |toggles| {
+                toggles.gamescope = enabled.then_some(size);
+            });
+        });
+    }
+
+    // MangoHud config preset (MANGOHUD_CONFIGFILE pointing at NaK Tools/MangoHud.conf)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_mangohud_preset(move |idx, enabled| {
+            log_action(&format!("Settings: Set MangoHud preset for prefix {} to {}", idx, enabled));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            let config = if enabled {
+                match nak_rust::installers::ensure_mangohud_conf(std::path::Path::new(&prefix.install_path)) {
+                    Ok(path) => path.to_string_lossy().into_owned(),
+                    Err(e) => {
+                        log_error(&format!("Failed to write the MangoHud preset: {}", e));
+                        return;
+                    }
+                }
+            } else {
+                String::new()
+            };
+            spawn_launch_toggles_update(&app_weak, idx, |toggles| {
+                toggles.mangohud_config = config;
+            });
+        });
+    }
+
+    // Launch log wrapper (NaK Tools/Launch Log.sh in front of the command)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_launch_log(move |idx, enabled| {
+            log_action(&format!("Settings: Set launch log for prefix {} to {}", idx, enabled));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            if enabled {
+                let install_dir = std::path::Path::new(&prefix.install_path);
+                if let Err(e) = nak_rust::installers::ensure_launch_log_script(&prefix.manager_type, install_dir) {
+                    log_error(&format!("Failed to write the launch log wrapper: {}", e));
+                    return;
+                }
+            }
+            spawn_launch_toggles_update(&app_weak, idx, |toggles| {
+                toggles.launch_log = enabled;
+            });
+        });
+    }
+
+    // Sync primitive (fsync/esync/ntsync env vars in the launch options)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_sync_mode(move |idx, mode_idx| {
+            let Some((mode, label, _)) = nak_rust::steam::available_sync_modes().get(mode_idx as usize).copied().copied() else {
+                return;
+            };
+            log_action(&format!("Settings: Set sync mode for prefix {} to {}", idx, mode));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Updating launch options for {}...", prefix.name);
+
+            TaskQueue::global().spawn(format!("Set sync mode ({})", prefix.name), move |_| {
+                let outcome = match nak_rust::steam::set_sync_mode(prefix.app_id, mode) {
+                    Ok(update) => {
+                        ManagedPrefixes::update_sync_mode(prefix.app_id, Some(mode));
+                        Ok(format!("Sync for {} set to {}.{}", prefix.name, label, steam_apply_hint(update)))
+                    }
+                    Err(e) => Err(format!("Failed to update launch options: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Locale (LANG/LC_ALL in the launch options, which also sets Wine's codepage)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_locale(move |idx, locale_idx| {
+            let Some((locale, label)) = nak_rust::steam::PREFIX_LOCALES.get(locale_idx as usize).copied() else {
+                return;
+            };
+            log_action(&format!("Settings: Set locale for prefix {} to {}", idx, label));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Updating launch options for {}...", prefix.name);
+
+            TaskQueue::global().spawn(format!("Set locale ({})", prefix.name), move |_| {
+                let locale = (!locale.is_empty()).then_some(locale);
+                let outcome = match nak_rust::steam::set_locale(prefix.app_id, locale) {
+                    Ok(update) => {
+                        ManagedPrefixes::update_locale(prefix.app_id, locale);
+                        Ok(format!("Locale for {} set to {}.{}", prefix.name, label, steam_apply_hint(update)))
+                    }
+                    Err(e) => Err(format!("Failed to update launch options: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Edit / reset the install's dxvk.conf
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_edit_dxvk_conf(move |idx| {
+            log_action(&format!("Settings: Edit dxvk.conf for prefix {}", idx));
+            spawn_dxvk_conf_action(&app_weak, idx, false);
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_reset_dxvk_conf(move |idx| {
+            log_action(&format!("Settings: Reset dxvk.conf for prefix {}", idx));
+            spawn_dxvk_conf_action(&app_weak, idx, true);
+        });
+    }
+
+    // Compare DLL overrides against NaK's baseline
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_diff_dll_overrides(move |idx| {
+            log_action(&format!("Settings: Diff DLL overrides for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to query DLL overrides");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Reading DLL overrides for {}...", prefix.name);
+
+            TaskQueue::global().spawn(format!("Check DLL overrides ({})", prefix.name), move |_| {
+                let outcome = match nak_rust::installers::diff_dll_overrides(
+                    std::path::Path::new(&prefix.prefix_path), &proton,
+                ) {
+                    Ok(changes) if changes.is_empty() => {
+                        Ok(format!("{}: DLL overrides match NaK's defaults", prefix.name))
+                    }
+                    Ok(changes) => {
+                        let list: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+                        Ok(format!(
+                            "{}: {} DLL override difference(s): {}",
+                            prefix.name,
+                            changes.len(),
+                            list.join(", ")
+                        ))
+                    }
+                    Err(e) => Err(format!("Failed to read DLL overrides: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Re-create a missing Steam shortcut for an existing install
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_add_to_steam(move |idx| {
+            log_action(&format!("Settings: Add prefix {} to Steam", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to add the shortcut");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Adding {} to Steam...", prefix.name);
+
+            TaskQueue::global().spawn(format!("Add to Steam ({})", prefix.name), move |_| {
+                let outcome = match nak_rust::installers::add_prefix_to_steam(&prefix, &proton) {
+                    Ok(app_id) => Ok(format!("Added {} to Steam (AppID {})", prefix.name, app_id)),
+                    Err(e) => Err(format!("Failed to add {} to Steam: {}", prefix.name, e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Back up / restore an MO2 instance's profiles and mod metadata (no mod files)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_export_modlist(move |idx| {
+            log_action(&format!("Settings: Export mod list for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let backup_dir = nak_rust::installers::modlist_backup_dir();
+            let _ = std::fs::create_dir_all(&backup_dir);
+            let Some(archive) = rfd::FileDialog::new()
+                .set_title(format!("Save mod list of {}", prefix.name))
+                .set_directory(&backup_dir)
+                .set_file_name(nak_rust::installers::modlist_archive_name(&prefix))
+                .add_filter("Mod list backup", &["gz"])
+                .save_file()
+            else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Exporting mod list of {}...", prefix.name);
+
+            TaskQueue::global().spawn(format!("Export mod list ({})", prefix.name), move |_| {
+                let outcome = match nak_rust::installers::export_modlist(&prefix, &archive) {
+                    Ok(count) => Ok(format!("Saved {} files from {} to {}", count, prefix.name, archive.display())),
+                    Err(e) => Err(format!("Failed to export mod list: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+    // Prefix recipes: the prefix's configuration without its files
+    {
+        window.on_prefix_export_recipe(move |idx| {
+            log_action(&format!("Settings: Export recipe for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize) else {
+                return;
+            };
+            let recipe_dir = nak_rust::installers::recipe_dir();
+            let _ = std::fs::create_dir_all(&recipe_dir);
+            let Some(path) = rfd::FileDialog::new()
+                .set_title(format!("Save recipe of {}", prefix.name))
+                .set_directory(&recipe_dir)
+                .set_file_name(nak_rust::installers::recipe_file_name(prefix))
+                .add_filter("NaK recipe", &["json"])
+                .save_file()
+            else {
+                return;
+            };
+            match nak_rust::installers::export_recipe(prefix, &path) {
+                Ok(_) => log_info(&format!("Saved recipe of {} to {}", prefix.name, path.display())),
+                Err(e) => log_error(&format!("Failed to export recipe: {}", e)),
+            }
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_import_recipe(move |idx| {
+            log_action(&format!("Settings: Import recipe for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(path) = rfd::FileDialog::new()
+                .set_title(format!("Apply a recipe to {}", prefix.name))
+                .set_directory(nak_rust::installers::recipe_dir())
+                .add_filter("NaK recipe", &["json"])
+                .pick_file()
+            else {
+                return;
+            };
+            let recipe = match nak_rust::installers::load_recipe(&path) {
+                Ok(recipe) => recipe,
+                Err(e) => {
+                    log_error(&format!("Failed to read recipe {}: {}", path.display(), e));
+                    return;
+                }
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            // The recipe's Proton if it's installed, else the prefix's own
+            let proton = recipe.proton.as_deref()
+                .and_then(|name| app_ref.steam_protons.iter().find(|p| p.config_name == name).cloned())
+                .or_else(|| proton_for_prefix(&app_ref, &prefix));
+            let Some(proton) = proton else {
+                log_error("No Proton available to apply the recipe");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            let report = app_ref.report_result.clone();
+            TaskQueue::global().spawn(format!("Apply recipe ({})", prefix.name), move |task| {
+                let status_cb = status.clone();
+                let ctx = TaskContext::new(
+                    move |msg| *status_cb.lock() = msg,
+                    |msg| log_info(&msg),
+                    |_| {},
+                    task.cancel_flag(),
+                )
+                .reporting_to(task);
+                let outcome = match nak_rust::installers::apply_recipe(&prefix, &recipe, &proton, &ctx) {
+                    Ok(notes) => {
+                        let text = if notes.is_empty() {
+                            format!("{} now matches {}.\n\nRestart Steam to pick up the launch options.", prefix.name, path.display())
+                        } else {
+                            format!(
+                                "Applied {} to {}, with {} problem(s):\n\n{}\n",
+                                path.display(), prefix.name, notes.len(), notes.join("\n")
+                            )
+                        };
+                        *report.lock() = Some((format!("Recipe: {}", prefix.name), text));
+                        Ok(format!("Applied recipe to {}", prefix.name))
+                    }
+                    Err(e) => Err(format!("Failed to apply recipe to {}: {}", prefix.name, e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_import_modlist(move |idx| {
+            log_action(&format!("Settings: Import mod list for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(archive) = rfd::FileDialog::new()
+                .set_title(format!("Restore mod list into {}", prefix.name))
+                .set_directory(nak_rust::installers::modlist_backup_dir())
+                .add_filter("Mod list backup", &["gz"])
+                .pick_file()
+            else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Restoring mod list into {}...", prefix.name);
+
+            TaskQueue::global().spawn(format!("Import mod list ({})", prefix.name), move |_| {
+                let outcome = match nak_rust::installers::import_modlist(&prefix, &archive) {
+                    Ok(report) => {
+                        let mut msg = format!("Restored {} files into {}", report.restored, prefix.name);
+                        if !report.missing_mods.is_empty() {
+                            log_warning(&format!("Mods in the backup but not installed: {}", report.missing_mods.join(", ")));
+                            msg = format!("{}. {} mod(s) in the backup aren't installed", msg, report.missing_mods.len());
+                        }
+                        Ok(msg)
+                    }
+                    Err(e) => Err(format!("Failed to import mod list: {}", e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Point the instance at a mod manager folder the user moved
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_relocate_install(move |idx| {
+            log_action(&format!("Settings: Relocate install for prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(new_dir) = rfd::FileDialog::new()
+                .set_title(format!("New location of {}", prefix.name))
+                .pick_folder()
+            else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to regenerate the scripts");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            *status.lock() = format!("Relocating {} to {}...", prefix.name, new_dir.display());
+
+            TaskQueue::global().spawn(format!("Relocate install ({})", prefix.name), move |_| {
+                let outcome = match nak_rust::installers::relocate_install(&prefix, &new_dir, &proton) {
+                    Ok(_) => Ok(format!("{} now uses {}", prefix.name, new_dir.display())),
+                    Err(e) => Err(format!("Failed to relocate {}: {}", prefix.name, e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Wipe and rebuild a prefix, keeping the mod manager install
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_reset(move |idx| {
+            log_action(&format!("Settings: Reset prefix {}", idx));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to reset the prefix");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+
+            TaskQueue::global().spawn(format!("Reset prefix ({})", prefix.name), move |task| {
+                let status_cb = status.clone();
+                let ctx = TaskContext::new(
+                    move |msg| *status_cb.lock() = msg,
+                    |msg| log_info(&msg),
+                    |_| {},
+                    task.cancel_flag(),
+                )
+                .reporting_to(task);
+                ctx.set_status(format!("Resetting prefix for {}...", prefix.name));
+                let outcome = nak_rust::installers::reset_prefix(&prefix, &proton, &ctx);
+                ManagedPrefix::forget_disk_usage(prefix.app_id);
+                let outcome = match outcome {
+                    Ok(()) => Ok(format!("Prefix for {} reset", prefix.name)),
+                    Err(e) => Err(format!("Failed to reset prefix for {}: {}", prefix.name, e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Copy a prefix into a new instance with its own Steam shortcut
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_clone(move |idx, new_name, copy_mods| {
+            log_action(&format!("Settings: Copy prefix {} as '{}' (mods: {})", idx, new_name, copy_mods));
+            let managed = ManagedPrefixes::load();
+            let Some(prefix) = managed.prefixes.get(idx as usize).cloned() else {
+                return;
+            };
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let app_ref = app_rc.borrow();
+            let Some(proton) = proton_for_prefix(&app_ref, &prefix) else {
+                log_error("No Proton available to copy the prefix");
+                return;
+            };
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            let new_name = new_name.to_string();
+
+            TaskQueue::global().spawn(format!("Copy prefix ({})", prefix.name), move |task| {
+                let status_cb = status.clone();
+                let ctx = TaskContext::new(
+                    move |msg| *status_cb.lock() = msg,
+                    |msg| log_info(&msg),
+                    |_| {},
+                    task.cancel_flag(),
+                )
+                .reporting_to(task);
+                ctx.set_status(format!("Copying {}...", prefix.name));
+                let outcome = match nak_rust::installers::clone_prefix(&prefix, &new_name, copy_mods, &proton, &ctx) {
+                    Ok(app_id) => Ok(format!("Copied {} to {} (AppID {})", prefix.name, new_name, app_id)),
+                    Err(e) => Err(format!("Failed to copy {}: {}", prefix.name, e)),
+                };
+                *status.lock() = match &outcome {
+                    Ok(msg) | Err(msg) => msg.clone(),
+                };
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
+    // Revert a DXVK override back to Proton's bundled DXVK
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_revert_dxvk(move |idx| {
+            log_action(&format!("Settings: Revert DXVK for prefix {}", idx));
             let managed = ManagedPrefixes::load();
             if let Some(prefix) = managed.prefixes.get(idx as usize) {
-                ManagedPrefixes::unregister(prefix.app_id);
+                if let Some(app_rc) = app_weak.upgrade() {
+                    let app_ref = app_rc.borrow();
+                    if let Some(proton) = proton_for_prefix(&app_ref, prefix) {
+                        match nak_rust::installers::revert_dxvk(std::path::Path::new(&prefix.prefix_path), &proton) {
+                            Ok(_) => {
+                                ManagedPrefixes::update_dxvk_version(prefix.app_id, None);
+                                log_info(&format!("Reverted DXVK for {}", prefix.name));
+                            }
+                            Err(e) => log_error(&format!("Failed to revert DXVK: {}", e)),
+                        }
+                    }
+                }
             }
 
             // Refresh prefixes
             if let Some(app_rc) = app_weak.upgrade() {
-                if let Some(window) = window_weak.upgrade() {
-                    window.set_prefixes(build_prefix_info(&app_rc.borrow()));
+                request_prefix_scan(&app_rc.borrow());
+            }
+        });
+    }
+
+    // Rename prefix (managed entry + Steam shortcut)
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_rename(move |idx, new_name| {
+            log_action(&format!("Settings: Rename prefix {} to '{}'", idx, new_name));
+            let new_name = new_name.trim().to_string();
+            let managed = ManagedPrefixes::load();
+            if let Some(prefix) = managed.prefixes.get(idx as usize) {
+                let shortcut_exists = get_active_shortcut_app_ids().contains(&prefix.app_id);
+                let result = if new_name.is_empty() {
+                    Err("Name cannot be empty".to_string())
+                } else if managed.prefixes.iter().any(|p| p.app_id != prefix.app_id && p.name == new_name) {
+                    Err(format!("A prefix named '{}' already exists", new_name))
+                } else if shortcut_exists {
+                    nak_rust::steam::rename_steam_shortcut(prefix.app_id, &new_name)
+                        .map_err(|e| e.to_string())
+                        .and_then(|_| ManagedPrefixes::rename(prefix.app_id, &new_name))
+                } else {
+                    ManagedPrefixes::rename(prefix.app_id, &new_name)
+                };
+
+                match result {
+                    Ok(_) => log_info(&format!("Renamed '{}' to '{}'", prefix.name, new_name)),
+                    Err(e) => {
+                        log_error(&format!("Failed to rename prefix: {}", e));
+                        if let Some(app_rc) = app_weak.upgrade() {
+                            *app_rc.borrow().prefix_action_status.lock() = format!("Rename failed: {}", e);
+                        }
+                    }
+                }
+            }
+
+            // Refresh prefixes
+            if let Some(app_rc) = app_weak.upgrade() {
+                request_prefix_scan(&app_rc.borrow());
+            }
+        });
+    }
+
+    // Add/remove Electron flags on the Steam shortcut
+    {
+        let app_weak = Rc::downgrade(app);
+        window.on_prefix_set_electron_flags(move |idx, enabled| {
+            log_action(&format!("Settings: Set Electron flags for prefix {} to {}", idx, enabled));
+            let managed = ManagedPrefixes::load();
+            if let Some(prefix) = managed.prefixes.get(idx as usize) {
+                match nak_rust::steam::set_electron_flags(prefix.app_id, enabled) {
+                    Ok(LaunchOptionsUpdate::Unchanged) => {
+                        log_info(&format!("Launch options for {} already up to date", prefix.name))
+                    }
+                    Ok(update) => log_info(&format!(
+                        "Updated launch options for {}.{}",
+                        prefix.name,
+                        steam_apply_hint(update)
+                    )),
+                    Err(e) => log_error(&format!("Failed to update launch options: {}", e)),
                 }
             }
+
+            // Refresh prefixes
+            if let Some(app_rc) = app_weak.upgrade() {
+                request_prefix_scan(&app_rc.borrow());
+            }
         });
     }
 
@@ -980,9 +3768,10 @@ fn setup_version_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
             if let Some(app_rc) = app_weak.upgrade() {
                 let app_ref = app_rc.borrow();
 
-                let url = app_ref.update_info.lock()
+                let (url, version) = app_ref.update_info.lock()
                     .as_ref()
-                    .and_then(|i| i.download_url.clone());
+                    .map(|i| (i.download_url.clone(), i.latest_version.clone()))
+                    .unwrap_or_default();
 
                 if let Some(url) = url {
                     *app_ref.is_installing_update.lock() = true;
@@ -991,13 +3780,18 @@ fn setup_version_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
                     let is_installing = app_ref.is_installing_update.clone();
                     let update_error = app_ref.update_error.clone();
                     let update_installed = app_ref.update_installed.clone();
+                    let toast = app_ref.toast.clone();
+                    let notes = app_ref.update_info.lock().as_ref().map(|i| i.release_notes.clone()).unwrap_or_default();
 
                     thread::spawn(move || {
                         match nak_rust::updater::install_update(&url) {
                             Ok(_) => {
+                                nak_rust::updater::save_release_notes(&version, &notes);
                                 *update_installed.lock() = true;
+                                notify_download_finished(&toast, &Ok(format!("NaK {} downloaded - restart to apply", version)));
                             }
                             Err(e) => {
+                                notify_download_finished(&toast, &Err(format!("NaK {} update failed: {}", version, e)));
                                 *update_error.lock() = Some(e.to_string());
                             }
                         }
@@ -1045,6 +3839,46 @@ fn setup_version_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
         });
     }
 
+    // Re-open the notes of the running version
+    {
+        let window_weak = window.as_weak();
+        window.on_show_whats_new(move || {
+            log_action("Version: What's New");
+            let version = env!("CARGO_PKG_VERSION");
+            let text = match nak_rust::updater::load_release_notes(version) {
+                Some(notes) => nak_rust::updater::render_release_notes(&notes),
+                None => format!(
+                    "No release notes were saved for v{} - they are kept when NaK updates itself. \
+                     See the releases page on GitHub for the full changelog.",
+                    version
+                ),
+            };
+            if let Some(window) = window_weak.upgrade() {
+                window.set_whats_new_text(text.into());
+                window.set_whats_new_visible(true);
+            }
+        });
+    }
+
+    // Dismiss "What's New" (the version counts as seen from here on)
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_whats_new_dismiss(move || {
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.whats_new = None;
+                if app_ref.config.last_seen_version != env!("CARGO_PKG_VERSION") {
+                    app_ref.config.last_seen_version = env!("CARGO_PKG_VERSION").to_string();
+                    app_ref.config.save();
+                }
+            }
+            if let Some(window) = window_weak.upgrade() {
+                window.set_whats_new_visible(false);
+            }
+        });
+    }
+
     // Set can_self_update
     window.set_can_self_update(nak_rust::updater::can_self_update());
 }
@@ -1064,6 +3898,66 @@ fn setup_migration_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
         });
     }
 
+    // Migrate legacy prefixes into Steam shortcuts
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_migration_migrate(move || {
+            log_action("Migration: Migrate legacy prefixes");
+            let Some(app_rc) = app_weak.upgrade() else {
+                return;
+            };
+            let mut app_ref = app_rc.borrow_mut();
+            let data_path = app_ref.config.get_data_path();
+            let proton = app_ref.config.selected_proton.as_deref()
+                .and_then(|name| app_ref.steam_protons.iter().find(|p| p.config_name == name))
+                .or_else(|| app_ref.steam_protons.first())
+                .cloned();
+            let Some(proton) = proton else {
+                if let Some(window) = window_weak.upgrade() {
+                    window.set_report_title("Migrate Old Prefixes".into());
+                    window.set_report_text("No Proton 10+ found. Install one through Steam, then try again.".into());
+                }
+                return;
+            };
+
+            app_ref.show_steam_migration_popup = false;
+            app_ref.config.steam_migration_shown = true;
+            app_ref.config.save();
+            if let Some(window) = window_weak.upgrade() {
+                window.set_show_migration_popup(false);
+            }
+
+            let status = app_ref.prefix_action_status.clone();
+            let result = app_ref.prefix_action_result.clone();
+            TaskQueue::global().spawn("Migrate old NaK prefixes", move |task| {
+                let status_cb = status.clone();
+                let ctx = TaskContext::new(
+                    move |msg| *status_cb.lock() = msg,
+                    |msg| log_info(&msg),
+                    |_| {},
+                    task.cancel_flag(),
+                )
+                .reporting_to(task);
+                ctx.set_status("Migrating old NaK prefixes...".to_string());
+                let report = nak_rust::installers::migrate_legacy_prefixes(&data_path, &proton, &ctx);
+                let summary = report.summary();
+                let outcome = if report.migrated.is_empty() && !report.skipped.is_empty() {
+                    Err(summary)
+                } else {
+                    Ok(summary)
+                };
+                *status.lock() = format!(
+                    "Migrated {} old prefix(es), {} left in place",
+                    report.migrated.len(),
+                    report.skipped.len()
+                );
+                *result.lock() = Some(outcome.clone());
+                outcome
+            });
+        });
+    }
+
     // Delete data
     {
         let app_weak = Rc::downgrade(app);
@@ -1107,12 +4001,70 @@ fn setup_migration_callbacks(window: &MainWindow, app: &Rc<RefCell<MyApp>>) {
             }
         });
     }
+
+    // Dismiss the atomic distro note (shown once)
+    {
+        let app_weak = Rc::downgrade(app);
+        let window_weak = window.as_weak();
+        window.on_atomic_note_dismiss(move || {
+            if let Some(app_rc) = app_weak.upgrade() {
+                let mut app_ref = app_rc.borrow_mut();
+                app_ref.show_atomic_distro_note = false;
+                app_ref.config.atomic_distro_note_shown = true;
+                app_ref.config.save();
+
+                if let Some(window) = window_weak.upgrade() {
+                    window.set_show_atomic_note(false);
+                }
+            }
+        });
+    }
 }
 
 // ============================================================================
 // Installation Logic (moved from mod_managers.rs)
 // ============================================================================
 
+/// Describe the dependency download size, alongside free disk space if known
+fn download_estimate_text(wizard: &InstallWizard) -> String {
+    let download_gb = estimate_configured_deps_size() as f64 / 1024.0;
+    if wizard.available_disk_gb > 0.0 {
+        format!(
+            "This will download ~{:.1} GB of dependencies ({:.1} GB free on target drive)",
+            download_gb, wizard.available_disk_gb
+        )
+    } else {
+        format!("This will download ~{:.1} GB of dependencies", download_gb)
+    }
+}
+
+/// Describe the dependencies an earlier install left in the prefix this MO2 install will get
+///
+/// None when that prefix has no `.nak_deps_complete` marker. Whether the
+/// marker still matches (Proton major version, components) is checked when
+/// installing.
+fn existing_deps_text(wizard: &InstallWizard) -> Option<String> {
+    if wizard.manager_type != "MO2" {
+        return None;
+    }
+    let exe_path = std::path::Path::new(&wizard.path).join("ModOrganizer.exe");
+    let prefix = nak_rust::steam::expected_shortcut_prefix(&wizard.name, exe_path.to_str()?)?;
+    let marker = DepsMarker::read(&prefix)?;
+    Some(format!(
+        "The prefix from an earlier install of this instance already has NaK's dependencies (installed with {}).",
+        marker.proton
+    ))
+}
+
+/// Advisory Proton line for the game this install is for (empty = no preference)
+///
+/// The game comes from an existing instance's `gameName`, else the instance name.
+fn proton_recommendation_text(wizard: &InstallWizard) -> String {
+    nak_rust::config::instance_game(std::path::Path::new(&wizard.path), &wizard.name)
+        .and_then(nak_rust::game_finder::recommend_proton_for_game)
+        .unwrap_or_default()
+}
+
 fn validate_path(wizard: &mut InstallWizard) {
     let path = std::path::Path::new(&wizard.path);
     wizard.validation_error = None;
@@ -1157,16 +4109,24 @@ fn validate_path(wizard: &mut InstallWizard) {
 
 fn start_installation(app: Rc<RefCell<MyApp>>) {
     // Clone Arc fields first, before borrowing wizard mutably
-    let (status_arc, busy_arc, logs_arc, progress_arc, cancel_arc, result_app_id_arc, result_prefix_path_arc);
-    let (instance_name, install_path, manager_type, install_type, skip_disk_check, selected_proton_name, steam_proton);
+    let (status_arc, busy_arc, logs_arc, progress_arc, phase_arc, cancel_arc, result_app_id_arc, result_prefix_path_arc);
+    let (instance_name, install_path, manager_type, install_type, skip_disk_check, reuse_deps, selected_proton_name, steam_proton);
     let plugin_manifest;
 
     {
         let app_ref = app.borrow();
+        if !app_ref.steam_detected {
+            drop(app_ref);
+            app.borrow_mut().install_wizard.last_install_error = Some(
+                "Steam was not found. See Getting Started for how to install it, then click Recheck.".to_string(),
+            );
+            return;
+        }
         status_arc = app_ref.install_status.clone();
         busy_arc = app_ref.is_installing_manager.clone();
         logs_arc = app_ref.logs.clone();
         progress_arc = app_ref.install_progress.clone();
+        phase_arc = app_ref.install_phase.clone();
         cancel_arc = app_ref.cancel_install.clone();
         result_app_id_arc = app_ref.install_result_app_id.clone();
         result_prefix_path_arc = app_ref.install_result_prefix_path.clone();
@@ -1178,6 +4138,7 @@ fn start_installation(app: Rc<RefCell<MyApp>>) {
         manager_type = wizard.manager_type.clone();
         install_type = wizard.install_type.clone();
         skip_disk_check = wizard.disk_space_override;
+        reuse_deps = wizard.reuse_deps && wizard.existing_deps.is_some();
         plugin_manifest = wizard.plugin_manifest.clone();
 
         selected_proton_name = match &wizard.selected_proton {
@@ -1199,6 +4160,11 @@ fn start_installation(app: Rc<RefCell<MyApp>>) {
         return;
     }
     let steam_proton = steam_proton.unwrap();
+    if let Err(e) = steam_proton.validate_binaries() {
+        log_error(&e);
+        app.borrow_mut().install_wizard.last_install_error = Some(e);
+        return;
+    }
 
     // Now update state
     {
@@ -1214,35 +4180,55 @@ fn start_installation(app: Rc<RefCell<MyApp>>) {
     let proton_config_name = selected_proton_name.clone();
 
     *busy_arc.lock() = true;
-    *status_arc.lock() = format!("Preparing to install {}...", manager_type);
+    *status_arc.lock() = if TaskQueue::global().is_busy() {
+        "Waiting for other background tasks to finish...".to_string()
+    } else {
+        format!("Preparing to install {}...", manager_type)
+    };
     *progress_arc.lock() = 0.0;
+    *phase_arc.lock() = None;
     cancel_arc.store(false, Ordering::Relaxed);
 
-    thread::spawn(move || {
+    // Clears the busy flag when the task ends, or is dropped because it was
+    // cancelled while still queued
+    struct BusyGuard {
+        busy: Arc<Mutex<bool>>,
+        status: Arc<Mutex<String>>,
+        started: bool,
+    }
+    impl Drop for BusyGuard {
+        fn drop(&mut self) {
+            if !self.started {
+                *self.status.lock() = "Cancelled before it started".to_string();
+            }
+            *self.busy.lock() = false;
+        }
+    }
+    let mut guard = BusyGuard { busy: busy_arc.clone(), status: status_arc.clone(), started: false };
+
+    let label = format!("Install {} ({})", manager_type, instance_name);
+    TaskQueue::global().spawn_with_cancel(label, cancel_arc.clone(), move |task| {
+        guard.started = true;
+        let _guard = guard;
         let cb_status = status_arc.clone();
         let cb_logs = logs_arc.clone();
         let cb_prog = progress_arc.clone();
-
-        struct BusyGuard(Arc<Mutex<bool>>);
-        impl Drop for BusyGuard {
-            fn drop(&mut self) {
-                *self.0.lock() = false;
-            }
-        }
-        let _guard = BusyGuard(busy_arc.clone());
+        let cb_phase = phase_arc.clone();
 
         let ctx = TaskContext::new(
             move |msg| *cb_status.lock() = msg,
             move |msg| cb_logs.lock().push(msg),
             move |p| *cb_prog.lock() = p,
             cancel_arc,
-        );
+        )
+        .on_phase(move |phase| *cb_phase.lock() = Some(phase))
+        .reporting_to(task);
 
         let install_result: Result<(u32, PathBuf), String> = match (manager_type.as_str(), install_type.as_str()) {
-            ("MO2", "New") => install_mo2(&instance_name, install_path, &steam_proton, ctx, skip_disk_check)
+            ("MO2", "New") => install_mo2(&instance_name, install_path, &steam_proton, ctx, skip_disk_check, reuse_deps)
                 .map(|r| (r.app_id, r.prefix_path))
                 .map_err(|e| e.to_string()),
-            ("MO2", "Existing") => setup_existing_mo2(&instance_name, install_path, &steam_proton, ctx)
+            ("MO2", "Existing") => setup_existing_mo2(&instance_name, install_path, &steam_proton, ctx, reuse_deps)
                 .map(|r| (r.app_id, r.prefix_path))
                 .map_err(|e| e.to_string()),
             ("Plugin", _) => {
@@ -1283,15 +4269,19 @@ fn start_installation(app: Rc<RefCell<MyApp>>) {
                     log_info(&format!("Set Proton '{}' for AppID {}", proton_config_name, app_id));
                 }
 
-                *status_arc.lock() = "Restarting Steam...".to_string();
-                match nak_rust::steam::restart_steam() {
-                    Ok(_) => {
-                        *status_arc.lock() = "Dependencies installed. Configuring DPI...".to_string();
-                    }
-                    Err(e) => {
-                        log_warning(&format!("Failed to restart Steam automatically: {}", e));
-                        *status_arc.lock() = "Dependencies installed. Please restart Steam manually after setup.".to_string();
+                if nak_rust::config::AppConfig::load().restart_steam_after_changes {
+                    *status_arc.lock() = "Restarting Steam...".to_string();
+                    match nak_rust::steam::restart_steam() {
+                        Ok(_) => {
+                            *status_arc.lock() = "Dependencies installed. Configuring DPI...".to_string();
+                        }
+                        Err(e) => {
+                            log_warning(&format!("Failed to restart Steam automatically: {}", e));
+                            *status_arc.lock() = "Dependencies installed. Please restart Steam manually after setup.".to_string();
+                        }
                     }
+                } else {
+                    *status_arc.lock() = "Dependencies installed. Please restart Steam manually after setup.".to_string();
                 }
             }
             Err(e) => {
@@ -1299,10 +4289,18 @@ fn start_installation(app: Rc<RefCell<MyApp>>) {
                 if e.contains("Cancelled") {
                     *status_arc.lock() = "Cancelled — installation cleaned up.".to_string();
                 } else {
+                    nak_rust::telemetry::report_install_failure(&e, &steam_proton);
                     *status_arc.lock() = format!("Error: {}", e);
                 }
             }
         }
+
+        let final_status = status_arc.lock().clone();
+        if final_status.starts_with("Error:") || final_status.starts_with("Cancelled") {
+            Err(final_status)
+        } else {
+            Ok(final_status)
+        }
     });
 }
 
@@ -1411,9 +4409,20 @@ fn handle_confirm_dpi(app: &mut MyApp) {
         kill_wineserver(&prefix_path, &proton);
         app.dpi_test_processes.lock().clear();
 
-        if app.install_wizard.selected_dpi != 96 {
-            if let Err(e) = apply_dpi(&prefix_path, &proton, app.install_wizard.selected_dpi) {
-                log_error(&format!("Failed to apply final DPI: {}", e));
+        // 96 too: a prefix reconfigured from the prefix manager may have another DPI set
+        if let Err(e) = apply_dpi(&prefix_path, &proton, app.install_wizard.selected_dpi) {
+            log_error(&format!("Failed to apply final DPI: {}", e));
+        }
+
+        // Refresh the applied settings record now the DPI is final
+        if !app.install_wizard.path.is_empty() {
+            kill_wineserver(&prefix_path, &proton);
+            if let Err(e) = nak_rust::installers::write_applied_settings(
+                &prefix_path,
+                std::path::Path::new(&app.install_wizard.path),
+                &proton.name,
+            ) {
+                log_warning(&format!("Failed to update applied settings summary: {}", e));
             }
         }
     }
@@ -1461,6 +4470,7 @@ fn start_plugin_installation(app: Rc<RefCell<MyApp>>, plugin_idx: usize, window_
         // Clear previous install state
         *app_ref.install_status.lock() = String::new();
         *app_ref.install_progress.lock() = 0.0;
+        *app_ref.install_phase.lock() = None;
         app_ref.logs.lock().clear();
         app_ref.cancel_install.store(false, Ordering::Relaxed);
     }
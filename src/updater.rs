@@ -6,11 +6,11 @@ use std::error::Error;
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::os::unix::fs::PermissionsExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use crate::github::GithubRelease;
-use crate::logging::{log_download, log_error, log_info};
+use crate::config::AppConfig;
+use crate::logging::{log_download, log_error, log_info, log_warning};
 
 const GITHUB_REPO: &str = "SulfurNitride/NaK";
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -29,11 +29,7 @@ pub struct UpdateInfo {
 /// Check GitHub for the latest release
 pub fn check_for_updates() -> Result<UpdateInfo, Box<dyn Error>> {
     let url = format!("https://api.github.com/repos/{}/releases/latest", GITHUB_REPO);
-    let agent = ureq::AgentBuilder::new().timeout(HTTP_TIMEOUT).build();
-    let response = agent.get(&url)
-        .set("User-Agent", "NaK-Updater")
-        .call()?;
-    let release: GithubRelease = response.into_json()?;
+    let release = crate::github::fetch_release(&url, "NaK-Updater")?;
 
     // Extract version number from tag (remove 'v' prefix if present)
     let latest_version = release.tag_name.trim_start_matches('v').to_string();
@@ -102,7 +98,7 @@ pub fn install_update(download_url: &str) -> Result<(), Box<dyn Error>> {
 
     // Download the update
     log_download("Downloading NaK update...");
-    let agent = ureq::AgentBuilder::new().timeout(HTTP_TIMEOUT).build();
+    let agent = crate::net::agent_for(download_url).timeout(HTTP_TIMEOUT).build();
     let response = agent.get(download_url)
         .set("User-Agent", "NaK-Updater")
         .call()?;
@@ -252,10 +248,104 @@ pub fn cleanup_update_backup() {
     }
 }
 
+/// Where the release notes of an installed version are kept
+fn release_notes_path(version: &str) -> PathBuf {
+    AppConfig::get_config_dir().join("release_notes").join(format!("{}.md", version))
+}
+
+/// Keep the release notes of a version being installed for the "What's New" dialog
+pub fn save_release_notes(version: &str, notes: &str) {
+    let path = release_notes_path(version);
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, notes));
+    if let Err(e) = result {
+        log_warning(&format!("Failed to save release notes for {}: {}", version, e));
+    }
+}
+
+/// Release notes saved for a version, if it was installed by the updater
+pub fn load_release_notes(version: &str) -> Option<String> {
+    fs::read_to_string(release_notes_path(version))
+        .ok()
+        .filter(|notes| !notes.trim().is_empty())
+}
+
+/// Markdown release notes as plain text for the UI
+///
+/// Headings keep their text, list items get a bullet, and emphasis, inline
+/// code and link targets are dropped. Blank-line runs collapse to one.
+pub fn render_release_notes(markdown: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in markdown.lines() {
+        let trimmed = line.trim_end();
+        let content = trimmed.trim_start();
+        let indent = &trimmed[..trimmed.len() - content.len()];
+
+        let rendered = if content.starts_with('#') {
+            content.trim_start_matches('#').trim().to_string()
+        } else if let Some(item) = content.strip_prefix("- ").or_else(|| content.strip_prefix("* ")) {
+            format!("{}\u{2022} {}", indent, item)
+        } else if content.starts_with("```") || is_rule(content) {
+            continue;
+        } else {
+            format!("{}{}", indent, content)
+        };
+        let rendered = strip_inline_markdown(&rendered);
+
+        if rendered.trim().is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(if rendered.trim().is_empty() { String::new() } else { rendered });
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// A `---` / `***` horizontal rule
+fn is_rule(line: &str) -> bool {
+    line.len() >= 3 && line.chars().all(|c| c == '-' || c == '*' || c == '_')
+}
+
+/// Drop `**`, `__`, backticks and `[text](url)` link targets from one line
+fn strip_inline_markdown(line: &str) -> String {
+    let line = line.replace("**", "").replace("__", "").replace('`', "");
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line.as_str();
+    while let Some(start) = rest.find('[') {
+        let after = &rest[start + 1..];
+        let link = after.find("](").and_then(|mid| after[mid + 2..].find(')').map(|end| (mid, mid + 2 + end)));
+        match link {
+            Some((mid, end)) => {
+                out.push_str(&rest[..start]);
+                out.push_str(&after[..mid]);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[..=start]);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
 /// Check if the current executable is in a writable location
 pub fn can_self_update() -> bool {
     match std::env::current_exe() {
         Ok(exe_path) => {
+            // Atomic distros: never write outside $HOME, even if the location happens to be writable
+            if crate::config::is_atomic_distro() {
+                let in_home = dirs::home_dir().map(|home| exe_path.starts_with(home)).unwrap_or(false);
+                if !in_home {
+                    return false;
+                }
+            }
+
             // Check if we can write to the directory
             if let Some(parent) = exe_path.parent() {
                 let test_file = parent.join(".nak_write_test");
@@ -283,4 +373,14 @@ mod tests {
         assert!(!is_newer_version("4.1.1", "4.1.2"));
         assert!(!is_newer_version("3.9.9", "4.0.0"));
     }
+
+    #[test]
+    fn test_render_release_notes() {
+        let notes = "## What's Changed\n\n\n* **Fixed** the `nxm` handler by @someone in [#12](https://github.com/x/y/pull/12)\n  - nested item\n---\nSee [the FAQ](https://example.com/faq) [sic]\n\n";
+        assert_eq!(
+            render_release_notes(notes),
+            "What's Changed\n\n\u{2022} Fixed the nxm handler by @someone in #12\n  \u{2022} nested item\nSee the FAQ [sic]"
+        );
+        assert_eq!(render_release_notes(""), "");
+    }
 }
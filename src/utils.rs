@@ -4,6 +4,18 @@ use std::error::Error;
 use std::fs;
 use std::path::Path;
 
+use crate::config::dir_size;
+
+/// Show a desktop notification via `notify-send` (silently ignored if unavailable)
+pub fn send_desktop_notification(summary: &str, body: &str, is_error: bool) {
+    let _ = std::process::Command::new("notify-send")
+        .arg("--app-name=NaK")
+        .arg(format!("--urgency={}", if is_error { "critical" } else { "normal" }))
+        .arg(summary)
+        .arg(body)
+        .spawn();
+}
+
 /// Download a file from URL to the specified path
 pub fn download_file(url: &str, path: &Path) -> Result<(), Box<dyn Error>> {
     // Ensure parent directory exists
@@ -11,9 +23,157 @@ pub fn download_file(url: &str, path: &Path) -> Result<(), Box<dyn Error>> {
         fs::create_dir_all(parent)?;
     }
 
-    let resp = ureq::get(url).call()?;
+    let resp = crate::net::get(url).call()?;
     let mut reader = resp.into_reader();
     let mut file = fs::File::create(path)?;
     std::io::copy(&mut reader, &mut file)?;
     Ok(())
 }
+
+/// Like `download_file`, but holds off while the task is paused and stops on cancel
+pub fn download_file_pausable(url: &str, path: &Path, ctx: &crate::installers::TaskContext) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let resp = crate::net::get(url).call()?;
+    let mut reader = ctx.pausable_reader(resp.into_reader());
+    let mut file = fs::File::create(path)?;
+    std::io::copy(&mut reader, &mut file)?;
+    Ok(())
+}
+
+/// Recursively copy a directory, reporting `(bytes_copied, total_bytes)` after each file.
+///
+/// Symlinks are recreated as symlinks rather than followed.
+pub fn copy_dir_recursive(
+    src: &Path,
+    dst: &Path,
+    progress: impl Fn(u64, u64),
+) -> Result<u64, Box<dyn Error>> {
+    let total = dir_size(src);
+    let mut copied: u64 = 0;
+
+    for entry in walkdir::WalkDir::new(src) {
+        let entry = entry?;
+        let rel = entry.path().strip_prefix(src)?;
+        let target = dst.join(rel);
+        let file_type = entry.file_type();
+
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if file_type.is_symlink() {
+            let link = fs::read_link(entry.path())?;
+            std::os::unix::fs::symlink(link, &target)?;
+        } else {
+            copied += fs::copy(entry.path(), &target)?;
+            progress(copied, total);
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Move a directory, reporting progress when a copy is needed.
+///
+/// Uses a plain rename when source and destination are on the same filesystem.
+/// Across filesystems it copies, verifies the copied size and only then deletes the source.
+pub fn move_dir(src: &Path, dst: &Path, progress: impl Fn(u64, u64)) -> Result<(), Box<dyn Error>> {
+    if dst.exists() {
+        return Err(format!("Destination already exists: {}", dst.display()).into());
+    }
+    if let Some(parent) = dst.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Fast path: same filesystem
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+
+    let expected = dir_size(src);
+    if let Err(e) = copy_dir_recursive(src, dst, &progress) {
+        let _ = fs::remove_dir_all(dst);
+        return Err(e);
+    }
+
+    let actual = dir_size(dst);
+    if actual != expected {
+        let _ = fs::remove_dir_all(dst);
+        return Err(format!(
+            "Copy verification failed: expected {} bytes, got {}",
+            expected, actual
+        ).into());
+    }
+
+    fs::remove_dir_all(src)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Nested folders with 15 bytes of files, a file symlink and a folder symlink
+    fn make_tree(root: &Path) {
+        fs::create_dir_all(root.join("mods/SkyUI/interface")).unwrap();
+        fs::write(root.join("ModOrganizer.ini"), [0u8; 5]).unwrap();
+        fs::write(root.join("mods/SkyUI/interface/skyui.swf"), [0u8; 10]).unwrap();
+        std::os::unix::fs::symlink("ModOrganizer.ini", root.join("ini-link")).unwrap();
+        std::os::unix::fs::symlink("mods/SkyUI", root.join("skyui-link")).unwrap();
+    }
+
+    fn assert_tree(root: &Path) {
+        assert_eq!(fs::read(root.join("mods/SkyUI/interface/skyui.swf")).unwrap().len(), 10);
+        assert_eq!(fs::read_link(root.join("ini-link")).unwrap(), Path::new("ModOrganizer.ini"));
+        assert_eq!(fs::read_link(root.join("skyui-link")).unwrap(), Path::new("mods/SkyUI"));
+        assert_eq!(dir_size(root), 15);
+    }
+
+    #[test]
+    fn test_copy_dir_recursive() {
+        let dir = std::env::temp_dir().join(format!("nak_copy_dir_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let src = dir.join("src");
+        make_tree(&src);
+
+        let last = Cell::new((0, 0));
+        let copied = copy_dir_recursive(&src, &dir.join("dst"), |done, total| last.set((done, total))).unwrap();
+        assert_eq!(copied, 15);
+        assert_eq!(last.get(), (15, 15));
+        assert_tree(&dir.join("dst"));
+        // Symlinks are copied as links, not followed
+        assert!(fs::symlink_metadata(dir.join("dst/skyui-link")).unwrap().file_type().is_symlink());
+        assert_tree(&src);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_move_dir() {
+        let dir = std::env::temp_dir().join(format!("nak_move_dir_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let src = dir.join("src");
+        make_tree(&src);
+
+        // Missing parents of the destination are created
+        let dst = dir.join("new/parent/dst");
+        move_dir(&src, &dst, |_, _| {}).unwrap();
+        assert!(!src.exists());
+        assert_tree(&dst);
+
+        // An existing destination is never merged into or replaced
+        make_tree(&src);
+        fs::create_dir_all(dir.join("taken")).unwrap();
+        fs::write(dir.join("taken/keep.txt"), "keep").unwrap();
+        assert!(move_dir(&src, &dir.join("taken"), |_, _| {}).is_err());
+        assert!(move_dir(&src, &dst, |_, _| {}).is_err());
+        assert_tree(&src);
+        assert_tree(&dst);
+        assert_eq!(fs::read_to_string(dir.join("taken/keep.txt")).unwrap(), "keep");
+        assert!(!dir.join("taken/mods").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}